@@ -114,6 +114,7 @@ impl<T: BaseWallet> ServiceRevocationRegistries<T> {
             .publish_local_revocations(
                 self.wallet.as_ref(),
                 &self.anoncreds,
+                self.ledger_read.as_ref(),
                 self.ledger_write.as_ref(),
                 &self.issuer_did,
             )
@@ -0,0 +1,20 @@
+-- Coordinate mediation 2.0 registers recipient DIDs rather than bare keys.
+-- Peer DIDs (did:peer:2 in particular) can grow well past what an index can hold,
+-- so uniqueness is enforced on a hash of the DID instead of the DID itself.
+
+CREATE TABLE IF NOT EXISTS recipient_dids (
+    seq_num SERIAL,
+    account_id BINARY(16) NOT NULL,
+    recipient_did TEXT NOT NULL,
+    recipient_did_hash BINARY(32) GENERATED ALWAYS AS (UNHEX(SHA2(recipient_did, 256))) STORED NOT NULL UNIQUE,
+    PRIMARY KEY (account_id, recipient_did_hash),
+    FOREIGN KEY (account_id) REFERENCES accounts(account_id)
+        ON DELETE CASCADE
+);
+
+-- Keys derived from a recipient DID are registered as regular recipients, so that
+-- DIDComm V1 forwards addressed to them keep working. Removing the DID removes its keys.
+ALTER TABLE recipients
+    ADD COLUMN recipient_did_hash BINARY(32) NULL,
+    ADD FOREIGN KEY (recipient_did_hash) REFERENCES recipient_dids(recipient_did_hash)
+        ON DELETE CASCADE;
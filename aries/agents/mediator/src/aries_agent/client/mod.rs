@@ -28,7 +28,7 @@ pub mod transports;
 
 use self::transports::AriesTransport;
 use super::Agent;
-use crate::utils::prelude::*;
+use crate::utils::{diddoc::did_document_from_legacy, prelude::*};
 
 // client role utilities
 impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
@@ -96,7 +96,8 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
     ) -> Result<(), String> {
         let their_vk = state.remote_vk().map_err(|e| e.to_string())?;
         let our_vk = &state.pairwise_info().pw_vk;
-        self.create_account(&their_vk, our_vk, state.their_did_doc())
+        let their_did_doc = did_document_from_legacy(state.their_did_doc())?;
+        self.create_account(&their_vk, our_vk, &their_did_doc)
             .await?;
         Ok(())
     }
@@ -1,9 +1,18 @@
 use std::{marker::PhantomData, sync::Arc};
 
 use aries_vcx::{
+    did_doc::schema::did_doc::DidDocument,
+    did_parser_nom::{Did, DidUrl},
+    did_peer::resolver::PeerDidResolver,
     handlers::out_of_band::sender::OutOfBandSender,
     messages::msg_fields::protocols::out_of_band::invitation::OobService,
-    utils::encryption_envelope::EncryptionEnvelope,
+    protocols::did_exchange::state_machine::helpers::create_peer_did_4,
+    utils::{
+        encryption_envelope::EncryptionEnvelope,
+        encryption_envelope_v2::{
+            resolve_key_agreement_keys, unpack_any, EncryptionEnvelopeV2, UnpackedEnvelope,
+        },
+    },
 };
 use aries_vcx_wallet::{
     errors::error::VcxWalletError,
@@ -13,6 +22,7 @@ use aries_vcx_wallet::{
         structs_io::UnpackMessageOutput,
     },
 };
+use did_resolver_registry::ResolverRegistry;
 use diddoc_legacy::aries::{diddoc::AriesDidDoc, service::AriesService};
 use messages::{
     msg_fields::protocols::{
@@ -25,12 +35,18 @@ use messages::{
     },
     AriesMessage,
 };
+use public_key::Key;
 use serde_json::json;
 use uuid::Uuid;
 
 use crate::{
     persistence::{get_persistence, AccountDetails, MediatorPersistence},
-    utils::{prelude::*, structs::VerKey, structs::URLInvitation},
+    utils::{
+        diddoc::{did_document_from_legacy, parse_verkey, resolve_packing_keys},
+        prelude::*,
+        structs::URLInvitation,
+        structs::VerKey,
+    },
 };
 
 #[cfg(any(test, feature = "client"))]
@@ -42,10 +58,24 @@ pub struct Agent<T: BaseWallet, P: MediatorPersistence> {
     wallet: Arc<T>,
     persistence: Arc<P>,
     service: Option<AriesService>,
+    /// Long form `did:peer:4` handed out to coordinate-mediation 2.0 clients
+    routing_did: Option<String>,
 }
 
 pub type ArcAgent<T, P> = Arc<Agent<T, P>>;
 
+/// DIDComm v2 message unpacked by [`Agent::unpack_didcomm_v2`].
+#[derive(Debug)]
+pub struct UnpackedDidCommV2 {
+    pub message: String,
+    /// Key agreement key the sender authcrypted with, which stands in for the sender verkey of
+    /// DIDComm v1 envelopes as the key of its account.
+    pub sender_verkey: VerKey,
+    /// Key agreement key of our routing did the envelope was addressed to.
+    pub recipient_verkey: VerKey,
+    pub their_did_doc: DidDocument,
+}
+
 pub struct AgentBuilder<T: BaseWallet> {
     _type_wallet: PhantomData<T>,
 }
@@ -62,6 +92,7 @@ impl<T: BaseWallet> AgentBuilder<T> {
             wallet,
             persistence,
             service: None,
+            routing_did: None,
         })
     }
     pub async fn new_demo_agent() -> Result<Agent<impl BaseWallet, sqlx::MySqlPool>, VcxWalletError>
@@ -87,6 +118,9 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
     pub fn get_service_ref(&self) -> Option<&AriesService> {
         self.service.as_ref()
     }
+    pub fn get_routing_did(&self) -> Option<&str> {
+        self.routing_did.as_deref()
+    }
     pub async fn reset_service(
         &mut self,
         routing_keys: Vec<String>,
@@ -98,12 +132,11 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
             id: "#inline".to_owned(),
             type_: "did-communication".to_owned(),
             priority: 0,
-            recipient_keys:
-                if credo_compatible {
-                    vec![format!("did:key:{}", did_data.verkey().fingerprint())]
-                } else {
-                    vec![did_data.verkey().base58()]
-                },
+            recipient_keys: if credo_compatible {
+                vec![format!("did:key:{}", did_data.verkey().fingerprint())]
+            } else {
+                vec![did_data.verkey().base58()]
+            },
             routing_keys,
             service_endpoint,
         };
@@ -117,7 +150,27 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
         service_endpoint: url::Url,
         credo_compatible: bool,
     ) -> Result<(), VcxWalletError> {
-        self.reset_service(routing_keys, service_endpoint, credo_compatible).await
+        self.reset_service(routing_keys, service_endpoint, credo_compatible)
+            .await
+    }
+    /// Creates the routing DID granted to coordinate-mediation 2.0 clients. Its service points
+    /// to our endpoint, so it must be called after the service is initialized.
+    pub async fn init_routing_did(&mut self) -> Result<(), String> {
+        let service = self
+            .service
+            .as_ref()
+            .ok_or("Service must be initialized before the routing did")?;
+        let (peer_did, _routing_key) = create_peer_did_4(
+            self.wallet.as_ref(),
+            service.service_endpoint.clone(),
+            vec![],
+        )
+        .await
+        .map_err(string_from_std_error)?;
+        let routing_did = peer_did.long_form().map_err(string_from_std_error)?;
+        info!("Initialized routing did {}", routing_did);
+        self.routing_did = Some(routing_did.to_string());
+        Ok(())
     }
     pub fn get_oob_invite(&self) -> Result<OOBInvitation, String> {
         if let Some(service) = &self.service {
@@ -146,7 +199,9 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
             // basically leaving the configured endpoint but in the same time persists its path
             u.path_segments_mut().unwrap().pop();
             let url = sender.invitation_to_url(u.as_str()).unwrap();
-            Ok(URLInvitation{invitation: url.to_string()})
+            Ok(URLInvitation {
+                invitation: url.to_string(),
+            })
         } else {
             Err("No service to create invite for".to_owned())
         }
@@ -161,6 +216,73 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
         Ok(unpacked)
     }
 
+    /// Resolves the did doc of our routing did, which DIDComm v2 messages are exchanged with.
+    pub async fn get_routing_did_doc(&self) -> Result<DidDocument, String> {
+        let routing_did = self
+            .routing_did
+            .as_deref()
+            .ok_or("Mediator has no routing did to exchange DIDComm v2 messages with")?;
+        let did = Did::parse(routing_did.to_owned()).map_err(string_from_std_error)?;
+        Ok(peer_did_resolver_registry()
+            .resolve(&did, &Default::default())
+            .await
+            .map_err(|e| e.to_string())?
+            .did_document)
+    }
+
+    /// Unpacks a DIDComm v2 envelope addressed to our routing did. Only authcrypted envelopes
+    /// are accepted, as the sender's key is what its account is looked up by.
+    pub async fn unpack_didcomm_v2(&self, didcomm_msg: &[u8]) -> Result<UnpackedDidCommV2, String> {
+        let resolver_registry = peer_did_resolver_registry();
+        let routing_did_doc = self.get_routing_did_doc().await?;
+        let unpacked = unpack_any(
+            self.wallet.as_ref(),
+            didcomm_msg,
+            &routing_did_doc,
+            &resolver_registry,
+        )
+        .await
+        .map_err(string_from_std_error)?;
+        let UnpackedEnvelope::V2 {
+            message,
+            sender_kid: Some(sender_kid),
+            recipient_kid,
+            ..
+        } = unpacked
+        else {
+            return Err("Anonymous sender can't be authenticated".to_owned());
+        };
+        let sender_kid_url = DidUrl::parse(sender_kid.clone()).map_err(string_from_std_error)?;
+        let sender_did = Did::try_from(&sender_kid_url).map_err(string_from_std_error)?;
+        let their_did_doc = resolver_registry
+            .resolve(&sender_did, &Default::default())
+            .await
+            .map_err(|e| e.to_string())?
+            .did_document;
+        Ok(UnpackedDidCommV2 {
+            message,
+            sender_verkey: key_agreement_key(&their_did_doc, &sender_kid)?.base58(),
+            recipient_verkey: key_agreement_key(&routing_did_doc, &recipient_kid)?.base58(),
+            their_did_doc,
+        })
+    }
+
+    /// Authcrypts message from our routing did to `their_did_doc` in a DIDComm v2 envelope.
+    pub async fn pack_didcomm_v2(
+        &self,
+        message: &[u8],
+        their_did_doc: &DidDocument,
+    ) -> Result<EncryptionEnvelopeV2, String> {
+        EncryptionEnvelopeV2::authcrypt(
+            self.wallet.as_ref(),
+            message,
+            &self.get_routing_did_doc().await?,
+            their_did_doc,
+        )
+        .await
+        .map_err(string_from_std_error)
+    }
+
     pub async fn pack_didcomm(
         &self,
         message: &[u8],
@@ -177,6 +299,26 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
         .map_err(string_from_std_error)
     }
 
+    /// Packs message for a registered account, whose did doc may be a converted legacy doc,
+    /// a peer did doc or a bare `did:key` doc.
+    pub async fn pack_didcomm_for_did_doc(
+        &self,
+        message: &[u8],
+        our_vk: &VerKey,
+        their_did_doc: &DidDocument,
+    ) -> Result<EncryptionEnvelope, String> {
+        let (recipient_key, routing_keys) = resolve_packing_keys(their_did_doc)?;
+        EncryptionEnvelope::create_from_keys(
+            self.wallet.as_ref(),
+            message,
+            Some(parse_verkey(our_vk)?),
+            recipient_key,
+            routing_keys,
+        )
+        .await
+        .map_err(string_from_std_error)
+    }
+
     pub async fn auth_and_get_details(
         &self,
         sender_verkey: &Option<VerKey>,
@@ -244,8 +386,13 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
         let auth_pubkey = their_keys
             .first()
             .ok_or("No recipient key for client :/ ?".to_owned())?;
-        self.create_account(auth_pubkey, &did_data.verkey().base58(), &their_diddoc)
-            .await?;
+        let their_did_document = did_document_from_legacy(&their_diddoc)?;
+        self.create_account(
+            auth_pubkey,
+            &did_data.verkey().base58(),
+            &their_did_document,
+        )
+        .await?;
         Ok(packed_response_envelope)
     }
 
@@ -253,7 +400,7 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
         &self,
         their_vk: &VerKey,
         our_vk: &VerKey,
-        did_doc: &AriesDidDoc,
+        did_doc: &DidDocument,
     ) -> Result<(), String> {
         self.persistence
             .create_account(their_vk, our_vk, &json!(did_doc).to_string())
@@ -263,6 +410,21 @@ impl<T: BaseWallet, P: MediatorPersistence> Agent<T, P> {
     }
 }
 
+fn key_agreement_key(did_doc: &DidDocument, kid: &str) -> Result<Key, String> {
+    Ok(resolve_key_agreement_keys(did_doc)
+        .map_err(string_from_std_error)?
+        .into_iter()
+        .find(|key| key.kid == kid)
+        .ok_or_else(|| format!("Key {} not found in did doc {}", kid, did_doc.id()))?
+        .key)
+}
+
+/// Both our routing did and the dids of DIDComm v2 wallets registering with us are peer dids.
+fn peer_did_resolver_registry() -> ResolverRegistry {
+    ResolverRegistry::new()
+        .register_resolver::<PeerDidResolver>("peer".into(), PeerDidResolver::new())
+}
+
 #[cfg(test)]
 mod test {
     use aries_vcx::{
@@ -285,7 +447,7 @@ mod test {
             .init_service(
                 vec![],
                 "http://127.0.0.1:8005/aries".to_string().parse().unwrap(),
-                false
+                false,
             )
             .await
             .unwrap();
@@ -42,6 +42,7 @@ async fn main() {
         )
         .await
         .unwrap();
+    agent.init_routing_did().await.unwrap();
     let app_router = mediator::http_routes::build_router(agent).await;
     info!("Starting server");
     let listener = tokio::net::TcpListener::bind(&endpoint_root).await.unwrap();
@@ -0,0 +1,63 @@
+use aries_vcx::{
+    protocols::trustping::build_ping_response, utils::encryption_envelope_v2::EncryptionEnvelopeV2,
+};
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use axum::Json;
+use messages::{
+    didcomm_v2::DidCommV2Message, msg_fields::protocols::trust_ping::TrustPing, AriesMessage,
+};
+use serde_json::Value;
+
+use super::{
+    mediator_coord::handle_mediation_coord_v2_from, pickup::handle_pickup_protocol,
+    unhandled_aries_message, utils::prelude::*,
+};
+
+/// Serves DIDComm v2 wallets writing to our routing did: coordinate-mediation 2.0, pickup 3.0
+/// and trust-ping 2.0, answered in DIDComm v2 envelopes. Messages are handled as their DIDComm v1
+/// counterparts, with the key the sender authcrypted with standing in for its verkey.
+pub async fn handle_aries_v2<T: BaseWallet, P: MediatorPersistence>(
+    agent: &ArcAgent<T, P>,
+    didcomm_msg: &[u8],
+) -> Result<Json<Value>, String> {
+    let unpacked = agent.unpack_didcomm_v2(didcomm_msg).await?;
+    let message: DidCommV2Message =
+        serde_json::from_str(&unpacked.message).map_err(string_from_std_error)?;
+    let aries_message = AriesMessage::try_from(message).map_err(string_from_std_error)?;
+    let aries_response = match aries_message {
+        AriesMessage::CoordinateMediationV2(coord_message) => {
+            handle_mediation_coord_v2_from(
+                agent,
+                coord_message,
+                &unpacked.sender_verkey,
+                &unpacked.their_did_doc,
+                &unpacked.recipient_verkey,
+            )
+            .await?
+        }
+        AriesMessage::Pickup(pickup_message) => {
+            let account_details = agent
+                .auth_and_get_details(&Some(unpacked.sender_verkey.clone()))
+                .await?;
+            handle_pickup_protocol(agent, pickup_message, &account_details.auth_pubkey).await?
+        }
+        AriesMessage::TrustPing(TrustPing::Ping(trust_ping)) => {
+            agent
+                .auth_and_get_details(&Some(unpacked.sender_verkey.clone()))
+                .await?;
+            let ping_response = build_ping_response(&trust_ping);
+            AriesMessage::TrustPing(TrustPing::PingResponse(ping_response))
+        }
+        aries_message => Err(unhandled_aries_message(aries_message))?,
+    };
+    let mut response = DidCommV2Message::try_from(aries_response).map_err(string_from_std_error)?;
+    response.from = agent.get_routing_did().map(ToOwned::to_owned);
+    response.to = vec![unpacked.their_did_doc.id().to_string()];
+    let response_bytes = serde_json::to_vec(&response).map_err(string_from_std_error)?;
+    let EncryptionEnvelopeV2(packed_message_bytes) = agent
+        .pack_didcomm_v2(&response_bytes, &unpacked.their_did_doc)
+        .await?;
+    let packed_json =
+        serde_json::from_slice(&packed_message_bytes).map_err(string_from_std_error)?;
+    Ok(Json(packed_json))
+}
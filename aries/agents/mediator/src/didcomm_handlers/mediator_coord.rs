@@ -1,10 +1,16 @@
+use aries_vcx::did_doc::schema::did_doc::DidDocument;
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
-use messages::msg_fields::protocols::coordinate_mediation::{
-    CoordinateMediation, MediateGrant, MediateGrantContent, MediateGrantDecorators,
+use messages::{
+    msg_fields::protocols::coordinate_mediation::{
+        v2::CoordinateMediationV2, CoordinateMediation, MediateGrant, MediateGrantContent,
+        MediateGrantDecorators,
+    },
+    AriesMessage,
 };
 use uuid::Uuid;
 
 use super::utils::prelude::*;
+use crate::utils::{diddoc::did_document_from_verkey, structs::VerKey};
 
 pub async fn handle_mediation_coord(
     agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
@@ -44,3 +50,76 @@ pub async fn handle_mediation_coord(
     .await;
     Ok(coord_response)
 }
+
+/// Coordinate-mediation 2.0 in DIDComm v1 envelopes. Mediate requests are accepted from unknown
+/// senders, who get an account keyed on their verkey; every other message requires a registered
+/// account. Returns the response along with the did doc and our key to pack it with.
+pub async fn handle_mediation_coord_v2(
+    agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
+    coord_msg: CoordinateMediationV2,
+    sender_verkey: &Option<VerKey>,
+    our_verkey: &VerKey,
+) -> Result<(AriesMessage, DidDocument, VerKey), String> {
+    if let CoordinateMediationV2::MediateRequest(_) = &coord_msg {
+        let auth_pubkey = sender_verkey
+            .as_deref()
+            .ok_or("Anonymous sender can't request mediation")?;
+        // Requester may be denied, so the response goes to the key it wrote from rather than
+        // to an account
+        let did_doc = did_document_from_verkey(auth_pubkey)?;
+        let response =
+            handle_mediation_coord_v2_from(agent, coord_msg, auth_pubkey, &did_doc, our_verkey)
+                .await?;
+        return Ok((response, did_doc, our_verkey.to_owned()));
+    };
+    let account_details = agent.auth_and_get_details(sender_verkey).await?;
+    let coord_response = crate::mediation::coordination::handle_coord_v2_authenticated(
+        agent.get_persistence_ref(),
+        coord_msg,
+        &account_details.auth_pubkey,
+    )
+    .await?;
+    Ok((
+        AriesMessage::CoordinateMediationV2(coord_response),
+        account_details.their_did_doc,
+        account_details.our_signing_key,
+    ))
+}
+
+/// Coordinate-mediation 2.0 from the sender identified by `auth_pubkey`, which gets an account
+/// for `their_did_doc` on mediate request. The response is a problem report when the mediator
+/// fails to process a mediate request.
+pub async fn handle_mediation_coord_v2_from(
+    agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
+    coord_msg: CoordinateMediationV2,
+    auth_pubkey: &str,
+    their_did_doc: &DidDocument,
+    our_verkey: &VerKey,
+) -> Result<AriesMessage, String> {
+    if let CoordinateMediationV2::MediateRequest(mediate_request) = coord_msg {
+        let response = crate::mediation::coordination::handle_mediate_request_v2(
+            agent.get_persistence_ref(),
+            mediate_request,
+            auth_pubkey,
+            &serde_json::to_string(their_did_doc).map_err(string_from_std_error)?,
+            our_verkey,
+            agent.get_routing_did(),
+        )
+        .await
+        .map_or_else(
+            AriesMessage::ReportProblem,
+            AriesMessage::CoordinateMediationV2,
+        );
+        return Ok(response);
+    };
+    let account_details = agent
+        .auth_and_get_details(&Some(auth_pubkey.to_owned()))
+        .await?;
+    let coord_response = crate::mediation::coordination::handle_coord_v2_authenticated(
+        agent.get_persistence_ref(),
+        coord_msg,
+        &account_details.auth_pubkey,
+    )
+    .await?;
+    Ok(AriesMessage::CoordinateMediationV2(coord_response))
+}
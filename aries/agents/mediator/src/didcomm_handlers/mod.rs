@@ -1,23 +1,27 @@
 use std::fmt::Debug;
 
-use aries_vcx::protocols::trustping::build_ping_response;
+use aries_vcx::{
+    protocols::trustping::build_ping_response, utils::encryption_envelope_v2::DidCommMediaType,
+};
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 use axum::{body::Bytes, extract::State, Json};
-use messages::AriesMessage;
 use messages::msg_fields::protocols::trust_ping::TrustPing;
+use messages::AriesMessage;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use utils::prelude::*;
 
 mod connection;
+mod didcomm_v2;
 mod forward;
 mod mediator_coord;
 mod pickup;
 mod utils;
 
 use connection::handle_aries_connection;
+use didcomm_v2::handle_aries_v2;
 use forward::handle_routing_forward;
-use mediator_coord::handle_mediation_coord;
+use mediator_coord::{handle_mediation_coord, handle_mediation_coord_v2};
 use pickup::handle_pickup_protocol;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -34,6 +38,11 @@ pub async fn handle_aries<T: BaseWallet, P: MediatorPersistence>(
     didcomm_msg: Bytes,
 ) -> Result<Json<Value>, String> {
     log::info!("processing message {:?}", &didcomm_msg);
+    if DidCommMediaType::detect(&didcomm_msg).map_err(string_from_std_error)?
+        == DidCommMediaType::EncryptedV2
+    {
+        return handle_aries_v2(&agent, &didcomm_msg).await;
+    }
     let unpacked = agent.unpack_didcomm(&didcomm_msg).await.unwrap();
     let aries_message: GeneralAriesMessage =
         serde_json::from_str(&unpacked.message).map_err(|e| e.to_string())?;
@@ -47,19 +56,30 @@ pub async fn handle_aries<T: BaseWallet, P: MediatorPersistence>(
         {
             handle_routing_forward(agent.clone(), forward).await?;
             return Ok(Json(json!({})));
+        } else if let GeneralAriesMessage::AriesVCXSupported(AriesMessage::CoordinateMediationV2(
+            coord_message,
+        )) = aries_message
+        {
+            let (aries_response, their_did_doc, our_signing_key) = handle_mediation_coord_v2(
+                &agent,
+                coord_message,
+                &unpacked.sender_verkey,
+                &unpacked.recipient_verkey,
+            )
+            .await?;
+            let aries_response_bytes =
+                serde_json::to_vec(&aries_response).map_err(string_from_std_error)?;
+            agent
+                .pack_didcomm_for_did_doc(&aries_response_bytes, &our_signing_key, &their_did_doc)
+                .await?
         } else {
             // Authenticated flow: Auth known VerKey then process account related messages
             let account_details = agent.auth_and_get_details(&unpacked.sender_verkey).await?;
             log::info!("Processing message for {:?}", account_details.account_name);
             let aries_response = match aries_message {
                 GeneralAriesMessage::AriesVCXSupported(AriesMessage::Pickup(pickup_message)) => {
-                    let pickup_response = handle_pickup_protocol(
-                        &agent,
-                        pickup_message,
-                        &account_details.auth_pubkey,
-                    )
-                    .await?;
-                    AriesMessage::Pickup(pickup_response)
+                    handle_pickup_protocol(&agent, pickup_message, &account_details.auth_pubkey)
+                        .await?
                 }
                 GeneralAriesMessage::AriesVCXSupported(AriesMessage::CoordinateMediation(
                     coord_message,
@@ -69,7 +89,9 @@ pub async fn handle_aries<T: BaseWallet, P: MediatorPersistence>(
                             .await?;
                     AriesMessage::CoordinateMediation(coord_response)
                 }
-                GeneralAriesMessage::AriesVCXSupported(AriesMessage::TrustPing(TrustPing::Ping(trust_ping))) => {
+                GeneralAriesMessage::AriesVCXSupported(AriesMessage::TrustPing(
+                    TrustPing::Ping(trust_ping),
+                )) => {
                     let ping_response = build_ping_response(&trust_ping);
                     AriesMessage::TrustPing(TrustPing::PingResponse(ping_response))
                 }
@@ -80,7 +102,7 @@ pub async fn handle_aries<T: BaseWallet, P: MediatorPersistence>(
             let aries_response_bytes =
                 serde_json::to_vec(&aries_response).map_err(string_from_std_error)?;
             agent
-                .pack_didcomm(
+                .pack_didcomm_for_did_doc(
                     &aries_response_bytes,
                     &account_details.our_signing_key,
                     &account_details.their_did_doc,
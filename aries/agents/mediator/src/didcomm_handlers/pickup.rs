@@ -1,18 +1,19 @@
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
-use messages::msg_fields::protocols::pickup::Pickup;
+use messages::{msg_fields::protocols::pickup::Pickup, AriesMessage};
 
 use super::utils::prelude::*;
 
+/// Answers with a pickup message, or with a problem report if the request can't be served.
 pub async fn handle_pickup_protocol(
     agent: &ArcAgent<impl BaseWallet, impl MediatorPersistence>,
     pickup_message: Pickup,
     auth_pubkey: &str,
-) -> Result<Pickup, String> {
+) -> Result<AriesMessage, String> {
     let pickup_response = crate::mediation::pickup::handle_pickup_authenticated(
         agent.get_persistence_ref(),
         pickup_message,
         auth_pubkey,
     )
     .await;
-    Ok(pickup_response)
+    Ok(pickup_response.map_or_else(AriesMessage::ReportProblem, AriesMessage::Pickup))
 }
@@ -1,21 +1,46 @@
 // Copyright 2023 Naian G.
 // SPDX-License-Identifier: Apache-2.0
 
-use std::sync::Arc;
+use crate::{
+    persistence::{errors::GetAccountIdError, MediatorPersistence},
+    utils::structs::VerKey,
+};
+use aries_vcx::{
+    did_parser_nom::Did,
+    did_peer::resolver::{PeerDidResolutionOptions, PeerDidResolver},
+    protocols::problem_report::{descriptors, Problem, ProblemCode, ProblemScope},
+    utils::didcomm_utils::{get_ed25519_recipient_keys, resolve_ed25519_key_agreement},
+};
+use did_resolver::traits::resolvable::DidResolvable;
+use log::{error, info};
+use messages::msg_fields::protocols::coordinate_mediation::v2::{
+    CoordinateMediationV2, MediateDenyV2, MediateDenyV2Content, MediateDenyV2Decorators,
+    MediateGrantV2, MediateGrantV2Content, MediateGrantV2Decorators, MediateRequestV2, Recipient,
+    RecipientContent, RecipientDecorators, RecipientItem, RecipientPagination, RecipientQuery,
+    RecipientUpdate, RecipientUpdateResponse, RecipientUpdateResponseContent,
+    RecipientUpdateResponseDecorators, RecipientUpdateResponseItem,
+};
+use messages::{
+    decorators::thread::Thread,
+    msg_fields::protocols::coordinate_mediation::{
+        keylist::KeylistItem,
+        keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
+        keylist_update_response::{KeylistUpdateItemResult, KeylistUpdateResponseItem},
+        CoordinateMediation, Keylist, KeylistContent, KeylistDecorators, KeylistQueryContent,
+        KeylistUpdateContent, KeylistUpdateResponse, KeylistUpdateResponseContent,
+        KeylistUpdateResponseDecorators, MediateDeny, MediateDenyContent, MediateDenyDecorators,
+        MediateGrant, MediateGrantContent, MediateGrantDecorators,
+    },
+    msg_fields::protocols::report_problem::{Impact, ProblemReport, WhoRetries},
+    msg_parts::MsgParts,
+};
 use public_key::Key;
-use messages::{decorators::thread::Thread, msg_fields::protocols::coordinate_mediation::{
-    keylist::KeylistItem,
-    keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
-    keylist_update_response::{KeylistUpdateItemResult, KeylistUpdateResponseItem},
-    CoordinateMediation, Keylist, KeylistContent, KeylistDecorators, KeylistQueryContent,
-    KeylistUpdateContent, KeylistUpdateResponse, KeylistUpdateResponseContent,
-    KeylistUpdateResponseDecorators, MediateDeny, MediateDenyContent, MediateDenyDecorators,
-    MediateGrant, MediateGrantContent, MediateGrantDecorators,
-}, msg_parts::MsgParts};
+use std::sync::Arc;
 use uuid::Uuid;
-use crate::persistence::MediatorPersistence;
 
 const DID_KEY_PREFIX: &str = "did:key:";
+const SUPPORTED_RECIPIENT_DID_PREFIXES: [&str; 2] = ["did:peer:2", "did:peer:4"];
+const DEFAULT_RECIPIENT_QUERY_LIMIT: u64 = 100;
 
 pub async fn handle_coord_authenticated(
     storage: Arc<impl MediatorPersistence>,
@@ -109,24 +134,24 @@ pub async fn handle_keylist_update<T: MediatorPersistence>(
     for update_item in updates.into_iter() {
         let result = match &update_item.action {
             KeylistUpdateItemAction::Add => {
-                let key_b58 = if let Some(stripped_key) = update_item.recipient_key.strip_prefix(DID_KEY_PREFIX) {
+                let key_b58 = if let Some(stripped_key) =
+                    update_item.recipient_key.strip_prefix(DID_KEY_PREFIX)
+                {
                     Key::from_fingerprint(stripped_key).unwrap().base58()
                 } else {
                     update_item.recipient_key.clone()
                 };
-                storage
-                    .add_recipient(auth_pubkey, &key_b58)
-                    .await
+                storage.add_recipient(auth_pubkey, &key_b58).await
             }
             KeylistUpdateItemAction::Remove => {
-                let key_b58 = if let Some(stripped_key) = update_item.recipient_key.strip_prefix(DID_KEY_PREFIX) {
+                let key_b58 = if let Some(stripped_key) =
+                    update_item.recipient_key.strip_prefix(DID_KEY_PREFIX)
+                {
                     Key::from_fingerprint(stripped_key).unwrap().base58()
                 } else {
                     update_item.recipient_key.clone()
                 };
-                storage
-                    .remove_recipient(auth_pubkey, &key_b58)
-                    .await
+                storage.remove_recipient(auth_pubkey, &key_b58).await
             }
         };
         let update_item_result = match result {
@@ -140,13 +165,7 @@ pub async fn handle_keylist_update<T: MediatorPersistence>(
         });
     }
     let decorators = KeylistUpdateResponseDecorators::builder()
-        .thread(
-            Thread::builder()
-                .thid(
-                    keylist_update_data.id
-                )
-                .build(),
-        )
+        .thread(Thread::builder().thid(keylist_update_data.id).build())
         .build();
     let keylist_update_response = KeylistUpdateResponse::builder()
         .content(KeylistUpdateResponseContent { updated })
@@ -155,3 +174,215 @@ pub async fn handle_keylist_update<T: MediatorPersistence>(
         .build();
     CoordinateMediation::KeylistUpdateResponse(keylist_update_response)
 }
+
+/// Grants mediation to a DIDComm wallet, creating an account for `auth_pubkey` the first time
+/// it asks. The grant hands out the mediator's routing DID rather than endpoint and keys.
+///
+/// Mediation is denied when the mediator has no routing DID. Failing to look up or create the
+/// account is no reason to deny it, the requester gets a problem report to retry later instead.
+pub async fn handle_mediate_request_v2<T: MediatorPersistence>(
+    storage: Arc<T>,
+    mediate_request: MediateRequestV2,
+    auth_pubkey: &str,
+    did_doc: &str,
+    our_signing_key: &str,
+    routing_did: Option<&str>,
+) -> Result<CoordinateMediationV2, ProblemReport> {
+    let account = match storage.get_account_id(auth_pubkey).await {
+        Ok(_) => Ok(()),
+        Err(GetAccountIdError::AccountNotFound(_)) => storage
+            .create_account(auth_pubkey, our_signing_key, did_doc)
+            .await
+            .map_err(|err| err.to_string()),
+        Err(err) => Err(err.to_string()),
+    };
+    if let Err(err) = account {
+        error!(
+            "Failed to set up the account of mediation requester {}: {}",
+            auth_pubkey, err
+        );
+        return Err(Problem::new(ProblemCode::error(
+            ProblemScope::Protocol,
+            descriptors::INTERNAL_ERROR,
+        ))
+        .impact(Impact::Thread)
+        .who_retries(WhoRetries::You)
+        .explain("Mediator failed to set up mediation, please retry later")
+        .build(&mediate_request.id));
+    }
+    let thread = Thread::builder().thid(mediate_request.id).build();
+    match routing_did {
+        Some(routing_did) => {
+            let mediate_grant_msg = MediateGrantV2::builder()
+                .content(MediateGrantV2Content {
+                    routing_did: vec![routing_did.to_owned()],
+                })
+                .decorators(MediateGrantV2Decorators::builder().thread(thread).build())
+                .id(Uuid::new_v4().to_string())
+                .build();
+            Ok(CoordinateMediationV2::MediateGrant(mediate_grant_msg))
+        }
+        None => {
+            let mediate_deny_msg = MediateDenyV2::builder()
+                .content(MediateDenyV2Content::default())
+                .decorators(MediateDenyV2Decorators::builder().thread(thread).build())
+                .id(Uuid::new_v4().to_string())
+                .build();
+            Ok(CoordinateMediationV2::MediateDeny(mediate_deny_msg))
+        }
+    }
+}
+
+/// Resolves a recipient DID into the verkeys DIDComm V1 senders address forwards to.
+/// Only `did:peer:2` and (long form) `did:peer:4` are accepted as recipient DIDs.
+pub async fn resolve_recipient_did_keys(recipient_did: &str) -> Result<Vec<VerKey>, String> {
+    if !SUPPORTED_RECIPIENT_DID_PREFIXES
+        .iter()
+        .any(|prefix| recipient_did.starts_with(prefix))
+    {
+        return Err(format!(
+            "Unsupported recipient DID {recipient_did}, expected did:peer:2 or did:peer:4"
+        ));
+    }
+    let did = Did::parse(recipient_did.to_owned()).map_err(|e| e.to_string())?;
+    let did_doc = PeerDidResolver::new()
+        .resolve(&did, &PeerDidResolutionOptions::default())
+        .await
+        .map_err(|e| e.to_string())?
+        .did_document;
+    let mut recipient_keys: Vec<VerKey> = Vec::new();
+    for service in did_doc.service() {
+        if let Ok(keys) = get_ed25519_recipient_keys(&did_doc, service.id()) {
+            recipient_keys.extend(keys.iter().map(Key::base58));
+        }
+    }
+    if let Ok(key) = resolve_ed25519_key_agreement(&did_doc) {
+        recipient_keys.push(key.base58());
+    }
+    recipient_keys.sort();
+    recipient_keys.dedup();
+    if recipient_keys.is_empty() {
+        return Err(format!(
+            "Recipient DID {recipient_did} doesn't expose any ed25519 key"
+        ));
+    }
+    Ok(recipient_keys)
+}
+
+pub async fn handle_coord_v2_authenticated(
+    storage: Arc<impl MediatorPersistence>,
+    message: CoordinateMediationV2,
+    auth_pubkey: &str,
+) -> Result<CoordinateMediationV2, String> {
+    match message {
+        CoordinateMediationV2::RecipientUpdate(recipient_update) => {
+            Ok(handle_recipient_update(storage, recipient_update, auth_pubkey).await)
+        }
+        CoordinateMediationV2::RecipientQuery(recipient_query) => {
+            handle_recipient_query(storage, recipient_query, auth_pubkey).await
+        }
+        CoordinateMediationV2::MediateRequest(_) => Err(
+            "Use handle_mediate_request_v2 directly. This handler is for preregistered clients."
+                .to_owned(),
+        ),
+        other => Err(format!(
+            "Mediator doesn't expect to receive {:?} from its clients",
+            other
+        )),
+    }
+}
+
+pub async fn handle_recipient_update<T: MediatorPersistence>(
+    storage: Arc<T>,
+    recipient_update: RecipientUpdate,
+    auth_pubkey: &str,
+) -> CoordinateMediationV2 {
+    let mut updated: Vec<RecipientUpdateResponseItem> = Vec::new();
+    for update_item in recipient_update.content.updates.into_iter() {
+        let result = match &update_item.action {
+            KeylistUpdateItemAction::Add => {
+                match resolve_recipient_did_keys(&update_item.recipient_did).await {
+                    Ok(recipient_keys) => {
+                        match storage
+                            .add_recipient_did(
+                                auth_pubkey,
+                                &update_item.recipient_did,
+                                &recipient_keys,
+                            )
+                            .await
+                        {
+                            Ok(()) => KeylistUpdateItemResult::Success,
+                            Err(err) => {
+                                info!("Failed to add recipient did: {}", err);
+                                KeylistUpdateItemResult::ServerError
+                            }
+                        }
+                    }
+                    Err(err) => {
+                        info!("Rejecting recipient did: {}", err);
+                        KeylistUpdateItemResult::ClientError
+                    }
+                }
+            }
+            KeylistUpdateItemAction::Remove => match storage
+                .remove_recipient_did(auth_pubkey, &update_item.recipient_did)
+                .await
+            {
+                Ok(()) => KeylistUpdateItemResult::Success,
+                Err(_msg) => KeylistUpdateItemResult::ServerError,
+            },
+        };
+        updated.push(RecipientUpdateResponseItem {
+            recipient_did: update_item.recipient_did,
+            action: update_item.action,
+            result,
+        });
+    }
+    let decorators = RecipientUpdateResponseDecorators::builder()
+        .thread(Thread::builder().thid(recipient_update.id).build())
+        .build();
+    let recipient_update_response = RecipientUpdateResponse::builder()
+        .content(RecipientUpdateResponseContent { updated })
+        .decorators(decorators)
+        .id(Uuid::new_v4().to_string())
+        .build();
+    CoordinateMediationV2::RecipientUpdateResponse(recipient_update_response)
+}
+
+pub async fn handle_recipient_query<T: MediatorPersistence>(
+    storage: Arc<T>,
+    recipient_query: RecipientQuery,
+    auth_pubkey: &str,
+) -> Result<CoordinateMediationV2, String> {
+    let paginate = recipient_query.content.paginate.unwrap_or_default();
+    let limit = paginate.limit.unwrap_or(DEFAULT_RECIPIENT_QUERY_LIMIT);
+    let offset = paginate.offset.unwrap_or(0);
+    let (recipient_dids, total) = storage
+        .list_recipient_dids(auth_pubkey, limit, offset)
+        .await
+        .map_err(|e| e.to_string())?;
+    let count = recipient_dids.len() as u64;
+    let pagination = RecipientPagination::builder()
+        .count(count)
+        .offset(offset)
+        .remaining(total.saturating_sub(offset + count))
+        .build();
+    let content = RecipientContent::builder()
+        .dids(
+            recipient_dids
+                .into_iter()
+                .map(|recipient_did| RecipientItem { recipient_did })
+                .collect(),
+        )
+        .pagination(pagination)
+        .build();
+    let decorators = RecipientDecorators::builder()
+        .thread(Thread::builder().thid(recipient_query.id).build())
+        .build();
+    let recipient = Recipient::builder()
+        .content(content)
+        .decorators(decorators)
+        .id(Uuid::new_v4().to_string())
+        .build();
+    Ok(CoordinateMediationV2::Recipient(recipient))
+}
@@ -2,56 +2,80 @@
 // SPDX-License-Identifier: Apache-2.0
 use std::sync::Arc;
 
+use aries_vcx::protocols::problem_report::{descriptors, Problem, ProblemCode, ProblemScope};
 use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
-use log::info;
+use log::{info, warn};
 use messages::{
     decorators::attachment::{Attachment, AttachmentData, AttachmentType},
-    msg_fields::protocols::pickup::{
-        Delivery, DeliveryContent, DeliveryRequestContent, Pickup, Status, StatusContent,
-        StatusDecorators, StatusRequestContent,
+    msg_fields::protocols::{
+        pickup::{
+            Delivery, DeliveryContent, DeliveryRequestContent, Pickup, Status, StatusContent,
+            StatusDecorators, StatusRequestContent,
+        },
+        report_problem::{Impact, ProblemReport},
     },
 };
 use uuid::Uuid;
 
-use crate::persistence::MediatorPersistence;
+use crate::{
+    mediation::coordination::resolve_recipient_did_keys, persistence::MediatorPersistence,
+};
 
 pub async fn handle_pickup_authenticated<T: MediatorPersistence>(
     storage: Arc<T>,
     pickup_message: Pickup,
     auth_pubkey: &str,
-) -> Pickup {
+) -> Result<Pickup, ProblemReport> {
     match &pickup_message {
         Pickup::StatusRequest(status_request) => {
-            handle_pickup_status_req(&status_request.content, storage, auth_pubkey).await
+            handle_pickup_status_req(&status_request.content, storage, auth_pubkey)
+                .await
+                .map_err(|err| unresolvable_recipient_problem(err, &status_request.id))
         }
         // Why is client sending us status? That's server's job.
         Pickup::Status(_status) =>
         // StatusCode::BAD_REQUEST,
         {
-            handle_pickup_default_status(storage, auth_pubkey).await
+            Ok(handle_pickup_default_status(storage, auth_pubkey).await)
         }
 
         Pickup::DeliveryRequest(delivery_request) => {
-            handle_pickup_delivery_req(&delivery_request.content, storage, auth_pubkey).await
+            handle_pickup_delivery_req(&delivery_request.content, storage, auth_pubkey)
+                .await
+                .map_err(|err| unresolvable_recipient_problem(err, &delivery_request.id))
         }
         _ => {
             info!("Received {:#?}", &pickup_message);
             // StatusCode::NOT_IMPLEMENTED,
-            handle_pickup_default_status(storage, auth_pubkey).await
+            Ok(handle_pickup_default_status(storage, auth_pubkey).await)
         }
     }
 }
 
+fn unresolvable_recipient_problem(err: String, thread_id: &str) -> ProblemReport {
+    warn!("Unable to answer pickup request {thread_id}: {err}");
+    Problem::new(ProblemCode::error(
+        ProblemScope::Message,
+        descriptors::INVALID_MESSAGE,
+    ))
+    .impact(Impact::Message)
+    .explain(err)
+    .build(thread_id)
+}
+
 async fn handle_pickup_status_req<T: MediatorPersistence>(
     status_request: &StatusRequestContent,
     storage: Arc<T>,
     auth_pubkey: &str,
-) -> Pickup {
+) -> Result<Pickup, String> {
     info!("Received {:#?}", &status_request);
-    let message_count = storage
-        .retrieve_pending_message_count(auth_pubkey, status_request.recipient_key.as_ref())
-        .await
-        .unwrap();
+    let mut message_count = 0;
+    for recipient_key in recipient_key_filters(status_request.recipient_key.as_ref()).await? {
+        message_count += storage
+            .retrieve_pending_message_count(auth_pubkey, recipient_key.as_ref())
+            .await
+            .unwrap();
+    }
     let status_content = if let Some(recipient_key) = status_request.recipient_key.clone() {
         StatusContent::builder()
             .message_count(message_count)
@@ -69,23 +93,28 @@ async fn handle_pickup_status_req<T: MediatorPersistence>(
         .build();
 
     info!("Sending {:#?}", &status);
-    Pickup::Status(status)
+    Ok(Pickup::Status(status))
 }
 
 async fn handle_pickup_delivery_req<T: MediatorPersistence>(
     delivery_request: &DeliveryRequestContent,
     storage: Arc<T>,
     auth_pubkey: &str,
-) -> Pickup {
+) -> Result<Pickup, String> {
     info!("Received {:#?}", &delivery_request);
-    let messages = storage
-        .retrieve_pending_messages(
-            auth_pubkey,
-            delivery_request.limit,
-            delivery_request.recipient_key.as_ref(),
-        )
-        .await
-        .unwrap();
+    let mut messages = Vec::new();
+    for recipient_key in recipient_key_filters(delivery_request.recipient_key.as_ref()).await? {
+        let limit = delivery_request.limit.saturating_sub(messages.len() as u32);
+        if limit == 0 {
+            break;
+        }
+        messages.extend(
+            storage
+                .retrieve_pending_messages(auth_pubkey, limit, recipient_key.as_ref())
+                .await
+                .unwrap(),
+        );
+    }
     // for (message_id, message_content) in messages.into_iter() {
     //     info!("Message {:#?} {:#?}", message_id, String::from_utf8(message_content).unwrap())
     // }
@@ -105,7 +134,7 @@ async fn handle_pickup_delivery_req<T: MediatorPersistence>(
         })
         .collect();
     if !attach.is_empty() {
-        Pickup::Delivery(
+        Ok(Pickup::Delivery(
             Delivery::builder()
                 .content(DeliveryContent {
                     recipient_key: delivery_request.recipient_key.to_owned(),
@@ -113,12 +142,28 @@ async fn handle_pickup_delivery_req<T: MediatorPersistence>(
                 })
                 .id(Uuid::new_v4().to_string())
                 .build(),
-        )
+        ))
     } else {
         // send default status message instead
-        handle_pickup_default_status(storage, auth_pubkey).await
+        Ok(handle_pickup_default_status(storage, auth_pubkey).await)
+    }
+}
+/// Messages are queued under the key they were forwarded to, whereas pickup 3.0 filters by
+/// recipient DID. A DID filter stands for all keys of the DID.
+async fn recipient_key_filters(recipient: Option<&String>) -> Result<Vec<Option<String>>, String> {
+    match recipient {
+        Some(recipient_did) if recipient_did.starts_with("did:") => {
+            let keys = resolve_recipient_did_keys(recipient_did)
+                .await
+                .map_err(|err| {
+                    format!("Unable to resolve keys of recipient {recipient_did}: {err}")
+                })?;
+            Ok(keys.into_iter().map(Some).collect())
+        }
+        recipient_key => Ok(vec![recipient_key.cloned()]),
     }
 }
+
 // Returns global status message for user (not restricted to recipient key)
 // async fn handle_pickup_default<T: MediatorPersistence>(
 //     storage: Arc<T>,
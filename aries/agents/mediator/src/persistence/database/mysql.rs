@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::anyhow;
+use aries_vcx::did_doc::schema::did_doc::DidDocument;
 use async_trait::async_trait;
 use diddoc_legacy::aries::diddoc::AriesDidDoc;
 use futures::TryStreamExt;
@@ -16,15 +17,30 @@ use crate::{
     persistence::{
         errors::{
             AccountNotFound, AddRecipientError, CreateAccountError, DecodeError,
-            GetAccountDetailsError, GetAccountIdError, ListAccountsError, ListRecipientKeysError,
-            PersistForwardMessageError, RemoveMessagesError, RemoveRecipientError, RetrievePendingMessageCountError,
-            RetrievePendingMessagesError, StorageBackendError,
+            GetAccountDetailsError, GetAccountIdError, ListAccountsError, ListRecipientDidsError,
+            ListRecipientKeysError, PersistForwardMessageError, RemoveMessagesError,
+            RemoveRecipientError, RetrievePendingMessageCountError, RetrievePendingMessagesError,
+            StorageBackendError,
         },
         AccountDetails,
     },
-    utils::structs::VerKey,
+    utils::{diddoc::did_document_from_legacy, structs::VerKey},
 };
 
+const DID_PREFIX: &str = "did:";
+
+/// Accounts created before coordinate-mediation 2.0 support hold a legacy did doc,
+/// recognisable by its `publicKey` section. Those are converted on read.
+fn decode_stored_did_doc(did_doc_json: serde_json::Value) -> Result<DidDocument, DecodeError> {
+    if did_doc_json.get("publicKey").is_some() {
+        let legacy = serde_json::from_value::<AriesDidDoc>(did_doc_json)
+            .map_err(|e| DecodeError(e.into()))?;
+        did_document_from_legacy(&legacy).map_err(|e| DecodeError(e.into()))
+    } else {
+        serde_json::from_value::<DidDocument>(did_doc_json).map_err(|e| DecodeError(e.into()))
+    }
+}
+
 pub async fn get_db_pool() -> MySqlPool {
     let _ = dotenvy::dotenv();
     let database_url = std::env::var("MYSQL_URL")
@@ -143,8 +159,7 @@ impl MediatorPersistence for sqlx::MySqlPool {
             account_name,
             auth_pubkey,
             our_signing_key,
-            their_did_doc: serde_json::from_value::<AriesDidDoc>(did_doc_json)
-                .map_err(|e| DecodeError(e.into()))?,
+            their_did_doc: decode_stored_did_doc(did_doc_json)?,
         };
         Ok(account_details)
     }
@@ -177,26 +192,43 @@ impl MediatorPersistence for sqlx::MySqlPool {
     // }
     async fn persist_forward_message(
         &self,
-        recipient_key: &str,
+        recipient: &str,
         message_data: &str,
     ) -> Result<(), PersistForwardMessageError> {
-        // Fetch recipient with given recipient_key
-        info!("Fetching recipient with recipient_key {:#?}", recipient_key);
-        let recipient_row = sqlx::query("SELECT * FROM recipients WHERE recipient_key = ?")
-            .bind(recipient_key)
+        // Fetch recipient with given recipient key or recipient did
+        let recipient_row = if recipient.starts_with(DID_PREFIX) {
+            // DIDComm v2 forwards may address a specific key of the DID
+            let recipient_did = recipient.split('#').next().unwrap_or(recipient);
+            info!("Fetching recipient with recipient_did {:#?}", recipient_did);
+            sqlx::query(
+                "SELECT account_id, NULL AS recipient_key FROM recipient_dids
+                WHERE recipient_did_hash = UNHEX(SHA2(?, 256))",
+            )
+            .bind(recipient_did)
             .fetch_one(self)
-            .await;
-        if let Err(err) = recipient_row {
-            info!("Error while finding target recipient, {:#}", err);
-            let mapped_err = match err {
-                sqlx::Error::RowNotFound => PersistForwardMessageError::AccountNotFound(
-                    AccountNotFound(format!("reipient_key={}", recipient_key.to_owned())),
-                ),
-                _ => StorageBackendError { source: err.into() }.into(),
-            };
-            return Err(mapped_err);
-        }
-        let account_id: Vec<u8> = recipient_row.unwrap().get("account_id");
+            .await
+        } else {
+            info!("Fetching recipient with recipient_key {:#?}", recipient);
+            sqlx::query("SELECT account_id, recipient_key FROM recipients WHERE recipient_key = ?")
+                .bind(recipient)
+                .fetch_one(self)
+                .await
+        };
+        let recipient_row = match recipient_row {
+            Ok(row) => row,
+            Err(err) => {
+                info!("Error while finding target recipient, {:#}", err);
+                let mapped_err = match err {
+                    sqlx::Error::RowNotFound => PersistForwardMessageError::AccountNotFound(
+                        AccountNotFound(format!("recipient={}", recipient.to_owned())),
+                    ),
+                    _ => StorageBackendError { source: err.into() }.into(),
+                };
+                return Err(mapped_err);
+            }
+        };
+        let account_id: Vec<u8> = recipient_row.get("account_id");
+        let recipient_key: Option<String> = recipient_row.get("recipient_key");
         // Save message for recipient
         info!("Persisting message for account {:x?}", account_id);
         let insert_result = sqlx::query(
@@ -210,7 +242,7 @@ impl MediatorPersistence for sqlx::MySqlPool {
         if let Err(err) = insert_result {
             info!(
                 "Error while saving message for recipient {:x?}, {:#}",
-                recipient_key, err
+                recipient, err
             );
             return Err(PersistForwardMessageError::StorageBackendError(
                 StorageBackendError { source: err.into() },
@@ -442,4 +474,137 @@ impl MediatorPersistence for sqlx::MySqlPool {
                 .collect();
         Ok(recipient_keys)
     }
+    async fn add_recipient_did(
+        &self,
+        auth_pubkey: &str,
+        recipient_did: &str,
+        recipient_keys: &[VerKey],
+    ) -> Result<(), AddRecipientError> {
+        info!(
+            "Adding recipient_did to account with auth_pubkey {:#?}",
+            auth_pubkey
+        );
+        let account_id: Vec<u8> = self
+            .get_account_id(auth_pubkey)
+            .await
+            .map_err(|e| match e {
+                GetAccountIdError::AccountNotFound(anf) => anf.into(),
+                GetAccountIdError::StorageBackendError(s) => s.into(),
+                GetAccountIdError::ZFhOt01Rdb0Error(anye) => AddRecipientError::ZFhOt01Rdb0Error(
+                    anye.context(format!("Couldn't get account id of pubkey {auth_pubkey}")),
+                ),
+            })?;
+        info!(
+            "Found matching account {:x?}. Proceeding with attempt to add recipient_did {:#?} \
+             with keys {:#?}",
+            account_id, recipient_did, recipient_keys
+        );
+        let mut tx = self
+            .begin()
+            .await
+            .map_err(|e| StorageBackendError { source: e.into() })?;
+        sqlx::query("INSERT INTO recipient_dids (account_id, recipient_did) VALUES (?, ?);")
+            .bind(&account_id)
+            .bind(recipient_did)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                anyhow!(e).context("Error while inserting recipient_did entry into the database")
+            })?;
+        for recipient_key in recipient_keys {
+            sqlx::query(
+                "INSERT INTO recipients (account_id, recipient_key, recipient_did_hash)
+                VALUES (?, ?, UNHEX(SHA2(?, 256)));",
+            )
+            .bind(&account_id)
+            .bind(recipient_key)
+            .bind(recipient_did)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| {
+                anyhow!(e).context("Error while inserting recipient_did key into the database")
+            })?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| StorageBackendError { source: e.into() })?;
+        Ok(())
+    }
+    async fn remove_recipient_did(
+        &self,
+        auth_pubkey: &str,
+        recipient_did: &str,
+    ) -> Result<(), RemoveRecipientError> {
+        info!(
+            "Removing recipient_did from account with auth_pubkey {:#?}",
+            auth_pubkey
+        );
+        let account_id: Vec<u8> = self
+            .get_account_id(auth_pubkey)
+            .await
+            .map_err(|e| match e {
+                GetAccountIdError::AccountNotFound(anf) => anf.into(),
+                GetAccountIdError::StorageBackendError(s) => s.into(),
+                GetAccountIdError::ZFhOt01Rdb0Error(anye) => {
+                    RemoveRecipientError::ZFhOt01Rdb0Error(
+                        anye.context(format!("Couldn't get account id of pubkey {auth_pubkey}")),
+                    )
+                }
+            })?;
+        // Keys registered alongside the DID are removed by the ON DELETE CASCADE
+        sqlx::query(
+            "DELETE FROM recipient_dids
+            WHERE (account_id = ?) AND (recipient_did_hash = UNHEX(SHA2(?, 256)));",
+        )
+        .bind(&account_id)
+        .bind(recipient_did)
+        .execute(self)
+        .await
+        .map_err(|e| anyhow!(e).context("Error while deleting recipient_did from the database"))?;
+        Ok(())
+    }
+    async fn list_recipient_dids(
+        &self,
+        auth_pubkey: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<String>, u64), ListRecipientDidsError> {
+        info!(
+            "Retrieving recipient_dids for account with auth_pubkey {:#?}",
+            auth_pubkey
+        );
+        let account_id: Vec<u8> = self
+            .get_account_id(auth_pubkey)
+            .await
+            .map_err(|e| match e {
+                GetAccountIdError::AccountNotFound(anf) => anf.into(),
+                GetAccountIdError::StorageBackendError(s) => s.into(),
+                GetAccountIdError::ZFhOt01Rdb0Error(anye) => {
+                    ListRecipientDidsError::ZFhOt01Rdb0Error(
+                        anye.context(format!("Couldn't get account id of pubkey {auth_pubkey}")),
+                    )
+                }
+            })?;
+        // MySQL COUNT(*) is a BIGINT, decoded as i64
+        let total: i64 = sqlx::query("SELECT COUNT(*) FROM recipient_dids WHERE account_id = ?;")
+            .bind(&account_id)
+            .fetch_one(self)
+            .await
+            .map_err(|e| anyhow!(e).context("Error while counting recipient_dids"))?
+            .get::<i64, &str>("COUNT(*)");
+        let recipient_dids: Vec<String> = sqlx::query(
+            "SELECT recipient_did FROM recipient_dids WHERE account_id = ?
+            ORDER BY seq_num LIMIT ? OFFSET ?;",
+        )
+        .bind(&account_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self)
+        .await
+        .map_err(|e| anyhow!(e).context("Error while fetching recipient_dids from database"))?
+        .into_iter()
+        .map(|row| row.get("recipient_did"))
+        .collect();
+        Ok((recipient_dids, total.try_into().map_err(|e| anyhow!(e))?))
+    }
 }
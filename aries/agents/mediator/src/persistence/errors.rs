@@ -56,6 +56,7 @@ error_compose!(AddRecipientError[StorageBackendError, AccountNotFound]);
 // Expected to fail similarly
 pub type RemoveRecipientError = AddRecipientError;
 error_compose!(ListRecipientKeysError[StorageBackendError, AccountNotFound]);
+pub type ListRecipientDidsError = ListRecipientKeysError;
 
 error_compose!(PersistForwardMessageError[StorageBackendError, AccountNotFound]);
 error_compose!(RetrievePendingMessageCountError[StorageBackendError, AccountNotFound]);
@@ -3,15 +3,16 @@
 
 pub mod database;
 pub mod errors;
+use aries_vcx::did_doc::schema::did_doc::DidDocument;
 use async_trait::async_trait;
 /// Database backend is used for default implementation of MediatorPersistence trait
 pub use database::get_db_pool as get_persistence;
-use diddoc_legacy::aries::diddoc::AriesDidDoc;
 
 use self::errors::{
     AddRecipientError, CreateAccountError, GetAccountDetailsError, GetAccountIdError,
-    ListAccountsError, ListRecipientKeysError, PersistForwardMessageError, RemoveMessagesError,
-    RemoveRecipientError, RetrievePendingMessageCountError, RetrievePendingMessagesError,
+    ListAccountsError, ListRecipientDidsError, ListRecipientKeysError, PersistForwardMessageError,
+    RemoveMessagesError, RemoveRecipientError, RetrievePendingMessageCountError,
+    RetrievePendingMessagesError,
 };
 use crate::utils::structs::VerKey;
 
@@ -39,9 +40,32 @@ pub trait MediatorPersistence: Send + Sync + 'static {
         &self,
         auth_pubkey: &str,
     ) -> Result<Vec<String>, ListRecipientKeysError>;
+    /// Registers a recipient DID (coordinate-mediation 2.0) along with the verkeys resolved
+    /// from it, so that forwards addressed to either are accepted.
+    async fn add_recipient_did(
+        &self,
+        auth_pubkey: &str,
+        recipient_did: &str,
+        recipient_keys: &[VerKey],
+    ) -> Result<(), AddRecipientError>;
+    /// Removes a recipient DID together with the verkeys registered alongside it.
+    async fn remove_recipient_did(
+        &self,
+        auth_pubkey: &str,
+        recipient_did: &str,
+    ) -> Result<(), RemoveRecipientError>;
+    /// Returns page of recipient DIDs starting at `offset`, plus the total number registered.
+    async fn list_recipient_dids(
+        &self,
+        auth_pubkey: &str,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<String>, u64), ListRecipientDidsError>;
+    /// `recipient` is either a registered verkey or a registered recipient DID (optionally
+    /// with a key fragment, as DIDComm v2 forwards address them).
     async fn persist_forward_message(
         &self,
-        recipient_key: &str,
+        recipient: &str,
         message_data: &str,
     ) -> Result<(), PersistForwardMessageError>;
     async fn retrieve_pending_message_count(
@@ -75,5 +99,5 @@ pub struct AccountDetails {
     pub account_name: String,
     pub auth_pubkey: VerKey,
     pub our_signing_key: VerKey,
-    pub their_did_doc: AriesDidDoc,
+    pub their_did_doc: DidDocument,
 }
@@ -0,0 +1,146 @@
+use aries_vcx::{
+    did_doc::schema::{
+        did_doc::DidDocument,
+        service::{service_key_kind::ServiceKeyKind, typed::didcommv1::ServiceDidCommV1, Service},
+        types::uri::Uri,
+        verification_method::{PublicKeyField, VerificationMethod, VerificationMethodType},
+    },
+    did_parser_nom::{Did, DidUrl},
+    utils::didcomm_utils::{
+        get_ed25519_recipient_keys, get_ed25519_routing_keys, resolve_ed25519_key_agreement,
+    },
+};
+use did_key::DidKey;
+use diddoc_legacy::aries::diddoc::AriesDidDoc;
+use public_key::{Key, KeyType};
+
+use super::prelude::*;
+
+const DID_KEY_PREFIX: &str = "did:key:";
+
+/// Parses a verkey in either of the forms peers send them to us: raw base58 or `did:key`.
+pub fn parse_verkey(verkey: &str) -> Result<Key, String> {
+    match verkey.strip_prefix(DID_KEY_PREFIX) {
+        Some(fingerprint) => Key::from_fingerprint(fingerprint),
+        None => Key::from_base58(verkey, KeyType::Ed25519),
+    }
+    .map_err(string_from_std_error)
+}
+
+fn to_did_key(key: Key) -> Result<DidKey, String> {
+    DidKey::try_from(key).map_err(string_from_std_error)
+}
+
+/// Converts a legacy did doc, as exchanged over the connection protocol, into a
+/// [`DidDocument`] carrying an equivalent DIDComm V1 service.
+pub fn did_document_from_legacy(legacy: &AriesDidDoc) -> Result<DidDocument, String> {
+    let recipient_keys = legacy
+        .recipient_keys()
+        .map_err(string_from_std_error)?
+        .iter()
+        .map(|key| parse_verkey(key).and_then(to_did_key))
+        .collect::<Result<Vec<_>, _>>()?;
+    let routing_keys = legacy
+        .routing_keys()
+        .iter()
+        .map(|key| parse_verkey(key).and_then(to_did_key))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Legacy docs frequently carry an unqualified or empty id, in which case the
+    // first recipient key is the best identity we have for the peer.
+    let did = match Did::parse(legacy.id.clone()) {
+        Ok(did) => did,
+        Err(_) => recipient_keys
+            .first()
+            .map(|did_key| did_key.did().clone())
+            .ok_or("Legacy did doc has neither a valid id nor recipient keys")?,
+    };
+    let mut did_doc = DidDocument::new(did);
+    if let Some(service_endpoint) = legacy.get_endpoint() {
+        let service: Service = ServiceDidCommV1::new(
+            Uri::new("#0").map_err(string_from_std_error)?,
+            service_endpoint,
+            0,
+            recipient_keys
+                .into_iter()
+                .map(ServiceKeyKind::DidKey)
+                .collect(),
+            routing_keys
+                .into_iter()
+                .map(ServiceKeyKind::DidKey)
+                .collect(),
+        )
+        .try_into()
+        .map_err(string_from_std_error)?;
+        did_doc.add_service(service);
+    }
+    Ok(did_doc)
+}
+
+/// Builds a `did:key` document for a peer we only know by its verkey, such as a DIDComm
+/// wallet requesting mediation without establishing a connection first. Responses to such
+/// peers are returned on the same HTTP exchange, so no service is needed.
+pub fn did_document_from_verkey(verkey: &str) -> Result<DidDocument, String> {
+    let key = parse_verkey(verkey)?;
+    let did_key = to_did_key(key.clone())?;
+    let did = did_key.did().clone();
+    let vm_id =
+        DidUrl::parse(format!("{}#{}", did, key.fingerprint())).map_err(string_from_std_error)?;
+    let vm = VerificationMethod::builder()
+        .id(vm_id)
+        .controller(did.clone())
+        .verification_method_type(VerificationMethodType::Ed25519VerificationKey2020)
+        .public_key(PublicKeyField::Multibase {
+            public_key_multibase: key.fingerprint(),
+        })
+        .build();
+    let mut did_doc = DidDocument::new(did);
+    did_doc.add_key_agreement_object(vm);
+    Ok(did_doc)
+}
+
+/// Resolves the recipient key and routing keys for packing a DIDComm V1 message to the owner
+/// of `did_doc`. The first service with ed25519 recipient keys wins; documents without one
+/// fall back to their key agreement key.
+pub fn resolve_packing_keys(did_doc: &DidDocument) -> Result<(Key, Vec<Key>), String> {
+    for service in did_doc.service() {
+        let recipient_keys =
+            get_ed25519_recipient_keys(did_doc, service.id()).map_err(string_from_std_error)?;
+        if let Some(recipient_key) = recipient_keys.into_iter().next() {
+            let routing_keys =
+                get_ed25519_routing_keys(did_doc, service.id()).map_err(string_from_std_error)?;
+            return Ok((recipient_key, routing_keys));
+        }
+    }
+    let recipient_key = resolve_ed25519_key_agreement(did_doc).map_err(string_from_std_error)?;
+    Ok((recipient_key, vec![]))
+}
+
+#[cfg(test)]
+mod tests {
+    use diddoc_legacy::aries::diddoc::test_utils::{
+        _did_doc_inlined_recipient_keys, _recipient_keys, _routing_keys,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_legacy_did_doc_keys_survive_conversion() {
+        let legacy = _did_doc_inlined_recipient_keys();
+        let did_doc = did_document_from_legacy(&legacy).unwrap();
+        let (recipient_key, routing_keys) = resolve_packing_keys(&did_doc).unwrap();
+        assert_eq!(recipient_key.base58(), _recipient_keys()[0]);
+        assert_eq!(
+            routing_keys.iter().map(Key::base58).collect::<Vec<_>>(),
+            _routing_keys()
+        );
+    }
+
+    #[test]
+    fn test_verkey_did_document_packs_to_verkey() {
+        let verkey = _recipient_keys()[0].clone();
+        let did_doc = did_document_from_verkey(&verkey).unwrap();
+        let (recipient_key, routing_keys) = resolve_packing_keys(&did_doc).unwrap();
+        assert_eq!(recipient_key.base58(), verkey);
+        assert!(routing_keys.is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 pub use prelude::*;
 
 pub mod binary_utils;
+pub mod diddoc;
 pub mod prelude;
 pub mod structs;
 ///// Utility function for mapping any error into a `500 Internal Server Error`
@@ -0,0 +1,351 @@
+mod common;
+
+use aries_vcx::{
+    did_doc::schema::did_doc::DidDocument,
+    did_parser_nom::Did,
+    did_peer::resolver::PeerDidResolver,
+    protocols::{
+        did_exchange::state_machine::helpers::create_peer_did_4,
+        oob::oob_invitation_to_legacy_did_doc,
+    },
+    utils::encryption_envelope_v2::EncryptionEnvelopeV2,
+};
+use aries_vcx_wallet::wallet::{
+    askar::AskarWallet,
+    base_wallet::{did_wallet::DidWallet, BaseWallet},
+};
+use did_resolver_registry::ResolverRegistry;
+use messages::{
+    didcomm_v2::DidCommV2Message,
+    msg_fields::protocols::{
+        coordinate_mediation::{
+            keylist_update::KeylistUpdateItemAction,
+            keylist_update_response::KeylistUpdateItemResult,
+            v2::{
+                CoordinateMediationV2, MediateRequestV2, MediateRequestV2Content, RecipientQuery,
+                RecipientQueryContent, RecipientUpdate, RecipientUpdateContent,
+                RecipientUpdateItem,
+            },
+        },
+        out_of_band::invitation::Invitation as OOBInvitation,
+        pickup::{Pickup, StatusRequest, StatusRequestContent},
+    },
+    AriesMessage,
+};
+use test_utils::mockdata::mock_ledger::MockLedger;
+
+use crate::common::{
+    agent_and_transport_utils::{
+        gen_mediator_connected_agent, send_message_and_pop_response_message,
+    },
+    prelude::*,
+    test_setup::setup_env_logging,
+};
+
+static LOGGING_INIT: std::sync::Once = std::sync::Once::new();
+
+const ENDPOINT_ROOT: &str = "http://localhost:8005";
+
+fn mediate_request_v2() -> AriesMessage {
+    AriesMessage::CoordinateMediationV2(CoordinateMediationV2::MediateRequest(
+        MediateRequestV2::builder()
+            .content(MediateRequestV2Content::default())
+            .id("mediate-request-v2-test".to_owned())
+            .build(),
+    ))
+}
+
+fn peer_did_resolver_registry() -> ResolverRegistry {
+    ResolverRegistry::new()
+        .register_resolver::<PeerDidResolver>("peer".into(), PeerDidResolver::new())
+}
+
+async fn resolve_peer_did(did: &str) -> Result<DidDocument> {
+    let did_doc = peer_did_resolver_registry()
+        .resolve(&Did::parse(did.to_owned())?, &Default::default())
+        .await
+        .map_err(|e| anyhow::anyhow!(e.to_string()))?
+        .did_document;
+    Ok(did_doc)
+}
+
+/// Sends message in a DIDComm v2 envelope authcrypted from `our_did_doc` to the routing did of
+/// the mediator and returns the unpacked response.
+async fn send_didcomm_v2_and_pop_response_message(
+    wallet: &impl BaseWallet,
+    message: AriesMessage,
+    our_did_doc: &DidDocument,
+    routing_did_doc: &DidDocument,
+) -> Result<AriesMessage> {
+    let mut message = DidCommV2Message::try_from(message)?;
+    message.from = Some(our_did_doc.id().to_string());
+    message.to = vec![routing_did_doc.id().to_string()];
+    let EncryptionEnvelopeV2(packed_message) = EncryptionEnvelopeV2::authcrypt(
+        wallet,
+        &serde_json::to_vec(&message)?,
+        our_did_doc,
+        routing_did_doc,
+    )
+    .await?;
+    let packed_json: serde_json::Value = serde_json::from_slice(&packed_message)?;
+    let response_envelope: serde_json::Value = reqwest::Client::new()
+        .post(ENDPOINT_ROOT.parse::<Url>()?.join("didcomm")?)
+        .json(&packed_json)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let unpacked = EncryptionEnvelopeV2::unpack(
+        wallet,
+        &serde_json::to_vec(&response_envelope)?,
+        our_did_doc,
+        &peer_did_resolver_registry(),
+    )
+    .await?;
+    let response: DidCommV2Message = serde_json::from_str(unpacked.message())?;
+    Ok(AriesMessage::try_from(response)?)
+}
+
+#[tokio::test]
+async fn test_mediate_grant_v2_without_connection() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    // a DIDComm wallet which knows nothing but the mediator's invitation
+    let agent = mediator::aries_agent::AgentBuilder::<AskarWallet>::new_demo_agent().await?;
+    let mut aries_transport = reqwest::Client::new();
+    let oobi: OOBInvitation = reqwest::Client::new()
+        .get(ENDPOINT_ROOT.parse::<Url>()?.join("invitation")?)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let mediator_diddoc = oob_invitation_to_legacy_did_doc(&MockLedger {}, &oobi).await?;
+    let our_verkey = agent
+        .get_wallet_ref()
+        .create_and_store_my_did(None, None)
+        .await?
+        .verkey()
+        .base58();
+    // send message and get response
+    let message_bytes = serde_json::to_vec(&mediate_request_v2())?;
+    let response_message = send_message_and_pop_response_message(
+        &message_bytes,
+        &agent,
+        &mut aries_transport,
+        &our_verkey,
+        &mediator_diddoc,
+    )
+    .await?;
+    // verify response
+    if let AriesMessage::CoordinateMediationV2(CoordinateMediationV2::MediateGrant(grant)) =
+        serde_json::from_str(&response_message)?
+    {
+        info!("Grant Data {:?}", grant);
+        assert!(grant.content.routing_did[0].starts_with("did:peer:4"));
+    } else {
+        panic!(
+            "Should get response that is of type Mediator Grant. Found {:?}",
+            response_message
+        )
+    };
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recipient_update_and_query_v2() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    // prepare connection parameters
+    let (agent, mut aries_transport, our_verkey, their_diddoc) =
+        gen_mediator_connected_agent().await?;
+    let message_bytes = serde_json::to_vec(&mediate_request_v2())?;
+    let _ = send_message_and_pop_response_message(
+        &message_bytes,
+        &agent,
+        &mut aries_transport,
+        &our_verkey,
+        &their_diddoc,
+    )
+    .await?;
+    // prepare request message: add recipient did
+    let (peer_did, _key) = create_peer_did_4(
+        agent.get_wallet_ref().as_ref(),
+        "http://localhost:9000/didcomm".parse()?,
+        vec![],
+    )
+    .await?;
+    let recipient_did = peer_did.long_form()?.to_string();
+    let recipient_update = RecipientUpdate::builder()
+        .content(RecipientUpdateContent {
+            updates: vec![RecipientUpdateItem {
+                recipient_did: recipient_did.clone(),
+                action: KeylistUpdateItemAction::Add,
+            }],
+        })
+        .id("recipient-add".to_owned())
+        .build();
+    let message = AriesMessage::CoordinateMediationV2(CoordinateMediationV2::RecipientUpdate(
+        recipient_update,
+    ));
+    info!("Sending {:?}", serde_json::to_string(&message).unwrap());
+    let message_bytes = serde_json::to_vec(&message)?;
+    let response_message = send_message_and_pop_response_message(
+        &message_bytes,
+        &agent,
+        &mut aries_transport,
+        &our_verkey,
+        &their_diddoc,
+    )
+    .await?;
+    if let AriesMessage::CoordinateMediationV2(CoordinateMediationV2::RecipientUpdateResponse(
+        update_response,
+    )) = serde_json::from_str(&response_message)?
+    {
+        info!("Received update response {:?}", update_response);
+        assert_eq!(
+            update_response.content.updated[0].result,
+            KeylistUpdateItemResult::Success
+        );
+    } else {
+        panic!(
+            "Expected message of type RecipientUpdateResponse. Found {:?}",
+            response_message
+        )
+    }
+    info!("Proceeding to recipient query");
+    let recipient_query = RecipientQuery::builder()
+        .content(RecipientQueryContent::default())
+        .id("recipient-query".to_owned())
+        .build();
+    let message =
+        AriesMessage::CoordinateMediationV2(CoordinateMediationV2::RecipientQuery(recipient_query));
+    let message_bytes = serde_json::to_vec(&message)?;
+    let response_message = send_message_and_pop_response_message(
+        &message_bytes,
+        &agent,
+        &mut aries_transport,
+        &our_verkey,
+        &their_diddoc,
+    )
+    .await?;
+    if let AriesMessage::CoordinateMediationV2(CoordinateMediationV2::Recipient(recipient)) =
+        serde_json::from_str(&response_message)?
+    {
+        info!("Recipients mediator sent {:?}", recipient.content);
+        assert!(recipient
+            .content
+            .dids
+            .iter()
+            .any(|item| item.recipient_did == recipient_did));
+    } else {
+        panic!(
+            "Expected message of type Recipient. Found {:?}",
+            response_message
+        )
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_recipient_update_rejects_unsupported_did_v2() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    let (agent, mut aries_transport, our_verkey, their_diddoc) =
+        gen_mediator_connected_agent().await?;
+    let recipient_update = RecipientUpdate::builder()
+        .content(RecipientUpdateContent {
+            updates: vec![RecipientUpdateItem {
+                recipient_did: "did:sov:VsKV7grR1BUE29mG2Fm2kX".to_owned(),
+                action: KeylistUpdateItemAction::Add,
+            }],
+        })
+        .id("recipient-add-unsupported".to_owned())
+        .build();
+    let message = AriesMessage::CoordinateMediationV2(CoordinateMediationV2::RecipientUpdate(
+        recipient_update,
+    ));
+    let message_bytes = serde_json::to_vec(&message)?;
+    let response_message = send_message_and_pop_response_message(
+        &message_bytes,
+        &agent,
+        &mut aries_transport,
+        &our_verkey,
+        &their_diddoc,
+    )
+    .await?;
+    if let AriesMessage::CoordinateMediationV2(CoordinateMediationV2::RecipientUpdateResponse(
+        update_response,
+    )) = serde_json::from_str(&response_message)?
+    {
+        assert_eq!(
+            update_response.content.updated[0].result,
+            KeylistUpdateItemResult::ClientError
+        );
+    } else {
+        panic!(
+            "Expected message of type RecipientUpdateResponse. Found {:?}",
+            response_message
+        )
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mediate_request_and_pickup_over_didcomm_v2() -> Result<()> {
+    LOGGING_INIT.call_once(setup_env_logging);
+    // a DIDComm v2 wallet which knows the mediator's routing did and a peer did of its own
+    let (agent, mut aries_transport, our_verkey, their_diddoc) =
+        gen_mediator_connected_agent().await?;
+    let message_bytes = serde_json::to_vec(&mediate_request_v2())?;
+    let response_message = send_message_and_pop_response_message(
+        &message_bytes,
+        &agent,
+        &mut aries_transport,
+        &our_verkey,
+        &their_diddoc,
+    )
+    .await?;
+    let AriesMessage::CoordinateMediationV2(CoordinateMediationV2::MediateGrant(grant)) =
+        serde_json::from_str(&response_message)?
+    else {
+        panic!("Expected message of type MediateGrant. Found {response_message:?}")
+    };
+    let routing_did_doc = resolve_peer_did(&grant.content.routing_did[0]).await?;
+    let (peer_did, _key) = create_peer_did_4(
+        agent.get_wallet_ref().as_ref(),
+        "http://localhost:9000/didcomm".parse()?,
+        vec![],
+    )
+    .await?;
+    let our_did_doc = resolve_peer_did(&peer_did.long_form()?.to_string()).await?;
+    // mediate request over DIDComm v2
+    let response = send_didcomm_v2_and_pop_response_message(
+        agent.get_wallet_ref().as_ref(),
+        mediate_request_v2(),
+        &our_did_doc,
+        &routing_did_doc,
+    )
+    .await?;
+    let AriesMessage::CoordinateMediationV2(CoordinateMediationV2::MediateGrant(grant)) = response
+    else {
+        panic!("Expected message of type MediateGrant. Found {response:?}")
+    };
+    assert!(grant.content.routing_did[0].starts_with("did:peer:4"));
+    // pickup 3.0 over DIDComm v2, from the account the mediate request created
+    let status_request = StatusRequest::builder()
+        .content(StatusRequestContent::builder().build())
+        .id("status-request-v2-test".to_owned())
+        .build();
+    let response = send_didcomm_v2_and_pop_response_message(
+        agent.get_wallet_ref().as_ref(),
+        AriesMessage::Pickup(Pickup::StatusRequest(status_request)),
+        &our_did_doc,
+        &routing_did_doc,
+    )
+    .await?;
+    let AriesMessage::Pickup(Pickup::Status(status)) = response else {
+        panic!("Expected message of type Status. Found {response:?}")
+    };
+    assert_eq!(status.content.message_count, 0);
+    Ok(())
+}
@@ -0,0 +1,462 @@
+//! Signing and integrity checks of attachments, in alignment with Aries RFC 0017:
+//! https://hyperledger.github.io/aries-rfcs/latest/concepts/0017-attachments/#signing-attachments.
+
+use std::collections::HashMap;
+
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use base64::Engine;
+use did_doc::schema::did_doc::DidDocument;
+use did_key::DidKey;
+use did_parser_nom::{Did, DidUrl};
+use did_resolver_registry::ResolverRegistry;
+use messages::decorators::attachment::{Attachment, AttachmentType};
+use public_key::{Key, KeyType};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use shared::http_client::HttpClient;
+
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    utils::base64::URL_SAFE_LENIENT,
+};
+
+/// Creates a detached JWS signature of the attachment with the provided verkey, identified by
+/// its `did:key` in the `kid` header. The created JWS signature is appended to the attachment.
+pub async fn jws_sign_attach(
+    mut attach: Attachment,
+    verkey: Key,
+    wallet: &impl BaseWallet,
+) -> VcxResult<Attachment> {
+    let AttachmentType::Base64(attach_base64) = &attach.data.content else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Cannot sign non-base64-encoded attachment",
+        ));
+    };
+    if verkey.key_type() != &KeyType::Ed25519 {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidVerkey,
+            "Only JWS signatures with Ed25519 based keys are currently supported.",
+        ));
+    }
+
+    let did_key: DidKey = verkey.clone().try_into()?;
+    let verkey_b64 = URL_SAFE_LENIENT.encode(verkey.key());
+
+    let protected_header = json!({
+        "alg": "EdDSA",
+        "jwk": {
+            "kty": "OKP",
+            "kid": did_key.to_string(),
+            "crv": "Ed25519",
+            "x": verkey_b64
+        }
+    });
+    let unprotected_header = json!({
+        "kid": did_key.to_string(),
+    });
+    let b64_protected = URL_SAFE_LENIENT.encode(protected_header.to_string());
+    let sign_input = format!("{}.{}", b64_protected, attach_base64).into_bytes();
+    let signed: Vec<u8> = wallet.sign(&verkey, &sign_input).await?;
+    let signature_base64 = URL_SAFE_LENIENT.encode(signed);
+
+    let jws = {
+        let mut jws = HashMap::new();
+        jws.insert("header".to_string(), unprotected_header);
+        jws.insert("protected".to_string(), Value::String(b64_protected));
+        jws.insert("signature".to_string(), Value::String(signature_base64));
+        jws
+    };
+    attach.data.jws = Some(jws);
+    Ok(attach)
+}
+
+/// Signs the attachment with the key of a verification method of our DID document. The key
+/// must be held by the wallet.
+pub async fn jws_sign_attach_with_verification_method(
+    attach: Attachment,
+    did_document: &DidDocument,
+    verification_method_id: &DidUrl,
+    wallet: &impl BaseWallet,
+) -> VcxResult<Attachment> {
+    let verkey = did_document
+        .dereference_key(verification_method_id)
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!(
+                    "Verification method {} not found in DID document {}",
+                    verification_method_id,
+                    did_document.id()
+                ),
+            )
+        })?
+        .public_key()?;
+    jws_sign_attach(attach, verkey, wallet).await
+}
+
+/// Verifies that the given has a JWS signature attached, which is a valid signature given
+/// the expected signer key.
+// NOTE: Does not handle attachments with multiple signatures.
+// NOTE: this is the specific use case where the signer is known by the function caller. Therefore
+// we do not need to attempt to decode key within the protected nor unprotected header.
+pub async fn jws_verify_attachment(
+    attach: &Attachment,
+    expected_signer: &Key,
+    wallet: &impl BaseWallet,
+) -> VcxResult<bool> {
+    let (sign_input, signature) = jws_sign_input_and_signature(attach)?;
+    let res = wallet
+        .verify(expected_signer, &sign_input, &signature)
+        .await?;
+
+    Ok(res)
+}
+
+/// Verifies the JWS signature of the attachment against the key its `kid` refers to, and
+/// returns that key. A `did:key` kid carries the key itself, while the DID documents of other
+/// DIDs are resolved through the `resolver_registry`. A kid without a fragment matches any
+/// verification method of the DID document.
+pub async fn jws_resolve_and_verify_attachment(
+    attach: &Attachment,
+    resolver_registry: &ResolverRegistry,
+    wallet: &impl BaseWallet,
+) -> VcxResult<Key> {
+    let (sign_input, signature) = jws_sign_input_and_signature(attach)?;
+    let kid = jws_kid(attach)?;
+
+    let candidates = match DidKey::parse(kid.split('#').next().unwrap_or_default()) {
+        Ok(did_key) => vec![did_key.key().clone()],
+        Err(_) => {
+            let kid = DidUrl::parse(kid.to_owned())?;
+            let did_document = resolver_registry
+                .resolve(&Did::try_from(&kid)?, &Default::default())
+                .await?
+                .did_document;
+            match kid.fragment() {
+                Some(_) => did_document
+                    .dereference_key(&kid)
+                    .map(|vm| vm.public_key())
+                    .transpose()?
+                    .into_iter()
+                    .collect(),
+                None => did_document
+                    .verification_method()
+                    .iter()
+                    .filter_map(|vm| vm.public_key().ok())
+                    .collect(),
+            }
+        }
+    };
+
+    for candidate in candidates
+        .into_iter()
+        .filter(|key| key.key_type() == &KeyType::Ed25519)
+    {
+        if wallet.verify(&candidate, &sign_input, &signature).await? {
+            return Ok(candidate);
+        }
+    }
+    Err(AriesVcxError::from_msg(
+        AriesVcxErrorKind::InvalidInput,
+        format!("Attachment JWS signature is not valid for signer {}", kid),
+    ))
+}
+
+fn jws_kid(attach: &Attachment) -> VcxResult<&str> {
+    let jws = attach.data.jws.as_ref().ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Attachment has no JWS signature attached. Cannot verify.",
+        )
+    })?;
+    jws.get("header")
+        .and_then(|header| header.get("kid"))
+        .and_then(Value::as_str)
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                "Attachment JWS does not identify its signer with a kid",
+            )
+        })
+}
+
+fn jws_sign_input_and_signature(attach: &Attachment) -> VcxResult<(Vec<u8>, Vec<u8>)> {
+    let AttachmentType::Base64(attach_base64) = &attach.data.content else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Cannot verify JWS of a non-base64-encoded attachment",
+        ));
+    };
+    // aries attachments do not REQUIRE that the attachment has no padding,
+    // but JWS does, so remove it; just incase.
+    let attach_base64 = attach_base64.replace('=', "");
+
+    let Some(ref jws) = attach.data.jws else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Attachment has no JWS signature attached. Cannot verify.",
+        ));
+    };
+
+    let (Some(b64_protected), Some(b64_signature)) = (
+        jws.get("protected").and_then(|s| s.as_str()),
+        jws.get("signature").and_then(|s| s.as_str()),
+    ) else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Attachment has an invalid JWS with missing fields. Cannot verify.",
+        ));
+    };
+
+    let sign_input = format!("{}.{}", b64_protected, attach_base64).into_bytes();
+    let signature = URL_SAFE_LENIENT.decode(b64_signature).map_err(|_| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::EncodeError,
+            "Attachment JWS signature was not correctly base64Url encoded.",
+        )
+    })?;
+
+    Ok((sign_input, signature))
+}
+
+/// Hex encoded SHA-256 digest, as used for the `sha256` field of attachment data.
+pub fn sha256_digest(content: &[u8]) -> String {
+    Sha256::digest(content)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// The inlined content of the attachment, or [`None`] for attachments given by links.
+fn inline_content(attach: &Attachment) -> VcxResult<Option<Vec<u8>>> {
+    match &attach.data.content {
+        AttachmentType::Base64(content) => Ok(Some(URL_SAFE_LENIENT.decode(content)?)),
+        AttachmentType::Json(content) => Ok(Some(serde_json::to_vec(content)?)),
+        AttachmentType::Links(_) => Ok(None),
+    }
+}
+
+/// Sets the `sha256` digest of an attachment with inlined content.
+pub fn set_attachment_digest(mut attach: Attachment) -> VcxResult<Attachment> {
+    let content = inline_content(&attach)?.ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Cannot compute the digest of an attachment given by links",
+        )
+    })?;
+    attach.data.sha256 = Some(sha256_digest(&content));
+    Ok(attach)
+}
+
+fn check_digest(expected: Option<&String>, content: &[u8]) -> VcxResult<()> {
+    match expected {
+        Some(expected) if !expected.eq_ignore_ascii_case(&sha256_digest(content)) => {
+            Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                "Attachment content does not match its sha256 digest",
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks the inlined content of the attachment against its `sha256` digest, if it has one.
+/// Use [`fetch_attachment_links`] for attachments given by links.
+pub fn verify_attachment_digest(attach: &Attachment) -> VcxResult<()> {
+    let content = inline_content(attach)?.ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Content of an attachment given by links must be fetched to be verified",
+        )
+    })?;
+    check_digest(attach.data.sha256.as_ref(), &content)
+}
+
+/// Fetches the content of an attachment given by links, trying each link in turn, and checks
+/// it against the `sha256` digest of the attachment if it has one.
+pub async fn fetch_attachment_links(
+    attach: &Attachment,
+    client: &HttpClient,
+) -> VcxResult<Vec<u8>> {
+    let AttachmentType::Links(links) = &attach.data.content else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Attachment content is inlined, not given by links",
+        ));
+    };
+
+    let mut last_error = AriesVcxError::from_msg(
+        AriesVcxErrorKind::InvalidInput,
+        "Attachment has no links to fetch its content from",
+    );
+    for link in links {
+        match client.get(link).await {
+            Ok(content) => {
+                check_digest(attach.data.sha256.as_ref(), &content)?;
+                return Ok(content);
+            }
+            Err(err) => {
+                warn!("Fetching attachment content from {} failed: {}", link, err);
+                last_error = err.into();
+            }
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use aries_vcx_wallet::wallet::base_wallet::did_wallet::DidWallet;
+    use messages::decorators::attachment::{Attachment, AttachmentData, AttachmentType};
+    use public_key::Key;
+    use test_utils::devsetup::build_setup_profile;
+
+    use super::*;
+
+    fn base64_attachment(content: &str) -> Attachment {
+        Attachment::builder()
+            .data(
+                AttachmentData::builder()
+                    .content(AttachmentType::Base64(URL_SAFE_LENIENT.encode(content)))
+                    .build(),
+            )
+            .build()
+    }
+
+    // assert self fulfilling
+    #[tokio::test]
+    async fn test_jws_sign_and_verify_attachment() -> Result<(), Box<dyn Error>> {
+        let setup = build_setup_profile().await;
+        let wallet = &setup.wallet;
+        let signer_did = wallet.create_and_store_my_did(None, None).await?;
+        let signer = signer_did.verkey();
+
+        let attach = base64_attachment("hello world");
+
+        let signed_attach = jws_sign_attach(attach, signer.clone(), wallet).await?;
+
+        // should contain signed JWS
+        assert_eq!(signed_attach.data.jws.as_ref().unwrap().len(), 3);
+
+        // verify
+        assert!(jws_verify_attachment(&signed_attach, signer, wallet).await?);
+
+        // verify with wrong key should be false
+        let wrong_did = wallet.create_and_store_my_did(None, None).await?;
+        let wrong_signer = wrong_did.verkey();
+        assert!(!jws_verify_attachment(&signed_attach, wrong_signer, wallet).await?);
+
+        Ok(())
+    }
+
+    // test vector taken from an ACApy 0.12.1 DIDExchange response
+    #[tokio::test]
+    async fn test_jws_verify_attachment_with_acapy_test_vector() -> Result<(), Box<dyn Error>> {
+        let setup = build_setup_profile().await;
+        let wallet = &setup.wallet;
+
+        let json = json!({
+          "@id": "18bec73c-c621-4ef2-b3d8-085c59ac9e2b",
+          "mime-type": "text/string",
+          "data": {
+            "jws": {
+              "signature": "QxC2oLxAYav-fPOvjkn4OpMLng9qOo2fjsy0MoQotDgyVM_PRjYlatsrw6_rADpRpWR_GMpBVlBskuKxpsJIBQ",
+              "header": {
+                "kid": "did:key:z6MkpNusbzt7HSBwrBiRpZmbyLiBEsNGs2fotoYhykU8Muaz"
+              },
+              "protected": "eyJhbGciOiAiRWREU0EiLCAiandrIjogeyJrdHkiOiAiT0tQIiwgImNydiI6ICJFZDI1NTE5IiwgIngiOiAiazNlOHZRTHpSZlFhZFhzVDBMUkMxMWhpX09LUlR6VFphd29ocmxhaW1ETSIsICJraWQiOiAiZGlkOmtleTp6Nk1rcE51c2J6dDdIU0J3ckJpUnBabWJ5TGlCRXNOR3MyZm90b1loeWtVOE11YXoifX0"
+            },
+            // NOTE: includes b64 padding, but not recommended
+            "base64": "ZGlkOnBlZXI6NHpRbVhza2o1Sjc3NXRyWUpkaVVFZVlaUU5mYXZZQUREb25YMzJUOHF4VHJiU05oOno2MmY5VlFROER0N1VWRXJXcmp6YTd4MUVKOG50NWVxOWlaZk1BUGoyYnpyeGJycGY4VXdUTEpXVUJTV2U4dHNoRFl4ZDhlcmVSclRhOHRqVlhKNmNEOTV0Qml5dVdRVll6QzNtZWtUckJ4MzNjeXFCb2g0c3JGamdXZm1lcE5yOEZpRFI5aEoySExxMlM3VGZNWXIxNVN4UG52OExRR2lIV24zODhzVlF3ODRURVJFaTg4OXlUejZzeVVmRXhEaXdxWHZOTk05akt1eHc4NERvbmtVUDRHYkh0Q3B4R2hKYVBKWnlUWmJVaFF2SHBENGc2YzYyWTN5ZGQ0V1BQdXBYQVFISzJScFZod2hQWlVnQWQzN1lrcW1jb3FiWGFZTWFnekZZY3kxTEJ6NkdYekV5NjRrOGQ4WGhlem5vUkpIV3F4RTV1am5LYkpOM0pRR241UzREaEtRaXJTbUZINUJOYUNvRTZqaFlWc3gzWlpEM1ZWZVVxUW9ZMmVHMkNRVVRRak1zY0ozOEdqeDFiaVVlRkhZVVRrejRRVDJFWXpXRlVEbW1URHExVmVoZExtelJDWnNQUjJKR1VpVExUVkNzdUNzZ21jd1FqWHY4WmN6ejRaZUo0ODc4S3hBRm5mam1ibk1EejV5NVJOMnZtRGtkaE42dFFMZjJEWVJuSm1vSjJ5VTNheXczU2NjV0VMVzNpWEN6UFROV1F3WmFEb2d5UFVXZFBobkw0OEVpMjI2cnRBcWoySGQxcTRua1Fwb0ZWQ1B3aXJGUmtub05Zc2NGV1dxN1JEVGVMcmlKcENrUVVFblh4WVBpU1F5S0RxbVpFN0FRVjI="
+          }
+        });
+        let mut attach: Attachment = serde_json::from_value(json)?;
+        let signer = Key::from_fingerprint("z6MkpNusbzt7HSBwrBiRpZmbyLiBEsNGs2fotoYhykU8Muaz")?;
+
+        // should verify with correct signer
+        assert!(jws_verify_attachment(&attach, &signer, wallet).await?);
+
+        // should resolve the signer from the did:key kid
+        assert_eq!(
+            jws_resolve_and_verify_attachment(&attach, &ResolverRegistry::new(), wallet).await?,
+            signer
+        );
+
+        // should not verify with wrong signer
+        let wrong_signer =
+            Key::from_fingerprint("z6Mkva1JM9mM3SMuLCtVDAXzAQTwkdtfzHXSYMKtfXK2cPye")?;
+        assert!(!jws_verify_attachment(&attach, &wrong_signer, wallet).await?);
+
+        // should not verify if wrong signature
+        attach.data.content = AttachmentType::Base64(String::from("d3JvbmcgZGF0YQ=="));
+        assert!(!jws_verify_attachment(&attach, &signer, wallet).await?);
+        jws_resolve_and_verify_attachment(&attach, &ResolverRegistry::new(), wallet)
+            .await
+            .unwrap_err();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_jws_sign_attach_with_verification_method() -> Result<(), Box<dyn Error>> {
+        let setup = build_setup_profile().await;
+        let wallet = &setup.wallet;
+        let signer_did = wallet.create_and_store_my_did(None, None).await?;
+        let did_document: DidDocument = serde_json::from_value(json!({
+            "id": "did:example:alice",
+            "verificationMethod": [{
+                "id": "did:example:alice#key-1",
+                "type": "Ed25519VerificationKey2018",
+                "controller": "did:example:alice",
+                "publicKeyBase58": signer_did.verkey().base58()
+            }]
+        }))?;
+
+        let signed_attach = jws_sign_attach_with_verification_method(
+            base64_attachment("hello world"),
+            &did_document,
+            &DidUrl::parse("did:example:alice#key-1".to_owned())?,
+            wallet,
+        )
+        .await?;
+
+        assert_eq!(
+            &jws_resolve_and_verify_attachment(&signed_attach, &ResolverRegistry::new(), wallet)
+                .await?,
+            signer_did.verkey()
+        );
+        jws_sign_attach_with_verification_method(
+            base64_attachment("hello world"),
+            &did_document,
+            &DidUrl::parse("did:example:alice#key-2".to_owned())?,
+            wallet,
+        )
+        .await
+        .unwrap_err();
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_attachment_digest() -> Result<(), Box<dyn Error>> {
+        let attach = set_attachment_digest(base64_attachment("hello world"))?;
+        assert_eq!(
+            attach.data.sha256.as_deref(),
+            Some("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9")
+        );
+        verify_attachment_digest(&attach)?;
+
+        let mut tampered = attach.clone();
+        tampered.data.content = AttachmentType::Base64(URL_SAFE_LENIENT.encode("hello there"));
+        verify_attachment_digest(&tampered).unwrap_err();
+
+        let mut links = attach;
+        links.data.content = AttachmentType::Links(vec!["https://example.org/hello".parse()?]);
+        verify_attachment_digest(&links).unwrap_err();
+
+        Ok(())
+    }
+}
@@ -0,0 +1,239 @@
+use aries_vcx_ledger::ledger::{base_ledger::IndyLedgerWrite, indy_vdr_ledger::UpdateRole};
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use did_parser_nom::Did;
+use public_key::Key;
+
+use crate::{
+    common::primitives::{
+        credential_definition::CredentialDef, credential_schema::Schema,
+        revocation_registry::RevocationRegistry,
+    },
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+};
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EndorsedTransactionType {
+    Nym,
+    Schema,
+    CredDef,
+    RevRegDef,
+}
+
+impl EndorsedTransactionType {
+    /// Maps the `operation.type` code of an indy ledger request to the transaction type.
+    pub fn from_ledger_txn_type(txn_type: &str) -> VcxResult<Self> {
+        match txn_type {
+            "1" => Ok(Self::Nym),
+            "101" => Ok(Self::Schema),
+            "102" => Ok(Self::CredDef),
+            "113" => Ok(Self::RevRegDef),
+            other => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!("Ledger transaction type {} can't be endorsed", other),
+            )),
+        }
+    }
+}
+
+/// A ledger write request signed by its author, waiting for the endorser's signature.
+///
+/// The author prepares the request and hands it over to the endorser, either out of band as a
+/// string or attached to a `transactions` protocol request message. The endorser adds its
+/// signature and submits it with [endorse_and_submit].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EndorsementRequest {
+    pub author_did: Did,
+    pub endorser_did: Did,
+    pub transaction_type: EndorsedTransactionType,
+    /// The serialized ledger request, carrying the author's signature.
+    pub request: String,
+}
+
+impl EndorsementRequest {
+    pub fn to_string(&self) -> VcxResult<String> {
+        serde_json::to_string(self).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::SerializationError,
+                format!("Cannot serialize EndorsementRequest: {}", err),
+            )
+        })
+    }
+
+    pub fn from_string(data: &str) -> VcxResult<Self> {
+        serde_json::from_str(data).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!("Cannot deserialize EndorsementRequest: {}", err),
+            )
+        })
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub async fn prepare_nym_for_endorsement(
+    wallet: &impl BaseWallet,
+    indy_ledger_write: &impl IndyLedgerWrite,
+    author_did: &Did,
+    target_did: &Did,
+    target_vk: Option<&Key>,
+    role: Option<UpdateRole>,
+    alias: Option<String>,
+    endorser_did: &Did,
+) -> VcxResult<EndorsementRequest> {
+    let request = indy_ledger_write
+        .prepare_nym_for_endorsement(
+            wallet,
+            author_did,
+            target_did,
+            target_vk,
+            role,
+            alias,
+            endorser_did,
+        )
+        .await?;
+    Ok(EndorsementRequest {
+        author_did: author_did.to_owned(),
+        endorser_did: endorser_did.to_owned(),
+        transaction_type: EndorsedTransactionType::Nym,
+        request,
+    })
+}
+
+pub async fn prepare_schema_for_endorsement(
+    wallet: &impl BaseWallet,
+    indy_ledger_write: &impl IndyLedgerWrite,
+    schema: &Schema,
+    author_did: &Did,
+    endorser_did: &Did,
+) -> VcxResult<EndorsementRequest> {
+    let request = indy_ledger_write
+        .prepare_schema_for_endorsement(
+            wallet,
+            schema.schema_json.clone(),
+            author_did,
+            endorser_did,
+        )
+        .await?;
+    Ok(EndorsementRequest {
+        author_did: author_did.to_owned(),
+        endorser_did: endorser_did.to_owned(),
+        transaction_type: EndorsedTransactionType::Schema,
+        request,
+    })
+}
+
+pub async fn prepare_cred_def_for_endorsement(
+    wallet: &impl BaseWallet,
+    indy_ledger_write: &impl IndyLedgerWrite,
+    cred_def: &CredentialDef,
+    author_did: &Did,
+    endorser_did: &Did,
+) -> VcxResult<EndorsementRequest> {
+    let request = indy_ledger_write
+        .prepare_cred_def_for_endorsement(
+            wallet,
+            cred_def.get_cred_def_json(),
+            author_did,
+            endorser_did,
+        )
+        .await?;
+    Ok(EndorsementRequest {
+        author_did: author_did.to_owned(),
+        endorser_did: endorser_did.to_owned(),
+        transaction_type: EndorsedTransactionType::CredDef,
+        request,
+    })
+}
+
+/// Prepares the definition of `rev_reg` with its tails file published at `tails_url`, the same
+/// way [RevocationRegistry::publish_rev_reg_def] would write it.
+pub async fn prepare_rev_reg_def_for_endorsement(
+    wallet: &impl BaseWallet,
+    indy_ledger_write: &impl IndyLedgerWrite,
+    rev_reg: &RevocationRegistry,
+    tails_url: &str,
+    author_did: &Did,
+    endorser_did: &Did,
+) -> VcxResult<EndorsementRequest> {
+    let mut rev_reg_def = rev_reg.get_rev_reg_def();
+    rev_reg_def.value.tails_location = String::from(tails_url);
+    let request = indy_ledger_write
+        .prepare_rev_reg_def_for_endorsement(wallet, rev_reg_def, author_did, endorser_did)
+        .await?;
+    Ok(EndorsementRequest {
+        author_did: author_did.to_owned(),
+        endorser_did: endorser_did.to_owned(),
+        transaction_type: EndorsedTransactionType::RevRegDef,
+        request,
+    })
+}
+
+/// Adds the signature of `endorser_did` to the prepared request and writes it to the ledger,
+/// returning the endorsed request.
+pub async fn endorse_and_submit(
+    wallet: &impl BaseWallet,
+    indy_ledger_write: &impl IndyLedgerWrite,
+    endorser_did: &Did,
+    endorsement_request: &EndorsementRequest,
+) -> VcxResult<String> {
+    if &endorsement_request.endorser_did != endorser_did {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            format!(
+                "Transaction was prepared for endorser {}, not {}",
+                endorsement_request.endorser_did, endorser_did
+            ),
+        ));
+    }
+    Ok(indy_ledger_write
+        .endorse_transaction(wallet, endorser_did, &endorsement_request.request)
+        .await?)
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use test_utils::{
+        constants::{DID, SCHEMA_JSON},
+        devsetup::build_setup_profile,
+        mockdata::mock_ledger::MockLedger,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_endorsement_request_roundtrip() {
+        let endorsement_request = EndorsementRequest {
+            author_did: DID.parse().unwrap(),
+            endorser_did: "V4SGRU86Z58d6TV7PBUe6f".parse().unwrap(),
+            transaction_type: EndorsedTransactionType::Schema,
+            request: SCHEMA_JSON.to_owned(),
+        };
+        let serialized = endorsement_request.to_string().unwrap();
+        assert!(serialized.contains("\"transaction_type\":\"SCHEMA\""));
+        assert_eq!(
+            EndorsementRequest::from_string(&serialized).unwrap(),
+            endorsement_request
+        );
+    }
+
+    #[tokio::test]
+    async fn test_should_not_endorse_transaction_prepared_for_other_endorser() {
+        let setup = build_setup_profile().await;
+        let endorsement_request = EndorsementRequest {
+            author_did: DID.parse().unwrap(),
+            endorser_did: "V4SGRU86Z58d6TV7PBUe6f".parse().unwrap(),
+            transaction_type: EndorsedTransactionType::Nym,
+            request: "{}".to_owned(),
+        };
+        let err = endorse_and_submit(
+            &setup.wallet,
+            &MockLedger,
+            &DID.parse().unwrap(),
+            &endorsement_request,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidInput);
+    }
+}
@@ -1,3 +1,4 @@
+pub mod attachments;
 pub mod credentials;
 pub mod keys;
 pub mod ledger;
@@ -1,3 +1,7 @@
 pub mod credential_definition;
 pub mod credential_schema;
 pub mod revocation_registry;
+pub mod revocation_registry_manager;
+pub mod revocation_service;
+pub mod tails_file_manager;
+pub mod tails_server;
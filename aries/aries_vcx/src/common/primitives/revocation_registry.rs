@@ -1,12 +1,20 @@
 use std::path::Path;
 
-use anoncreds_types::data_types::{
-    identifiers::cred_def_id::CredentialDefinitionId,
-    ledger::rev_reg_def::RevocationRegistryDefinition,
+use anoncreds_types::{
+    data_types::{
+        identifiers::cred_def_id::CredentialDefinitionId,
+        ledger::{
+            rev_reg::RevocationRegistry as LedgerRevocationRegistry,
+            rev_reg_def::RevocationRegistryDefinition, rev_reg_delta::RevocationRegistryDeltaValue,
+            rev_status_list::RevocationStatusList,
+        },
+    },
+    utils::conversions::from_revocation_registry_delta_to_revocation_status_list,
 };
 use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
 use aries_vcx_ledger::ledger::base_ledger::{AnoncredsLedgerRead, AnoncredsLedgerWrite};
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use chrono::Utc;
 use did_parser_nom::Did;
 
 use super::credential_definition::PublicEntityStateType;
@@ -18,12 +26,15 @@ pub struct RevocationRegistry {
     issuer_did: Did,
     pub rev_reg_id: String,
     rev_reg_def: RevocationRegistryDefinition,
+    // initial revocation status list of the registry, or the initial revocation registry entry
+    // for registries created by an earlier version, see initial_rev_status_list
     pub(in crate::common) rev_reg_entry: String,
     pub tails_dir: String,
     pub(in crate::common) max_creds: u32,
     pub(in crate::common) tag: u32,
     rev_reg_def_state: PublicEntityStateType,
-    rev_reg_delta_state: PublicEntityStateType,
+    #[serde(alias = "rev_reg_delta_state")]
+    rev_status_list_state: PublicEntityStateType,
 }
 
 impl RevocationRegistry {
@@ -74,7 +85,7 @@ impl RevocationRegistry {
             max_creds,
             tag,
             rev_reg_def_state: PublicEntityStateType::Built,
-            rev_reg_delta_state: PublicEntityStateType::Built,
+            rev_status_list_state: PublicEntityStateType::Built,
         })
     }
 
@@ -98,8 +109,8 @@ impl RevocationRegistry {
         self.rev_reg_def_state == PublicEntityStateType::Published
     }
 
-    pub fn was_rev_reg_delta_published(&self) -> bool {
-        self.rev_reg_delta_state == PublicEntityStateType::Published
+    pub fn was_rev_status_list_published(&self) -> bool {
+        self.rev_status_list_state == PublicEntityStateType::Published
     }
 
     pub async fn publish_rev_reg_def(
@@ -134,32 +145,48 @@ impl RevocationRegistry {
         Ok(())
     }
 
-    pub async fn publish_rev_reg_delta(
+    fn initial_rev_status_list(&self) -> VcxResult<RevocationStatusList> {
+        if let Ok(rev_status_list) = serde_json::from_str(&self.rev_reg_entry) {
+            return Ok(rev_status_list);
+        }
+        // Nothing is revoked yet in the initial entry, so its accumulator is all there is to the
+        // initial status list
+        let rev_reg_entry: LedgerRevocationRegistry = serde_json::from_str(&self.rev_reg_entry)?;
+        Ok(from_revocation_registry_delta_to_revocation_status_list(
+            &RevocationRegistryDeltaValue {
+                prev_accum: None,
+                accum: rev_reg_entry.value.accum,
+                issued: Vec::new(),
+                revoked: Vec::new(),
+            },
+            Utc::now().timestamp() as u64,
+            &self.rev_reg_def.id,
+            self.max_creds as usize,
+            self.rev_reg_def.issuer_id.clone(),
+        )?)
+    }
+
+    pub async fn publish_rev_status_list(
         &mut self,
         wallet: &impl BaseWallet,
         ledger_write: &impl AnoncredsLedgerWrite,
         issuer_did: &Did,
     ) -> VcxResult<()> {
         trace!(
-            "RevocationRegistry::publish_rev_reg_delta >>> issuer_did:{}, rev_reg_id: {}",
+            "RevocationRegistry::publish_rev_status_list >>> issuer_did:{}, rev_reg_id: {}",
             issuer_did,
             self.rev_reg_id
         );
         ledger_write
-            .publish_rev_reg_delta(
-                wallet,
-                &self.rev_reg_id.to_string().try_into()?,
-                serde_json::from_str(&self.rev_reg_entry)?,
-                issuer_did,
-            )
+            .publish_rev_status_list(wallet, self.initial_rev_status_list()?, None, issuer_did)
             .await
             .map_err(|err| {
                 AriesVcxError::from_msg(
                     AriesVcxErrorKind::InvalidRevocationEntry,
-                    format!("Cannot publish revocation entry; {err}"),
+                    format!("Cannot publish revocation status list; {err}"),
                 )
             })?;
-        self.rev_reg_delta_state = PublicEntityStateType::Published;
+        self.rev_status_list_state = PublicEntityStateType::Published;
         Ok(())
     }
 
@@ -175,19 +202,20 @@ impl RevocationRegistry {
         );
         self.publish_built_rev_reg_def(wallet, ledger_write, tails_url)
             .await?;
-        self.publish_built_rev_reg_delta(wallet, ledger_write).await
+        self.publish_built_rev_status_list(wallet, ledger_write)
+            .await
     }
 
-    async fn publish_built_rev_reg_delta(
+    async fn publish_built_rev_status_list(
         &mut self,
         wallet: &impl BaseWallet,
         ledger_write: &impl AnoncredsLedgerWrite,
     ) -> VcxResult<()> {
         let issuer_did = &self.issuer_did.clone();
-        if self.was_rev_reg_delta_published() {
-            info!("No unpublished revocation registry delta found, nothing to publish")
+        if self.was_rev_status_list_published() {
+            info!("No unpublished revocation status list found, nothing to publish")
         } else {
-            self.publish_rev_reg_delta(wallet, ledger_write, issuer_did)
+            self.publish_rev_status_list(wallet, ledger_write, issuer_did)
                 .await?;
         }
         Ok(())
@@ -234,18 +262,12 @@ impl RevocationRegistry {
         ledger: &impl AnoncredsLedgerRead,
         cred_rev_id: u32,
     ) -> VcxResult<()> {
-        #[allow(deprecated)] // TODO - https://github.com/hyperledger/aries-vcx/issues/1309
-        let rev_reg_delta_json = ledger
-            .get_rev_reg_delta_json(&self.rev_reg_id.to_string().try_into()?, None, None)
-            .await?
-            .0;
+        let rev_reg_id = self.rev_reg_id.to_owned().try_into()?;
+        let (ledger_rev_status_list, _) = ledger
+            .get_rev_status_list(&rev_reg_id, Utc::now().timestamp() as u64, None)
+            .await?;
         anoncreds
-            .revoke_credential_local(
-                wallet,
-                &self.rev_reg_id.to_owned().try_into()?,
-                cred_rev_id,
-                rev_reg_delta_json,
-            )
+            .revoke_credential_local(wallet, &rev_reg_id, cred_rev_id, ledger_rev_status_list)
             .await
             .map_err(|err| err.into())
     }
@@ -254,34 +276,39 @@ impl RevocationRegistry {
         &self,
         wallet: &impl BaseWallet,
         anoncreds: &impl BaseAnonCreds,
+        ledger_read: &impl AnoncredsLedgerRead,
         ledger_write: &impl AnoncredsLedgerWrite,
         submitter_did: &Did,
     ) -> VcxResult<()> {
-        if let Some(delta) = anoncreds
-            .get_rev_reg_delta(wallet, &self.rev_reg_id.to_owned().try_into()?)
+        let rev_reg_id = self.rev_reg_id.to_owned().try_into()?;
+        if let Some(rev_status_list) = anoncreds
+            .get_pending_rev_status_list(wallet, &rev_reg_id)
             .await?
         {
+            let (ledger_rev_status_list, _) = ledger_read
+                .get_rev_status_list(&rev_reg_id, Utc::now().timestamp() as u64, None)
+                .await?;
             ledger_write
-                .publish_rev_reg_delta(
+                .publish_rev_status_list(
                     wallet,
-                    &self.rev_reg_id.to_string().try_into()?,
-                    delta,
+                    rev_status_list,
+                    Some(&ledger_rev_status_list),
                     submitter_did,
                 )
                 .await?;
 
             info!(
-                "publish_local_revocations >>> rev_reg_delta published for rev_reg_id {}",
+                "publish_local_revocations >>> rev_status_list published for rev_reg_id {}",
                 self.rev_reg_id
             );
 
             match anoncreds
-                .clear_rev_reg_delta(wallet, &self.rev_reg_id.to_owned().try_into()?)
+                .clear_pending_rev_status_list(wallet, &rev_reg_id)
                 .await
             {
                 Ok(_) => {
                     info!(
-                        "publish_local_revocations >>> rev_reg_delta storage cleared for \
+                        "publish_local_revocations >>> pending rev_status_list cleared for \
                          rev_reg_id {}",
                         self.rev_reg_id
                     );
@@ -290,7 +317,8 @@ impl RevocationRegistry {
                 Err(err) => Err(AriesVcxError::from_msg(
                     AriesVcxErrorKind::RevDeltaFailedToClear,
                     format!(
-                        "Failed to clear revocation delta storage for rev_reg_id: {}, error: {err}",
+                        "Failed to clear pending revocation status list for rev_reg_id: {}, \
+                         error: {err}",
                         self.rev_reg_id
                     ),
                 )),
@@ -299,8 +327,8 @@ impl RevocationRegistry {
             Err(AriesVcxError::from_msg(
                 AriesVcxErrorKind::RevDeltaNotFound,
                 format!(
-                    "Failed to publish revocation delta for revocation registry {}, no delta \
-                     found. Possibly already published?",
+                    "Failed to publish revocation status list for revocation registry {}, no \
+                     local revocation found. Possibly already published?",
                     self.rev_reg_id
                 ),
             ))
@@ -327,7 +355,7 @@ pub async fn generate_rev_reg(
         tag
     );
 
-    let (rev_reg_id, rev_reg_def_json, rev_reg_entry_json) = anoncreds
+    let (rev_reg_id, rev_reg_def_json, rev_status_list) = anoncreds
         .issuer_create_and_store_revoc_reg(
             wallet,
             issuer_did,
@@ -341,6 +369,52 @@ pub async fn generate_rev_reg(
     Ok((
         rev_reg_id.to_string(),
         rev_reg_def_json,
-        serde_json::to_string(&rev_reg_entry_json)?,
+        serde_json::to_string(&rev_status_list)?,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use test_utils::constants::{rev_def_json, INSTITUTION_DID, REV_REG_ID, REV_REG_JSON};
+
+    use super::*;
+
+    #[test]
+    fn test_initial_rev_status_list_of_legacy_registry() {
+        let rev_reg = RevocationRegistry::from_string(
+            &json!({
+                "cred_def_id": "V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag1",
+                "issuer_did": INSTITUTION_DID,
+                "rev_reg_id": REV_REG_ID,
+                "rev_reg_def": rev_def_json(),
+                "rev_reg_entry": REV_REG_JSON,
+                "tails_dir": "/tmp/tails",
+                "max_creds": 10,
+                "tag": 1,
+                "rev_reg_def_state": 1,
+                "rev_reg_delta_state": 0
+            })
+            .to_string(),
+        )
+        .unwrap();
+        assert!(!rev_reg.was_rev_status_list_published());
+
+        let rev_status_list = rev_reg.initial_rev_status_list().unwrap();
+        let rev_reg_entry: LedgerRevocationRegistry = serde_json::from_str(REV_REG_JSON).unwrap();
+        assert_eq!(rev_status_list.accum(), Some(rev_reg_entry.value.accum));
+        assert_eq!(rev_status_list.state().len(), 10);
+        assert!(rev_status_list.state().not_any());
+
+        // A registry serialized again keeps its initial status list
+        let rev_reg = RevocationRegistry {
+            rev_reg_entry: serde_json::to_string(&rev_status_list).unwrap(),
+            ..rev_reg
+        };
+        let rev_reg = RevocationRegistry::from_string(&rev_reg.to_string().unwrap()).unwrap();
+        assert_eq!(
+            serde_json::to_value(rev_reg.initial_rev_status_list().unwrap()).unwrap(),
+            serde_json::to_value(&rev_status_list).unwrap()
+        );
+    }
+}
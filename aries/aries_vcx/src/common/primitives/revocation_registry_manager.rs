@@ -0,0 +1,299 @@
+use std::{collections::BTreeSet, path::Path};
+
+use anoncreds_types::data_types::identifiers::cred_def_id::CredentialDefinitionId;
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerWrite;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use did_parser_nom::Did;
+
+use super::{revocation_registry::RevocationRegistry, tails_server::TailsServerClient};
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+#[derive(Clone, Copy, Deserialize, Debug, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RegistryStatus {
+    /// Credentials are currently issued against the registry.
+    Active,
+    /// Published and ready to take over once the active registry is exhausted.
+    Standby,
+    /// Every index of the registry has been issued.
+    Full,
+}
+
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct ManagedRevocationRegistry {
+    registry: RevocationRegistry,
+    status: RegistryStatus,
+    issued_indices: BTreeSet<u32>,
+}
+
+impl ManagedRevocationRegistry {
+    pub fn registry(&self) -> &RevocationRegistry {
+        &self.registry
+    }
+
+    pub fn status(&self) -> RegistryStatus {
+        self.status
+    }
+
+    pub fn issued_indices(&self) -> &BTreeSet<u32> {
+        &self.issued_indices
+    }
+
+    pub fn remaining(&self) -> u32 {
+        self.registry
+            .max_creds
+            .saturating_sub(self.issued_indices.len() as u32)
+    }
+}
+
+/// Registry created locally whose tails file upload or ledger publication hasn't completed yet.
+#[derive(Clone, Deserialize, Debug, Serialize)]
+struct PendingRevocationRegistry {
+    registry: RevocationRegistry,
+    tails_url: Option<String>,
+}
+
+/// Keeps a credential definition supplied with a revocation registry to issue against.
+///
+/// The next registry is created and published once no more than `rotation_threshold` indices
+/// of the active one are left, so that it can take over as soon as the active registry is full.
+/// Tails files of new registries are uploaded through a [TailsServerClient]. A registry which
+/// fails to be uploaded or published is kept pending and its publication is resumed on the next
+/// attempt, so its tag is never skipped.
+#[derive(Clone, Deserialize, Debug, Serialize)]
+pub struct RevocationRegistryManager {
+    cred_def_id: CredentialDefinitionId,
+    issuer_did: Did,
+    tails_dir: String,
+    max_creds: u32,
+    rotation_threshold: u32,
+    next_tag: u32,
+    registries: Vec<ManagedRevocationRegistry>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pending: Option<PendingRevocationRegistry>,
+}
+
+impl RevocationRegistryManager {
+    pub fn new(
+        issuer_did: Did,
+        cred_def_id: CredentialDefinitionId,
+        tails_dir: &str,
+        max_creds: u32,
+        rotation_threshold: u32,
+    ) -> VcxResult<Self> {
+        if max_creds == 0 || rotation_threshold >= max_creds {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidConfiguration,
+                format!(
+                    "Rotation threshold {rotation_threshold} must be lower than the capacity \
+                     {max_creds} of the revocation registries"
+                ),
+            ));
+        }
+        Ok(Self {
+            cred_def_id,
+            issuer_did,
+            tails_dir: tails_dir.to_string(),
+            max_creds,
+            rotation_threshold,
+            next_tag: 1,
+            registries: Vec::new(),
+            pending: None,
+        })
+    }
+
+    pub fn get_cred_def_id(&self) -> &CredentialDefinitionId {
+        &self.cred_def_id
+    }
+
+    pub fn get_tails_dir(&self) -> String {
+        self.tails_dir.clone()
+    }
+
+    pub fn registries(&self) -> &[ManagedRevocationRegistry] {
+        &self.registries
+    }
+
+    pub fn get_registry(&self, rev_reg_id: &str) -> Option<&ManagedRevocationRegistry> {
+        self.registries
+            .iter()
+            .find(|managed| managed.registry.rev_reg_id == rev_reg_id)
+    }
+
+    /// Returns the registry to issue the next credential against, first creating and publishing
+    /// registries as needed: the active one when there's none left with free indices, and the
+    /// standby one when the active registry reaches the rotation threshold.
+    ///
+    /// Failing to set up the standby registry doesn't prevent issuing against the active one; the
+    /// failure is logged and the standby registry is retried on the next call.
+    pub async fn active_registry(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_write: &impl AnoncredsLedgerWrite,
+        tails_server: &impl TailsServerClient,
+    ) -> VcxResult<&RevocationRegistry> {
+        let active = match self.position_of(RegistryStatus::Active) {
+            Some(active) => active,
+            None => match self.position_of(RegistryStatus::Standby) {
+                Some(standby) => standby,
+                None => {
+                    self.create_registry(wallet, anoncreds, ledger_write, tails_server)
+                        .await?
+                }
+            },
+        };
+        self.registries[active].status = RegistryStatus::Active;
+
+        if self.registries[active].remaining() <= self.rotation_threshold
+            && self.position_of(RegistryStatus::Standby).is_none()
+        {
+            match self
+                .create_registry(wallet, anoncreds, ledger_write, tails_server)
+                .await
+            {
+                Ok(standby) => self.registries[standby].status = RegistryStatus::Standby,
+                Err(err) => warn!(
+                    "active_registry >> failed to set up standby revocation registry of {}, \
+                     will retry on next issuance: {}",
+                    self.cred_def_id, err
+                ),
+            }
+        }
+        Ok(&self.registries[active].registry)
+    }
+
+    /// Records the index a credential was issued with, marking the registry full once all of
+    /// its indices are taken.
+    pub fn record_issuance(&mut self, rev_reg_id: &str, cred_rev_id: u32) -> VcxResult<()> {
+        let managed = self
+            .registries
+            .iter_mut()
+            .find(|managed| managed.registry.rev_reg_id == rev_reg_id)
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::RevRegDefNotFound,
+                    format!("Revocation registry {rev_reg_id} is not managed"),
+                )
+            })?;
+        if cred_rev_id == 0 || cred_rev_id > managed.registry.max_creds {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!(
+                    "Credential revocation id {cred_rev_id} out of range of revocation registry \
+                     {rev_reg_id}"
+                ),
+            ));
+        }
+        if !managed.issued_indices.insert(cred_rev_id) {
+            warn!(
+                "record_issuance >> credential revocation id {} of revocation registry {} was \
+                 already recorded",
+                cred_rev_id, rev_reg_id
+            );
+        }
+        if managed.remaining() == 0 {
+            info!(
+                "record_issuance >> revocation registry {} is full",
+                rev_reg_id
+            );
+            managed.status = RegistryStatus::Full;
+        }
+        Ok(())
+    }
+
+    pub fn to_string(&self) -> VcxResult<String> {
+        serde_json::to_string(&self).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::SerializationError,
+                format!("Cannot serialize revocation registry manager: {:?}", err),
+            )
+        })
+    }
+
+    pub fn from_string(manager_data: &str) -> VcxResult<Self> {
+        serde_json::from_str(manager_data).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!("Cannot deserialize revocation registry manager: {:?}", err),
+            )
+        })
+    }
+
+    fn position_of(&self, status: RegistryStatus) -> Option<usize> {
+        self.registries
+            .iter()
+            .position(|managed| managed.status == status)
+    }
+
+    async fn create_registry(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_write: &impl AnoncredsLedgerWrite,
+        tails_server: &impl TailsServerClient,
+    ) -> VcxResult<usize> {
+        let mut pending = match self.pending.take() {
+            Some(pending) => pending,
+            None => PendingRevocationRegistry {
+                registry: RevocationRegistry::create(
+                    wallet,
+                    anoncreds,
+                    &self.issuer_did,
+                    &self.cred_def_id,
+                    &self.tails_dir,
+                    self.max_creds,
+                    self.next_tag,
+                )
+                .await?,
+                tails_url: None,
+            },
+        };
+        if let Err(err) =
+            Self::publish_pending(&mut pending, wallet, ledger_write, tails_server).await
+        {
+            self.pending = Some(pending);
+            return Err(err);
+        }
+        self.next_tag += 1;
+
+        self.registries.push(ManagedRevocationRegistry {
+            registry: pending.registry,
+            status: RegistryStatus::Standby,
+            issued_indices: BTreeSet::new(),
+        });
+        Ok(self.registries.len() - 1)
+    }
+
+    async fn publish_pending(
+        pending: &mut PendingRevocationRegistry,
+        wallet: &impl BaseWallet,
+        ledger_write: &impl AnoncredsLedgerWrite,
+        tails_server: &impl TailsServerClient,
+    ) -> VcxResult<()> {
+        let tails_url = match &pending.tails_url {
+            Some(tails_url) => tails_url.clone(),
+            None => {
+                // until published, the tails location of the definition is the path of the tails
+                // file
+                let tails_file = pending.registry.get_rev_reg_def().value.tails_location;
+                let tails_url = tails_server
+                    .upload_tails_file(&pending.registry.get_rev_reg_id(), Path::new(&tails_file))
+                    .await?;
+                pending.tails_url = Some(tails_url.clone());
+                tails_url
+            }
+        };
+        pending
+            .registry
+            .publish_revocation_primitives(wallet, ledger_write, &tails_url)
+            .await?;
+        info!(
+            "create_registry >> revocation registry {} published with tails file at {}",
+            pending.registry.get_rev_reg_id(),
+            tails_url
+        );
+        Ok(())
+    }
+}
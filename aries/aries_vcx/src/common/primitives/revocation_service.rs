@@ -0,0 +1,349 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    future::Future,
+    pin::pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anoncreds_types::data_types::{
+    identifiers::rev_reg_def_id::RevocationRegistryDefinitionId,
+    ledger::rev_status_list::RevocationStatusList,
+};
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_ledger::ledger::base_ledger::{AnoncredsLedgerRead, AnoncredsLedgerWrite};
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use chrono::Utc;
+use did_parser_nom::Did;
+use futures::future::{self, Either};
+use messages::AriesMessage;
+
+use crate::{
+    errors::error::VcxResult,
+    handlers::revocation_notification::sender::RevocationNotificationSender,
+    protocols::{revocation_notification::sender::state_machine::SenderConfigBuilder, SendClosure},
+};
+
+/// Delivers `revocation_notification` messages to the holders of revoked credentials, which
+/// only the application knows how to reach.
+#[async_trait]
+pub trait RevocationNotifier: Send + Sync {
+    async fn send_to_holder(
+        &self,
+        rev_reg_id: &str,
+        cred_rev_id: u32,
+        message: AriesMessage,
+    ) -> VcxResult<()>;
+}
+
+/// Outcome of a [`RevocationService::publish_pending`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PublishReport {
+    /// Credential revocation ids published, by revocation registry.
+    pub published: BTreeMap<String, Vec<u32>>,
+    /// Registries whose status list failed to publish, with the error. Their revocations stay
+    /// pending for the next attempt.
+    pub failed: BTreeMap<String, String>,
+    pub notifications_sent: usize,
+    pub notifications_failed: usize,
+}
+
+impl PublishReport {
+    pub fn published_count(&self) -> usize {
+        self.published.values().map(Vec::len).sum()
+    }
+}
+
+/// Revokes credentials in batches, across any number of revocation registries.
+///
+/// Revocations accumulate in the pending status lists of their registries in the wallet until
+/// [`RevocationService::publish_pending`] publishes every registry with pending revocations,
+/// either on demand or periodically through [`RevocationService::publish_periodically`].
+///
+/// Revoking and publishing are serialized per registry, so a revocation made while its registry
+/// is being published stays pending for the next publish. This only holds for revocations made
+/// through the same service.
+pub struct RevocationService {
+    submitter_did: Did,
+    notifier: Option<Arc<dyn RevocationNotifier>>,
+    notification_comment: Option<String>,
+    registry_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl RevocationService {
+    pub fn new(submitter_did: Did) -> Self {
+        Self {
+            submitter_did,
+            notifier: None,
+            notification_comment: None,
+            registry_locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn registry_lock(&self, rev_reg_id: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.registry_locks
+            .lock()
+            .unwrap()
+            .entry(rev_reg_id.to_owned())
+            .or_default()
+            .clone()
+    }
+
+    /// Notifies holders of their revoked credentials once the revocations are published.
+    pub fn with_notifier(
+        mut self,
+        notifier: Arc<dyn RevocationNotifier>,
+        comment: Option<String>,
+    ) -> Self {
+        self.notifier = Some(notifier);
+        self.notification_comment = comment;
+        self
+    }
+
+    /// Adds the `(rev_reg_id, cred_rev_id)` pairs to the pending revocations, updating the
+    /// pending status list of each registry once. Returns the number of credentials revoked
+    /// per registry.
+    ///
+    /// Revoking is idempotent, so a batch which failed part way through can be retried as is.
+    pub async fn revoke_batch(
+        &self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_read: &impl AnoncredsLedgerRead,
+        revocations: impl IntoIterator<Item = (String, u32)>,
+    ) -> VcxResult<BTreeMap<String, usize>> {
+        let mut by_registry: BTreeMap<String, BTreeSet<u32>> = BTreeMap::new();
+        for (rev_reg_id, cred_rev_id) in revocations {
+            by_registry
+                .entry(rev_reg_id)
+                .or_default()
+                .insert(cred_rev_id);
+        }
+
+        let mut revoked = BTreeMap::new();
+        for (rev_reg_id, cred_rev_ids) in by_registry {
+            let id: RevocationRegistryDefinitionId = rev_reg_id.clone().try_into()?;
+            let lock = self.registry_lock(&rev_reg_id);
+            let _guard = lock.lock().await;
+            let (ledger_rev_status_list, _) = ledger_read
+                .get_rev_status_list(&id, Utc::now().timestamp() as u64, None)
+                .await?;
+            let count = cred_rev_ids.len();
+            anoncreds
+                .revoke_credentials_local(wallet, &id, cred_rev_ids, ledger_rev_status_list)
+                .await?;
+            debug!(
+                "revoke_batch >> {} credentials of revocation registry {} pending revocation",
+                count, rev_reg_id
+            );
+            revoked.insert(rev_reg_id, count);
+        }
+        Ok(revoked)
+    }
+
+    /// Registries with revocations waiting to be published.
+    pub async fn pending_registries(
+        &self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+    ) -> VcxResult<Vec<String>> {
+        Ok(anoncreds
+            .get_rev_reg_ids_with_pending_revocations(wallet)
+            .await?
+            .into_iter()
+            .map(|id| id.to_string())
+            .collect())
+    }
+
+    /// Publishes the pending status list of every registry with pending revocations. A registry
+    /// failing to publish doesn't prevent publishing the others.
+    pub async fn publish_pending(
+        &self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_read: &impl AnoncredsLedgerRead,
+        ledger_write: &impl AnoncredsLedgerWrite,
+    ) -> VcxResult<PublishReport> {
+        let mut report = PublishReport::default();
+        for id in anoncreds
+            .get_rev_reg_ids_with_pending_revocations(wallet)
+            .await?
+        {
+            match self
+                .publish_registry(wallet, anoncreds, ledger_read, ledger_write, &id)
+                .await
+            {
+                Ok(cred_rev_ids) => {
+                    report.published.insert(id.to_string(), cred_rev_ids);
+                }
+                Err(err) => {
+                    warn!(
+                        "publish_pending >> failed to publish pending revocations of revocation \
+                         registry {}; err: {}",
+                        id, err
+                    );
+                    report.failed.insert(id.to_string(), err.to_string());
+                }
+            }
+        }
+
+        if let Some(notifier) = &self.notifier {
+            for (rev_reg_id, cred_rev_ids) in &report.published {
+                for cred_rev_id in cred_rev_ids {
+                    match self
+                        .notify_holder(notifier.as_ref(), rev_reg_id, *cred_rev_id)
+                        .await
+                    {
+                        Ok(()) => report.notifications_sent += 1,
+                        Err(err) => {
+                            warn!(
+                                "publish_pending >> failed to notify holder of credential {} of \
+                                 revocation registry {}; err: {}",
+                                cred_rev_id, rev_reg_id, err
+                            );
+                            report.notifications_failed += 1;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    /// Calls [`RevocationService::publish_pending`] every `interval` until `shutdown`
+    /// completes, handing the report of every round which had anything to publish to
+    /// `on_report`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn publish_periodically(
+        &self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_read: &impl AnoncredsLedgerRead,
+        ledger_write: &impl AnoncredsLedgerWrite,
+        interval: Duration,
+        shutdown: impl Future<Output = ()>,
+        mut on_report: impl FnMut(PublishReport),
+    ) {
+        let mut shutdown = pin!(shutdown);
+        loop {
+            let tick = pin!(tokio::time::sleep(interval));
+            if let Either::Right(_) = future::select(tick, shutdown.as_mut()).await {
+                return;
+            }
+            match self
+                .publish_pending(wallet, anoncreds, ledger_read, ledger_write)
+                .await
+            {
+                Ok(report) if report == PublishReport::default() => {}
+                Ok(report) => {
+                    info!(
+                        "publish_periodically >> published {} revocations of {} revocation \
+                         registries, {} registries failed",
+                        report.published_count(),
+                        report.published.len(),
+                        report.failed.len()
+                    );
+                    on_report(report)
+                }
+                Err(err) => warn!(
+                    "publish_periodically >> failed to publish pending revocations; err: {}",
+                    err
+                ),
+            }
+        }
+    }
+
+    async fn publish_registry(
+        &self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_read: &impl AnoncredsLedgerRead,
+        ledger_write: &impl AnoncredsLedgerWrite,
+        id: &RevocationRegistryDefinitionId,
+    ) -> VcxResult<Vec<u32>> {
+        // held until the published status list is cleared, so no revocation made meanwhile is
+        // cleared along with it
+        let lock = self.registry_lock(&id.to_string());
+        let _guard = lock.lock().await;
+        let Some(rev_status_list) = anoncreds.get_pending_rev_status_list(wallet, id).await? else {
+            return Ok(Vec::new());
+        };
+        let (ledger_rev_status_list, _) = ledger_read
+            .get_rev_status_list(id, Utc::now().timestamp() as u64, None)
+            .await?;
+        let cred_rev_ids = newly_revoked(&rev_status_list, &ledger_rev_status_list);
+        ledger_write
+            .publish_rev_status_list(
+                wallet,
+                rev_status_list,
+                Some(&ledger_rev_status_list),
+                &self.submitter_did,
+            )
+            .await?;
+        anoncreds.clear_pending_rev_status_list(wallet, id).await?;
+        info!(
+            "publish_registry >> published {} revocations of revocation registry {}",
+            cred_rev_ids.len(),
+            id
+        );
+        Ok(cred_rev_ids)
+    }
+
+    async fn notify_holder(
+        &self,
+        notifier: &dyn RevocationNotifier,
+        rev_reg_id: &str,
+        cred_rev_id: u32,
+    ) -> VcxResult<()> {
+        let config = SenderConfigBuilder::default()
+            .rev_reg_id(rev_reg_id.to_owned())
+            .cred_rev_id(cred_rev_id)
+            .comment(self.notification_comment.clone())
+            .ack_on(vec![])
+            .build()?;
+        let rev_reg_id = rev_reg_id.to_owned();
+        let send_message: SendClosure = Box::new(move |message| {
+            Box::pin(async move {
+                notifier
+                    .send_to_holder(&rev_reg_id, cred_rev_id, message)
+                    .await
+            })
+        });
+        RevocationNotificationSender::build()
+            .send_revocation_notification(config, send_message)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Indices revoked in `pending` but not in the published `ledger` status list.
+fn newly_revoked(pending: &RevocationStatusList, ledger: &RevocationStatusList) -> Vec<u32> {
+    pending
+        .state()
+        .iter_ones()
+        .filter(|idx| !ledger.state().get(*idx).is_some_and(|bit| *bit))
+        .map(|idx| idx as u32)
+        .collect()
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    fn status_list(revocation_list: &[u8]) -> RevocationStatusList {
+        serde_json::from_value(json!({
+            "issuerId": "4xE68b6S5VRFrKMMG1U95M",
+            "revocationList": revocation_list,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_newly_revoked_ignores_published_revocations() {
+        let ledger = status_list(&[0, 1, 0, 0, 1]);
+        let pending = status_list(&[1, 1, 0, 1, 1]);
+        assert_eq!(newly_revoked(&pending, &ledger), vec![0, 3]);
+        assert!(newly_revoked(&ledger, &ledger).is_empty());
+    }
+}
@@ -0,0 +1,490 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+use shared::http_client::{HttpClient, HttpClientConfig};
+use url::Url;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// Tails files of registries issuing up to 32768 credentials take about 8 MB.
+pub const DEFAULT_MAX_TAILS_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+const PARTIAL_FILE_EXTENSION: &str = "partial";
+
+/// Partial files older than this are leftovers of interrupted downloads rather than downloads
+/// in progress.
+const PARTIAL_FILE_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Fetches the content of tails files from their `tailsLocation`.
+#[async_trait]
+pub trait TailsFileFetcher: Send + Sync {
+    /// Fetches the tails file, failing as soon as its content is known to be larger than
+    /// `max_size` bytes rather than reading it in full.
+    async fn fetch(&self, tails_location: &str, max_size: u64) -> VcxResult<Vec<u8>>;
+}
+
+#[async_trait]
+impl<T> TailsFileFetcher for &T
+where
+    T: TailsFileFetcher + ?Sized,
+{
+    async fn fetch(&self, tails_location: &str, max_size: u64) -> VcxResult<Vec<u8>> {
+        (**self).fetch(tails_location, max_size).await
+    }
+}
+
+/// Downloads tails files located by `http` and `https` URLs.
+#[derive(Debug, Clone)]
+pub struct HttpTailsFileFetcher {
+    client: HttpClient,
+}
+
+impl HttpTailsFileFetcher {
+    pub fn new(config: HttpClientConfig) -> VcxResult<Self> {
+        Ok(Self {
+            client: HttpClient::new(config)?,
+        })
+    }
+}
+
+#[async_trait]
+impl TailsFileFetcher for HttpTailsFileFetcher {
+    async fn fetch(&self, tails_location: &str, max_size: u64) -> VcxResult<Vec<u8>> {
+        let url = Url::parse(tails_location).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidUrl,
+                format!("Invalid tails location {tails_location}: {err}"),
+            )
+        })?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::ActionNotSupported,
+                format!(
+                    "Can't download tails file from {tails_location}, only http(s) is supported"
+                ),
+            ));
+        }
+        Ok(self.client.get_with_max_size(&url, max_size).await?)
+    }
+}
+
+/// Keeps the tails files the holder needs to prove the non-revocation of its credentials.
+///
+/// Files are stored under their hash in a single directory, which is the layout
+/// [BaseAnonCreds::create_revocation_state] expects, so the directory is passed on as the
+/// `tails_dir` of the credentials. Missing files are fetched from their tails location and only
+/// kept if their content matches the hash of the revocation registry definition.
+#[derive(Clone)]
+pub struct TailsFileManager {
+    tails_dir: PathBuf,
+    fetcher: Arc<dyn TailsFileFetcher>,
+    max_file_size: u64,
+    max_total_size: Option<u64>,
+}
+
+impl std::fmt::Debug for TailsFileManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TailsFileManager")
+            .field("tails_dir", &self.tails_dir)
+            .field("max_file_size", &self.max_file_size)
+            .field("max_total_size", &self.max_total_size)
+            .finish()
+    }
+}
+
+impl TailsFileManager {
+    pub fn new(tails_dir: impl Into<PathBuf>, fetcher: Arc<dyn TailsFileFetcher>) -> Self {
+        Self {
+            tails_dir: tails_dir.into(),
+            fetcher,
+            max_file_size: DEFAULT_MAX_TAILS_FILE_SIZE,
+            max_total_size: None,
+        }
+    }
+
+    /// Rejects tails files larger than `max_file_size` bytes.
+    pub fn with_max_file_size(mut self, max_file_size: u64) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Rejects tails files which would grow the directory beyond `max_total_size` bytes. Space
+    /// is reclaimed with [TailsFileManager::remove_unused].
+    pub fn with_max_total_size(mut self, max_total_size: u64) -> Self {
+        self.max_total_size = Some(max_total_size);
+        self
+    }
+
+    pub fn tails_dir(&self) -> &Path {
+        &self.tails_dir
+    }
+
+    pub fn tails_file_path(&self, tails_hash: &str) -> VcxResult<PathBuf> {
+        validate_tails_hash(tails_hash)?;
+        Ok(self.tails_dir.join(tails_hash))
+    }
+
+    pub fn has_tails_file(&self, tails_hash: &str) -> VcxResult<bool> {
+        Ok(self.tails_file_path(tails_hash)?.is_file())
+    }
+
+    /// Returns the path of the tails file with the hash, first downloading it from
+    /// `tails_location` unless it's already stored.
+    pub async fn ensure_tails_file(
+        &self,
+        tails_location: &str,
+        tails_hash: &str,
+    ) -> VcxResult<PathBuf> {
+        let path = self.tails_file_path(tails_hash)?;
+        if path.is_file() {
+            return Ok(path);
+        }
+
+        debug!(
+            "ensure_tails_file >> downloading tails file {} from {}",
+            tails_hash, tails_location
+        );
+        let tails = self
+            .fetcher
+            .fetch(tails_location, self.max_file_size)
+            .await?;
+        let size = tails.len() as u64;
+        if size > self.max_file_size {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidRevocationDetails,
+                format!(
+                    "Tails file at {tails_location} is {size} bytes, more than the limit of {} \
+                     bytes",
+                    self.max_file_size
+                ),
+            ));
+        }
+        let actual_hash = bs58::encode(Sha256::digest(&tails)).into_string();
+        if actual_hash != tails_hash {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidRevocationDetails,
+                format!(
+                    "Tails file at {tails_location} has hash {actual_hash}, expected {tails_hash}"
+                ),
+            ));
+        }
+        if let Some(max_total_size) = self.max_total_size {
+            let stored_size = self.stored_size()?;
+            if stored_size + size > max_total_size {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidRevocationDetails,
+                    format!(
+                        "Storing tails file {tails_hash} of {size} bytes would exceed the limit \
+                         of {max_total_size} bytes of tails files, {stored_size} bytes are in use"
+                    ),
+                ));
+            }
+        }
+
+        // written aside and renamed, so that a file stored under its hash is always complete
+        fs::create_dir_all(&self.tails_dir).map_err(io_error)?;
+        let partial_path = path.with_extension(PARTIAL_FILE_EXTENSION);
+        fs::write(&partial_path, tails).map_err(io_error)?;
+        fs::rename(&partial_path, &path).map_err(io_error)?;
+        info!(
+            "ensure_tails_file >> stored tails file {} of {} bytes",
+            tails_hash, size
+        );
+        Ok(path)
+    }
+
+    /// Returns the path of the tails file of the revocation registry, downloading it if needed.
+    pub async fn ensure_tails_file_for_rev_reg(
+        &self,
+        ledger_read: &impl AnoncredsLedgerRead,
+        rev_reg_id: &str,
+    ) -> VcxResult<PathBuf> {
+        let (rev_reg_def, _) = ledger_read
+            .get_rev_reg_def_json(&rev_reg_id.to_owned().try_into()?)
+            .await?;
+        self.ensure_tails_file(
+            &rev_reg_def.value.tails_location,
+            &rev_reg_def.value.tails_hash,
+        )
+        .await
+    }
+
+    /// Removes the stored tails files whose hash isn't in `tails_hashes_in_use`, along with
+    /// leftovers of interrupted downloads. Files not named after a tails hash are left alone, so
+    /// the directory may be shared. Returns the names of the removed files.
+    pub fn remove_unused(&self, tails_hashes_in_use: &HashSet<String>) -> VcxResult<Vec<String>> {
+        let now = SystemTime::now();
+        let mut removed = Vec::new();
+        for file in self.dir_files()? {
+            let partial_of = file
+                .path
+                .extension()
+                .filter(|extension| *extension == PARTIAL_FILE_EXTENSION)
+                .and(file.path.file_stem())
+                .and_then(|stem| stem.to_str());
+            let unused = match partial_of {
+                Some(tails_hash) => {
+                    validate_tails_hash(tails_hash).is_ok()
+                        && now
+                            .duration_since(file.modified)
+                            .is_ok_and(|age| age > PARTIAL_FILE_MAX_AGE)
+                }
+                None => {
+                    validate_tails_hash(&file.name).is_ok()
+                        && !tails_hashes_in_use.contains(&file.name)
+                }
+            };
+            if !unused {
+                continue;
+            }
+            fs::remove_file(&file.path).map_err(io_error)?;
+            debug!("remove_unused >> removed tails file {}", file.name);
+            removed.push(file.name);
+        }
+        Ok(removed)
+    }
+
+    /// Removes the stored tails files which none of the revocable credentials in the wallet
+    /// refers to.
+    pub async fn remove_unused_for_wallet(
+        &self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_read: &impl AnoncredsLedgerRead,
+    ) -> VcxResult<Vec<String>> {
+        let rev_reg_ids: HashSet<String> = anoncreds
+            .prover_get_credentials(wallet, None)
+            .await?
+            .into_iter()
+            .filter_map(|cred| cred.rev_reg_id)
+            .collect();
+        let mut in_use = HashSet::new();
+        for rev_reg_id in rev_reg_ids {
+            let (rev_reg_def, _) = ledger_read
+                .get_rev_reg_def_json(&rev_reg_id.try_into()?)
+                .await?;
+            in_use.insert(rev_reg_def.value.tails_hash);
+        }
+        self.remove_unused(&in_use)
+    }
+
+    /// Total size in bytes of the stored tails files.
+    pub fn stored_size(&self) -> VcxResult<u64> {
+        Ok(self
+            .dir_files()?
+            .iter()
+            .filter(|file| validate_tails_hash(&file.name).is_ok())
+            .map(|file| file.size)
+            .sum())
+    }
+
+    fn dir_files(&self) -> VcxResult<Vec<DirFile>> {
+        let entries = match fs::read_dir(&self.tails_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(io_error(err)),
+        };
+        let mut files = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(io_error)?;
+            let metadata = entry.metadata().map_err(io_error)?;
+            if !metadata.is_file() {
+                continue;
+            }
+            let Some(name) = entry.file_name().to_str().map(ToOwned::to_owned) else {
+                continue;
+            };
+            files.push(DirFile {
+                name,
+                path: entry.path(),
+                size: metadata.len(),
+                modified: metadata.modified().map_err(io_error)?,
+            });
+        }
+        Ok(files)
+    }
+}
+
+struct DirFile {
+    name: String,
+    path: PathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// Tails hashes come from ledger data and messages, and are used as file names, so they must be
+/// proper base58 encoded SHA-256 digests.
+fn validate_tails_hash(tails_hash: &str) -> VcxResult<()> {
+    match bs58::decode(tails_hash).into_vec() {
+        Ok(digest) if digest.len() == 32 => Ok(()),
+        _ => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidRevocationDetails,
+            format!("Invalid tails hash: {tails_hash}"),
+        )),
+    }
+}
+
+fn io_error(err: std::io::Error) -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::IOError,
+        format!("Failed to access tails files: {err}"),
+    )
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use test_utils::tails_server::LocalTailsServer;
+
+    use super::*;
+
+    struct StaticFetcher(Vec<u8>);
+
+    #[async_trait]
+    impl TailsFileFetcher for StaticFetcher {
+        async fn fetch(&self, _tails_location: &str, _max_size: u64) -> VcxResult<Vec<u8>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    fn tails_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tails_file_manager_{name}"));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn hash(tails: &[u8]) -> String {
+        bs58::encode(Sha256::digest(tails)).into_string()
+    }
+
+    #[tokio::test]
+    async fn test_ensure_tails_file_stores_verified_file() {
+        let tails = b"tails content".to_vec();
+        let manager =
+            TailsFileManager::new(tails_dir("stores"), Arc::new(StaticFetcher(tails.clone())));
+        let path = manager
+            .ensure_tails_file("http://tails.example/1", &hash(&tails))
+            .await
+            .unwrap();
+        assert_eq!(path, manager.tails_dir().join(hash(&tails)));
+        assert_eq!(fs::read(&path).unwrap(), tails);
+        assert_eq!(manager.stored_size().unwrap(), tails.len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_ensure_tails_file_rejects_hash_mismatch() {
+        let manager = TailsFileManager::new(
+            tails_dir("mismatch"),
+            Arc::new(StaticFetcher(b"tampered".to_vec())),
+        );
+        let expected = hash(b"tails content");
+        let err = manager
+            .ensure_tails_file("http://tails.example/1", &expected)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidRevocationDetails);
+        assert!(!manager.has_tails_file(&expected).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_ensure_tails_file_enforces_size_limits() {
+        let tails = vec![7; 100];
+        let manager =
+            TailsFileManager::new(tails_dir("limits"), Arc::new(StaticFetcher(tails.clone())))
+                .with_max_file_size(99);
+        assert!(manager
+            .ensure_tails_file("http://tails.example/1", &hash(&tails))
+            .await
+            .is_err());
+
+        let manager = manager.with_max_file_size(100).with_max_total_size(150);
+        fs::create_dir_all(manager.tails_dir()).unwrap();
+        fs::write(manager.tails_dir().join(hash(b"other")), vec![0; 60]).unwrap();
+        assert!(manager
+            .ensure_tails_file("http://tails.example/1", &hash(&tails))
+            .await
+            .is_err());
+
+        manager.remove_unused(&HashSet::new()).unwrap();
+        manager
+            .ensure_tails_file("http://tails.example/1", &hash(&tails))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_http_fetcher_stops_at_max_size() {
+        let tails_server = LocalTailsServer::start().await;
+        let tails_location = format!("{}rev_reg", tails_server.url());
+        HttpClient::new(Default::default())
+            .unwrap()
+            .put(vec![7; 100], &tails_location.parse().unwrap())
+            .await
+            .unwrap();
+
+        let fetcher = HttpTailsFileFetcher::new(Default::default()).unwrap();
+        fetcher.fetch(&tails_location, 99).await.unwrap_err();
+        assert_eq!(
+            fetcher.fetch(&tails_location, 100).await.unwrap(),
+            vec![7; 100]
+        );
+    }
+
+    #[test]
+    fn test_tails_file_path_rejects_invalid_hash() {
+        let manager = TailsFileManager::new(tails_dir("paths"), Arc::new(StaticFetcher(vec![])));
+        assert!(manager.tails_file_path("../../etc/passwd").is_err());
+        assert!(manager.tails_file_path(&hash(b"tails")).is_ok());
+    }
+
+    #[test]
+    fn test_remove_unused_keeps_files_in_use() {
+        let manager = TailsFileManager::new(tails_dir("cleanup"), Arc::new(StaticFetcher(vec![])));
+        fs::create_dir_all(manager.tails_dir()).unwrap();
+        let (used, unused) = (hash(b"used"), hash(b"unused"));
+        for name in [&used, &unused] {
+            fs::write(manager.tails_dir().join(name), b"tails").unwrap();
+        }
+        let removed = manager
+            .remove_unused(&HashSet::from([used.clone()]))
+            .unwrap();
+        assert_eq!(removed, vec![unused.clone()]);
+        assert!(manager.has_tails_file(&used).unwrap());
+        assert!(!manager.has_tails_file(&unused).unwrap());
+    }
+
+    #[test]
+    fn test_remove_unused_keeps_unrelated_files_and_recent_downloads() {
+        let manager = TailsFileManager::new(tails_dir("shared"), Arc::new(StaticFetcher(vec![])));
+        let dir = manager.tails_dir().to_owned();
+        fs::create_dir_all(&dir).unwrap();
+        let unrelated = dir.join("issuer_notes.txt");
+        let downloading = dir.join(format!("{}.partial", hash(b"downloading")));
+        let interrupted_name = format!("{}.partial", hash(b"interrupted"));
+        let interrupted = dir.join(&interrupted_name);
+        for path in [&unrelated, &downloading, &interrupted] {
+            fs::write(path, b"content").unwrap();
+        }
+        fs::File::options()
+            .write(true)
+            .open(&interrupted)
+            .unwrap()
+            .set_modified(SystemTime::now() - 2 * PARTIAL_FILE_MAX_AGE)
+            .unwrap();
+
+        let removed = manager.remove_unused(&HashSet::new()).unwrap();
+        assert_eq!(removed, vec![interrupted_name]);
+        assert!(unrelated.is_file());
+        assert!(downloading.is_file());
+        assert_eq!(manager.stored_size().unwrap(), 0);
+    }
+}
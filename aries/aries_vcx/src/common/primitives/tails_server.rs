@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use shared::http_client::{HttpClient, HttpClientConfig};
+use url::Url;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// Client of a tails server, making the tails files of revocation registries available to
+/// holders, which need them to create proofs of non-revocation.
+#[async_trait]
+pub trait TailsServerClient: Send + Sync {
+    /// Uploads the tails file of the revocation registry and returns the URL it can be
+    /// downloaded from, to be published as the tails location of the registry definition.
+    async fn upload_tails_file(&self, rev_reg_id: &str, tails_file: &Path) -> VcxResult<String>;
+}
+
+#[async_trait]
+impl<T> TailsServerClient for &T
+where
+    T: TailsServerClient + ?Sized,
+{
+    async fn upload_tails_file(&self, rev_reg_id: &str, tails_file: &Path) -> VcxResult<String> {
+        (**self).upload_tails_file(rev_reg_id, tails_file).await
+    }
+}
+
+/// Uploads tails files with a PUT request to `{base_url}/{rev_reg_id}`, from where they are
+/// served back to holders.
+#[derive(Debug, Clone)]
+pub struct HttpTailsServerClient {
+    client: HttpClient,
+    base_url: Url,
+}
+
+impl HttpTailsServerClient {
+    pub fn new(base_url: Url, config: HttpClientConfig) -> VcxResult<Self> {
+        Ok(Self {
+            client: HttpClient::new(config)?,
+            base_url,
+        })
+    }
+
+    /// URL the tails file of the revocation registry is uploaded to and served from.
+    pub fn tails_url(&self, rev_reg_id: &str) -> VcxResult<Url> {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .map_err(|_| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidUrl,
+                    format!("Tails server URL can't be a base: {}", self.base_url),
+                )
+            })?
+            .pop_if_empty()
+            .push(rev_reg_id);
+        Ok(url)
+    }
+}
+
+#[async_trait]
+impl TailsServerClient for HttpTailsServerClient {
+    async fn upload_tails_file(&self, rev_reg_id: &str, tails_file: &Path) -> VcxResult<String> {
+        let tails = std::fs::read(tails_file).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::IOError,
+                format!("Failed to read tails file {}: {err}", tails_file.display()),
+            )
+        })?;
+        let url = self.tails_url(rev_reg_id)?;
+        self.client.put(tails, &url).await?;
+        Ok(url.to_string())
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_tails_url_appends_rev_reg_id() {
+        let rev_reg_id = "4xE68b6S5VRFrKMMG1U95M:4:4xE68b6S5VRFrKMMG1U95M:3:CL:59232:default:\
+                          CL_ACCUM:4ae1cc6c-f6bd-486c-8057-88f2ce74e960";
+        for base_url in [
+            "http://localhost:6543/tails",
+            "http://localhost:6543/tails/",
+        ] {
+            let client =
+                HttpTailsServerClient::new(base_url.parse().unwrap(), Default::default()).unwrap();
+            let url = client.tails_url(rev_reg_id).unwrap();
+            assert_eq!(
+                url.as_str(),
+                format!("http://localhost:6543/tails/{rev_reg_id}")
+            );
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod prover_internal;
+pub mod revocation_state_cache;
 
 use std::collections::HashMap;
 
@@ -11,9 +12,12 @@ use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 
 use crate::{
-    common::proofs::prover::prover_internal::{
-        build_cred_defs_json_prover, build_requested_credentials_json, build_rev_states_json,
-        build_schemas_json_prover, credential_def_identifiers,
+    common::{
+        primitives::tails_file_manager::TailsFileManager,
+        proofs::prover::prover_internal::{
+            build_cred_defs_json_prover, build_requested_credentials_json, build_rev_states_json,
+            build_schemas_json_prover, credential_def_identifiers,
+        },
     },
     errors::error::prelude::*,
     global::settings,
@@ -23,6 +27,7 @@ pub async fn generate_indy_proof(
     wallet: &impl BaseWallet,
     ledger: &impl AnoncredsLedgerRead,
     anoncreds: &impl BaseAnonCreds,
+    tails_file_manager: Option<&TailsFileManager>,
     credentials: &SelectedCredentials,
     self_attested_attrs: HashMap<String, String>,
     proof_req_data_json: PresentationRequest,
@@ -36,8 +41,14 @@ pub async fn generate_indy_proof(
     let mut credentials_identifiers =
         credential_def_identifiers(credentials, &proof_req_data_json)?;
 
-    let revoc_states_json =
-        build_rev_states_json(ledger, anoncreds, &mut credentials_identifiers).await?;
+    let revoc_states_json = build_rev_states_json(
+        wallet,
+        ledger,
+        anoncreds,
+        tails_file_manager,
+        &mut credentials_identifiers,
+    )
+    .await?;
     let requested_credentials = build_requested_credentials_json(
         &credentials_identifiers,
         self_attested_attrs,
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, path::Path};
+use std::collections::HashMap;
 
 use anoncreds_types::data_types::{
     identifiers::{cred_def_id::CredentialDefinitionId, schema_id::SchemaId},
@@ -12,9 +12,10 @@ use aries_vcx_anoncreds::anoncreds::base_anoncreds::{
     BaseAnonCreds, CredentialDefinitionsMap, RevocationStatesMap, SchemasMap,
 };
 use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
-use chrono::Utc;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 
-use crate::errors::error::prelude::*;
+use super::revocation_state_cache::RevocationStateCache;
+use crate::{common::primitives::tails_file_manager::TailsFileManager, errors::error::prelude::*};
 
 // TODO: Move to anoncreds_types
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
@@ -141,8 +142,10 @@ fn _get_revocation_interval(
 }
 
 pub async fn build_rev_states_json(
+    wallet: &impl BaseWallet,
     ledger_read: &impl AnoncredsLedgerRead,
     anoncreds: &impl BaseAnonCreds,
+    tails_file_manager: Option<&TailsFileManager>,
     credentials_identifiers: &mut Vec<CredInfoProver>,
 ) -> VcxResult<RevocationStatesMap> {
     trace!(
@@ -151,36 +154,38 @@ pub async fn build_rev_states_json(
     );
     let mut rtn: RevocationStatesMap = HashMap::new();
     let mut timestamps: HashMap<String, u64> = HashMap::new();
+    let cache = RevocationStateCache::default();
+    let managed_tails_dir =
+        tails_file_manager.map(|manager| manager.tails_dir().to_string_lossy().into_owned());
 
     for cred_info in credentials_identifiers.iter_mut() {
-        if let (Some(rev_reg_id), Some(cred_rev_id), Some(tails_dir)) = (
-            &cred_info.rev_reg_id,
-            &cred_info.cred_rev_id,
-            &cred_info.tails_dir,
-        ) {
+        // credentials selected without a tails dir use the one of the tails file manager
+        let tails_dir = cred_info
+            .tails_dir
+            .clone()
+            .or_else(|| managed_tails_dir.clone());
+        if let (Some(rev_reg_id), Some(cred_rev_id), Some(tails_dir)) =
+            (&cred_info.rev_reg_id, &cred_info.cred_rev_id, &tails_dir)
+        {
             if !rtn.contains_key(rev_reg_id) {
+                if let Some(manager) = tails_file_manager {
+                    if managed_tails_dir.as_ref() == Some(tails_dir) {
+                        manager
+                            .ensure_tails_file_for_rev_reg(ledger_read, rev_reg_id)
+                            .await?;
+                    }
+                }
                 // Does this make sense in case cred_info's for same rev_reg_ids have different
                 // revocation intervals
-                let (_from, to) = if let Some(ref interval) = cred_info.revocation_interval {
-                    (interval.from, interval.to)
-                } else {
-                    (None, None)
-                };
-
-                let parsed_id = &rev_reg_id.to_owned().try_into()?;
-                let (rev_reg_def_json, meta) = ledger_read.get_rev_reg_def_json(parsed_id).await?;
-
-                let on_or_before = to.unwrap_or(Utc::now().timestamp() as u64);
-                let (rev_status_list, timestamp) = ledger_read
-                    .get_rev_status_list(parsed_id, on_or_before, Some(&meta))
-                    .await?;
-
-                let rev_state_json = anoncreds
-                    .create_revocation_state(
-                        Path::new(tails_dir),
-                        rev_reg_def_json,
-                        rev_status_list,
+                let (timestamp, rev_state_json) = cache
+                    .get_revocation_state(
+                        wallet,
+                        ledger_read,
+                        anoncreds,
+                        tails_dir,
+                        rev_reg_id,
                         *cred_rev_id,
+                        cred_info.revocation_interval.as_ref(),
                     )
                     .await?;
 
@@ -281,8 +286,10 @@ pub mod pool_tests {
         let mut empty_credential_identifiers = Vec::new();
         assert_eq!(
             build_rev_states_json(
+                &setup.wallet,
                 &setup.ledger_read,
                 &setup.anoncreds,
+                None,
                 empty_credential_identifiers.as_mut()
             )
             .await?,
@@ -303,8 +310,14 @@ pub mod pool_tests {
             revealed: None,
         };
         assert_eq!(
-            build_rev_states_json(&setup.ledger_read, &setup.anoncreds, vec![cred1].as_mut())
-                .await?,
+            build_rev_states_json(
+                &setup.wallet,
+                &setup.ledger_read,
+                &setup.anoncreds,
+                None,
+                vec![cred1].as_mut()
+            )
+            .await?,
             HashMap::new()
         );
         Ok(())
@@ -322,6 +335,7 @@ pub mod unit_tests {
             LICENCE_CRED_ID, REV_REG_ID, REV_STATE_JSON, SCHEMA_ID,
         },
         devsetup::*,
+        mock_wallet::MockWallet,
         mockdata::{mock_anoncreds::MockAnoncreds, mock_ledger::MockLedger},
     };
 
@@ -706,9 +720,15 @@ pub mod unit_tests {
         let mut cred_info = vec![cred1];
         let anoncreds = MockAnoncreds;
         let ledger_read = MockLedger;
-        let states = build_rev_states_json(&ledger_read, &anoncreds, cred_info.as_mut())
-            .await
-            .unwrap();
+        let states = build_rev_states_json(
+            &MockWallet,
+            &ledger_read,
+            &anoncreds,
+            None,
+            cred_info.as_mut(),
+        )
+        .await
+        .unwrap();
         let expected: RevocationStatesMap = vec![(
             REV_REG_ID.to_string(),
             vec![(1, serde_json::from_str(REV_STATE_JSON).unwrap())]
@@ -0,0 +1,263 @@
+use std::path::Path;
+
+use anoncreds_types::data_types::{
+    identifiers::rev_reg_def_id::RevocationRegistryDefinitionId,
+    ledger::rev_status_list::RevocationStatusList,
+    messages::{pres_request::NonRevokedInterval, revocation_state::CredentialRevocationState},
+};
+use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
+use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_wallet::{
+    errors::error::VcxWalletError,
+    wallet::{
+        base_wallet::{record::Record, record_category::RecordCategory, BaseWallet},
+        record_tags::{RecordTag, RecordTags},
+    },
+};
+use chrono::Utc;
+
+use crate::errors::error::prelude::*;
+
+const REV_REG_ID_TAG: &str = "rev_reg_id";
+
+pub const DEFAULT_MAX_CACHED_STATES: usize = 5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedRevocationState {
+    timestamp: u64,
+    rev_state: CredentialRevocationState,
+    // status list the witness of the state was computed for, needed to update it
+    rev_status_list: RevocationStatusList,
+}
+
+/// Cached states of a credential, ordered by timestamp.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct CachedRevocationStates {
+    states: Vec<CachedRevocationState>,
+}
+
+/// Wallet backed cache of the revocation states of the holder's credentials, keyed by
+/// `(rev_reg_id, cred_rev_id)`.
+///
+/// Creating a revocation state reads the whole tails file, which is slow. Cached states are
+/// reused for requests whose `non_revoked` interval they fall in, and otherwise updated to a
+/// newer status list incrementally. The cache is best-effort: failing to read or write it is
+/// logged, and the revocation state is computed from the ledger as if nothing was cached.
+#[derive(Debug, Clone, Copy)]
+pub struct RevocationStateCache {
+    max_states_per_credential: usize,
+}
+
+impl Default for RevocationStateCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CACHED_STATES)
+    }
+}
+
+impl RevocationStateCache {
+    /// Keeps up to `max_states_per_credential` states of a credential, the newest ones.
+    pub fn new(max_states_per_credential: usize) -> Self {
+        Self {
+            max_states_per_credential: max_states_per_credential.max(1),
+        }
+    }
+
+    /// Returns the timestamp and revocation state of the credential to prove its
+    /// non-revocation within `interval`, defaulting to now.
+    ///
+    /// When the interval has a `from`, the newest cached state within the interval is used
+    /// without contacting the ledger. Otherwise the state is brought up to the status list the
+    /// ledger has at the end of the interval.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn get_revocation_state(
+        &self,
+        wallet: &impl BaseWallet,
+        ledger_read: &impl AnoncredsLedgerRead,
+        anoncreds: &impl BaseAnonCreds,
+        tails_dir: &str,
+        rev_reg_id: &str,
+        cred_rev_id: u32,
+        interval: Option<&NonRevokedInterval>,
+    ) -> VcxResult<(u64, CredentialRevocationState)> {
+        let to = interval
+            .and_then(|interval| interval.to)
+            .unwrap_or(Utc::now().timestamp() as u64);
+        let from = interval.and_then(|interval| interval.from);
+        let name = record_name(rev_reg_id, cred_rev_id);
+        let (mut cached, is_stored) = self.load(wallet, &name).await;
+
+        if let Some(from) = from {
+            if let Some(state) = cached
+                .states
+                .iter()
+                .rev()
+                .find(|state| from <= state.timestamp && state.timestamp <= to)
+            {
+                debug!(
+                    "get_revocation_state >> using cached revocation state of {} at {}",
+                    name, state.timestamp
+                );
+                return Ok((state.timestamp, state.rev_state.clone()));
+            }
+        }
+
+        let parsed_id: RevocationRegistryDefinitionId = rev_reg_id.to_owned().try_into()?;
+        let (rev_reg_def, meta) = ledger_read.get_rev_reg_def_json(&parsed_id).await?;
+        let (rev_status_list, timestamp) = ledger_read
+            .get_rev_status_list(&parsed_id, to, Some(&meta))
+            .await?;
+
+        if let Some(state) = cached
+            .states
+            .iter()
+            .find(|state| state.timestamp == timestamp)
+        {
+            debug!(
+                "get_revocation_state >> cached revocation state of {} is up to date at {}",
+                name, timestamp
+            );
+            return Ok((timestamp, state.rev_state.clone()));
+        }
+
+        let previous = cached
+            .states
+            .iter()
+            .rev()
+            .find(|state| state.timestamp < timestamp)
+            .cloned();
+        let rev_state = match previous {
+            Some(previous) => {
+                debug!(
+                    "get_revocation_state >> updating revocation state of {} from {} to {}",
+                    name, previous.timestamp, timestamp
+                );
+                anoncreds
+                    .update_revocation_state(
+                        Path::new(tails_dir),
+                        rev_reg_def,
+                        rev_status_list.clone(),
+                        cred_rev_id,
+                        previous.rev_state,
+                        previous.rev_status_list,
+                    )
+                    .await?
+            }
+            None => {
+                anoncreds
+                    .create_revocation_state(
+                        Path::new(tails_dir),
+                        rev_reg_def,
+                        rev_status_list.clone(),
+                        cred_rev_id,
+                    )
+                    .await?
+            }
+        };
+
+        cached.states.push(CachedRevocationState {
+            timestamp,
+            rev_state: rev_state.clone(),
+            rev_status_list,
+        });
+        cached.states.sort_by_key(|state| state.timestamp);
+        let excess = cached
+            .states
+            .len()
+            .saturating_sub(self.max_states_per_credential);
+        cached.states.drain(..excess);
+        if let Err(err) = self
+            .store(wallet, &name, rev_reg_id, &cached, is_stored)
+            .await
+        {
+            warn!(
+                "Failed to cache revocation state of {} at {}; err: {}",
+                name, timestamp, err
+            );
+        }
+
+        Ok((timestamp, rev_state))
+    }
+
+    /// Removes the cached states of the credential, e.g. once it's deleted.
+    pub async fn clear(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &str,
+        cred_rev_id: u32,
+    ) -> VcxResult<()> {
+        match wallet
+            .delete_record(
+                RecordCategory::RevState,
+                &record_name(rev_reg_id, cred_rev_id),
+            )
+            .await
+        {
+            Ok(()) | Err(VcxWalletError::RecordNotFound(_)) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    // Returns the cached states along with whether a record holding them exists.
+    async fn load(&self, wallet: &impl BaseWallet, name: &str) -> (CachedRevocationStates, bool) {
+        match wallet.get_record(RecordCategory::RevState, name).await {
+            Ok(record) => match serde_json::from_str(record.value()) {
+                Ok(cached) => (cached, true),
+                Err(err) => {
+                    warn!(
+                        "Discarding unreadable cached revocation states of {}; err: {}",
+                        name, err
+                    );
+                    (CachedRevocationStates::default(), true)
+                }
+            },
+            Err(VcxWalletError::RecordNotFound(_)) => (CachedRevocationStates::default(), false),
+            Err(err) => {
+                warn!(
+                    "Failed to read cached revocation states of {}; err: {}",
+                    name, err
+                );
+                (CachedRevocationStates::default(), false)
+            }
+        }
+    }
+
+    async fn store(
+        &self,
+        wallet: &impl BaseWallet,
+        name: &str,
+        rev_reg_id: &str,
+        cached: &CachedRevocationStates,
+        is_stored: bool,
+    ) -> VcxResult<()> {
+        let value = serde_json::to_string(cached)?;
+        if is_stored {
+            wallet
+                .update_record_value(RecordCategory::RevState, name, &value)
+                .await?;
+        } else {
+            let record = Record::builder()
+                .category(RecordCategory::RevState)
+                .name(name.to_owned())
+                .value(value.clone())
+                .tags(RecordTags::new(vec![RecordTag::new(
+                    REV_REG_ID_TAG,
+                    rev_reg_id,
+                )]))
+                .build();
+            match wallet.add_record(record).await {
+                // stored concurrently, e.g. by another presentation using the same credential
+                Err(VcxWalletError::DuplicateRecord(_)) => {
+                    wallet
+                        .update_record_value(RecordCategory::RevState, name, &value)
+                        .await?
+                }
+                res => res?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn record_name(rev_reg_id: &str, cred_rev_id: u32) -> String {
+    format!("{rev_reg_id}::{cred_rev_id}")
+}
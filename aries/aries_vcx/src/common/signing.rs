@@ -43,13 +43,14 @@ async fn get_signature_data(
     Ok((signature, sig_data))
 }
 
-pub async fn sign_connection_response(
+/// Signs `data` with `key` according to the `ed25519Sha512_single` signature scheme, which
+/// covers the current time followed by the data.
+pub async fn sign_data(
     wallet: &impl BaseWallet,
     key: &str,
-    con_data: &ConnectionData,
+    data: String,
 ) -> VcxResult<ConnectionSignature> {
-    let con_data = json!(con_data).to_string();
-    let (signature, sig_data) = get_signature_data(wallet, con_data, key).await?;
+    let (signature, sig_data) = get_signature_data(wallet, data, key).await?;
 
     let sig_data = URL_SAFE_LENIENT.encode(sig_data);
     let signature = URL_SAFE_LENIENT.encode(signature);
@@ -60,52 +61,82 @@ pub async fn sign_connection_response(
         key.to_string()
     };
 
-    let connection_sig = ConnectionSignature::new(signature, sig_data, key_b58);
-
-    Ok(connection_sig)
+    Ok(ConnectionSignature::new(signature, sig_data, key_b58))
 }
 
-pub async fn decode_signed_connection_response(
+/// Verifies an `ed25519Sha512_single` signature made by `their_vk` and returns the signed data,
+/// stripped of the signing time.
+pub async fn verify_signed_data(
     wallet: &impl BaseWallet,
-    response: ResponseContent,
+    signature: &ConnectionSignature,
     their_vk: &str,
-) -> VcxResult<ConnectionData> {
-    let signature = URL_SAFE_LENIENT
-        .decode(response.connection_sig.signature.as_bytes())
+) -> VcxResult<Vec<u8>> {
+    let sig_bytes = URL_SAFE_LENIENT
+        .decode(signature.signature.as_bytes())
         .map_err(|err| {
             AriesVcxError::from_msg(
                 AriesVcxErrorKind::InvalidJson,
-                format!("Cannot decode ConnectionResponse: {:?}", err),
+                format!("Cannot decode signature: {:?}", err),
             )
         })?;
 
-    let sig_data = base64url_decode(&response.connection_sig.sig_data)?;
+    let sig_data = base64url_decode(&signature.sig_data)?;
 
     if !wallet
         .verify(
             &Key::from_base58(their_vk, KeyType::Ed25519)?,
             &sig_data,
-            &signature,
+            &sig_bytes,
         )
         .await?
     {
         return Err(AriesVcxError::from_msg(
             AriesVcxErrorKind::InvalidJson,
-            "ConnectionResponse signature is invalid for original Invite recipient key",
+            "Signature is invalid for the expected signer",
         ));
     }
 
-    if response.connection_sig.signer != their_vk {
+    if signature.signer != their_vk {
         return Err(AriesVcxError::from_msg(
             AriesVcxErrorKind::InvalidJson,
-            "Signer declared in ConnectionResponse signed response is not matching the actual \
-             signer. Connection ",
+            "Signer declared in the signature is not matching the actual signer",
         ));
     }
 
-    let sig_data = &sig_data[8..];
+    if sig_data.len() < 8 {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            "Signed data is missing the signing time",
+        ));
+    }
+
+    Ok(sig_data[8..].to_vec())
+}
+
+pub async fn sign_connection_response(
+    wallet: &impl BaseWallet,
+    key: &str,
+    con_data: &ConnectionData,
+) -> VcxResult<ConnectionSignature> {
+    let con_data = json!(con_data).to_string();
+    sign_data(wallet, key, con_data).await
+}
+
+pub async fn decode_signed_connection_response(
+    wallet: &impl BaseWallet,
+    response: ResponseContent,
+    their_vk: &str,
+) -> VcxResult<ConnectionData> {
+    let sig_data = verify_signed_data(wallet, &response.connection_sig, their_vk)
+        .await
+        .map_err(|err| {
+            AriesVcxError::from_msg(
+                err.kind(),
+                format!("Cannot verify ConnectionResponse signature: {}", err),
+            )
+        })?;
 
-    let connection: ConnectionData = serde_json::from_slice(sig_data)
+    let connection: ConnectionData = serde_json::from_slice(&sig_data)
         .map_err(|err| AriesVcxError::from_msg(AriesVcxErrorKind::InvalidJson, err.to_string()))?;
 
     Ok(connection)
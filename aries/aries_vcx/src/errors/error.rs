@@ -166,6 +166,8 @@ pub enum AriesVcxErrorKind {
 
     #[error("Invalid message format")]
     InvalidMessageFormat,
+    #[error("The counterparty reported a problem")]
+    ProblemReportReceived,
 }
 
 #[derive(thiserror::Error)]
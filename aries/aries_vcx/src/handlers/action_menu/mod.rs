@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+
+use messages::msg_fields::protocols::action_menu::{
+    menu::{Menu, MenuContent, MenuOption},
+    menu_request::MenuRequest,
+    perform::Perform,
+};
+
+use super::util::matches_opt_thread_id;
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::{
+        action_menu::{
+            build_menu, build_menu_request, build_perform, validate_menu, validate_selection,
+        },
+        SendClosure,
+    },
+};
+
+/// Side of the action menu protocol requesting menus and performing their options.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ActionMenuRequester {
+    menu_request: Option<MenuRequest>,
+    menu: Option<Menu>,
+}
+
+impl ActionMenuRequester {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The menu most recently received, unless one of its options has been performed since.
+    pub fn get_menu(&self) -> Option<&MenuContent> {
+        self.menu.as_ref().map(|menu| &menu.content)
+    }
+
+    pub async fn send_menu_request(&mut self, send_message: SendClosure<'_>) -> VcxResult<()> {
+        let menu_request = build_menu_request();
+        send_message(menu_request.clone().into()).await?;
+        self.menu_request = Some(menu_request);
+        Ok(())
+    }
+
+    /// Stores the menu, replacing the previous one. The responder may send a menu at any time,
+    /// but one answering our menu request must be on its thread.
+    pub fn handle_menu(&mut self, menu: Menu) -> VcxResult<()> {
+        if let Some(request) = self.menu_request.as_ref() {
+            if !matches_opt_thread_id!(menu, request.id.as_str()) {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidState,
+                    "Thread ID mismatch",
+                ));
+            }
+        }
+        validate_menu(&menu.content)?;
+        self.menu_request = None;
+        self.menu = Some(menu);
+        Ok(())
+    }
+
+    /// Performs an option of the current menu, which is then discarded as the responder is
+    /// expected to follow up with a new one if needed.
+    pub async fn send_perform(
+        &mut self,
+        name: &str,
+        params: HashMap<String, String>,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<()> {
+        let menu = self.menu.as_ref().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "No menu to perform an option of has been received",
+            )
+        })?;
+        validate_selection(&menu.content, name, &params)?;
+        let thread_id = menu
+            .decorators
+            .thread
+            .as_ref()
+            .map(|thread| thread.thid.clone())
+            .unwrap_or_else(|| menu.id.clone());
+
+        send_message(build_perform(name.to_owned(), params, Some(thread_id)).into()).await?;
+        self.menu = None;
+        Ok(())
+    }
+}
+
+/// Side of the action menu protocol offering a menu and acting on the options performed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActionMenuResponder {
+    menu: MenuContent,
+}
+
+impl ActionMenuResponder {
+    pub fn new(menu: MenuContent) -> VcxResult<Self> {
+        validate_menu(&menu)?;
+        Ok(Self { menu })
+    }
+
+    pub fn get_menu(&self) -> &MenuContent {
+        &self.menu
+    }
+
+    pub fn update_menu(&mut self, menu: MenuContent) -> VcxResult<()> {
+        validate_menu(&menu)?;
+        self.menu = menu;
+        Ok(())
+    }
+
+    /// Sends the menu unprompted, starting a new thread.
+    pub async fn send_menu(&self, send_message: SendClosure<'_>) -> VcxResult<()> {
+        send_message(build_menu(self.menu.clone(), None).into()).await
+    }
+
+    pub async fn handle_menu_request(
+        &self,
+        menu_request: &MenuRequest,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<()> {
+        let thread_id = menu_request
+            .decorators
+            .thread
+            .as_ref()
+            .map(|thread| thread.thid.clone())
+            .unwrap_or_else(|| menu_request.id.clone());
+        send_message(build_menu(self.menu.clone(), Some(thread_id)).into()).await
+    }
+
+    /// Validates the performed option against the menu and returns it, so that the caller can
+    /// act on it. If the selection is invalid, the caller may answer with
+    /// [`ActionMenuResponder::send_error_menu`].
+    pub fn handle_perform(&self, perform: &Perform) -> VcxResult<&MenuOption> {
+        validate_selection(&self.menu, &perform.content.name, &perform.content.params)
+    }
+
+    /// Sends the menu again, on the thread of the failed `perform`, explaining what went wrong.
+    pub async fn send_error_menu(
+        &self,
+        perform: &Perform,
+        errormsg: String,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<()> {
+        let thread_id = perform
+            .decorators
+            .thread
+            .as_ref()
+            .map(|thread| thread.thid.clone())
+            .unwrap_or_else(|| perform.id.clone());
+        let content = MenuContent {
+            errormsg: Some(errormsg),
+            ..self.menu.clone()
+        };
+        send_message(build_menu(content, Some(thread_id)).into()).await
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use messages::{
+        msg_fields::protocols::action_menu::{
+            menu::{MenuForm, MenuFormParam},
+            ActionMenu,
+        },
+        AriesMessage,
+    };
+
+    use super::*;
+    use crate::protocols::test_utils::SentMessages;
+
+    fn _menu_content() -> MenuContent {
+        let search = MenuOption::builder()
+            .name("search".to_owned())
+            .title("Search".to_owned())
+            .form(
+                MenuForm::builder()
+                    .params(vec![MenuFormParam::builder()
+                        .name("query".to_owned())
+                        .title("Query".to_owned())
+                        .required(true)
+                        .build()])
+                    .build(),
+            )
+            .build();
+        let admin = MenuOption::builder()
+            .name("admin".to_owned())
+            .title("Administration".to_owned())
+            .disabled(true)
+            .build();
+        MenuContent::builder()
+            .title("Main menu".to_owned())
+            .options(vec![search, admin])
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_menu_request_menu_perform() {
+        let sent = SentMessages::default();
+        let mut requester = ActionMenuRequester::new();
+        let responder = ActionMenuResponder::new(_menu_content()).unwrap();
+
+        requester
+            .send_menu_request(sent.send_closure())
+            .await
+            .unwrap();
+        let AriesMessage::ActionMenu(ActionMenu::MenuRequest(menu_request)) = sent.last().unwrap()
+        else {
+            panic!("expected a menu request");
+        };
+        responder
+            .handle_menu_request(&menu_request, sent.send_closure())
+            .await
+            .unwrap();
+        let AriesMessage::ActionMenu(ActionMenu::Menu(menu)) = sent.last().unwrap() else {
+            panic!("expected a menu");
+        };
+        requester.handle_menu(menu).unwrap();
+        assert_eq!(requester.get_menu(), Some(&_menu_content()));
+
+        let params = HashMap::from([("query".to_owned(), "Alice".to_owned())]);
+        requester
+            .send_perform("search", params, sent.send_closure())
+            .await
+            .unwrap();
+        assert!(requester.get_menu().is_none());
+        let AriesMessage::ActionMenu(ActionMenu::Perform(perform)) = sent.last().unwrap() else {
+            panic!("expected a perform");
+        };
+        assert_eq!(
+            perform.decorators.thread.as_ref().unwrap().thid,
+            menu_request.id
+        );
+        assert_eq!(responder.handle_perform(&perform).unwrap().name, "search");
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_invalid_selections() {
+        let sent = SentMessages::default();
+        let mut requester = ActionMenuRequester::new();
+        let responder = ActionMenuResponder::new(_menu_content()).unwrap();
+
+        requester
+            .send_perform("search", HashMap::new(), sent.send_closure())
+            .await
+            .unwrap_err();
+
+        responder.send_menu(sent.send_closure()).await.unwrap();
+        let AriesMessage::ActionMenu(ActionMenu::Menu(menu)) = sent.last().unwrap() else {
+            panic!("expected a menu");
+        };
+        requester.handle_menu(menu).unwrap();
+        for (name, params) in [
+            ("unknown", HashMap::new()),
+            ("admin", HashMap::new()),
+            ("search", HashMap::new()),
+            (
+                "search",
+                HashMap::from([
+                    ("query".to_owned(), "Alice".to_owned()),
+                    ("limit".to_owned(), "10".to_owned()),
+                ]),
+            ),
+        ] {
+            requester
+                .send_perform(name, params.clone(), sent.send_closure())
+                .await
+                .unwrap_err();
+            let perform = build_perform(name.to_owned(), params, None);
+            responder.handle_perform(&perform).unwrap_err();
+        }
+        assert_eq!(sent.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_error_menu_is_sent_on_perform_thread() {
+        let sent = SentMessages::default();
+        let responder = ActionMenuResponder::new(_menu_content()).unwrap();
+        let perform = build_perform("admin".to_owned(), HashMap::new(), None);
+
+        responder
+            .send_error_menu(
+                &perform,
+                "Administration is disabled".to_owned(),
+                sent.send_closure(),
+            )
+            .await
+            .unwrap();
+
+        let AriesMessage::ActionMenu(ActionMenu::Menu(menu)) = sent.last().unwrap() else {
+            panic!("expected a menu");
+        };
+        assert_eq!(menu.decorators.thread.unwrap().thid, perform.id);
+        assert_eq!(
+            menu.content.errormsg.as_deref(),
+            Some("Administration is disabled")
+        );
+    }
+
+    #[test]
+    fn test_should_reject_duplicate_option_names() {
+        let mut menu = _menu_content();
+        menu.options.push(menu.options[0].clone());
+        ActionMenuResponder::new(menu).unwrap_err();
+    }
+}
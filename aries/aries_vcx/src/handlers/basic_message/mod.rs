@@ -0,0 +1,480 @@
+use aries_vcx_wallet::wallet::{
+    base_wallet::{record::Record, record_category::RecordCategory, BaseWallet},
+    record_tags::{RecordTag, RecordTags},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use messages::{
+    decorators::localization::Locale, msg_fields::protocols::basic_message::BasicMessage,
+    AriesMessage,
+};
+
+use super::dispatcher::{InboundMessage, MessageHandler};
+use crate::{
+    errors::error::prelude::*,
+    protocols::{
+        basic_message::{build_basic_message, get_locale},
+        SendClosure,
+    },
+};
+
+const CONNECTION_ID_TAG: &str = "connection_id";
+const DIRECTION_TAG: &str = "direction";
+// Plaintext, so that the wallet can compare it in range queries.
+const TIMESTAMP_TAG: &str = "~timestamp";
+
+/// Default number of messages in a page of [`BasicMessageService::get_history`].
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageDirection {
+    Sent,
+    Received,
+}
+
+impl MessageDirection {
+    fn as_tag(&self) -> &'static str {
+        match self {
+            Self::Sent => "sent",
+            Self::Received => "received",
+        }
+    }
+}
+
+/// A basic message as kept in the conversation history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredBasicMessage {
+    /// Id of the basic message itself.
+    pub id: String,
+    pub connection_id: String,
+    pub direction: MessageDirection,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<Locale>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    /// Time the sender claims to have sent the message at.
+    pub sent_time: DateTime<Utc>,
+    /// Time the message was sent or received by us, which the history is ordered by.
+    pub timestamp: DateTime<Utc>,
+}
+
+impl StoredBasicMessage {
+    fn new(
+        connection_id: &str,
+        direction: MessageDirection,
+        message: &BasicMessage,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: message.id.clone(),
+            connection_id: connection_id.to_owned(),
+            direction,
+            content: message.content.content.clone(),
+            locale: get_locale(message),
+            thread_id: message
+                .decorators
+                .thread
+                .as_ref()
+                .map(|thread| thread.thid.clone()),
+            sent_time: message.content.sent_time,
+            timestamp,
+        }
+    }
+
+    fn record_name(&self) -> String {
+        record_name(&self.connection_id, self.direction, &self.id)
+    }
+
+    fn tags(&self) -> RecordTags {
+        RecordTags::new(vec![
+            RecordTag::new(CONNECTION_ID_TAG, &self.connection_id),
+            RecordTag::new(DIRECTION_TAG, self.direction.as_tag()),
+            RecordTag::new(TIMESTAMP_TAG, &timestamp_tag(&self.timestamp)),
+        ])
+    }
+}
+
+// Message ids are chosen by the sender, so they are only unique within the messages of a
+// connection going in one direction. Encoded as a json array, so that no combination of
+// connection id and message id can collide with another.
+fn record_name(connection_id: &str, direction: MessageDirection, id: &str) -> String {
+    json!([connection_id, direction.as_tag(), id]).to_string()
+}
+
+// Zero padded, so that the lexicographic order of the tag values is chronological.
+fn timestamp_tag(timestamp: &DateTime<Utc>) -> String {
+    format!("{:020}", timestamp.timestamp_millis().max(0))
+}
+
+/// Filter and page of a [`BasicMessageService::get_history`] query. Messages are returned
+/// newest first.
+///
+/// The connection and time range filters are evaluated by the wallet, whereas the page is cut
+/// out of all the matching messages. Bound the time range to bound the work of a query on a
+/// long conversation.
+#[derive(Debug, Clone)]
+pub struct HistoryQuery {
+    connection_id: Option<String>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    offset: usize,
+    limit: usize,
+}
+
+impl Default for HistoryQuery {
+    fn default() -> Self {
+        Self {
+            connection_id: None,
+            since: None,
+            until: None,
+            offset: 0,
+            limit: DEFAULT_PAGE_SIZE,
+        }
+    }
+}
+
+impl HistoryQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn connection_id(mut self, connection_id: &str) -> Self {
+        self.connection_id = Some(connection_id.to_owned());
+        self
+    }
+
+    /// Only messages stored at or after the given time.
+    pub fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Only messages stored before the given time.
+    pub fn until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// The query for the page following the one returned for this query.
+    pub fn next_page(&self) -> Self {
+        Self {
+            offset: self.offset + self.limit,
+            ..self.clone()
+        }
+    }
+
+    fn to_wql(&self) -> Option<String> {
+        let mut wql = serde_json::Map::new();
+        if let Some(connection_id) = &self.connection_id {
+            wql.insert(CONNECTION_ID_TAG.to_owned(), json!(connection_id));
+        }
+        let mut range = serde_json::Map::new();
+        if let Some(since) = &self.since {
+            range.insert("$gte".to_owned(), json!(timestamp_tag(since)));
+        }
+        if let Some(until) = &self.until {
+            range.insert("$lt".to_owned(), json!(timestamp_tag(until)));
+        }
+        if !range.is_empty() {
+            wql.insert(TIMESTAMP_TAG.to_owned(), serde_json::Value::Object(range));
+        }
+        (!wql.is_empty()).then(|| serde_json::Value::Object(wql).to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryPage {
+    pub messages: Vec<StoredBasicMessage>,
+    /// Total number of messages matching the query, across all pages.
+    pub total: usize,
+}
+
+impl HistoryPage {
+    pub fn has_more(&self, query: &HistoryQuery) -> bool {
+        query.offset + self.messages.len() < self.total
+    }
+}
+
+/// Sends and receives basic messages (Aries RFC 0095) and keeps the conversation history of
+/// each connection in the wallet, so that it survives restarts.
+///
+/// Sending goes through a [`SendClosure`], so the service works with any kind of connection.
+/// Received messages are stored by [`BasicMessageService::handle_message`], or by registering
+/// the service with the [`crate::handlers::dispatcher::Dispatcher`].
+#[derive(Debug)]
+pub struct BasicMessageService<W> {
+    wallet: W,
+}
+
+impl<W> BasicMessageService<W>
+where
+    W: BaseWallet,
+{
+    pub fn new(wallet: W) -> Self {
+        Self { wallet }
+    }
+
+    /// Sends a message to the connection and stores it in the history once it has been sent.
+    pub async fn send_message(
+        &self,
+        connection_id: &str,
+        content: String,
+        locale: Option<Locale>,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<StoredBasicMessage> {
+        self.send(connection_id, content, locale, None, send_message)
+            .await
+    }
+
+    /// Like [`Self::send_message`], but replies on the thread of an earlier message.
+    pub async fn send_reply(
+        &self,
+        connection_id: &str,
+        content: String,
+        locale: Option<Locale>,
+        thread_id: String,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<StoredBasicMessage> {
+        self.send(
+            connection_id,
+            content,
+            locale,
+            Some(thread_id),
+            send_message,
+        )
+        .await
+    }
+
+    async fn send(
+        &self,
+        connection_id: &str,
+        content: String,
+        locale: Option<Locale>,
+        thread_id: Option<String>,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<StoredBasicMessage> {
+        let message = build_basic_message(content, locale, thread_id);
+        let stored = StoredBasicMessage::new(
+            connection_id,
+            MessageDirection::Sent,
+            &message,
+            message.content.sent_time,
+        );
+        send_message(message.into()).await?;
+        self.store(&stored).await?;
+        Ok(stored)
+    }
+
+    /// Stores a message received over the connection. A message which is delivered again over
+    /// the same connection is not stored twice, the originally stored one is returned instead.
+    pub async fn handle_message(
+        &self,
+        connection_id: &str,
+        message: &BasicMessage,
+    ) -> VcxResult<StoredBasicMessage> {
+        let stored = StoredBasicMessage::new(
+            connection_id,
+            MessageDirection::Received,
+            message,
+            Utc::now(),
+        );
+        match self.store(&stored).await {
+            Ok(()) => Ok(stored),
+            Err(err) if err.kind() == AriesVcxErrorKind::DuplicationWalletRecord => {
+                debug!(
+                    "Basic message {} was already received over connection {}, ignoring the \
+                     redelivery",
+                    message.id, connection_id
+                );
+                self.get_message(connection_id, MessageDirection::Received, &message.id)
+                    .await
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn get_message(
+        &self,
+        connection_id: &str,
+        direction: MessageDirection,
+        id: &str,
+    ) -> VcxResult<StoredBasicMessage> {
+        let record = self
+            .wallet
+            .get_record(
+                RecordCategory::BasicMessage,
+                &record_name(connection_id, direction, id),
+            )
+            .await?;
+        deserialize_record(&record)
+    }
+
+    /// Returns a page of the stored messages matching the query, newest first. See
+    /// [`HistoryQuery`] for what bounds the work of a query.
+    pub async fn get_history(&self, query: &HistoryQuery) -> VcxResult<HistoryPage> {
+        let mut messages = self
+            .wallet
+            .search_record(RecordCategory::BasicMessage, query.to_wql())
+            .await?
+            .iter()
+            .map(deserialize_record)
+            .collect::<VcxResult<Vec<_>>>()?;
+        messages.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| b.id.cmp(&a.id)));
+        let total = messages.len();
+        let messages = messages
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit)
+            .collect();
+        Ok(HistoryPage { messages, total })
+    }
+
+    /// Deletes the whole history of the connection, returning the number of deleted messages.
+    pub async fn delete_history(&self, connection_id: &str) -> VcxResult<usize> {
+        let records = self
+            .wallet
+            .search_record(
+                RecordCategory::BasicMessage,
+                HistoryQuery::new().connection_id(connection_id).to_wql(),
+            )
+            .await?;
+        for record in records.iter() {
+            self.wallet
+                .delete_record(RecordCategory::BasicMessage, record.name())
+                .await?;
+        }
+        Ok(records.len())
+    }
+
+    async fn store(&self, stored: &StoredBasicMessage) -> VcxResult<()> {
+        let record = Record::builder()
+            .category(RecordCategory::BasicMessage)
+            .name(stored.record_name())
+            .value(serde_json::to_string(stored)?)
+            .tags(stored.tags())
+            .build();
+        Ok(self.wallet.add_record(record).await?)
+    }
+}
+
+#[async_trait]
+impl<W> MessageHandler for BasicMessageService<W>
+where
+    W: BaseWallet,
+{
+    async fn handle(&self, inbound: InboundMessage) -> VcxResult<()> {
+        let AriesMessage::BasicMessage(message) = &inbound.message else {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Expected a basic message, received: {:?}", inbound.message),
+            ));
+        };
+        let connection_id = inbound.connection_id.as_deref().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                format!(
+                    "Basic message {} did not arrive over a known connection",
+                    message.id
+                ),
+            )
+        })?;
+        self.handle_message(connection_id, message)
+            .await
+            .map(|_| ())
+    }
+}
+
+fn deserialize_record(record: &Record) -> VcxResult<StoredBasicMessage> {
+    serde_json::from_str(record.value()).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!(
+                "Cannot deserialize basic message {}: {}",
+                record.name(),
+                err
+            ),
+        )
+    })
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_history_query_wql() {
+        assert_eq!(HistoryQuery::new().to_wql(), None);
+
+        let since = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        let wql: serde_json::Value = serde_json::from_str(
+            &HistoryQuery::new()
+                .connection_id("connection-1")
+                .since(since)
+                .to_wql()
+                .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            wql,
+            json!({
+                "connection_id": "connection-1",
+                "~timestamp": { "$gte": "00000001700000000000" }
+            })
+        );
+    }
+
+    #[test]
+    fn test_history_paging() {
+        let query = HistoryQuery::new().connection_id("connection-1").limit(2);
+        let page = HistoryPage {
+            messages: vec![],
+            total: 3,
+        };
+        assert!(page.has_more(&query));
+
+        let next = query.next_page();
+        assert_eq!(next.offset, 2);
+        assert_eq!(next.connection_id.as_deref(), Some("connection-1"));
+        let page = HistoryPage {
+            messages: vec![],
+            total: 2,
+        };
+        assert!(!page.has_more(&next));
+    }
+
+    #[test]
+    fn test_record_names_are_scoped_to_connection_and_direction() {
+        let name = record_name("connection-1", MessageDirection::Received, "id");
+        assert_ne!(
+            name,
+            record_name("connection-2", MessageDirection::Received, "id")
+        );
+        assert_ne!(
+            name,
+            record_name("connection-1", MessageDirection::Sent, "id")
+        );
+        assert_ne!(
+            record_name("a:b", MessageDirection::Sent, "c"),
+            record_name("a", MessageDirection::Sent, "b:c")
+        );
+    }
+
+    #[test]
+    fn test_timestamp_tags_order_chronologically() {
+        let earlier = DateTime::from_timestamp(999, 0).unwrap();
+        let later = DateTime::from_timestamp(1_000, 0).unwrap();
+        assert!(timestamp_tag(&earlier) < timestamp_tag(&later));
+    }
+}
@@ -0,0 +1,393 @@
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use messages::{
+    decorators::service::Service,
+    msg_fields::protocols::{
+        cred_issuance::{v1::CredentialIssuanceV1, CredentialIssuance},
+        present_proof::{v1::PresentProofV1, PresentProof},
+    },
+    AriesMessage,
+};
+use public_key::{Key, KeyType};
+use url::Url;
+
+use crate::{
+    errors::error::prelude::*, protocols::oob::normalize_keys_as_naked, transport::Transport,
+    utils::encryption_envelope::EncryptionEnvelope,
+};
+
+/// Our side of a present-proof or issuance exchange run without a connection, as in
+/// [Aries RFC 0056](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0056-service-decorator/README.md>).
+///
+/// Messages decorated with its `~service` tell the counterparty where to send the response,
+/// e.g. a presentation request embedded in an out-of-band invitation shown as a QR code.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EphemeralService {
+    verkey: String,
+    service: Service,
+}
+
+impl EphemeralService {
+    /// Creates a new key to receive responses with, reachable at `service_endpoint`.
+    pub async fn create(
+        wallet: &impl BaseWallet,
+        service_endpoint: Url,
+        routing_keys: Vec<String>,
+    ) -> VcxResult<Self> {
+        let did_data = wallet.create_and_store_my_did(None, None).await?;
+        let verkey = did_data.verkey().base58();
+        let service = Service::builder()
+            .recipient_keys(vec![verkey.clone()])
+            .routing_keys(routing_keys)
+            .service_endpoint(service_endpoint)
+            .build();
+        Ok(Self { verkey, service })
+    }
+
+    pub fn verkey(&self) -> &str {
+        &self.verkey
+    }
+
+    pub fn service(&self) -> &Service {
+        &self.service
+    }
+
+    /// Sets our `~service` decorator on the message, so that the counterparty can respond.
+    pub fn decorate(&self, message: AriesMessage) -> VcxResult<AriesMessage> {
+        set_service(message, self.service.clone())
+    }
+
+    /// Packs the message from our key to the service of the counterparty, typically taken
+    /// from the message being responded to with [`get_service`], and sends it.
+    pub async fn send_message(
+        &self,
+        wallet: &impl BaseWallet,
+        message: &AriesMessage,
+        their_service: &Service,
+        transport: &impl Transport,
+    ) -> VcxResult<()> {
+        let recipient_key = recipient_key(their_service)?;
+        let routing_keys = normalize_keys_as_naked(&their_service.routing_keys)?
+            .iter()
+            .map(|key| Key::from_base58(key, KeyType::Ed25519))
+            .collect::<Result<Vec<_>, _>>()?;
+        let sender_key = Key::from_base58(&self.verkey, KeyType::Ed25519)?;
+
+        let envelope = EncryptionEnvelope::create_from_keys(
+            wallet,
+            json!(message).to_string().as_bytes(),
+            Some(sender_key),
+            recipient_key,
+            routing_keys,
+        )
+        .await?;
+        transport
+            .send_message(envelope.0, &their_service.service_endpoint)
+            .await
+    }
+}
+
+/// A present-proof or issuance exchange run over our [`EphemeralService`] rather than a
+/// connection.
+///
+/// The service of the counterparty is learned from the `~service` decorator of the messages
+/// received, so that each response is sent where the counterparty asked for it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConnectionlessExchange {
+    our_service: EphemeralService,
+    their_service: Option<Service>,
+}
+
+impl ConnectionlessExchange {
+    pub fn new(our_service: EphemeralService) -> Self {
+        Self {
+            our_service,
+            their_service: None,
+        }
+    }
+
+    pub fn our_service(&self) -> &EphemeralService {
+        &self.our_service
+    }
+
+    pub fn their_service(&self) -> Option<&Service> {
+        self.their_service.as_ref()
+    }
+
+    /// Sets our `~service` decorator on the first message of the exchange, e.g. a presentation
+    /// request to be attached to an out-of-band invitation.
+    pub fn decorate(&self, message: AriesMessage) -> VcxResult<AriesMessage> {
+        self.our_service.decorate(message)
+    }
+
+    /// Records where to respond to a message received without a connection, such as the
+    /// request extracted from an out-of-band invitation.
+    pub fn receive(&mut self, message: &AriesMessage) {
+        if let Some(service) = get_service(message) {
+            self.their_service = Some(service.clone());
+        }
+    }
+
+    /// Unpacks a message sent to our service and records where to respond to it. Once the
+    /// service of the counterparty is known, messages must be authcrypted by its key.
+    pub async fn unpack(
+        &mut self,
+        wallet: &impl BaseWallet,
+        payload: &[u8],
+    ) -> VcxResult<AriesMessage> {
+        let expected_sender_key = self.their_service.as_ref().map(recipient_key).transpose()?;
+        let (message, _sender_key, recipient_key) =
+            EncryptionEnvelope::unpack_aries_msg(wallet, payload, &expected_sender_key).await?;
+        if recipient_key.base58() != self.our_service.verkey() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!(
+                    "Message was packed for {}, not for our key {}",
+                    recipient_key.base58(),
+                    self.our_service.verkey()
+                ),
+            ));
+        }
+        self.receive(&message);
+        Ok(message)
+    }
+
+    /// Sends the message to the service of the counterparty. Messages which can carry the
+    /// `~service` decorator get ours, so that the counterparty can respond in turn.
+    pub async fn send(
+        &self,
+        wallet: &impl BaseWallet,
+        message: AriesMessage,
+        transport: &impl Transport,
+    ) -> VcxResult<()> {
+        let their_service = self.their_service.as_ref().ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "No service of the counterparty received to send the message to",
+            )
+        })?;
+        let message = if can_carry_service(&message) {
+            self.decorate(message)?
+        } else {
+            message
+        };
+        self.our_service
+            .send_message(wallet, &message, their_service, transport)
+            .await
+    }
+}
+
+fn recipient_key(service: &Service) -> VcxResult<Key> {
+    normalize_keys_as_naked(&service.recipient_keys)?
+        .first()
+        .map(|key| Key::from_base58(key, KeyType::Ed25519))
+        .transpose()?
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!("No recipient key found in service: {service:?}"),
+            )
+        })
+}
+
+/// Sets the `~service` decorator on a message of the present-proof or issuance protocols
+/// which can be exchanged without a connection.
+pub fn set_service(message: AriesMessage, service: Service) -> VcxResult<AriesMessage> {
+    match message {
+        AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::RequestPresentation(
+            mut msg,
+        ))) => {
+            msg.decorators.service = Some(service);
+            Ok(msg.into())
+        }
+        AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::Presentation(mut msg))) => {
+            msg.decorators.service = Some(service);
+            Ok(msg.into())
+        }
+        AriesMessage::CredentialIssuance(CredentialIssuance::V1(
+            CredentialIssuanceV1::OfferCredential(mut msg),
+        )) => {
+            msg.decorators.service = Some(service);
+            Ok(msg.into())
+        }
+        AriesMessage::CredentialIssuance(CredentialIssuance::V1(
+            CredentialIssuanceV1::RequestCredential(mut msg),
+        )) => {
+            msg.decorators.service = Some(service);
+            Ok(msg.into())
+        }
+        msg => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::ActionNotSupported,
+            format!("Message type {msg:?} can't be sent without a connection"),
+        )),
+    }
+}
+
+fn can_carry_service(message: &AriesMessage) -> bool {
+    matches!(
+        message,
+        AriesMessage::PresentProof(PresentProof::V1(
+            PresentProofV1::RequestPresentation(_) | PresentProofV1::Presentation(_)
+        )) | AriesMessage::CredentialIssuance(CredentialIssuance::V1(
+            CredentialIssuanceV1::OfferCredential(_) | CredentialIssuanceV1::RequestCredential(_)
+        ))
+    )
+}
+
+/// Reads the `~service` decorator, telling where to respond to a message received without a
+/// connection.
+pub fn get_service(message: &AriesMessage) -> Option<&Service> {
+    match message {
+        AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::RequestPresentation(msg))) => {
+            msg.decorators.service.as_ref()
+        }
+        AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::Presentation(msg))) => {
+            msg.decorators.service.as_ref()
+        }
+        AriesMessage::CredentialIssuance(CredentialIssuance::V1(
+            CredentialIssuanceV1::OfferCredential(msg),
+        )) => msg.decorators.service.as_ref(),
+        AriesMessage::CredentialIssuance(CredentialIssuance::V1(
+            CredentialIssuanceV1::RequestCredential(msg),
+        )) => msg.decorators.service.as_ref(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use messages::{
+        decorators::attachment::{Attachment, AttachmentData, AttachmentType},
+        msg_fields::protocols::{
+            present_proof::v1::request::{
+                RequestPresentationV1, RequestPresentationV1Content,
+                RequestPresentationV1Decorators,
+            },
+            trust_ping::TrustPing,
+        },
+    };
+    use test_utils::devsetup::build_setup_profile;
+
+    use super::*;
+    use crate::protocols::trustping::build_ping;
+
+    #[derive(Default)]
+    struct Outbox(Mutex<Vec<(Vec<u8>, Url)>>);
+
+    #[async_trait]
+    impl Transport for Outbox {
+        async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()> {
+            self.0.lock().unwrap().push((msg, service_endpoint.clone()));
+            Ok(())
+        }
+    }
+
+    fn _request() -> AriesMessage {
+        let attach = Attachment::builder()
+            .data(
+                AttachmentData::builder()
+                    .content(AttachmentType::Base64("e30".to_owned()))
+                    .build(),
+            )
+            .build();
+        RequestPresentationV1::builder()
+            .id("request-1".to_owned())
+            .content(
+                RequestPresentationV1Content::builder()
+                    .request_presentations_attach(vec![attach])
+                    .build(),
+            )
+            .decorators(RequestPresentationV1Decorators::default())
+            .build()
+            .into()
+    }
+
+    fn _service() -> Service {
+        Service::builder()
+            .recipient_keys(vec![
+                "8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K".to_owned()
+            ])
+            .service_endpoint("https://example.org/agent".parse().unwrap())
+            .build()
+    }
+
+    #[test]
+    fn test_set_and_get_service() {
+        let request = _request();
+        assert_eq!(get_service(&request), None);
+
+        let request = set_service(request, _service()).unwrap();
+        assert_eq!(get_service(&request), Some(&_service()));
+
+        let serialized = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            serialized["~service"]["serviceEndpoint"],
+            json!("https://example.org/agent")
+        );
+        let deserialized: AriesMessage = serde_json::from_value(serialized).unwrap();
+        assert_eq!(get_service(&deserialized), Some(&_service()));
+    }
+
+    #[test]
+    fn test_set_service_rejects_unsupported_message() {
+        let ping: AriesMessage = build_ping(false, None).into();
+        let err = set_service(ping, _service()).unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::ActionNotSupported);
+    }
+
+    #[tokio::test]
+    async fn test_exchange_responds_to_service_of_counterparty() {
+        let setup = build_setup_profile().await;
+        let verifier_endpoint: Url = "https://kiosk.example.org/didcomm".parse().unwrap();
+        let prover_endpoint: Url = "https://wallet.example.org/didcomm".parse().unwrap();
+        let mut verifier_exchange = ConnectionlessExchange::new(
+            EphemeralService::create(&setup.wallet, verifier_endpoint.clone(), vec![])
+                .await
+                .unwrap(),
+        );
+        let mut prover_exchange = ConnectionlessExchange::new(
+            EphemeralService::create(&setup.wallet, prover_endpoint.clone(), vec![])
+                .await
+                .unwrap(),
+        );
+        let outbox = Outbox::default();
+
+        let request = verifier_exchange.decorate(_request()).unwrap();
+        assert!(prover_exchange
+            .send(&setup.wallet, request.clone(), &outbox)
+            .await
+            .is_err());
+        prover_exchange.receive(&request);
+        assert_eq!(
+            prover_exchange.their_service(),
+            Some(verifier_exchange.our_service().service())
+        );
+
+        let ping = build_ping(false, None);
+        prover_exchange
+            .send(&setup.wallet, ping.clone().into(), &outbox)
+            .await
+            .unwrap();
+        let (msg, endpoint) = outbox.0.lock().unwrap().remove(0);
+        assert_eq!(endpoint, verifier_endpoint);
+        let received = verifier_exchange.unpack(&setup.wallet, &msg).await.unwrap();
+        let AriesMessage::TrustPing(TrustPing::Ping(received)) = received else {
+            panic!("Unexpected message {received:?}");
+        };
+        assert_eq!(received.id, ping.id);
+        assert_eq!(verifier_exchange.their_service(), None);
+
+        prover_exchange
+            .send(&setup.wallet, request, &outbox)
+            .await
+            .unwrap();
+        let (msg, _endpoint) = outbox.0.lock().unwrap().remove(0);
+        verifier_exchange.unpack(&setup.wallet, &msg).await.unwrap();
+        assert_eq!(
+            verifier_exchange.their_service(),
+            Some(prover_exchange.our_service().service())
+        );
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    didcomm_v2::{
+        conversions::{DISCOVER_FEATURES_DISCLOSE, DISCOVER_FEATURES_QUERIES},
+        DidCommV2Message,
+    },
+    msg_fields::protocols::discover_features::{
+        disclose::{Disclose, DiscloseContent, DiscloseDecorators},
+        query::{Query, QueryContent, QueryDecorators},
+        ProtocolDescriptor,
+    },
+    msg_types::{registry::PROTOCOL_REGISTRY, Protocol, Role},
+};
+use serde_json::Value;
+use shared::maybe_known::MaybeKnown;
+use uuid::Uuid;
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+const FEATURE_TYPE_PROTOCOL: &str = "protocol";
+const FEATURE_TYPE_GOAL_CODE: &str = "goal-code";
+
+/// What this agent discloses when queried about its features.
+#[derive(Debug, Clone, Default)]
+pub struct DiscoverFeaturesConfig {
+    /// Patterns, optionally ending with a `*` wildcard, of the protocols which may be disclosed.
+    /// All protocols of the registry are disclosed if not set.
+    pub enabled_protocols: Option<Vec<String>>,
+    /// Roles this agent plays. Only these roles are disclosed, and protocols in which the agent
+    /// plays none of its roles are not disclosed at all. All roles are disclosed if not set.
+    pub roles: Option<Vec<Role>>,
+    /// Goal codes this agent can act on, disclosed in answer to discover-features 2.0 queries.
+    pub goal_codes: Vec<String>,
+}
+
+/// Features a peer disclosed about itself.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeerFeatures {
+    pub protocols: Vec<ProtocolDescriptor>,
+    #[serde(default)]
+    pub goal_codes: Vec<String>,
+}
+
+impl PeerFeatures {
+    /// Whether the peer disclosed a protocol with the same name and major version as `protocol`.
+    pub fn supports(&self, protocol: &Protocol) -> bool {
+        let (name, major, _) = protocol.as_parts();
+        self.disclosed_pids()
+            .filter_map(pid_parts)
+            .any(|(pid_name, pid_major, _)| pid_name == name && pid_major == major)
+    }
+
+    /// Picks the first of `candidates`, in order of preference, supported by the peer. Used to
+    /// select between versions of a protocol, such as issue-credential 1.0 and 2.0.
+    pub fn select_protocol(&self, candidates: &[Protocol]) -> Option<Protocol> {
+        candidates
+            .iter()
+            .find(|protocol| self.supports(protocol))
+            .copied()
+    }
+
+    fn disclosed_pids(&self) -> impl Iterator<Item = String> + '_ {
+        self.protocols.iter().map(|protocol| match &protocol.pid {
+            MaybeKnown::Known(pid) => pid.to_string(),
+            MaybeKnown::Unknown(pid) => pid.clone(),
+        })
+    }
+}
+
+/// Splits a `https://didcomm.org/<name>/<major>.<minor>` protocol id into its parts.
+fn pid_parts(pid: String) -> Option<(String, u8, u8)> {
+    let (rest, version) = pid.rsplit_once('/')?;
+    let (_, name) = rest.rsplit_once('/')?;
+    let (major, minor) = version.split_once('.')?;
+    Some((name.to_owned(), major.parse().ok()?, minor.parse().ok()?))
+}
+
+fn pattern_matches(pattern: &str, id: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => id.starts_with(prefix),
+        None => id == pattern,
+    }
+}
+
+/// Answers discover-features 1.0 and 2.0 queries from the [`PROTOCOL_REGISTRY`] and keeps track
+/// of the features peers disclose.
+#[derive(Debug, Default)]
+pub struct FeatureDiscovery {
+    config: DiscoverFeaturesConfig,
+    peer_features: HashMap<String, PeerFeatures>,
+}
+
+impl FeatureDiscovery {
+    pub fn new(config: DiscoverFeaturesConfig) -> Self {
+        Self {
+            config,
+            peer_features: HashMap::new(),
+        }
+    }
+
+    pub fn build_query(query: String, comment: Option<String>) -> Query {
+        let content = QueryContent::builder().query(query);
+        let content = match comment {
+            None => content.build(),
+            Some(comment) => content.comment(comment).build(),
+        };
+        let decorators = QueryDecorators::builder()
+            .timing(Timing::builder().out_time(Utc::now()).build())
+            .build();
+
+        Query::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(content)
+            .decorators(decorators)
+            .build()
+    }
+
+    /// Builds the discover-features 2.0 `queries` message for the given protocol patterns.
+    pub fn build_query_v2(protocol_patterns: &[&str]) -> DidCommV2Message {
+        let queries: Vec<_> = protocol_patterns
+            .iter()
+            .map(|pattern| json!({ "feature-type": FEATURE_TYPE_PROTOCOL, "match": pattern }))
+            .collect();
+
+        DidCommV2Message::builder()
+            .id(Uuid::new_v4().to_string())
+            .msg_type(DISCOVER_FEATURES_QUERIES.to_owned())
+            .body(json!({ "queries": queries }))
+            .build()
+    }
+
+    /// Protocols of the registry matching `pattern` which this agent is configured to disclose.
+    pub fn lookup_protocols(&self, pattern: &str) -> Vec<ProtocolDescriptor> {
+        let mut protocols = Vec::new();
+        for entry in PROTOCOL_REGISTRY.values().flatten() {
+            if !pattern_matches(pattern, &entry.str_pid) || !self.is_enabled(&entry.str_pid) {
+                continue;
+            }
+            let roles: Vec<_> = match &self.config.roles {
+                Some(roles) => entry
+                    .roles
+                    .iter()
+                    .filter(|role| matches!(role, MaybeKnown::Known(role) if roles.contains(role)))
+                    .cloned()
+                    .collect(),
+                None => entry.roles.clone(),
+            };
+            if self.config.roles.is_some() && roles.is_empty() {
+                continue;
+            }
+            protocols.push(
+                ProtocolDescriptor::builder()
+                    .pid(MaybeKnown::Known(entry.protocol))
+                    .roles(roles)
+                    .build(),
+            );
+        }
+        protocols
+    }
+
+    fn is_enabled(&self, pid: &str) -> bool {
+        match &self.config.enabled_protocols {
+            Some(enabled) => enabled.iter().any(|pattern| pattern_matches(pattern, pid)),
+            None => true,
+        }
+    }
+
+    /// Answers a discover-features 1.0 query.
+    pub fn handle_query(&self, query: &Query) -> Disclose {
+        let thread = Thread::builder().thid(query.id.clone()).build();
+        let decorators = DiscloseDecorators::builder()
+            .thread(thread)
+            .timing(Timing::builder().out_time(Utc::now()).build())
+            .build();
+
+        Disclose::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(
+                DiscloseContent::builder()
+                    .protocols(self.lookup_protocols(&query.content.query))
+                    .build(),
+            )
+            .decorators(decorators)
+            .build()
+    }
+
+    /// Answers a discover-features 2.0 `queries` message.
+    pub fn handle_query_v2(&self, query: &DidCommV2Message) -> VcxResult<DidCommV2Message> {
+        if query.msg_type != DISCOVER_FEATURES_QUERIES {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!("Expected a discover-features query, got {}", query.msg_type),
+            ));
+        }
+        let mut disclosures = Vec::new();
+        for (feature_type, pattern) in features(&query.body, "queries", "match") {
+            match feature_type {
+                FEATURE_TYPE_PROTOCOL => {
+                    for protocol in self.lookup_protocols(pattern) {
+                        disclosures.push(json!({
+                            "feature-type": FEATURE_TYPE_PROTOCOL,
+                            "id": protocol.pid,
+                            "roles": protocol.roles.unwrap_or_default(),
+                        }));
+                    }
+                }
+                FEATURE_TYPE_GOAL_CODE => {
+                    for goal_code in self.config.goal_codes.iter() {
+                        if pattern_matches(pattern, goal_code) {
+                            disclosures.push(json!({
+                                "feature-type": FEATURE_TYPE_GOAL_CODE,
+                                "id": goal_code,
+                            }));
+                        }
+                    }
+                }
+                other => debug!("Ignoring query for unsupported feature type {}", other),
+            }
+        }
+
+        Ok(DidCommV2Message::builder()
+            .id(Uuid::new_v4().to_string())
+            .msg_type(DISCOVER_FEATURES_DISCLOSE.to_owned())
+            .thid(query.thread_id().to_owned())
+            .body(json!({ "disclosures": disclosures }))
+            .build())
+    }
+
+    /// Records the protocols disclosed by the peer on the given connection, replacing what it
+    /// disclosed before.
+    pub fn handle_disclose(&mut self, connection_id: &str, disclose: &Disclose) {
+        self.peer_features.insert(
+            connection_id.to_owned(),
+            PeerFeatures {
+                protocols: disclose.content.protocols.clone(),
+                goal_codes: Vec::new(),
+            },
+        );
+    }
+
+    /// Records the features disclosed by the peer on the given connection in a discover-features
+    /// 2.0 `disclose` message, replacing what it disclosed before.
+    pub fn handle_disclose_v2(
+        &mut self,
+        connection_id: &str,
+        disclose: &DidCommV2Message,
+    ) -> VcxResult<()> {
+        if disclose.msg_type != DISCOVER_FEATURES_DISCLOSE {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                format!(
+                    "Expected a discover-features disclose, got {}",
+                    disclose.msg_type
+                ),
+            ));
+        }
+        let mut peer_features = PeerFeatures::default();
+        for (feature_type, id) in features(&disclose.body, "disclosures", "id") {
+            match feature_type {
+                FEATURE_TYPE_PROTOCOL => {
+                    let roles = disclose_roles(&disclose.body, id);
+                    peer_features.protocols.push(ProtocolDescriptor {
+                        pid: id
+                            .parse()
+                            .map(MaybeKnown::Known)
+                            .unwrap_or_else(|_| MaybeKnown::Unknown(id.to_owned())),
+                        roles,
+                    });
+                }
+                FEATURE_TYPE_GOAL_CODE => peer_features.goal_codes.push(id.to_owned()),
+                other => debug!("Ignoring disclosure of unsupported feature type {}", other),
+            }
+        }
+        self.peer_features
+            .insert(connection_id.to_owned(), peer_features);
+        Ok(())
+    }
+
+    /// Features disclosed by the peer on the given connection, if it has disclosed any.
+    pub fn peer_features(&self, connection_id: &str) -> Option<&PeerFeatures> {
+        self.peer_features.get(connection_id)
+    }
+}
+
+/// The `(feature-type, <field>)` pairs of the `queries` or `disclosures` of a discover-features
+/// 2.0 message body.
+fn features<'a>(
+    body: &'a Value,
+    list: &str,
+    field: &'a str,
+) -> impl Iterator<Item = (&'a str, &'a str)> {
+    body.get(list)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(move |feature| {
+            Some((
+                feature.get("feature-type")?.as_str()?,
+                feature.get(field)?.as_str()?,
+            ))
+        })
+}
+
+fn disclose_roles(body: &Value, id: &str) -> Option<Vec<MaybeKnown<Role>>> {
+    body.get("disclosures")?
+        .as_array()?
+        .iter()
+        .find(|disclosure| disclosure.get("id").and_then(Value::as_str) == Some(id))?
+        .get("roles")
+        .cloned()
+        .and_then(|roles| serde_json::from_value(roles).ok())
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    const ISSUE_CREDENTIAL_V1: &str = "https://didcomm.org/issue-credential/1.0";
+    const ISSUE_CREDENTIAL_V2: &str = "https://didcomm.org/issue-credential/2.0";
+
+    #[test]
+    fn test_wildcard_query_discloses_registry() {
+        let discovery = FeatureDiscovery::default();
+        let query = FeatureDiscovery::build_query("https://didcomm.org/*".to_owned(), None);
+
+        let disclose = discovery.handle_query(&query);
+
+        assert_eq!(disclose.decorators.thread.thid, query.id);
+        assert_eq!(
+            disclose.content.protocols.len(),
+            PROTOCOL_REGISTRY.values().flatten().count()
+        );
+    }
+
+    #[test]
+    fn test_query_filtered_by_enabled_protocols_and_roles() {
+        let discovery = FeatureDiscovery::new(DiscoverFeaturesConfig {
+            enabled_protocols: Some(vec![
+                "https://didcomm.org/issue-credential/*".to_owned(),
+                "https://didcomm.org/trust_ping/*".to_owned(),
+            ]),
+            roles: Some(vec![Role::Holder]),
+            goal_codes: Vec::new(),
+        });
+        let query = FeatureDiscovery::build_query("https://didcomm.org/*".to_owned(), None);
+
+        let disclose = discovery.handle_query(&query);
+
+        let pids: Vec<_> = disclose
+            .content
+            .protocols
+            .iter()
+            .map(|protocol| serde_json::to_value(&protocol.pid).unwrap())
+            .collect();
+        assert_eq!(pids.len(), 2);
+        assert!(pids.contains(&json!(ISSUE_CREDENTIAL_V1)));
+        assert!(pids.contains(&json!(ISSUE_CREDENTIAL_V2)));
+        for protocol in disclose.content.protocols.iter() {
+            assert_eq!(protocol.roles, Some(vec![MaybeKnown::Known(Role::Holder)]));
+        }
+    }
+
+    #[test]
+    fn test_v2_query_discloses_protocols_and_goal_codes() {
+        let discovery = FeatureDiscovery::new(DiscoverFeaturesConfig {
+            goal_codes: vec!["aries.vc.issue".to_owned(), "aries.rel.build".to_owned()],
+            ..Default::default()
+        });
+        let mut query = FeatureDiscovery::build_query_v2(&["https://didcomm.org/trust_ping/*"]);
+        query.body["queries"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({ "feature-type": "goal-code", "match": "aries.vc.*" }));
+
+        let disclose = discovery.handle_query_v2(&query).unwrap();
+
+        assert_eq!(disclose.thid.as_deref(), Some(query.id.as_str()));
+        assert_eq!(
+            disclose.body["disclosures"],
+            json!([
+                {
+                    "feature-type": "protocol",
+                    "id": "https://didcomm.org/trust_ping/1.0",
+                    "roles": ["sender", "receiver"],
+                },
+                { "feature-type": "goal-code", "id": "aries.vc.issue" },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_records_peer_disclosures_for_version_selection() {
+        let mut discovery = FeatureDiscovery::default();
+        let responder = FeatureDiscovery::new(DiscoverFeaturesConfig {
+            enabled_protocols: Some(vec!["https://didcomm.org/issue-credential/2.*".to_owned()]),
+            ..Default::default()
+        });
+        let query = FeatureDiscovery::build_query("https://didcomm.org/*".to_owned(), None);
+        discovery.handle_disclose("connection-1", &responder.handle_query(&query));
+
+        let query = FeatureDiscovery::build_query_v2(&["https://didcomm.org/*"]);
+        discovery
+            .handle_disclose_v2("connection-2", &responder.handle_query_v2(&query).unwrap())
+            .unwrap();
+
+        let v1: Protocol = ISSUE_CREDENTIAL_V1.parse().unwrap();
+        let v2: Protocol = ISSUE_CREDENTIAL_V2.parse().unwrap();
+        for connection_id in ["connection-1", "connection-2"] {
+            let peer = discovery.peer_features(connection_id).unwrap();
+            assert!(!peer.supports(&v1));
+            assert_eq!(peer.select_protocol(&[v1, v2]), Some(v2));
+        }
+        assert!(discovery.peer_features("connection-3").is_none());
+    }
+}
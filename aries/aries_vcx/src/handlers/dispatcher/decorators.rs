@@ -0,0 +1,131 @@
+use chrono::{DateTime, Duration, Utc};
+use messages::{
+    decorators::{
+        please_ack::{AckOn, PleaseAck},
+        timing::Timing,
+    },
+    AriesMessage,
+};
+
+use crate::errors::error::VcxResult;
+
+/// Decorators which are enforced for every inbound message, regardless of its protocol.
+///
+/// They are read from the serialized message, so only decorators which the message type
+/// declares are taken into account.
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct CommonDecorators {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "~timing")]
+    #[serde(default)]
+    pub timing: Option<Timing>,
+    #[serde(rename = "~please_ack")]
+    #[serde(default)]
+    pub please_ack: Option<PleaseAck>,
+}
+
+impl CommonDecorators {
+    pub fn of(message: &AriesMessage) -> VcxResult<Self> {
+        Ok(serde_json::from_value(serde_json::to_value(message)?)?)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.timing
+            .as_ref()
+            .and_then(|timing| timing.expires_time)
+            .is_some_and(|expires_time| expires_time <= now)
+    }
+
+    /// Time before which the sender asked for the message not to be processed, through either
+    /// `wait_until_time` or `delay_milli`, if that is still in the future.
+    pub fn deferred_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let timing = self.timing.as_ref()?;
+        let delayed = timing
+            .delay_milli
+            .map(|delay| now + Duration::milliseconds(delay.into()));
+        timing
+            .wait_until_time
+            .into_iter()
+            .chain(delayed)
+            .max()
+            .filter(|until| *until > now)
+    }
+
+    pub fn wants_ack_on(&self, on: &AckOn) -> bool {
+        self.please_ack
+            .as_ref()
+            .is_some_and(|please_ack| please_ack.on.contains(on))
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use messages::msg_fields::protocols::revocation::revoke::{
+        RevocationFormat, Revoke, RevokeContent, RevokeDecorators,
+    };
+    use shared::maybe_known::MaybeKnown;
+
+    use super::*;
+
+    fn _revoke(decorators: RevokeDecorators) -> AriesMessage {
+        Revoke::builder()
+            .id("revoke-1".to_owned())
+            .content(
+                RevokeContent::builder()
+                    .credential_id("cred-1".to_owned())
+                    .revocation_format(MaybeKnown::Known(RevocationFormat::IndyAnoncreds))
+                    .build(),
+            )
+            .decorators(decorators)
+            .build()
+            .into()
+    }
+
+    #[test]
+    fn test_reads_common_decorators() {
+        let now = Utc::now();
+        let decorators = RevokeDecorators::builder()
+            .please_ack(PleaseAck::builder().on(vec![AckOn::Outcome]).build())
+            .timing(Timing::builder().expires_time(now).build())
+            .build();
+
+        let common = CommonDecorators::of(&_revoke(decorators)).unwrap();
+
+        assert_eq!(common.id, "revoke-1");
+        assert!(common.is_expired(now));
+        assert!(!common.is_expired(now - Duration::seconds(1)));
+        assert!(common.wants_ack_on(&AckOn::Outcome));
+        assert!(!common.wants_ack_on(&AckOn::Receipt));
+    }
+
+    #[test]
+    fn test_deferred_until_latest_of_wait_and_delay() {
+        let now = Utc::now();
+        let wait_until_time = now + Duration::seconds(10);
+        let decorators = RevokeDecorators::builder()
+            .timing(
+                Timing::builder()
+                    .wait_until_time(wait_until_time)
+                    .delay_milli(1_000)
+                    .build(),
+            )
+            .build();
+        let common = CommonDecorators::of(&_revoke(decorators)).unwrap();
+
+        let deferred_until = common.deferred_until(now).unwrap();
+        assert_eq!(deferred_until.timestamp(), wait_until_time.timestamp());
+
+        let decorators = RevokeDecorators::builder()
+            .timing(Timing::builder().wait_until_time(now).build())
+            .build();
+        let common = CommonDecorators::of(&_revoke(decorators)).unwrap();
+        assert_eq!(common.deferred_until(now + Duration::seconds(1)), None);
+        assert_eq!(
+            CommonDecorators::of(&_revoke(RevokeDecorators::default()))
+                .unwrap()
+                .deferred_until(now),
+            None
+        );
+    }
+}
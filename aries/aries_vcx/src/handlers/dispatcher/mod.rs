@@ -0,0 +1,1092 @@
+mod decorators;
+mod thread;
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use aries_vcx_wallet::{
+    errors::error::VcxWalletError,
+    wallet::{
+        base_wallet::{record::Record, record_category::RecordCategory, BaseWallet},
+        record_tags::{RecordTag, RecordTags},
+    },
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use messages::{
+    decorators::please_ack::AckOn,
+    msg_fields::protocols::{
+        notification::ack::AckStatus,
+        report_problem::{Impact, ProblemReport},
+    },
+    AriesMessage,
+};
+use public_key::Key;
+
+use self::decorators::CommonDecorators;
+pub use self::thread::ThreadIds;
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::{
+        common::build_ack_msg,
+        problem_report::{
+            descriptors::{MESSAGE_EXPIRED, UNSUPPORTED_MESSAGE_TYPE},
+            Problem, ProblemCode, ProblemScope, ReceivedProblem,
+        },
+    },
+    utils::encryption_envelope::EncryptionEnvelope,
+};
+
+// Plaintext, so that the wallet can compare it in range queries.
+const HANDLED_AT_TAG: &str = "~handled_at";
+
+/// Protocol family of an [`AriesMessage`], used as the key handlers are registered under.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageFamily {
+    Routing,
+    Connection,
+    Revocation,
+    CredentialIssuance,
+    ReportProblem,
+    PresentProof,
+    TrustPing,
+    DiscoverFeatures,
+    BasicMessage,
+    OutOfBand,
+    Notification,
+    Pickup,
+    CoordinateMediation,
+    CoordinateMediationV2,
+    DidExchange,
+    ActionMenu,
+    QuestionAnswer,
+    Transactions,
+}
+
+impl From<&AriesMessage> for MessageFamily {
+    fn from(message: &AriesMessage) -> Self {
+        match message {
+            AriesMessage::Routing(_) => Self::Routing,
+            AriesMessage::Connection(_) => Self::Connection,
+            AriesMessage::Revocation(_) => Self::Revocation,
+            AriesMessage::CredentialIssuance(_) => Self::CredentialIssuance,
+            AriesMessage::ReportProblem(_) => Self::ReportProblem,
+            AriesMessage::PresentProof(_) => Self::PresentProof,
+            AriesMessage::TrustPing(_) => Self::TrustPing,
+            AriesMessage::DiscoverFeatures(_) => Self::DiscoverFeatures,
+            AriesMessage::BasicMessage(_) => Self::BasicMessage,
+            AriesMessage::OutOfBand(_) => Self::OutOfBand,
+            AriesMessage::Notification(_) => Self::Notification,
+            AriesMessage::Pickup(_) => Self::Pickup,
+            AriesMessage::CoordinateMediation(_) => Self::CoordinateMediation,
+            AriesMessage::CoordinateMediationV2(_) => Self::CoordinateMediationV2,
+            AriesMessage::DidExchange(_) => Self::DidExchange,
+            AriesMessage::ActionMenu(_) => Self::ActionMenu,
+            AriesMessage::QuestionAnswer(_) => Self::QuestionAnswer,
+            AriesMessage::Transactions(_) => Self::Transactions,
+        }
+    }
+}
+
+/// Unpacked inbound message along with everything the dispatcher learned about where it
+/// belongs.
+#[derive(Debug, Clone)]
+pub struct InboundMessage {
+    pub message: AriesMessage,
+    /// Absent for anoncrypted messages.
+    pub sender_verkey: Option<Key>,
+    pub recipient_verkey: Key,
+    /// Connection the recipient key belongs to, if any.
+    pub connection_id: Option<String>,
+    pub thread: ThreadIds,
+    /// Protocol instance tracked under the message's thread, or else under its parent thread.
+    pub protocol_instance_id: Option<String>,
+}
+
+impl InboundMessage {
+    /// If the message is a problem report of any protocol, the problem as an error to fail the
+    /// protocol instance it was correlated with.
+    pub fn reported_problem(&self) -> Option<AriesVcxError> {
+        let problem = ReceivedProblem::from_message(&self.message)?;
+        let err = AriesVcxError::from(problem);
+        Some(match &self.protocol_instance_id {
+            Some(id) => err.extend(format!("Protocol instance: {}", id)),
+            None => err,
+        })
+    }
+}
+
+/// Looks up connections for the dispatcher. Implemented by whatever stores the agent's
+/// connections.
+#[async_trait]
+pub trait ConnectionRegistry: Send + Sync {
+    /// Returns the id of the connection on which `recipient_key` is one of our keys.
+    async fn connection_id_by_recipient_key(
+        &self,
+        recipient_key: &Key,
+    ) -> VcxResult<Option<String>>;
+
+    /// Sends a message to the counterparty of the connection.
+    async fn send_message(&self, connection_id: &str, message: AriesMessage) -> VcxResult<()>;
+}
+
+/// Handles the inbound messages of one protocol family.
+#[async_trait]
+pub trait MessageHandler: Send + Sync {
+    async fn handle(&self, inbound: InboundMessage) -> VcxResult<()>;
+}
+
+#[derive(Debug)]
+pub enum DispatchOutcome {
+    Handled,
+    /// No handler is registered for the message's protocol family. Contains the problem report
+    /// sent back to the sender, if the message arrived over a known connection.
+    Unhandled {
+        inbound: InboundMessage,
+        problem_report: Option<ProblemReport>,
+    },
+    /// The message was received after its `~timing.expires_time` and was not handled. Contains
+    /// the problem report sent back to the sender, if the message arrived over a known
+    /// connection.
+    Expired {
+        inbound: InboundMessage,
+        problem_report: Option<ProblemReport>,
+    },
+    /// The sender asked for the message not to be processed before the given time, through
+    /// `~timing.wait_until_time` or `~timing.delay_milli`. It is handled by a later
+    /// [`Dispatcher::dispatch_deferred`].
+    Deferred {
+        until: DateTime<Utc>,
+    },
+    /// A message with the same id has already been handled, or is being handled. It is
+    /// acknowledged again if the sender asked for it, but not handled twice.
+    Duplicate {
+        inbound: InboundMessage,
+    },
+}
+
+/// Routes inbound messages to the handler registered for their protocol family.
+///
+/// Protocol instances are correlated with the threads they run on through
+/// [`Dispatcher::track_thread`], so that handlers are told which instance a message is meant
+/// for. Messages starting a protocol, such as a connection request answering an invitation, are
+/// correlated through their parent thread.
+///
+/// The `~timing` and `~please_ack` decorators are enforced before handlers get to see a
+/// message: expired messages are rejected with a problem report, messages asking to wait are
+/// deferred, and acks are sent on receipt or on the outcome of the handler as requested. A
+/// handler failing on a message whose outcome was asked for is reported with a problem report.
+///
+/// Redeliveries of a message being or already handled are not handled again. The ids of handled
+/// messages are stored in the wallet, so that redeliveries are recognized after a restart too;
+/// [`Dispatcher::forget_handled_before`] prunes them.
+pub struct Dispatcher<C> {
+    connections: C,
+    handlers: HashMap<MessageFamily, Arc<dyn MessageHandler>>,
+    threads: RwLock<HashMap<String, String>>,
+    deferred: Mutex<Vec<(DateTime<Utc>, InboundMessage)>>,
+    in_flight: Mutex<HashSet<String>>,
+    auto_ack: bool,
+}
+
+// Whether a message was seen before, by its id.
+enum Seen<'a> {
+    New(InFlight<'a>),
+    InFlight,
+    Handled,
+}
+
+// Marks a message as being handled until dropped.
+struct InFlight<'a> {
+    ids: &'a Mutex<HashSet<String>>,
+    id: String,
+}
+
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        match self.ids.lock() {
+            Ok(mut ids) => {
+                ids.remove(&self.id);
+            }
+            Err(err) => error!("Dispatcher lock is poisoned: {}", err),
+        }
+    }
+}
+
+impl<C> Dispatcher<C>
+where
+    C: ConnectionRegistry,
+{
+    pub fn new(connections: C) -> Self {
+        Self {
+            connections,
+            handlers: HashMap::new(),
+            threads: RwLock::new(HashMap::new()),
+            deferred: Mutex::new(Vec::new()),
+            in_flight: Mutex::new(HashSet::new()),
+            auto_ack: true,
+        }
+    }
+
+    /// Whether acks requested through `~please_ack` are sent by the dispatcher, which is the
+    /// default. Disable this if the registered handlers send acks themselves.
+    pub fn set_auto_ack(&mut self, auto_ack: bool) -> &mut Self {
+        self.auto_ack = auto_ack;
+        self
+    }
+
+    /// Registers the handler for a protocol family, replacing any previously registered one.
+    pub fn register_handler(
+        &mut self,
+        family: MessageFamily,
+        handler: impl MessageHandler + 'static,
+    ) -> &mut Self {
+        self.handlers.insert(family, Arc::new(handler));
+        self
+    }
+
+    /// Correlates a thread with the protocol instance running on it.
+    pub fn track_thread(&self, thread_id: &str, protocol_instance_id: &str) -> VcxResult<()> {
+        self.threads
+            .write()
+            .map_err(|err| lock_error(err.to_string()))?
+            .insert(thread_id.to_owned(), protocol_instance_id.to_owned());
+        Ok(())
+    }
+
+    /// Stops correlating a thread, e.g. once its protocol instance has finished.
+    pub fn forget_thread(&self, thread_id: &str) -> VcxResult<()> {
+        self.threads
+            .write()
+            .map_err(|err| lock_error(err.to_string()))?
+            .remove(thread_id);
+        Ok(())
+    }
+
+    /// Unpacks an encrypted envelope and dispatches the message it contains.
+    pub async fn dispatch(
+        &self,
+        wallet: &impl BaseWallet,
+        envelope: &[u8],
+    ) -> VcxResult<DispatchOutcome> {
+        let (message, sender_verkey, recipient_verkey) =
+            EncryptionEnvelope::unpack_aries_msg(wallet, envelope, &None).await?;
+        self.dispatch_unpacked(wallet, message, sender_verkey, recipient_verkey)
+            .await
+    }
+
+    /// Dispatches a message which has already been unpacked.
+    pub async fn dispatch_unpacked(
+        &self,
+        wallet: &impl BaseWallet,
+        message: AriesMessage,
+        sender_verkey: Option<Key>,
+        recipient_verkey: Key,
+    ) -> VcxResult<DispatchOutcome> {
+        let connection_id = self
+            .connections
+            .connection_id_by_recipient_key(&recipient_verkey)
+            .await?;
+        let thread = ThreadIds::of(&message);
+        let protocol_instance_id = self.find_protocol_instance(&thread)?;
+        let family = MessageFamily::from(&message);
+        debug!(
+            "Dispatcher >> dispatching {:?} message on thread {:?}, connection: {:?}",
+            family, thread, connection_id
+        );
+        let inbound = InboundMessage {
+            message,
+            sender_verkey,
+            recipient_verkey,
+            connection_id,
+            thread,
+            protocol_instance_id,
+        };
+        self.process(wallet, inbound, true).await
+    }
+
+    /// Dispatches the deferred messages which are due by now.
+    pub async fn dispatch_deferred(
+        &self,
+        wallet: &impl BaseWallet,
+    ) -> VcxResult<Vec<DispatchOutcome>> {
+        let now = Utc::now();
+        let due: Vec<_> = {
+            let mut deferred = self
+                .deferred
+                .lock()
+                .map_err(|err| lock_error(err.to_string()))?;
+            let (due, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut *deferred)
+                .into_iter()
+                .partition(|(until, _)| *until <= now);
+            *deferred = pending;
+            due
+        };
+        let mut outcomes = Vec::with_capacity(due.len());
+        for (_, inbound) in due {
+            outcomes.push(self.process(wallet, inbound, false).await?);
+        }
+        Ok(outcomes)
+    }
+
+    /// Number of deferred messages which have not been dispatched yet.
+    pub fn deferred_count(&self) -> VcxResult<usize> {
+        Ok(self
+            .deferred
+            .lock()
+            .map_err(|err| lock_error(err.to_string()))?
+            .len())
+    }
+
+    /// Forgets the ids of the messages handled before the given time, returning how many were
+    /// forgotten. Redeliveries of these messages are handled again.
+    pub async fn forget_handled_before(
+        &self,
+        wallet: &impl BaseWallet,
+        before: DateTime<Utc>,
+    ) -> VcxResult<usize> {
+        let query = json!({ HANDLED_AT_TAG: { "$lt": handled_at_tag(&before) } }).to_string();
+        let records = wallet
+            .search_record(RecordCategory::DispatchedMessage, Some(query))
+            .await?;
+        for record in records.iter() {
+            wallet
+                .delete_record(RecordCategory::DispatchedMessage, record.name())
+                .await?;
+        }
+        Ok(records.len())
+    }
+
+    async fn process(
+        &self,
+        wallet: &impl BaseWallet,
+        inbound: InboundMessage,
+        defer: bool,
+    ) -> VcxResult<DispatchOutcome> {
+        let decorators = CommonDecorators::of(&inbound.message)?;
+        let now = Utc::now();
+        if decorators.is_expired(now) {
+            let problem_report = self.report_expired(&inbound).await?;
+            return Ok(DispatchOutcome::Expired {
+                inbound,
+                problem_report,
+            });
+        }
+        if defer {
+            if let Some(until) = decorators.deferred_until(now) {
+                debug!(
+                    "Dispatcher >> deferring message {} until {}",
+                    decorators.id, until
+                );
+                self.deferred
+                    .lock()
+                    .map_err(|err| lock_error(err.to_string()))?
+                    .push((until, inbound));
+                return Ok(DispatchOutcome::Deferred { until });
+            }
+        }
+
+        let Some(handler) = self.handlers.get(&MessageFamily::from(&inbound.message)) else {
+            let problem_report = self.report_unhandled(&inbound).await?;
+            return Ok(DispatchOutcome::Unhandled {
+                inbound,
+                problem_report,
+            });
+        };
+
+        let in_flight = match self.mark_in_flight(wallet, &decorators.id).await? {
+            Seen::New(in_flight) => in_flight,
+            seen => {
+                debug!(
+                    "Dispatcher >> message {} is being or was already handled, ignoring the \
+                     redelivery",
+                    decorators.id
+                );
+                if decorators.wants_ack_on(&AckOn::Receipt)
+                    || decorators.wants_ack_on(&AckOn::Outcome)
+                {
+                    // the outcome of a message still being handled is acked once known
+                    let status = match seen {
+                        Seen::InFlight if decorators.wants_ack_on(&AckOn::Outcome) => {
+                            AckStatus::Pending
+                        }
+                        _ => AckStatus::Ok,
+                    };
+                    self.send_ack(&inbound, status).await?;
+                }
+                return Ok(DispatchOutcome::Duplicate { inbound });
+            }
+        };
+
+        if decorators.wants_ack_on(&AckOn::Receipt) {
+            let status = if decorators.wants_ack_on(&AckOn::Outcome) {
+                AckStatus::Pending
+            } else {
+                AckStatus::Ok
+            };
+            self.send_ack(&inbound, status).await?;
+        }
+        let connection_id = inbound.connection_id.clone();
+        let thread_id = inbound.thread.thid.clone();
+        if let Err(err) = handler.handle(inbound).await {
+            if decorators.wants_ack_on(&AckOn::Outcome) {
+                self.report_failure(connection_id.as_deref(), &thread_id, &err)
+                    .await;
+            }
+            return Err(err);
+        }
+        mark_handled(wallet, in_flight).await?;
+        if decorators.wants_ack_on(&AckOn::Outcome) {
+            self.send_ack_to(connection_id.as_deref(), &thread_id, AckStatus::Ok)
+                .await?;
+        }
+        Ok(DispatchOutcome::Handled)
+    }
+
+    // Checking and marking the message in flight happen under the same lock, so that concurrent
+    // redeliveries are handled once.
+    async fn mark_in_flight(
+        &self,
+        wallet: &impl BaseWallet,
+        message_id: &str,
+    ) -> VcxResult<Seen<'_>> {
+        let in_flight = {
+            let mut ids = self
+                .in_flight
+                .lock()
+                .map_err(|err| lock_error(err.to_string()))?;
+            if !ids.insert(message_id.to_owned()) {
+                return Ok(Seen::InFlight);
+            }
+            InFlight {
+                ids: &self.in_flight,
+                id: message_id.to_owned(),
+            }
+        };
+        match wallet
+            .get_record(RecordCategory::DispatchedMessage, message_id)
+            .await
+        {
+            Ok(_) => Ok(Seen::Handled),
+            Err(VcxWalletError::RecordNotFound(_)) => Ok(Seen::New(in_flight)),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn send_ack(&self, inbound: &InboundMessage, status: AckStatus) -> VcxResult<()> {
+        self.send_ack_to(
+            inbound.connection_id.as_deref(),
+            &inbound.thread.thid,
+            status,
+        )
+        .await
+    }
+
+    async fn send_ack_to(
+        &self,
+        connection_id: Option<&str>,
+        thread_id: &str,
+        status: AckStatus,
+    ) -> VcxResult<()> {
+        if !self.auto_ack {
+            return Ok(());
+        }
+        let Some(connection_id) = connection_id else {
+            warn!(
+                "Cannot acknowledge message on thread {} received outside of a connection",
+                thread_id
+            );
+            return Ok(());
+        };
+        self.connections
+            .send_message(connection_id, build_ack_msg(status, thread_id).into())
+            .await
+    }
+
+    fn find_protocol_instance(&self, thread: &ThreadIds) -> VcxResult<Option<String>> {
+        let threads = self
+            .threads
+            .read()
+            .map_err(|err| lock_error(err.to_string()))?;
+        Ok(threads
+            .get(&thread.thid)
+            .or_else(|| thread.pthid.as_ref().and_then(|pthid| threads.get(pthid)))
+            .cloned())
+    }
+
+    async fn report_unhandled(&self, inbound: &InboundMessage) -> VcxResult<Option<ProblemReport>> {
+        if ReceivedProblem::from_message(&inbound.message).is_some() {
+            warn!(
+                "Received problem report without a registered handler: {}",
+                inbound.message
+            );
+            return Ok(None);
+        }
+        let Some(connection_id) = inbound.connection_id.as_deref() else {
+            warn!(
+                "Dropping message without a registered handler received outside of a connection: \
+                 {}",
+                inbound.message
+            );
+            return Ok(None);
+        };
+        let problem = Problem::new(ProblemCode::error(
+            ProblemScope::Message,
+            UNSUPPORTED_MESSAGE_TYPE,
+        ))
+        .impact(Impact::MessageContent)
+        .explain(format!(
+            "Messages of the {:?} protocol are not supported",
+            MessageFamily::from(&inbound.message)
+        ));
+        self.report_problem(connection_id, &inbound.thread.thid, problem)
+            .await
+            .map(Some)
+    }
+
+    async fn report_expired(&self, inbound: &InboundMessage) -> VcxResult<Option<ProblemReport>> {
+        let Some(connection_id) = inbound.connection_id.as_deref() else {
+            warn!(
+                "Dropping expired message received outside of a connection: {}",
+                inbound.message
+            );
+            return Ok(None);
+        };
+        if ReceivedProblem::from_message(&inbound.message).is_some() {
+            warn!("Received expired problem report: {}", inbound.message);
+            return Ok(None);
+        }
+        let problem = Problem::new(ProblemCode::error(ProblemScope::Message, MESSAGE_EXPIRED))
+            .impact(Impact::MessageContent);
+        self.report_problem(connection_id, &inbound.thread.thid, problem)
+            .await
+            .map(Some)
+    }
+
+    // Answers a message whose outcome was asked for in place of the ack, when handling it failed.
+    async fn report_failure(
+        &self,
+        connection_id: Option<&str>,
+        thread_id: &str,
+        err: &AriesVcxError,
+    ) {
+        if !self.auto_ack {
+            return;
+        }
+        let Some(connection_id) = connection_id else {
+            warn!(
+                "Cannot report failure to handle message on thread {} received outside of a \
+                 connection",
+                thread_id
+            );
+            return;
+        };
+        if let Err(report_err) = self
+            .report_problem(connection_id, thread_id, Problem::from(err))
+            .await
+        {
+            warn!(
+                "Failed to report failure to handle message on thread {}: {}",
+                thread_id, report_err
+            );
+        }
+    }
+
+    async fn report_problem(
+        &self,
+        connection_id: &str,
+        thread_id: &str,
+        problem: Problem,
+    ) -> VcxResult<ProblemReport> {
+        let problem_report = problem.build(thread_id);
+        self.connections
+            .send_message(connection_id, problem_report.clone().into())
+            .await?;
+        Ok(problem_report)
+    }
+}
+
+async fn mark_handled(wallet: &impl BaseWallet, in_flight: InFlight<'_>) -> VcxResult<()> {
+    let handled_at = Utc::now();
+    let record = Record::builder()
+        .category(RecordCategory::DispatchedMessage)
+        .name(in_flight.id.clone())
+        .value(handled_at.to_rfc3339())
+        .tags(RecordTags::new(vec![RecordTag::new(
+            HANDLED_AT_TAG,
+            &handled_at_tag(&handled_at),
+        )]))
+        .build();
+    match wallet.add_record(record).await {
+        // handled by another dispatcher over the same wallet
+        Ok(()) | Err(VcxWalletError::DuplicateRecord(_)) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+// Zero padded, so that the lexicographic order of the tag values is chronological.
+fn handled_at_tag(timestamp: &DateTime<Utc>) -> String {
+    format!("{:020}", timestamp.timestamp_millis().max(0))
+}
+
+fn lock_error(err: String) -> AriesVcxError {
+    AriesVcxError::from_msg(
+        AriesVcxErrorKind::InvalidState,
+        format!("Dispatcher lock is poisoned: {}", err),
+    )
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use chrono::Duration;
+    use messages::{
+        decorators::{please_ack::PleaseAck, timing::Timing},
+        msg_fields::protocols::{
+            basic_message::{BasicMessage, BasicMessageContent, BasicMessageDecorators},
+            notification::Notification,
+            revocation::revoke::{RevocationFormat, Revoke, RevokeContent, RevokeDecorators},
+            trust_ping::{ping::Ping, TrustPing},
+        },
+    };
+    use public_key::KeyType;
+    use shared::maybe_known::MaybeKnown;
+    use test_utils::devsetup::dev_build_featured_wallet;
+
+    use super::*;
+    use crate::protocols::{problem_report::build_rejection_msg, trustping::build_ping};
+
+    const CONNECTION_ID: &str = "connection-1";
+
+    async fn _wallet() -> impl BaseWallet {
+        dev_build_featured_wallet("000000000000000000000000Dispatch")
+            .await
+            .1
+    }
+
+    fn _recipient_key() -> Key {
+        Key::new(vec![1; 32], KeyType::Ed25519).unwrap()
+    }
+
+    #[derive(Default)]
+    struct StubConnections {
+        sent: Mutex<Vec<(String, AriesMessage)>>,
+    }
+
+    #[async_trait]
+    impl ConnectionRegistry for Arc<StubConnections> {
+        async fn connection_id_by_recipient_key(
+            &self,
+            recipient_key: &Key,
+        ) -> VcxResult<Option<String>> {
+            Ok((recipient_key == &_recipient_key()).then(|| CONNECTION_ID.to_owned()))
+        }
+
+        async fn send_message(&self, connection_id: &str, message: AriesMessage) -> VcxResult<()> {
+            self.sent
+                .lock()
+                .unwrap()
+                .push((connection_id.to_owned(), message));
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordingHandler {
+        received: Mutex<Vec<InboundMessage>>,
+        failing: AtomicBool,
+    }
+
+    #[async_trait]
+    impl MessageHandler for Arc<RecordingHandler> {
+        async fn handle(&self, inbound: InboundMessage) -> VcxResult<()> {
+            self.received.lock().unwrap().push(inbound);
+            // gives concurrent redeliveries the chance to arrive while the message is handled
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidRevocationDetails,
+                    "Unknown credential",
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    fn _basic_message() -> BasicMessage {
+        BasicMessage::builder()
+            .id("basic-1".to_owned())
+            .content(
+                BasicMessageContent::builder()
+                    .content("hello".to_owned())
+                    .sent_time(chrono::Utc::now())
+                    .build(),
+            )
+            .decorators(BasicMessageDecorators::default())
+            .build()
+    }
+
+    fn _revoke(decorators: RevokeDecorators) -> AriesMessage {
+        Revoke::builder()
+            .id("revoke-1".to_owned())
+            .content(
+                RevokeContent::builder()
+                    .credential_id("cred-1".to_owned())
+                    .revocation_format(MaybeKnown::Known(RevocationFormat::IndyAnoncreds))
+                    .build(),
+            )
+            .decorators(decorators)
+            .build()
+            .into()
+    }
+
+    fn _sent_ack_statuses(connections: &StubConnections) -> Vec<AckStatus> {
+        connections
+            .sent
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(_, message)| match message {
+                AriesMessage::Notification(Notification::Ack(ack)) => {
+                    assert_eq!(ack.decorators.thread.thid, "revoke-1");
+                    Some(ack.content.status)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_dispatches_to_handler_with_correlated_protocol_instance() {
+        let wallet = _wallet().await;
+        let connections = Arc::new(StubConnections::default());
+        let handler = Arc::new(RecordingHandler::default());
+        let mut dispatcher = Dispatcher::new(connections.clone());
+        dispatcher.register_handler(MessageFamily::TrustPing, handler.clone());
+
+        let ping: Ping = build_ping(true, None);
+        dispatcher.track_thread(&ping.id, "ping-instance").unwrap();
+        let outcome = dispatcher
+            .dispatch_unpacked(
+                &wallet,
+                TrustPing::Ping(ping.clone()).into(),
+                None,
+                _recipient_key(),
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(outcome, DispatchOutcome::Handled));
+        let received = handler.received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0].connection_id.as_deref(), Some(CONNECTION_ID));
+        assert_eq!(received[0].thread.thid, ping.id);
+        assert_eq!(
+            received[0].protocol_instance_id.as_deref(),
+            Some("ping-instance")
+        );
+        assert!(connections.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unhandled_message_gets_problem_report() {
+        let wallet = _wallet().await;
+        let connections = Arc::new(StubConnections::default());
+        let dispatcher = Dispatcher::new(connections.clone());
+
+        let outcome = dispatcher
+            .dispatch_unpacked(&wallet, _basic_message().into(), None, _recipient_key())
+            .await
+            .unwrap();
+
+        let DispatchOutcome::Unhandled { problem_report, .. } = outcome else {
+            panic!("Expected message to be unhandled");
+        };
+        let problem_report = problem_report.unwrap();
+        assert_eq!(
+            problem_report.content.description.code,
+            "e.m.unsupported-message-type"
+        );
+        assert_eq!(
+            problem_report.decorators.thread.as_ref().unwrap().thid,
+            "basic-1"
+        );
+        let sent = connections.sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].0, CONNECTION_ID);
+        assert_eq!(sent[0].1, AriesMessage::from(problem_report));
+    }
+
+    #[tokio::test]
+    async fn test_problem_reports_are_not_answered() {
+        let wallet = _wallet().await;
+        let connections = Arc::new(StubConnections::default());
+        let dispatcher = Dispatcher::new(connections.clone());
+        let problem_report = build_rejection_msg(Some("foo".to_owned()), "thread-1");
+
+        let outcome = dispatcher
+            .dispatch_unpacked(&wallet, problem_report.into(), None, _recipient_key())
+            .await
+            .unwrap();
+
+        let DispatchOutcome::Unhandled {
+            inbound,
+            problem_report: None,
+        } = outcome
+        else {
+            panic!("Expected problem report to be unhandled and unanswered");
+        };
+        assert!(connections.sent.lock().unwrap().is_empty());
+        assert_eq!(
+            inbound.reported_problem().unwrap().kind(),
+            AriesVcxErrorKind::ProblemReportReceived
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_message_is_rejected() {
+        let wallet = _wallet().await;
+        let connections = Arc::new(StubConnections::default());
+        let handler = Arc::new(RecordingHandler::default());
+        let mut dispatcher = Dispatcher::new(connections.clone());
+        dispatcher.register_handler(MessageFamily::Revocation, handler.clone());
+
+        let decorators = RevokeDecorators::builder()
+            .timing(
+                Timing::builder()
+                    .expires_time(Utc::now() - Duration::minutes(1))
+                    .build(),
+            )
+            .build();
+        let outcome = dispatcher
+            .dispatch_unpacked(&wallet, _revoke(decorators), None, _recipient_key())
+            .await
+            .unwrap();
+
+        let DispatchOutcome::Expired { problem_report, .. } = outcome else {
+            panic!("Expected message to be rejected as expired");
+        };
+        assert_eq!(
+            problem_report.unwrap().content.description.code,
+            "e.m.message-expired"
+        );
+        assert!(handler.received.lock().unwrap().is_empty());
+        assert_eq!(connections.sent.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_message_is_deferred_until_wait_until_time() {
+        let wallet = _wallet().await;
+        let connections = Arc::new(StubConnections::default());
+        let handler = Arc::new(RecordingHandler::default());
+        let mut dispatcher = Dispatcher::new(connections.clone());
+        dispatcher.register_handler(MessageFamily::Revocation, handler.clone());
+
+        let decorators = RevokeDecorators::builder()
+            .timing(
+                Timing::builder()
+                    .wait_until_time(Utc::now() + Duration::milliseconds(50))
+                    .build(),
+            )
+            .build();
+        let outcome = dispatcher
+            .dispatch_unpacked(&wallet, _revoke(decorators), None, _recipient_key())
+            .await
+            .unwrap();
+
+        assert!(matches!(outcome, DispatchOutcome::Deferred { .. }));
+        assert!(dispatcher
+            .dispatch_deferred(&wallet)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(handler.received.lock().unwrap().is_empty());
+
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        let outcomes = dispatcher.dispatch_deferred(&wallet).await.unwrap();
+        assert!(matches!(outcomes[..], [DispatchOutcome::Handled]));
+        assert_eq!(handler.received.lock().unwrap().len(), 1);
+        assert_eq!(dispatcher.deferred_count().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_acks_on_receipt_and_outcome() {
+        let wallet = _wallet().await;
+        let connections = Arc::new(StubConnections::default());
+        let handler = Arc::new(RecordingHandler::default());
+        let mut dispatcher = Dispatcher::new(connections.clone());
+        dispatcher.register_handler(MessageFamily::Revocation, handler.clone());
+
+        let decorators = RevokeDecorators::builder()
+            .please_ack(
+                PleaseAck::builder()
+                    .on(vec![AckOn::Receipt, AckOn::Outcome])
+                    .build(),
+            )
+            .build();
+        let outcome = dispatcher
+            .dispatch_unpacked(&wallet, _revoke(decorators), None, _recipient_key())
+            .await
+            .unwrap();
+
+        assert!(matches!(outcome, DispatchOutcome::Handled));
+        assert_eq!(
+            _sent_ack_statuses(&connections),
+            vec![AckStatus::Pending, AckStatus::Ok]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_redelivered_message_is_acked_but_not_handled_again() {
+        let wallet = _wallet().await;
+        let connections = Arc::new(StubConnections::default());
+        let handler = Arc::new(RecordingHandler::default());
+        let mut dispatcher = Dispatcher::new(connections.clone());
+        dispatcher.register_handler(MessageFamily::Revocation, handler.clone());
+
+        let decorators = RevokeDecorators::builder()
+            .please_ack(PleaseAck::builder().on(vec![AckOn::Outcome]).build())
+            .build();
+        let message = _revoke(decorators);
+        dispatcher
+            .dispatch_unpacked(&wallet, message.clone(), None, _recipient_key())
+            .await
+            .unwrap();
+        let outcome = dispatcher
+            .dispatch_unpacked(&wallet, message, None, _recipient_key())
+            .await
+            .unwrap();
+
+        assert!(matches!(outcome, DispatchOutcome::Duplicate { .. }));
+        assert_eq!(handler.received.lock().unwrap().len(), 1);
+        assert_eq!(
+            _sent_ack_statuses(&connections),
+            vec![AckStatus::Ok, AckStatus::Ok]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auto_ack_can_be_disabled() {
+        let wallet = _wallet().await;
+        let connections = Arc::new(StubConnections::default());
+        let handler = Arc::new(RecordingHandler::default());
+        let mut dispatcher = Dispatcher::new(connections.clone());
+        dispatcher
+            .register_handler(MessageFamily::Revocation, handler.clone())
+            .set_auto_ack(false);
+
+        let decorators = RevokeDecorators::builder()
+            .please_ack(PleaseAck::builder().on(vec![AckOn::Receipt]).build())
+            .build();
+        dispatcher
+            .dispatch_unpacked(&wallet, _revoke(decorators), None, _recipient_key())
+            .await
+            .unwrap();
+
+        assert_eq!(handler.received.lock().unwrap().len(), 1);
+        assert!(connections.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_redeliveries_are_handled_once() {
+        let wallet = _wallet().await;
+        let connections = Arc::new(StubConnections::default());
+        let handler = Arc::new(RecordingHandler::default());
+        let mut dispatcher = Dispatcher::new(connections.clone());
+        dispatcher.register_handler(MessageFamily::Revocation, handler.clone());
+
+        let decorators = RevokeDecorators::builder()
+            .please_ack(PleaseAck::builder().on(vec![AckOn::Outcome]).build())
+            .build();
+        let message = _revoke(decorators);
+        let (first, second) = tokio::join!(
+            dispatcher.dispatch_unpacked(&wallet, message.clone(), None, _recipient_key()),
+            dispatcher.dispatch_unpacked(&wallet, message, None, _recipient_key())
+        );
+
+        assert!(matches!(first.unwrap(), DispatchOutcome::Handled));
+        assert!(matches!(second.unwrap(), DispatchOutcome::Duplicate { .. }));
+        assert_eq!(handler.received.lock().unwrap().len(), 1);
+        assert_eq!(
+            _sent_ack_statuses(&connections),
+            vec![AckStatus::Pending, AckStatus::Ok]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handled_messages_are_remembered_across_dispatchers() {
+        let wallet = _wallet().await;
+        let connections = Arc::new(StubConnections::default());
+        let handler = Arc::new(RecordingHandler::default());
+        let message = _revoke(RevokeDecorators::default());
+        let mut dispatcher = Dispatcher::new(connections.clone());
+        dispatcher.register_handler(MessageFamily::Revocation, handler.clone());
+        dispatcher
+            .dispatch_unpacked(&wallet, message.clone(), None, _recipient_key())
+            .await
+            .unwrap();
+
+        let mut restarted = Dispatcher::new(connections.clone());
+        restarted.register_handler(MessageFamily::Revocation, handler.clone());
+        let outcome = restarted
+            .dispatch_unpacked(&wallet, message.clone(), None, _recipient_key())
+            .await
+            .unwrap();
+        assert!(matches!(outcome, DispatchOutcome::Duplicate { .. }));
+        assert_eq!(handler.received.lock().unwrap().len(), 1);
+
+        let forgotten = restarted
+            .forget_handled_before(&wallet, Utc::now() + Duration::minutes(1))
+            .await
+            .unwrap();
+        assert_eq!(forgotten, 1);
+        let outcome = restarted
+            .dispatch_unpacked(&wallet, message, None, _recipient_key())
+            .await
+            .unwrap();
+        assert!(matches!(outcome, DispatchOutcome::Handled));
+        assert_eq!(handler.received.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_handling_is_reported_and_retried_on_redelivery() {
+        let wallet = _wallet().await;
+        let connections = Arc::new(StubConnections::default());
+        let handler = Arc::new(RecordingHandler::default());
+        let mut dispatcher = Dispatcher::new(connections.clone());
+        dispatcher.register_handler(MessageFamily::Revocation, handler.clone());
+
+        let decorators = RevokeDecorators::builder()
+            .please_ack(PleaseAck::builder().on(vec![AckOn::Outcome]).build())
+            .build();
+        let message = _revoke(decorators);
+        handler.failing.store(true, Ordering::SeqCst);
+        let err = dispatcher
+            .dispatch_unpacked(&wallet, message.clone(), None, _recipient_key())
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidRevocationDetails);
+        {
+            let sent = connections.sent.lock().unwrap();
+            let [(_, AriesMessage::ReportProblem(problem_report))] = &sent[..] else {
+                panic!("Expected the failure to be reported, sent: {:?}", sent);
+            };
+            assert_eq!(
+                problem_report.decorators.thread.as_ref().unwrap().thid,
+                "revoke-1"
+            );
+        }
+
+        handler.failing.store(false, Ordering::SeqCst);
+        let outcome = dispatcher
+            .dispatch_unpacked(&wallet, message, None, _recipient_key())
+            .await
+            .unwrap();
+        assert!(matches!(outcome, DispatchOutcome::Handled));
+        assert_eq!(handler.received.lock().unwrap().len(), 2);
+        assert_eq!(_sent_ack_statuses(&connections), vec![AckStatus::Ok]);
+    }
+}
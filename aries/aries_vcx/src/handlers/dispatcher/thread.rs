@@ -0,0 +1,165 @@
+use messages::{
+    decorators::thread::Thread,
+    msg_fields::protocols::{
+        action_menu::ActionMenu,
+        connection::Connection,
+        coordinate_mediation::{v2::CoordinateMediationV2, CoordinateMediation},
+        cred_issuance::{v1::CredentialIssuanceV1, v2::CredentialIssuanceV2, CredentialIssuance},
+        did_exchange::{v1_0::DidExchangeV1_0, v1_1::DidExchangeV1_1, DidExchange},
+        discover_features::DiscoverFeatures,
+        notification::Notification,
+        out_of_band::OutOfBand,
+        pickup::Pickup,
+        present_proof::{v1::PresentProofV1, v2::PresentProofV2, PresentProof},
+        question_answer::QuestionAnswer,
+        revocation::Revocation,
+        transactions::Transactions,
+        trust_ping::TrustPing,
+    },
+    AriesMessage,
+};
+
+macro_rules! with_thread {
+    ($msg:expr) => {
+        ($msg.id.as_str(), Some(&$msg.decorators.thread))
+    };
+}
+
+macro_rules! with_opt_thread {
+    ($msg:expr) => {
+        ($msg.id.as_str(), $msg.decorators.thread.as_ref())
+    };
+}
+
+macro_rules! without_thread {
+    ($msg:expr) => {
+        ($msg.id.as_str(), None)
+    };
+}
+
+/// Thread an inbound message belongs to, as used for correlating it with a protocol instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThreadIds {
+    /// The `~thread.thid` of the message, or its own id if it starts a new thread.
+    pub thid: String,
+    pub pthid: Option<String>,
+}
+
+impl ThreadIds {
+    pub fn of(message: &AriesMessage) -> Self {
+        let (id, thread) = id_and_thread(message);
+        match thread {
+            Some(thread) => Self {
+                thid: thread.thid.clone(),
+                pthid: thread.pthid.clone(),
+            },
+            None => Self {
+                thid: id.to_owned(),
+                pthid: None,
+            },
+        }
+    }
+}
+
+fn id_and_thread(message: &AriesMessage) -> (&str, Option<&Thread>) {
+    match message {
+        AriesMessage::BasicMessage(msg) => with_opt_thread!(msg),
+        AriesMessage::Connection(Connection::Invitation(msg)) => without_thread!(msg),
+        AriesMessage::Connection(Connection::ProblemReport(msg)) => with_thread!(msg),
+        AriesMessage::Connection(Connection::Request(msg)) => with_opt_thread!(msg),
+        AriesMessage::Connection(Connection::Response(msg)) => with_thread!(msg),
+        AriesMessage::CredentialIssuance(CredentialIssuance::V1(msg)) => match msg {
+            CredentialIssuanceV1::Ack(msg) => with_thread!(msg),
+            CredentialIssuanceV1::IssueCredential(msg) => with_thread!(msg),
+            CredentialIssuanceV1::OfferCredential(msg) => with_opt_thread!(msg),
+            CredentialIssuanceV1::ProposeCredential(msg) => with_opt_thread!(msg),
+            CredentialIssuanceV1::RequestCredential(msg) => with_opt_thread!(msg),
+            CredentialIssuanceV1::ProblemReport(msg) => with_opt_thread!(msg),
+        },
+        AriesMessage::CredentialIssuance(CredentialIssuance::V2(msg)) => match msg {
+            CredentialIssuanceV2::Ack(msg) => with_thread!(msg),
+            CredentialIssuanceV2::IssueCredential(msg) => with_thread!(msg),
+            CredentialIssuanceV2::OfferCredential(msg) => with_opt_thread!(msg),
+            CredentialIssuanceV2::ProposeCredential(msg) => with_opt_thread!(msg),
+            CredentialIssuanceV2::RequestCredential(msg) => with_opt_thread!(msg),
+            CredentialIssuanceV2::ProblemReport(msg) => with_opt_thread!(msg),
+        },
+        AriesMessage::DiscoverFeatures(DiscoverFeatures::Query(msg)) => without_thread!(msg),
+        AriesMessage::DiscoverFeatures(DiscoverFeatures::Disclose(msg)) => with_thread!(msg),
+        AriesMessage::Notification(Notification::Ack(msg)) => with_thread!(msg),
+        AriesMessage::Notification(Notification::ProblemReport(msg)) => with_opt_thread!(msg),
+        AriesMessage::OutOfBand(OutOfBand::Invitation(msg)) => without_thread!(msg),
+        AriesMessage::OutOfBand(OutOfBand::HandshakeReuse(msg)) => with_thread!(msg),
+        AriesMessage::OutOfBand(OutOfBand::HandshakeReuseAccepted(msg)) => with_thread!(msg),
+        AriesMessage::PresentProof(PresentProof::V1(msg)) => match msg {
+            PresentProofV1::Ack(msg) => with_thread!(msg),
+            PresentProofV1::Presentation(msg) => with_thread!(msg),
+            PresentProofV1::ProposePresentation(msg) => with_opt_thread!(msg),
+            PresentProofV1::RequestPresentation(msg) => with_opt_thread!(msg),
+            PresentProofV1::ProblemReport(msg) => with_opt_thread!(msg),
+        },
+        AriesMessage::PresentProof(PresentProof::V2(msg)) => match msg {
+            PresentProofV2::Ack(msg) => with_thread!(msg),
+            PresentProofV2::Presentation(msg) => with_thread!(msg),
+            PresentProofV2::ProposePresentation(msg) => with_opt_thread!(msg),
+            PresentProofV2::RequestPresentation(msg) => with_opt_thread!(msg),
+            PresentProofV2::ProblemReport(msg) => with_opt_thread!(msg),
+        },
+        AriesMessage::ReportProblem(msg) => with_opt_thread!(msg),
+        AriesMessage::Revocation(Revocation::Revoke(msg)) => with_opt_thread!(msg),
+        AriesMessage::Revocation(Revocation::Ack(msg)) => with_thread!(msg),
+        AriesMessage::Routing(msg) => without_thread!(msg),
+        AriesMessage::TrustPing(TrustPing::Ping(msg)) => with_opt_thread!(msg),
+        AriesMessage::TrustPing(TrustPing::PingResponse(msg)) => with_thread!(msg),
+        AriesMessage::Pickup(msg) => match msg {
+            Pickup::Status(msg) => with_opt_thread!(msg),
+            Pickup::StatusRequest(msg) => with_opt_thread!(msg),
+            Pickup::Delivery(msg) => with_opt_thread!(msg),
+            Pickup::DeliveryRequest(msg) => with_opt_thread!(msg),
+            Pickup::MessagesReceived(msg) => with_opt_thread!(msg),
+            Pickup::LiveDeliveryChange(msg) => with_opt_thread!(msg),
+        },
+        AriesMessage::CoordinateMediation(msg) => match msg {
+            CoordinateMediation::MediateRequest(msg) => with_opt_thread!(msg),
+            CoordinateMediation::MediateDeny(msg) => with_opt_thread!(msg),
+            CoordinateMediation::MediateGrant(msg) => with_opt_thread!(msg),
+            CoordinateMediation::KeylistUpdate(msg) => with_opt_thread!(msg),
+            CoordinateMediation::KeylistUpdateResponse(msg) => with_opt_thread!(msg),
+            CoordinateMediation::KeylistQuery(msg) => with_opt_thread!(msg),
+            CoordinateMediation::Keylist(msg) => with_opt_thread!(msg),
+        },
+        AriesMessage::CoordinateMediationV2(msg) => match msg {
+            CoordinateMediationV2::MediateRequest(msg) => without_thread!(msg),
+            CoordinateMediationV2::MediateDeny(msg) => with_opt_thread!(msg),
+            CoordinateMediationV2::MediateGrant(msg) => with_opt_thread!(msg),
+            CoordinateMediationV2::RecipientUpdate(msg) => without_thread!(msg),
+            CoordinateMediationV2::RecipientUpdateResponse(msg) => with_opt_thread!(msg),
+            CoordinateMediationV2::RecipientQuery(msg) => without_thread!(msg),
+            CoordinateMediationV2::Recipient(msg) => with_opt_thread!(msg),
+        },
+        AriesMessage::DidExchange(DidExchange::V1_0(msg)) => match msg {
+            DidExchangeV1_0::Request(msg) => with_opt_thread!(msg),
+            DidExchangeV1_0::Response(msg) => with_thread!(msg),
+            DidExchangeV1_0::Complete(msg) => with_thread!(msg),
+            DidExchangeV1_0::ProblemReport(msg) => with_thread!(msg),
+        },
+        AriesMessage::DidExchange(DidExchange::V1_1(msg)) => match msg {
+            DidExchangeV1_1::Request(msg) => with_opt_thread!(msg),
+            DidExchangeV1_1::Response(msg) => with_thread!(msg),
+            DidExchangeV1_1::Complete(msg) => with_thread!(msg),
+            DidExchangeV1_1::ProblemReport(msg) => with_thread!(msg),
+        },
+        AriesMessage::ActionMenu(msg) => match msg {
+            ActionMenu::Menu(msg) => with_opt_thread!(msg),
+            ActionMenu::MenuRequest(msg) => with_opt_thread!(msg),
+            ActionMenu::Perform(msg) => with_opt_thread!(msg),
+        },
+        AriesMessage::QuestionAnswer(QuestionAnswer::Question(msg)) => with_opt_thread!(msg),
+        AriesMessage::QuestionAnswer(QuestionAnswer::Answer(msg)) => with_thread!(msg),
+        AriesMessage::Transactions(msg) => match msg {
+            Transactions::Request(msg) => with_opt_thread!(msg),
+            Transactions::Endorse(msg) => with_thread!(msg),
+            Transactions::Refuse(msg) => with_thread!(msg),
+        },
+    }
+}
@@ -0,0 +1,337 @@
+use aries_vcx_ledger::ledger::base_ledger::IndyLedgerWrite;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use did_parser_nom::Did;
+use messages::msg_fields::protocols::transactions::{
+    endorse::EndorseTransaction, refuse::RefuseTransaction, request::TransactionRequest,
+};
+
+use super::util::matches_thread_id;
+use crate::{
+    common::ledger::endorsement::{endorse_and_submit, EndorsementRequest},
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::{
+        endorsement::{
+            build_endorse_transaction, build_refuse_transaction, build_transaction_request,
+            extract_endorsement_request,
+        },
+        SendClosure,
+    },
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionState {
+    Pending,
+    Endorsed,
+    Refused,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionAuthor {
+    endorsement_request: EndorsementRequest,
+    request: TransactionRequest,
+    state: TransactionState,
+    refusal_comment: Option<String>,
+}
+
+impl TransactionAuthor {
+    /// Wraps a prepared ledger request into a `transactions` request message, asking the
+    /// endorser to endorse it and write it to the ledger.
+    pub fn build(endorsement_request: EndorsementRequest) -> VcxResult<Self> {
+        let request = build_transaction_request(&endorsement_request)?;
+        Ok(Self {
+            endorsement_request,
+            request,
+            state: TransactionState::Pending,
+            refusal_comment: None,
+        })
+    }
+
+    pub fn get_request(&self) -> &TransactionRequest {
+        &self.request
+    }
+
+    pub fn get_endorsement_request(&self) -> &EndorsementRequest {
+        &self.endorsement_request
+    }
+
+    pub fn get_state(&self) -> TransactionState {
+        self.state
+    }
+
+    pub fn get_refusal_comment(&self) -> Option<&str> {
+        self.refusal_comment.as_deref()
+    }
+
+    pub fn get_thread_id(&self) -> &str {
+        self.request
+            .decorators
+            .thread
+            .as_ref()
+            .map(|t| t.thid.as_str())
+            .unwrap_or(self.request.id.as_str())
+    }
+
+    pub async fn send_request(&self, send_message: SendClosure<'_>) -> VcxResult<()> {
+        send_message(self.request.clone().into()).await
+    }
+
+    fn ensure_response_expected(&self, thread_matches: bool, endorser_did: &str) -> VcxResult<()> {
+        if !thread_matches {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Thread ID mismatch",
+            ));
+        }
+        if self.state != TransactionState::Pending {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!("Transaction has already been {:?}", self.state),
+            ));
+        }
+        if endorser_did != self.endorsement_request.endorser_did.to_string() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!(
+                    "Transaction was sent to endorser {}, but {} responded",
+                    self.endorsement_request.endorser_did, endorser_did
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn handle_endorse(&mut self, endorse: EndorseTransaction) -> VcxResult<()> {
+        self.ensure_response_expected(
+            matches_thread_id!(endorse, self.get_thread_id()),
+            &endorse.content.endorser_did,
+        )?;
+        self.state = TransactionState::Endorsed;
+        Ok(())
+    }
+
+    pub fn handle_refuse(&mut self, refuse: RefuseTransaction) -> VcxResult<()> {
+        self.ensure_response_expected(
+            matches_thread_id!(refuse, self.get_thread_id()),
+            &refuse.content.endorser_did,
+        )?;
+        self.state = TransactionState::Refused;
+        self.refusal_comment = refuse.content.comment;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TransactionEndorser {
+    request: TransactionRequest,
+    endorsement_request: EndorsementRequest,
+    state: TransactionState,
+}
+
+impl TransactionEndorser {
+    pub fn from_request(request: TransactionRequest) -> VcxResult<Self> {
+        let endorsement_request = extract_endorsement_request(&request)?;
+        Ok(Self {
+            request,
+            endorsement_request,
+            state: TransactionState::Pending,
+        })
+    }
+
+    /// The transaction the author asks to endorse, to be reviewed before endorsing it.
+    pub fn get_endorsement_request(&self) -> &EndorsementRequest {
+        &self.endorsement_request
+    }
+
+    pub fn get_state(&self) -> TransactionState {
+        self.state
+    }
+
+    fn ensure_pending(&self) -> VcxResult<()> {
+        if self.state != TransactionState::Pending {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                format!("Transaction has already been {:?}", self.state),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Signs the transaction as `endorser_did`, writes it to the ledger and lets the author
+    /// know.
+    pub async fn endorse(
+        &mut self,
+        wallet: &impl BaseWallet,
+        indy_ledger_write: &impl IndyLedgerWrite,
+        endorser_did: &Did,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<()> {
+        self.ensure_pending()?;
+        if !self.request.content.endorser_write_txn {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::ActionNotSupported,
+                "Only transactions written to the ledger by the endorser are supported",
+            ));
+        }
+        let endorsed_request = endorse_and_submit(
+            wallet,
+            indy_ledger_write,
+            endorser_did,
+            &self.endorsement_request,
+        )
+        .await?;
+        self.state = TransactionState::Endorsed;
+
+        let endorse =
+            build_endorse_transaction(&self.request, &endorser_did.to_string(), &endorsed_request);
+        send_message(endorse.into()).await
+    }
+
+    pub async fn refuse(
+        &mut self,
+        endorser_did: &Did,
+        comment: Option<String>,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<()> {
+        self.ensure_pending()?;
+        let refuse = build_refuse_transaction(&self.request, &endorser_did.to_string(), comment);
+        send_message(refuse.into()).await?;
+        self.state = TransactionState::Refused;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use messages::{
+        decorators::attachment::AttachmentType, msg_fields::protocols::transactions::Transactions,
+        AriesMessage,
+    };
+    use serde_json::{json, Value};
+    use test_utils::{
+        constants::{DID, REQUEST_WITH_ENDORSER},
+        devsetup::build_setup_profile,
+        mockdata::mock_ledger::MockLedger,
+    };
+
+    use super::*;
+    use crate::{
+        common::ledger::endorsement::EndorsedTransactionType, protocols::test_utils::SentMessages,
+    };
+
+    const ENDORSER_DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+
+    fn _author() -> TransactionAuthor {
+        let request = json!({
+            "identifier": DID,
+            "endorser": ENDORSER_DID,
+            "operation": { "type": "101" },
+            "protocolVersion": 2,
+            "reqId": 1,
+            "signatures": { DID: "author_signature" }
+        });
+        TransactionAuthor::build(EndorsementRequest {
+            author_did: DID.parse().unwrap(),
+            endorser_did: ENDORSER_DID.parse().unwrap(),
+            transaction_type: EndorsedTransactionType::Schema,
+            request: request.to_string(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_author_attaches_raw_ledger_request() {
+        let author = _author();
+        let AttachmentType::Json(attached) =
+            &author.get_request().content.messages_attach[0].data.content
+        else {
+            panic!("expected a json attachment");
+        };
+        assert_eq!(
+            attached,
+            &Value::String(author.get_endorsement_request().request.clone())
+        );
+    }
+
+    #[test]
+    fn test_endorser_extracts_attached_transaction() {
+        let author = _author();
+        let endorser = TransactionEndorser::from_request(author.get_request().clone()).unwrap();
+        assert_eq!(
+            endorser.get_endorsement_request(),
+            author.get_endorsement_request()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_author_handles_endorse() {
+        let setup = build_setup_profile().await;
+        let mut author = _author();
+        let mut endorser = TransactionEndorser::from_request(author.get_request().clone()).unwrap();
+
+        let sent = SentMessages::default();
+        endorser
+            .endorse(
+                &setup.wallet,
+                &MockLedger,
+                &ENDORSER_DID.parse().unwrap(),
+                sent.send_closure(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(endorser.get_state(), TransactionState::Endorsed);
+
+        let Some(AriesMessage::Transactions(Transactions::Endorse(endorse))) = sent.last() else {
+            panic!("expected an endorse message");
+        };
+        assert_eq!(
+            endorse.content.signature_response[0].signature[ENDORSER_DID],
+            REQUEST_WITH_ENDORSER
+        );
+        author.handle_endorse(endorse.clone()).unwrap();
+        assert_eq!(author.get_state(), TransactionState::Endorsed);
+        author.handle_endorse(endorse).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_author_handles_refuse() {
+        let mut author = _author();
+        let mut endorser = TransactionEndorser::from_request(author.get_request().clone()).unwrap();
+
+        let sent = SentMessages::default();
+        endorser
+            .refuse(
+                &ENDORSER_DID.parse().unwrap(),
+                Some("Unknown author".to_owned()),
+                sent.send_closure(),
+            )
+            .await
+            .unwrap();
+
+        let Some(AriesMessage::Transactions(Transactions::Refuse(refuse))) = sent.last() else {
+            panic!("expected a refuse message");
+        };
+        author.handle_refuse(refuse).unwrap();
+        assert_eq!(author.get_state(), TransactionState::Refused);
+        assert_eq!(author.get_refusal_comment(), Some("Unknown author"));
+    }
+
+    #[tokio::test]
+    async fn test_should_not_endorse_as_other_endorser() {
+        let setup = build_setup_profile().await;
+        let author = _author();
+        let mut endorser = TransactionEndorser::from_request(author.get_request().clone()).unwrap();
+
+        let sent = SentMessages::default();
+        endorser
+            .endorse(
+                &setup.wallet,
+                &MockLedger,
+                &DID.parse().unwrap(),
+                sent.send_closure(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(endorser.get_state(), TransactionState::Pending);
+        assert!(sent.is_empty());
+    }
+}
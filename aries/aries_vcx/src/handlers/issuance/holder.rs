@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
 use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
@@ -17,7 +19,10 @@ use messages::{
             },
             CredentialIssuance,
         },
-        notification::ack::{AckContent, AckDecorators, AckStatus},
+        notification::{
+            ack::{AckContent, AckDecorators, AckStatus},
+            Notification,
+        },
         report_problem::ProblemReport,
         revocation::revoke::Revoke,
     },
@@ -26,10 +31,17 @@ use messages::{
 use uuid::Uuid;
 
 use crate::{
-    common::credentials::get_cred_rev_id,
+    common::{credentials::get_cred_rev_id, primitives::tails_file_manager::TailsFileManager},
     errors::error::prelude::*,
-    handlers::revocation_notification::receiver::RevocationNotificationReceiver,
-    protocols::issuance::holder::state_machine::{HolderFullState, HolderSM, HolderState},
+    handlers::{
+        connectionless::ConnectionlessExchange,
+        revocation_notification::receiver::RevocationNotificationReceiver,
+    },
+    protocols::{
+        issuance::holder::state_machine::{HolderFullState, HolderSM, HolderState},
+        problem_report::ReceivedProblem,
+    },
+    transport::Transport,
 };
 
 fn build_credential_ack(thread_id: &str) -> AckCredentialV1 {
@@ -51,13 +63,18 @@ fn build_credential_ack(thread_id: &str) -> AckCredentialV1 {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Holder {
     holder_sm: HolderSM,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    problem: Option<ReceivedProblem>,
 }
 
 impl Holder {
     pub fn create(source_id: &str) -> VcxResult<Holder> {
         trace!("Holder::create >>> source_id: {:?}", source_id);
         let holder_sm = HolderSM::new(source_id.to_string());
-        Ok(Holder { holder_sm })
+        Ok(Holder {
+            holder_sm,
+            problem: None,
+        })
     }
 
     pub fn get_proposal(&self) -> VcxResult<ProposeCredentialV1> {
@@ -74,7 +91,10 @@ impl Holder {
             propose_credential
         );
         let holder_sm = HolderSM::with_proposal(propose_credential, source_id.to_string());
-        Ok(Holder { holder_sm })
+        Ok(Holder {
+            holder_sm,
+            problem: None,
+        })
     }
 
     pub fn create_from_offer(
@@ -87,7 +107,10 @@ impl Holder {
             credential_offer
         );
         let holder_sm = HolderSM::from_offer(credential_offer, source_id.to_string());
-        Ok(Holder { holder_sm })
+        Ok(Holder {
+            holder_sm,
+            problem: None,
+        })
     }
 
     pub fn set_proposal(&mut self, credential_proposal: ProposeCredentialV1) -> VcxResult<()> {
@@ -118,6 +141,23 @@ impl Holder {
         }
     }
 
+    /// Sends the credential request, or the problem report if it couldn't be prepared, to the
+    /// service of the issuer, for an offer received without a connection.
+    pub async fn send_credential_request_connectionless(
+        &mut self,
+        wallet: &impl BaseWallet,
+        ledger: &impl AnoncredsLedgerRead,
+        anoncreds: &impl BaseAnonCreds,
+        my_pw_did: Did,
+        exchange: &ConnectionlessExchange,
+        transport: &impl Transport,
+    ) -> VcxResult<()> {
+        let message = self
+            .prepare_credential_request(wallet, ledger, anoncreds, my_pw_did)
+            .await?;
+        exchange.send(wallet, message, transport).await
+    }
+
     pub fn get_msg_credential_request(&self) -> VcxResult<RequestCredentialV1> {
         match self.holder_sm.state {
             HolderFullState::RequestSet(ref state) => {
@@ -156,6 +196,25 @@ impl Holder {
         Ok(())
     }
 
+    /// Stores the credential received over the connectionless exchange, acknowledging it to the
+    /// service of the issuer when the issuer asked for an ack.
+    pub async fn process_credential_connectionless(
+        &mut self,
+        wallet: &impl BaseWallet,
+        ledger: &impl AnoncredsLedgerRead,
+        anoncreds: &impl BaseAnonCreds,
+        exchange: &ConnectionlessExchange,
+        credential: IssueCredentialV1,
+        transport: &impl Transport,
+    ) -> VcxResult<()> {
+        self.process_credential(wallet, ledger, anoncreds, credential)
+            .await?;
+        if let Some(ack) = self.get_final_message()? {
+            exchange.send(wallet, ack, transport).await?;
+        }
+        Ok(())
+    }
+
     pub fn is_terminal_state(&self) -> bool {
         self.holder_sm.is_terminal_state()
     }
@@ -192,6 +251,17 @@ impl Holder {
         self.holder_sm.get_tails_hash()
     }
 
+    /// Makes sure the tails file of the revocation registry of the received credential is
+    /// stored by the `tails_file_manager`, downloading it if needed, and returns its path.
+    pub async fn fetch_tails_file(
+        &self,
+        tails_file_manager: &TailsFileManager,
+    ) -> VcxResult<PathBuf> {
+        tails_file_manager
+            .ensure_tails_file(&self.get_tails_location()?, &self.get_tails_hash()?)
+            .await
+    }
+
     pub fn get_rev_reg_id(&self) -> VcxResult<String> {
         self.holder_sm.get_rev_reg_id()
     }
@@ -261,10 +331,30 @@ impl Holder {
         }
     }
 
+    pub async fn receive_problem_report(&mut self, problem_report: ProblemReport) -> VcxResult<()> {
+        let problem = ReceivedProblem::from(&problem_report);
+        let state = self.get_state();
+        self.holder_sm = self
+            .holder_sm
+            .clone()
+            .receive_problem_report(problem_report)?;
+        // reports received in states they can't end the issuance in are ignored
+        if self.get_state() != state {
+            self.problem = Some(problem);
+        }
+        Ok(())
+    }
+
+    /// Problem report the issuance ended with, whether sent or received.
     pub fn get_problem_report(&self) -> VcxResult<ProblemReport> {
         self.holder_sm.get_problem_report()
     }
 
+    /// The problem the issuer reported, if it ended the issuance with a problem report.
+    pub fn get_problem(&self) -> Option<ReceivedProblem> {
+        self.problem.clone()
+    }
+
     pub async fn process_aries_msg(
         &mut self,
         wallet: &impl BaseWallet,
@@ -284,9 +374,16 @@ impl Holder {
                     .receive_credential(wallet, ledger, anoncreds, credential)
                     .await?
             }
-            // TODO: What about credential issuance problem report?
             AriesMessage::ReportProblem(report) => {
-                self.holder_sm.clone().receive_problem_report(report)?
+                return self.receive_problem_report(report).await;
+            }
+            AriesMessage::Notification(Notification::ProblemReport(report)) => {
+                return self.receive_problem_report(report.into()).await;
+            }
+            AriesMessage::CredentialIssuance(CredentialIssuance::V1(
+                CredentialIssuanceV1::ProblemReport(report),
+            )) => {
+                return self.receive_problem_report(report.into()).await;
             }
             _ => self.holder_sm.clone(),
         };
@@ -1,6 +1,7 @@
 use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
-use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_ledger::ledger::base_ledger::{AnoncredsLedgerRead, AnoncredsLedgerWrite};
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use chrono::Utc;
 use messages::{
     misc::MimeType,
     msg_fields::protocols::{
@@ -20,14 +21,23 @@ use messages::{
 };
 
 use crate::{
+    common::primitives::{
+        revocation_registry_manager::RevocationRegistryManager, tails_server::TailsServerClient,
+    },
     errors::error::prelude::*,
-    handlers::util::OfferInfo,
-    protocols::issuance::issuer::state_machine::{IssuerSM, IssuerState, RevocationInfoV1},
+    handlers::{connectionless::ConnectionlessExchange, util::OfferInfo},
+    protocols::{
+        issuance::issuer::state_machine::{IssuerSM, IssuerState, RevocationInfoV1},
+        problem_report::ReceivedProblem,
+    },
+    transport::Transport,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Issuer {
     issuer_sm: IssuerSM,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    problem: Option<ReceivedProblem>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -122,7 +132,10 @@ impl Issuer {
     pub fn create(source_id: &str) -> VcxResult<Issuer> {
         trace!("Issuer::create >>> source_id: {:?}", source_id);
         let issuer_sm = IssuerSM::new(source_id);
-        Ok(Issuer { issuer_sm })
+        Ok(Issuer {
+            issuer_sm,
+            problem: None,
+        })
     }
 
     pub fn create_from_proposal(
@@ -135,7 +148,10 @@ impl Issuer {
             credential_proposal
         );
         let issuer_sm = IssuerSM::from_proposal(source_id, credential_proposal);
-        Ok(Issuer { issuer_sm })
+        Ok(Issuer {
+            issuer_sm,
+            problem: None,
+        })
     }
 
     // todo: "build_credential_offer_msg" should take optional revReg as parameter, build OfferInfo
@@ -160,6 +176,33 @@ impl Issuer {
         Ok(())
     }
 
+    /// Builds the credential offer against the active revocation registry of the manager,
+    /// which creates and publishes the next registry when needed.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn build_credential_offer_msg_with_rev_reg_manager(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        ledger_write: &impl AnoncredsLedgerWrite,
+        tails_server: &impl TailsServerClient,
+        rev_reg_manager: &mut RevocationRegistryManager,
+        credential_json: String,
+        comment: Option<String>,
+    ) -> VcxResult<()> {
+        let rev_reg = rev_reg_manager
+            .active_registry(wallet, anoncreds, ledger_write, tails_server)
+            .await?;
+        let (rev_reg_id, tails_dir) = (rev_reg.get_rev_reg_id(), rev_reg.get_tails_dir());
+        let offer_info = OfferInfo::new(
+            credential_json,
+            rev_reg_manager.get_cred_def_id().to_owned(),
+            Some(rev_reg_id),
+            Some(tails_dir),
+        );
+        self.build_credential_offer_msg(wallet, anoncreds, offer_info, comment)
+            .await
+    }
+
     pub fn get_credential_offer(&self) -> VcxResult<OfferCredentialV1> {
         self.issuer_sm.get_credential_offer_msg()
     }
@@ -169,6 +212,15 @@ impl Issuer {
         Ok(offer.into())
     }
 
+    /// Returns the credential offer decorated with our `~service`, to be attached to an
+    /// out-of-band invitation and answered without a connection.
+    pub fn get_credential_offer_msg_connectionless(
+        &self,
+        exchange: &ConnectionlessExchange,
+    ) -> VcxResult<AriesMessage> {
+        exchange.decorate(self.get_credential_offer_msg()?)
+    }
+
     pub fn process_credential_request(&mut self, request: RequestCredentialV1) -> VcxResult<()> {
         self.issuer_sm = self.issuer_sm.clone().receive_request(request)?;
         Ok(())
@@ -192,6 +244,38 @@ impl Issuer {
         Ok(())
     }
 
+    /// Builds the credential and records its revocation index with the manager, so that full
+    /// registries get rotated out.
+    pub async fn build_credential_with_rev_reg_manager(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        rev_reg_manager: &mut RevocationRegistryManager,
+    ) -> VcxResult<()> {
+        self.build_credential(wallet, anoncreds).await?;
+        if self.is_revokable() {
+            rev_reg_manager.record_issuance(&self.get_rev_reg_id()?, self.get_revocation_id()?)?;
+        }
+        Ok(())
+    }
+
+    /// Builds the credential for the request received over the connectionless exchange and
+    /// sends it, or the problem report if it couldn't be built, to the service of the holder.
+    pub async fn send_credential_connectionless(
+        &mut self,
+        wallet: &impl BaseWallet,
+        anoncreds: &impl BaseAnonCreds,
+        exchange: &ConnectionlessExchange,
+        transport: &impl Transport,
+    ) -> VcxResult<()> {
+        self.build_credential(wallet, anoncreds).await?;
+        let message = match self.get_state() {
+            IssuerState::CredentialSet => self.get_msg_issue_credential()?.into(),
+            _ => self.get_problem_report()?.into(),
+        };
+        exchange.send(wallet, message, transport).await
+    }
+
     pub fn get_msg_issue_credential(&mut self) -> VcxResult<IssueCredentialV1> {
         self.issuer_sm.clone().get_msg_issue_credential()
     }
@@ -241,17 +325,16 @@ impl Issuer {
             revocation_info.rev_reg_id,
             revocation_info.tails_file,
         ) {
-            #[allow(deprecated)] // TODO - https://github.com/hyperledger/aries-vcx/issues/1309
-            let rev_reg_delta_json = ledger
-                .get_rev_reg_delta_json(&rev_reg_id.to_owned().try_into()?, None, None)
-                .await?
-                .0;
+            let rev_reg_id = rev_reg_id.try_into()?;
+            let (ledger_rev_status_list, _) = ledger
+                .get_rev_status_list(&rev_reg_id, Utc::now().timestamp() as u64, None)
+                .await?;
             anoncreds
                 .revoke_credential_local(
                     wallet,
-                    &rev_reg_id.try_into()?,
+                    &rev_reg_id,
                     cred_rev_id.parse()?,
-                    rev_reg_delta_json,
+                    ledger_rev_status_list,
                 )
                 .await?;
         } else {
@@ -307,17 +390,29 @@ impl Issuer {
     }
 
     pub async fn receive_problem_report(&mut self, problem_report: ProblemReport) -> VcxResult<()> {
+        let problem = ReceivedProblem::from(&problem_report);
+        let state = self.get_state();
         self.issuer_sm = self
             .issuer_sm
             .clone()
             .receive_problem_report(problem_report)?;
+        // reports received in states they can't end the issuance in are ignored
+        if self.get_state() != state {
+            self.problem = Some(problem);
+        }
         Ok(())
     }
 
+    /// Problem report the issuance ended with, whether sent or received.
     pub fn get_problem_report(&self) -> VcxResult<ProblemReport> {
         self.issuer_sm.get_problem_report()
     }
 
+    /// The problem the holder reported, if it ended the issuance with a problem report.
+    pub fn get_problem(&self) -> Option<ReceivedProblem> {
+        self.problem.clone()
+    }
+
     // todo: will ultimately end up in generic SM layer
     pub async fn process_aries_msg(&mut self, msg: AriesMessage) -> VcxResult<()> {
         let issuer_sm = match msg {
@@ -331,18 +426,16 @@ impl Issuer {
                 CredentialIssuanceV1::Ack(ack),
             )) => self.issuer_sm.clone().receive_ack(ack)?,
             AriesMessage::ReportProblem(report) => {
-                self.issuer_sm.clone().receive_problem_report(report)?
+                return self.receive_problem_report(report).await;
+            }
+            AriesMessage::Notification(Notification::ProblemReport(report)) => {
+                return self.receive_problem_report(report.into()).await;
             }
-            AriesMessage::Notification(Notification::ProblemReport(report)) => self
-                .issuer_sm
-                .clone()
-                .receive_problem_report(report.into())?,
             AriesMessage::CredentialIssuance(CredentialIssuance::V1(
                 CredentialIssuanceV1::ProblemReport(report),
-            )) => self
-                .issuer_sm
-                .clone()
-                .receive_problem_report(report.into())?,
+            )) => {
+                return self.receive_problem_report(report.into()).await;
+            }
             _ => self.issuer_sm.clone(),
         };
         self.issuer_sm = issuer_sm;
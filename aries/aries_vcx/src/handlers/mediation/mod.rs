@@ -0,0 +1,2 @@
+pub mod pickup;
+pub mod recipient;
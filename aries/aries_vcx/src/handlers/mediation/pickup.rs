@@ -0,0 +1,152 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use messages::{
+    decorators::attachment::AttachmentType,
+    msg_fields::protocols::pickup::{Delivery, Status},
+};
+
+use crate::{
+    errors::error::prelude::*,
+    protocols::{
+        mediation::{build_delivery_request, build_messages_received, build_status_request},
+        SendClosure,
+    },
+    utils::base64::URL_SAFE_LENIENT,
+};
+
+/// A message held by the mediator, as delivered over the pickup protocol. The payload is
+/// still packed for the recipient.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickedUpMessage {
+    pub id: String,
+    pub packed: Vec<u8>,
+}
+
+/// Client side of the pickup protocol (Aries RFC 0685), used to fetch the messages a mediator
+/// queued for us.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MessagePickup {
+    recipient_key: Option<String>,
+    message_count: Option<u32>,
+    // Ids of delivered messages which have not been acknowledged to the mediator yet.
+    unacknowledged: Vec<String>,
+}
+
+impl MessagePickup {
+    /// Creates a pickup client. If `recipient_key` is provided, only messages for this key are
+    /// requested, otherwise all messages queued for the recipient.
+    pub fn new(recipient_key: Option<String>) -> Self {
+        Self {
+            recipient_key,
+            ..Default::default()
+        }
+    }
+
+    /// Message count reported by the most recent status message.
+    pub fn get_message_count(&self) -> Option<u32> {
+        self.message_count
+    }
+
+    pub async fn send_status_request(&self, send_message: SendClosure<'_>) -> VcxResult<()> {
+        send_message(build_status_request(self.recipient_key.clone()).into()).await
+    }
+
+    pub fn handle_status(&mut self, status: &Status) -> VcxResult<u32> {
+        if status.content.recipient_key.is_some()
+            && status.content.recipient_key != self.recipient_key
+        {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                "Status was reported for an unexpected recipient key",
+            ));
+        }
+        self.message_count = Some(status.content.message_count);
+        Ok(status.content.message_count)
+    }
+
+    pub async fn send_delivery_request(
+        &self,
+        limit: u32,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<()> {
+        send_message(build_delivery_request(limit, self.recipient_key.clone()).into()).await
+    }
+
+    /// Extracts the packed messages from a delivery. Their ids are remembered until
+    /// acknowledged with [`Self::send_messages_received`].
+    pub fn handle_delivery(&mut self, delivery: &Delivery) -> VcxResult<Vec<PickedUpMessage>> {
+        let mut messages = Vec::with_capacity(delivery.content.attach.len());
+        for attach in delivery.content.attach.iter() {
+            let Some(id) = attach.id.clone() else {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidMessages,
+                    "Delivered attachment has no id",
+                ));
+            };
+            let packed = match &attach.data.content {
+                // RFC 0017 mandates base64url, but some mediators deliver standard base64
+                AttachmentType::Base64(encoded) => URL_SAFE_LENIENT
+                    .decode(encoded)
+                    .or_else(|_| STANDARD.decode(encoded))?,
+                AttachmentType::Json(json) => serde_json::to_vec(json)?,
+                _ => {
+                    return Err(AriesVcxError::from_msg(
+                        AriesVcxErrorKind::InvalidMessages,
+                        format!("Delivered attachment {id} has unsupported content"),
+                    ))
+                }
+            };
+            messages.push(PickedUpMessage { id, packed });
+        }
+        self.unacknowledged
+            .extend(messages.iter().map(|message| message.id.clone()));
+        Ok(messages)
+    }
+
+    /// Acknowledges all delivered messages, allowing the mediator to discard them.
+    pub async fn send_messages_received(&mut self, send_message: SendClosure<'_>) -> VcxResult<()> {
+        if self.unacknowledged.is_empty() {
+            return Ok(());
+        }
+        let messages_received = build_messages_received(self.unacknowledged.clone(), None);
+        send_message(messages_received.into()).await?;
+        self.unacknowledged.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use messages::{
+        decorators::attachment::{Attachment, AttachmentData},
+        msg_fields::protocols::pickup::DeliveryContent,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_handle_delivery_decodes_attachments() {
+        let attach = Attachment::builder()
+            .id("msg-1".to_owned())
+            .data(
+                AttachmentData::builder()
+                    .content(AttachmentType::Base64(URL_SAFE_LENIENT.encode(b"packed")))
+                    .build(),
+            )
+            .build();
+        let delivery: Delivery = Delivery::builder()
+            .id("delivery".to_owned())
+            .content(DeliveryContent::builder().attach(vec![attach]).build())
+            .build();
+
+        let mut pickup = MessagePickup::new(None);
+        let messages = pickup.handle_delivery(&delivery).unwrap();
+        assert_eq!(
+            messages,
+            vec![PickedUpMessage {
+                id: "msg-1".to_owned(),
+                packed: b"packed".to_vec()
+            }]
+        );
+        assert_eq!(pickup.unacknowledged, vec!["msg-1".to_owned()]);
+    }
+}
@@ -0,0 +1,449 @@
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use did_peer::peer_did::{numalgos::numalgo4::Numalgo4, PeerDid};
+use diddoc_legacy::aries::service::AriesService;
+use messages::msg_fields::protocols::{
+    coordinate_mediation::{
+        keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
+        keylist_update_response::KeylistUpdateItemResult,
+        Keylist, KeylistUpdateResponse, MediateDeny, MediateGrant, MediateRequest,
+    },
+    out_of_band::invitation::OobService,
+};
+use public_key::Key;
+use url::Url;
+
+use crate::{
+    errors::error::prelude::*,
+    handlers::util::matches_opt_thread_id,
+    protocols::{
+        did_exchange::state_machine::helpers::create_peer_did_4,
+        mediation::{
+            build_keylist_query, build_keylist_update, build_keylist_update_item,
+            build_mediate_request,
+        },
+        SendClosure,
+    },
+};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum MediationState {
+    Initial,
+    Requested,
+    Granted {
+        endpoint: Url,
+        routing_keys: Vec<String>,
+    },
+    Denied,
+}
+
+/// Client side of the coordinate-mediation protocol (Aries RFC 0211). Requests mediation, keeps
+/// track of the routing information granted by the mediator and of the recipient keys the
+/// mediator has confirmed to route for us.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MediationRecipient {
+    request: MediateRequest,
+    state: MediationState,
+    keylist: Vec<String>,
+    pending_thread_ids: Vec<String>,
+}
+
+impl Default for MediationRecipient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MediationRecipient {
+    pub fn new() -> Self {
+        Self {
+            request: build_mediate_request(),
+            state: MediationState::Initial,
+            keylist: Vec::new(),
+            pending_thread_ids: Vec::new(),
+        }
+    }
+
+    pub fn get_thread_id(&self) -> &str {
+        self.request.id.as_str()
+    }
+
+    pub fn get_state(&self) -> &MediationState {
+        &self.state
+    }
+
+    pub fn is_granted(&self) -> bool {
+        matches!(self.state, MediationState::Granted { .. })
+    }
+
+    pub fn get_endpoint(&self) -> VcxResult<&Url> {
+        match &self.state {
+            MediationState::Granted { endpoint, .. } => Ok(endpoint),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Mediation has not been granted",
+            )),
+        }
+    }
+
+    pub fn get_routing_keys(&self) -> VcxResult<&[String]> {
+        match &self.state {
+            MediationState::Granted { routing_keys, .. } => Ok(routing_keys),
+            _ => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Mediation has not been granted",
+            )),
+        }
+    }
+
+    /// Recipient keys the mediator has confirmed it routes messages for.
+    pub fn get_keylist(&self) -> &[String] {
+        &self.keylist
+    }
+
+    pub async fn send_mediate_request(&mut self, send_message: SendClosure<'_>) -> VcxResult<()> {
+        if self.state != MediationState::Initial {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Mediation has already been requested",
+            ));
+        }
+        send_message(self.request.clone().into()).await?;
+        self.state = MediationState::Requested;
+        Ok(())
+    }
+
+    pub fn handle_mediate_grant(&mut self, grant: MediateGrant) -> VcxResult<()> {
+        self.verify_request_thread(matches_opt_thread_id!(grant, self.get_thread_id()))?;
+        let endpoint = Url::parse(&grant.content.endpoint)?;
+        self.state = MediationState::Granted {
+            endpoint,
+            routing_keys: grant.content.routing_keys,
+        };
+        Ok(())
+    }
+
+    pub fn handle_mediate_deny(&mut self, deny: &MediateDeny) -> VcxResult<()> {
+        self.verify_request_thread(matches_opt_thread_id!(deny, self.get_thread_id()))?;
+        self.state = MediationState::Denied;
+        Ok(())
+    }
+
+    fn verify_request_thread(&self, thread_matches: bool) -> VcxResult<()> {
+        if self.state != MediationState::Requested {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Message was not expected, mediation has not been requested",
+            ));
+        }
+        if !thread_matches {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Thread ID mismatch",
+            ));
+        }
+        Ok(())
+    }
+
+    fn ensure_granted(&self) -> VcxResult<()> {
+        self.get_endpoint().map(|_| ())
+    }
+
+    /// Sends a keylist-update and returns its id, which the mediator uses as the thread id of
+    /// its response.
+    pub async fn send_keylist_update(
+        &mut self,
+        updates: Vec<KeylistUpdateItem>,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<String> {
+        self.ensure_granted()?;
+        let keylist_update = build_keylist_update(updates);
+        let thread_id = keylist_update.id.clone();
+        send_message(keylist_update.into()).await?;
+        self.pending_thread_ids.push(thread_id.clone());
+        Ok(thread_id)
+    }
+
+    pub async fn add_recipient_key(
+        &mut self,
+        recipient_key: String,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<String> {
+        let update = build_keylist_update_item(recipient_key, KeylistUpdateItemAction::Add);
+        self.send_keylist_update(vec![update], send_message).await
+    }
+
+    pub async fn remove_recipient_key(
+        &mut self,
+        recipient_key: String,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<String> {
+        let update = build_keylist_update_item(recipient_key, KeylistUpdateItemAction::Remove);
+        self.send_keylist_update(vec![update], send_message).await
+    }
+
+    /// Applies the mediator's verdict to the local keylist. Updates the mediator rejected are
+    /// returned to the caller.
+    pub fn handle_keylist_update_response(
+        &mut self,
+        response: &KeylistUpdateResponse,
+    ) -> VcxResult<Vec<KeylistUpdateItem>> {
+        self.take_pending_thread(response.decorators.thread.as_ref().map(|t| t.thid.as_str()))?;
+        let mut rejected = Vec::new();
+        for item in response.content.updated.iter() {
+            match (&item.result, &item.action) {
+                (
+                    KeylistUpdateItemResult::Success | KeylistUpdateItemResult::NoChange,
+                    KeylistUpdateItemAction::Add,
+                ) => {
+                    if !self.keylist.contains(&item.recipient_key) {
+                        self.keylist.push(item.recipient_key.clone());
+                    }
+                }
+                (
+                    KeylistUpdateItemResult::Success | KeylistUpdateItemResult::NoChange,
+                    KeylistUpdateItemAction::Remove,
+                ) => self.keylist.retain(|key| key != &item.recipient_key),
+                (
+                    KeylistUpdateItemResult::ClientError | KeylistUpdateItemResult::ServerError,
+                    _,
+                ) => {
+                    warn!(
+                        "Mediator rejected keylist update of {} with {:?}",
+                        item.recipient_key, item.result
+                    );
+                    rejected.push(build_keylist_update_item(
+                        item.recipient_key.clone(),
+                        item.action.clone(),
+                    ));
+                }
+            }
+        }
+        Ok(rejected)
+    }
+
+    /// Sends a keylist-query and returns its id, which the mediator uses as the thread id of
+    /// its response.
+    pub async fn send_keylist_query(
+        &mut self,
+        limit: Option<u64>,
+        offset: Option<u64>,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<String> {
+        self.ensure_granted()?;
+        let keylist_query = build_keylist_query(limit, offset);
+        let thread_id = keylist_query.id.clone();
+        send_message(keylist_query.into()).await?;
+        self.pending_thread_ids.push(thread_id.clone());
+        Ok(thread_id)
+    }
+
+    /// Returns the recipient keys listed by the mediator. A full (unpaginated) keylist
+    /// replaces the locally tracked one.
+    pub fn handle_keylist(&mut self, keylist: &Keylist) -> VcxResult<Vec<String>> {
+        self.take_pending_thread(keylist.decorators.thread.as_ref().map(|t| t.thid.as_str()))?;
+        let keys: Vec<String> = keylist
+            .content
+            .keys
+            .iter()
+            .map(|item| item.recipient_key.clone())
+            .collect();
+        if keylist.content.pagination.is_none() {
+            self.keylist = keys.clone();
+        }
+        Ok(keys)
+    }
+
+    // Mediators are not required to thread their responses, in which case the oldest
+    // outstanding request is assumed to be answered.
+    fn take_pending_thread(&mut self, thread_id: Option<&str>) -> VcxResult<()> {
+        let position = match thread_id {
+            Some(thread_id) => self
+                .pending_thread_ids
+                .iter()
+                .position(|pending| pending == thread_id),
+            None => (!self.pending_thread_ids.is_empty()).then_some(0),
+        };
+        match position {
+            Some(position) => {
+                self.pending_thread_ids.remove(position);
+                Ok(())
+            }
+            None => Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Thread ID mismatch",
+            )),
+        }
+    }
+
+    /// Returns the routing keys and endpoint to put into a connection invitation, so that
+    /// inviters reach us through the mediator.
+    pub fn invitation_routing(&self) -> VcxResult<(Vec<String>, Url)> {
+        Ok((
+            self.get_routing_keys()?.to_vec(),
+            self.get_endpoint()?.clone(),
+        ))
+    }
+
+    /// Builds an out-of-band service for `recipient_keys` which is reachable through the
+    /// mediator.
+    pub fn build_oob_service(&self, recipient_keys: Vec<String>) -> VcxResult<OobService> {
+        let service = AriesService::create()
+            .set_service_endpoint(self.get_endpoint()?.clone())
+            .set_recipient_keys(recipient_keys)
+            .set_routing_keys(self.get_routing_keys()?.to_vec());
+        Ok(OobService::AriesService(service))
+    }
+
+    /// Creates a did:peer:4 whose DIDComm service routes through the mediator. The returned key
+    /// still has to be registered with the mediator through [`Self::add_recipient_key`].
+    pub async fn create_peer_did(
+        &self,
+        wallet: &impl BaseWallet,
+    ) -> VcxResult<(PeerDid<Numalgo4>, Key)> {
+        create_peer_did_4(
+            wallet,
+            self.get_endpoint()?.clone(),
+            self.get_routing_keys()?.to_vec(),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use messages::{
+        decorators::thread::Thread,
+        msg_fields::protocols::coordinate_mediation::{
+            keylist_update_response::KeylistUpdateResponseItem, KeylistUpdateResponseContent,
+            KeylistUpdateResponseDecorators, MediateGrantContent, MediateGrantDecorators,
+        },
+        AriesMessage,
+    };
+    use test_utils::devsetup::SetupMocks;
+
+    use super::*;
+
+    fn _send_message() -> SendClosure<'static> {
+        Box::new(|_: AriesMessage| Box::pin(async { VcxResult::Ok(()) }))
+    }
+
+    fn _grant(thread_id: &str) -> MediateGrant {
+        MediateGrant::builder()
+            .id("grant".to_owned())
+            .content(
+                MediateGrantContent::builder()
+                    .endpoint("https://mediator.example.org/didcomm".to_owned())
+                    .routing_keys(vec![
+                        "did:key:z6MkpTHR8VNsBxYAAWHut2Geadd9jSwuBV8xRoAnwWsdvktH".to_owned(),
+                    ])
+                    .build(),
+            )
+            .decorators(
+                MediateGrantDecorators::builder()
+                    .thread(Thread::builder().thid(thread_id.to_owned()).build())
+                    .build(),
+            )
+            .build()
+    }
+
+    fn _update_response(thread_id: &str, recipient_key: &str) -> KeylistUpdateResponse {
+        KeylistUpdateResponse::builder()
+            .id("response".to_owned())
+            .content(
+                KeylistUpdateResponseContent::builder()
+                    .updated(vec![KeylistUpdateResponseItem::builder()
+                        .recipient_key(recipient_key.to_owned())
+                        .action(KeylistUpdateItemAction::Add)
+                        .result(KeylistUpdateItemResult::Success)
+                        .build()])
+                    .build(),
+            )
+            .decorators(
+                KeylistUpdateResponseDecorators::builder()
+                    .thread(Thread::builder().thid(thread_id.to_owned()).build())
+                    .build(),
+            )
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_mediation_grant_exposes_routing() {
+        let _setup = SetupMocks::init();
+        let mut recipient = MediationRecipient::new();
+        recipient
+            .send_mediate_request(_send_message())
+            .await
+            .unwrap();
+        let grant = _grant(recipient.get_thread_id());
+        recipient.handle_mediate_grant(grant.clone()).unwrap();
+
+        let (routing_keys, endpoint) = recipient.invitation_routing().unwrap();
+        assert_eq!(routing_keys, grant.content.routing_keys);
+        assert_eq!(endpoint.as_str(), grant.content.endpoint);
+        let OobService::AriesService(service) = recipient
+            .build_oob_service(vec!["verkey".to_owned()])
+            .unwrap()
+        else {
+            panic!("Expected an inline service");
+        };
+        assert_eq!(service.routing_keys, grant.content.routing_keys);
+    }
+
+    #[tokio::test]
+    async fn test_should_fail_on_thread_id_mismatch() {
+        let _setup = SetupMocks::init();
+        let mut recipient = MediationRecipient::new();
+        recipient
+            .send_mediate_request(_send_message())
+            .await
+            .unwrap();
+        recipient
+            .handle_mediate_grant(_grant("unrelated"))
+            .unwrap_err();
+        assert!(!recipient.is_granted());
+    }
+
+    #[tokio::test]
+    async fn test_should_fail_on_grant_before_request() {
+        let _setup = SetupMocks::init();
+        let mut recipient = MediationRecipient::new();
+        let grant = _grant(recipient.get_thread_id());
+        recipient.handle_mediate_grant(grant).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_keylist_update_requires_grant() {
+        let _setup = SetupMocks::init();
+        let mut recipient = MediationRecipient::new();
+        recipient
+            .add_recipient_key("verkey".to_owned(), _send_message())
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_keylist_update_response_updates_keylist() {
+        let _setup = SetupMocks::init();
+        let mut recipient = MediationRecipient::new();
+        recipient
+            .send_mediate_request(_send_message())
+            .await
+            .unwrap();
+        recipient
+            .handle_mediate_grant(_grant(recipient.get_thread_id()))
+            .unwrap();
+        let thread_id = recipient
+            .add_recipient_key("verkey".to_owned(), _send_message())
+            .await
+            .unwrap();
+
+        recipient
+            .handle_keylist_update_response(&_update_response("unrelated", "verkey"))
+            .unwrap_err();
+        let rejected = recipient
+            .handle_keylist_update_response(&_update_response(&thread_id, "verkey"))
+            .unwrap();
+        assert!(rejected.is_empty());
+        assert_eq!(recipient.get_keylist(), ["verkey".to_owned()]);
+    }
+}
@@ -1,6 +1,14 @@
+pub mod action_menu;
+pub mod basic_message;
+pub mod connectionless;
+pub mod discover_features;
+pub mod dispatcher;
+pub mod endorsement;
 pub mod issuance;
+pub mod mediation;
 pub mod out_of_band;
 pub mod proof_presentation;
+pub mod question_answer;
 pub mod revocation_notification;
 pub mod trust_ping;
 pub mod util;
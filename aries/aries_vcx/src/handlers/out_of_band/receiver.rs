@@ -2,20 +2,29 @@ use std::{clone::Clone, fmt::Display, str::FromStr};
 
 use base64::{engine::general_purpose, Engine};
 use messages::{
-    decorators::attachment::{Attachment, AttachmentType},
+    decorators::{
+        attachment::{Attachment, AttachmentType},
+        service::Service,
+    },
     msg_fields::protocols::{
         cred_issuance::v1::offer_credential::OfferCredentialV1,
-        out_of_band::{invitation::Invitation, OutOfBand},
+        out_of_band::{
+            invitation::{Invitation, OobService},
+            OutOfBand,
+        },
         present_proof::v1::request::RequestPresentationV1,
     },
     AriesMessage,
 };
 use serde::Deserialize;
 use serde_json::Value;
+use shared::http_client::HttpClient;
 use url::Url;
 
 use crate::{
-    errors::error::prelude::*, handlers::util::AttachmentId, utils::base64::URL_SAFE_LENIENT,
+    errors::error::prelude::*,
+    handlers::{connectionless::get_service, util::AttachmentId},
+    utils::base64::URL_SAFE_LENIENT,
 };
 
 #[derive(Debug, PartialEq, Clone)]
@@ -48,7 +57,6 @@ impl OutOfBandReceiver {
     }
 
     pub fn create_from_url_encoded_oob(oob_url_string: &str) -> VcxResult<Self> {
-        // TODO - URL Shortening
         Ok(Self {
             oob: extract_encoded_invitation_from_json_string(
                 &extract_encoded_invitation_from_base64_url(&extract_encoded_invitation_from_url(
@@ -58,6 +66,19 @@ impl OutOfBandReceiver {
         })
     }
 
+    /// Resolves a short invitation URL, such as one made by
+    /// [`super::sender::OutOfBandSender::invitation_to_short_url`]. The invitation is read from
+    /// the URL redirected to, if it embeds one, and otherwise from the JSON response.
+    pub async fn create_from_short_url(client: &HttpClient, short_url: &str) -> VcxResult<Self> {
+        let (url, body) = client.get_json(&Url::parse(short_url)?).await?;
+        if url.query_pairs().any(|(name, _value)| name == "oob") {
+            return Self::create_from_url_encoded_oob(url.as_str());
+        }
+        Ok(Self {
+            oob: serde_json::from_slice(&body)?,
+        })
+    }
+
     pub fn get_id(&self) -> String {
         self.oob.id.clone()
     }
@@ -78,6 +99,31 @@ impl OutOfBandReceiver {
         }
     }
 
+    /// Service to respond to the attached request at without setting up a connection. It is
+    /// taken from the `~service` decorator of the request, falling back to the first inline
+    /// service of the invitation.
+    pub fn connectionless_service(&self) -> VcxResult<Option<Service>> {
+        if let Some(service) = self.extract_a2a_message()?.as_ref().and_then(get_service) {
+            return Ok(Some(service.clone()));
+        }
+        let inline_service = self
+            .oob
+            .content
+            .services
+            .iter()
+            .find_map(|service| match service {
+                OobService::AriesService(service) => Some(
+                    Service::builder()
+                        .recipient_keys(service.recipient_keys.clone())
+                        .routing_keys(service.routing_keys.clone())
+                        .service_endpoint(service.service_endpoint.clone())
+                        .build(),
+                ),
+                OobService::Did(_) => None,
+            });
+        Ok(inline_service)
+    }
+
     pub fn invitation_to_aries_message(&self) -> AriesMessage {
         self.oob.clone().into()
     }
@@ -196,9 +242,14 @@ fn attachment_to_aries_message(attach: &Attachment) -> VcxResult<Option<AriesMes
 #[cfg(test)]
 mod tests {
     use messages::{
-        msg_fields::protocols::out_of_band::{
-            invitation::{Invitation, InvitationContent, InvitationDecorators, OobService},
-            OobGoalCode,
+        msg_fields::protocols::{
+            out_of_band::{
+                invitation::{Invitation, InvitationContent, InvitationDecorators, OobService},
+                OobGoalCode,
+            },
+            present_proof::v1::request::{
+                RequestPresentationV1Content, RequestPresentationV1Decorators,
+            },
         },
         msg_types::{
             connection::{ConnectionType, ConnectionTypeV1},
@@ -209,6 +260,7 @@ mod tests {
     use shared::maybe_known::MaybeKnown;
 
     use super::*;
+    use crate::handlers::out_of_band::sender::OutOfBandSender;
 
     // Example invite formats referenced (with change to use OOB 1.1) from example invite in RFC 0434 - https://github.com/hyperledger/aries-rfcs/tree/main/features/0434-outofband
     const JSON_OOB_INVITE: &str = r#"{
@@ -337,4 +389,41 @@ mod tests {
 
         assert_eq!(OOB_URL, oob_url);
     }
+
+    #[test]
+    fn connectionless_service_from_attached_request() {
+        let service = Service::builder()
+            .recipient_keys(vec![
+                "8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K".to_owned()
+            ])
+            .service_endpoint("https://example.org/agent".parse().unwrap())
+            .build();
+        let request = RequestPresentationV1::builder()
+            .id("request-1".to_owned())
+            .content(
+                RequestPresentationV1Content::builder()
+                    .request_presentations_attach(Vec::new())
+                    .build(),
+            )
+            .decorators(
+                RequestPresentationV1Decorators::builder()
+                    .service(service.clone())
+                    .build(),
+            )
+            .build();
+        let sender = OutOfBandSender::create()
+            .append_a2a_message(request.into())
+            .unwrap();
+
+        let receiver =
+            OutOfBandReceiver::create_from_a2a_msg(&sender.invitation_to_aries_message()).unwrap();
+
+        assert_eq!(receiver.connectionless_service().unwrap(), Some(service));
+        let invitation_without_request =
+            OutOfBandReceiver::create_from_json_encoded_oob(JSON_OOB_INVITE).unwrap();
+        assert_eq!(
+            invitation_without_request.connectionless_service().unwrap(),
+            None
+        );
+    }
 }
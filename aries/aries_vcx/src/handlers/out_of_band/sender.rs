@@ -151,6 +151,20 @@ impl OutOfBandSender {
             .to_owned();
         Ok(oob_url)
     }
+
+    /// URL referencing the invitation by its id rather than embedding it, short enough for a
+    /// QR code even when the invitation carries a request, see [Aries RFC 0434](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0434-outofband/README.md#url-shortening>).
+    ///
+    /// The agent serving `domain_path` must answer a GET of this URL with the invitation, either
+    /// as JSON or by redirecting to [`Self::invitation_to_url`].
+    pub fn invitation_to_short_url(&self, domain_path: &str) -> VcxResult<Url> {
+        let short_url = Url::parse(domain_path)?
+            .query_pairs_mut()
+            .append_pair("id", &self.oob.id)
+            .finish()
+            .to_owned();
+        Ok(short_url)
+    }
 }
 
 impl Display for OutOfBandSender {
@@ -241,6 +255,21 @@ mod tests {
 
         assert_eq!(OOB_URL, oob_url);
     }
+
+    #[test]
+    fn invitation_to_short_url() {
+        let out_of_band_sender = OutOfBandSender::create_from_invitation(_create_invitation());
+
+        let short_url = out_of_band_sender
+            .invitation_to_short_url("http://example.com/ssi")
+            .unwrap()
+            .to_string();
+
+        assert_eq!(
+            "http://example.com/ssi?id=69212a3a-d068-4f9d-a2dd-4741bca89af3",
+            short_url
+        );
+    }
 }
 
 // #[cfg(test)]
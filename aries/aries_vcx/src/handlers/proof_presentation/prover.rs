@@ -24,23 +24,31 @@ use messages::{
             },
             PresentProof,
         },
+        report_problem::ProblemReport,
     },
     AriesMessage,
 };
 use uuid::Uuid;
 
 use crate::{
+    common::primitives::tails_file_manager::TailsFileManager,
     errors::error::prelude::*,
-    handlers::util::{get_attach_as_string, PresentationProposalData},
+    handlers::{
+        connectionless::ConnectionlessExchange,
+        util::{get_attach_as_string, PresentationProposalData},
+    },
     protocols::{
-        common::build_problem_report_msg,
+        problem_report::{build_rejection_msg, ReceivedProblem},
         proof_presentation::prover::state_machine::{ProverSM, ProverState},
     },
+    transport::Transport,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Prover {
     prover_sm: ProverSM,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    problem: Option<ReceivedProblem>,
 }
 
 impl Prover {
@@ -48,6 +56,7 @@ impl Prover {
         trace!("Prover::create >>> source_id: {}", source_id);
         Ok(Prover {
             prover_sm: ProverSM::new(source_id.to_string()),
+            problem: None,
         })
     }
 
@@ -62,6 +71,7 @@ impl Prover {
         );
         Ok(Prover {
             prover_sm: ProverSM::from_request(presentation_request, source_id.to_string()),
+            problem: None,
         })
     }
 
@@ -109,7 +119,47 @@ impl Prover {
         self.prover_sm = self
             .prover_sm
             .clone()
-            .generate_presentation(wallet, ledger, anoncreds, credentials, self_attested_attrs)
+            .generate_presentation(
+                wallet,
+                ledger,
+                anoncreds,
+                None,
+                credentials,
+                self_attested_attrs,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Like [Prover::generate_presentation], but credentials selected without a tails dir are
+    /// proven non-revoked with the tails files of the `tails_file_manager`, which downloads the
+    /// missing ones.
+    pub async fn generate_presentation_with_tails_file_manager(
+        &mut self,
+        wallet: &impl BaseWallet,
+        ledger: &impl AnoncredsLedgerRead,
+        anoncreds: &impl BaseAnonCreds,
+        tails_file_manager: &TailsFileManager,
+        credentials: SelectedCredentials,
+        self_attested_attrs: HashMap<String, String>,
+    ) -> VcxResult<()> {
+        trace!(
+            "Prover::generate_presentation_with_tails_file_manager >>> credentials: {:?}, \
+             self_attested_attrs: {:?}",
+            credentials,
+            self_attested_attrs
+        );
+        self.prover_sm = self
+            .prover_sm
+            .clone()
+            .generate_presentation(
+                wallet,
+                ledger,
+                anoncreds,
+                Some(tails_file_manager),
+                credentials,
+                self_attested_attrs,
+            )
             .await?;
         Ok(())
     }
@@ -147,6 +197,18 @@ impl Prover {
         }
     }
 
+    /// Sends the presentation, or the problem report if it couldn't be generated, to the
+    /// service of the verifier, for a request received without a connection.
+    pub async fn send_presentation_connectionless(
+        &mut self,
+        wallet: &impl BaseWallet,
+        exchange: &ConnectionlessExchange,
+        transport: &impl Transport,
+    ) -> VcxResult<()> {
+        let message = self.mark_presentation_sent()?;
+        exchange.send(wallet, message, transport).await
+    }
+
     pub fn process_presentation_ack(&mut self, ack: AckPresentationV1) -> VcxResult<()> {
         trace!("Prover::process_presentation_ack >>>");
         self.prover_sm = self.prover_sm.clone().receive_presentation_ack(ack)?;
@@ -207,17 +269,12 @@ impl Prover {
             AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::Ack(ack))) => {
                 self.prover_sm.clone().receive_presentation_ack(ack)?
             }
-            AriesMessage::ReportProblem(report) => {
-                self.prover_sm.clone().receive_presentation_reject(report)?
+            AriesMessage::ReportProblem(report) => return self.receive_problem_report(report),
+            AriesMessage::Notification(Notification::ProblemReport(report)) => {
+                return self.receive_problem_report(report.into());
             }
-            AriesMessage::Notification(Notification::ProblemReport(report)) => self
-                .prover_sm
-                .clone()
-                .receive_presentation_reject(report.into())?,
             AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::ProblemReport(report))) => {
-                self.prover_sm
-                    .clone()
-                    .receive_presentation_reject(report.into())?
+                return self.receive_problem_report(report.into());
             }
             _ => self.prover_sm.clone(),
         };
@@ -225,6 +282,25 @@ impl Prover {
         Ok(())
     }
 
+    fn receive_problem_report(&mut self, problem_report: ProblemReport) -> VcxResult<()> {
+        let problem = ReceivedProblem::from(&problem_report);
+        let state = self.get_state();
+        self.prover_sm = self
+            .prover_sm
+            .clone()
+            .receive_presentation_reject(problem_report)?;
+        // reports received in states they can't end the presentation in are ignored
+        if self.get_state() != state {
+            self.problem = Some(problem);
+        }
+        Ok(())
+    }
+
+    /// The problem the verifier reported, if it ended the presentation with a problem report.
+    pub fn get_problem(&self) -> Option<ReceivedProblem> {
+        self.problem.clone()
+    }
+
     // TODO: Can we delete this (please)?
     pub async fn decline_presentation_request(
         &mut self,
@@ -239,7 +315,7 @@ impl Prover {
         let (sm, message) = match (reason, proposal) {
             (Some(reason), None) => {
                 let thread_id = self.prover_sm.get_thread_id()?;
-                let problem_report = build_problem_report_msg(Some(reason), &thread_id);
+                let problem_report = build_rejection_msg(Some(reason), &thread_id);
                 (
                     self.prover_sm
                         .clone()
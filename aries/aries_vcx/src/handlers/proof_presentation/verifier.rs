@@ -1,6 +1,7 @@
 use anoncreds_types::data_types::messages::pres_request::PresentationRequest;
 use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
 use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 use messages::{
     msg_fields::protocols::{
         notification::Notification,
@@ -18,19 +19,22 @@ use messages::{
 
 use crate::{
     errors::error::prelude::*,
-    handlers::util::get_attach_as_string,
+    handlers::{connectionless::ConnectionlessExchange, util::get_attach_as_string},
     protocols::{
-        common::build_problem_report_msg,
+        problem_report::{build_rejection_msg, ReceivedProblem},
         proof_presentation::verifier::{
             state_machine::{VerifierSM, VerifierState},
             verification_status::PresentationVerificationStatus,
         },
     },
+    transport::Transport,
 };
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct Verifier {
     verifier_sm: VerifierSM,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    problem: Option<ReceivedProblem>,
 }
 
 impl Verifier {
@@ -39,6 +43,7 @@ impl Verifier {
 
         Ok(Self {
             verifier_sm: VerifierSM::new(source_id),
+            problem: None,
         })
     }
 
@@ -52,7 +57,10 @@ impl Verifier {
             presentation_request
         );
         let verifier_sm = VerifierSM::from_request(&source_id, presentation_request)?;
-        Ok(Self { verifier_sm })
+        Ok(Self {
+            verifier_sm,
+            problem: None,
+        })
     }
 
     pub fn create_from_proposal(
@@ -66,6 +74,7 @@ impl Verifier {
         );
         Ok(Self {
             verifier_sm: VerifierSM::from_proposal(source_id, presentation_proposal),
+            problem: None,
         })
     }
 
@@ -91,6 +100,16 @@ impl Verifier {
         }
     }
 
+    /// Marks the presentation request sent and returns it decorated with our `~service`, to be
+    /// attached to an out-of-band invitation and answered without a connection.
+    pub fn mark_presentation_request_sent_connectionless(
+        &mut self,
+        exchange: &ConnectionlessExchange,
+    ) -> VcxResult<AriesMessage> {
+        let request = self.mark_presentation_request_sent()?;
+        exchange.decorate(request.into())
+    }
+
     // todo: verification and sending ack should be separate apis
     pub async fn verify_presentation(
         &mut self,
@@ -184,25 +203,17 @@ impl Verifier {
                     .await?;
                 (sm.clone(), Some(sm.get_final_message()?))
             }
-            AriesMessage::ReportProblem(report) => (
-                self.verifier_sm
-                    .clone()
-                    .receive_presentation_request_reject(report)?,
-                None,
-            ),
-            AriesMessage::Notification(Notification::ProblemReport(report)) => (
-                self.verifier_sm
-                    .clone()
-                    .receive_presentation_request_reject(report.into())?,
-                None,
-            ),
+            AriesMessage::ReportProblem(report) => {
+                self.receive_problem_report(report)?;
+                return Ok(None);
+            }
+            AriesMessage::Notification(Notification::ProblemReport(report)) => {
+                self.receive_problem_report(report.into())?;
+                return Ok(None);
+            }
             AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::ProblemReport(report))) => {
-                (
-                    self.verifier_sm
-                        .clone()
-                        .receive_presentation_request_reject(report.into())?,
-                    None,
-                )
+                self.receive_problem_report(report.into())?;
+                return Ok(None);
             }
             _ => (self.verifier_sm.clone(), None),
         };
@@ -210,6 +221,43 @@ impl Verifier {
         Ok(message)
     }
 
+    /// Processes a message received over the connectionless exchange, sending the ack or
+    /// problem report concluding the presentation to the service of the prover.
+    pub async fn process_connectionless_msg(
+        &mut self,
+        wallet: &impl BaseWallet,
+        ledger: &impl AnoncredsLedgerRead,
+        anoncreds: &impl BaseAnonCreds,
+        exchange: &mut ConnectionlessExchange,
+        message: AriesMessage,
+        transport: &impl Transport,
+    ) -> VcxResult<()> {
+        exchange.receive(&message);
+        if let Some(response) = self.process_aries_msg(ledger, anoncreds, message).await? {
+            exchange.send(wallet, response, transport).await?;
+        }
+        Ok(())
+    }
+
+    fn receive_problem_report(&mut self, problem_report: ProblemReport) -> VcxResult<()> {
+        let problem = ReceivedProblem::from(&problem_report);
+        let state = self.get_state();
+        self.verifier_sm = self
+            .verifier_sm
+            .clone()
+            .receive_presentation_request_reject(problem_report)?;
+        // reports received in states they can't end the presentation in are ignored
+        if self.get_state() != state {
+            self.problem = Some(problem);
+        }
+        Ok(())
+    }
+
+    /// The problem the prover reported, if it ended the presentation with a problem report.
+    pub fn get_problem(&self) -> Option<ReceivedProblem> {
+        self.problem.clone()
+    }
+
     pub fn progressable_by_message(&self) -> bool {
         self.verifier_sm.progressable_by_message()
     }
@@ -229,7 +277,7 @@ impl Verifier {
                 Some(thread) => thread.thid,
                 None => proposal.id,
             };
-            let problem_report = build_problem_report_msg(Some(reason.to_string()), &thread_id);
+            let problem_report = build_rejection_msg(Some(reason.to_string()), &thread_id);
             self.verifier_sm = self
                 .verifier_sm
                 .clone()
@@ -0,0 +1,325 @@
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use chrono::{DateTime, Utc};
+use messages::msg_fields::protocols::question_answer::{answer::Answer, question::Question};
+
+use super::util::matches_thread_id;
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::{
+        question_answer::{build_answer, build_question, verify_answer_signature},
+        SendClosure,
+    },
+};
+
+fn ensure_not_expired(question: &Question) -> VcxResult<()> {
+    let expires_time = question
+        .decorators
+        .timing
+        .as_ref()
+        .and_then(|timing| timing.expires_time);
+    match expires_time {
+        Some(expires_time) if expires_time < Utc::now() => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            format!("Question expired at {}", expires_time),
+        )),
+        _ => Ok(()),
+    }
+}
+
+fn ensure_valid_response(question: &Question, response: &str) -> VcxResult<()> {
+    if !question
+        .content
+        .valid_responses
+        .iter()
+        .any(|valid| valid.text == response)
+    {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            format!("{:?} is not a valid response to the question", response),
+        ));
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuestionAnswerQuestioner {
+    question: Question,
+    answer: Option<Answer>,
+}
+
+impl QuestionAnswerQuestioner {
+    pub fn build(
+        question_text: String,
+        question_detail: Option<String>,
+        valid_responses: Vec<String>,
+        signature_required: bool,
+        expires_time: Option<DateTime<Utc>>,
+    ) -> Self {
+        Self {
+            question: build_question(
+                question_text,
+                question_detail,
+                valid_responses,
+                signature_required,
+                expires_time,
+            ),
+            answer: None,
+        }
+    }
+
+    pub fn get_question(&self) -> &Question {
+        &self.question
+    }
+
+    pub fn get_thread_id(&self) -> &str {
+        self.question
+            .decorators
+            .thread
+            .as_ref()
+            .map(|t| t.thid.as_str())
+            .unwrap_or(self.question.id.as_str())
+    }
+
+    /// The response chosen by the responder, once a valid answer has been received.
+    pub fn get_response(&self) -> Option<&str> {
+        self.answer
+            .as_ref()
+            .map(|answer| answer.content.response.as_str())
+    }
+
+    pub async fn send_question(&self, send_message: SendClosure<'_>) -> VcxResult<()> {
+        send_message(self.question.clone().into()).await
+    }
+
+    /// Accepts the answer if it is one of the valid responses, arrived before the question
+    /// expired and, if the question requires it, is signed by `their_vk`.
+    pub async fn handle_answer(
+        &mut self,
+        wallet: &impl BaseWallet,
+        answer: Answer,
+        their_vk: &str,
+    ) -> VcxResult<()> {
+        if !matches_thread_id!(answer, self.get_thread_id()) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Thread ID mismatch",
+            ));
+        }
+        if self.answer.is_some() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                "Question has already been answered",
+            ));
+        }
+        ensure_not_expired(&self.question)?;
+        ensure_valid_response(&self.question, &answer.content.response)?;
+        if self.question.content.signature_required {
+            verify_answer_signature(wallet, &self.question.content, &answer.content, their_vk)
+                .await?;
+        }
+        self.answer = Some(answer);
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuestionAnswerResponder {
+    question: Question,
+    answer: Option<Answer>,
+}
+
+impl QuestionAnswerResponder {
+    pub fn from_question(question: Question) -> Self {
+        Self {
+            question,
+            answer: None,
+        }
+    }
+
+    pub fn get_question(&self) -> &Question {
+        &self.question
+    }
+
+    pub fn get_valid_responses(&self) -> Vec<&str> {
+        self.question
+            .content
+            .valid_responses
+            .iter()
+            .map(|valid| valid.text.as_str())
+            .collect()
+    }
+
+    /// Answers the question, signing the answer with `signer_key` if the question requires it.
+    pub async fn send_answer(
+        &mut self,
+        wallet: &impl BaseWallet,
+        response: String,
+        signer_key: &str,
+        send_message: SendClosure<'_>,
+    ) -> VcxResult<()> {
+        if self.answer.is_some() {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::NotReady,
+                "Question has already been answered",
+            ));
+        }
+        ensure_not_expired(&self.question)?;
+        ensure_valid_response(&self.question, &response)?;
+
+        let answer = build_answer(wallet, &self.question, response, signer_key).await?;
+        send_message(answer.clone().into()).await?;
+        self.answer = Some(answer);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use aries_vcx_wallet::wallet::base_wallet::did_wallet::DidWallet;
+    use chrono::Duration;
+    use messages::{msg_fields::protocols::question_answer::QuestionAnswer, AriesMessage};
+    use test_utils::devsetup::build_setup_profile;
+
+    use super::*;
+    use crate::protocols::test_utils::SentMessages;
+
+    fn _questioner(signature_required: bool) -> QuestionAnswerQuestioner {
+        QuestionAnswerQuestioner::build(
+            "Are you on the phone with Bob from Faber Bank?".to_owned(),
+            None,
+            vec!["Yes, it's me".to_owned(), "No, that's not me!".to_owned()],
+            signature_required,
+            None,
+        )
+    }
+
+    async fn _answer(
+        wallet: &impl BaseWallet,
+        question: &Question,
+        response: &str,
+        signer_key: &str,
+    ) -> VcxResult<Answer> {
+        let sent = SentMessages::default();
+        let mut responder = QuestionAnswerResponder::from_question(question.clone());
+        responder
+            .send_answer(wallet, response.to_owned(), signer_key, sent.send_closure())
+            .await?;
+        let Some(AriesMessage::QuestionAnswer(QuestionAnswer::Answer(answer))) = sent.last() else {
+            panic!("expected an answer");
+        };
+        Ok(answer)
+    }
+
+    #[tokio::test]
+    async fn test_signed_answer_is_verified() {
+        let setup = build_setup_profile().await;
+        let responder_key = setup
+            .wallet
+            .create_and_store_my_did(None, None)
+            .await
+            .unwrap()
+            .verkey()
+            .base58();
+        let mut questioner = _questioner(true);
+
+        let answer = _answer(
+            &setup.wallet,
+            questioner.get_question(),
+            "Yes, it's me",
+            &responder_key,
+        )
+        .await
+        .unwrap();
+        assert!(answer.content.response_sig.is_some());
+
+        questioner
+            .handle_answer(&setup.wallet, answer, &responder_key)
+            .await
+            .unwrap();
+        assert_eq!(questioner.get_response(), Some("Yes, it's me"));
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_answer_signed_by_other_key() {
+        let setup = build_setup_profile().await;
+        let responder_key = setup
+            .wallet
+            .create_and_store_my_did(None, None)
+            .await
+            .unwrap()
+            .verkey()
+            .base58();
+        let other_key = setup
+            .wallet
+            .create_and_store_my_did(None, None)
+            .await
+            .unwrap()
+            .verkey()
+            .base58();
+        let mut questioner = _questioner(true);
+
+        let answer = _answer(
+            &setup.wallet,
+            questioner.get_question(),
+            "Yes, it's me",
+            &other_key,
+        )
+        .await
+        .unwrap();
+
+        questioner
+            .handle_answer(&setup.wallet, answer, &responder_key)
+            .await
+            .unwrap_err();
+        assert!(questioner.get_response().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_unsigned_answer_to_question_requiring_signature() {
+        let setup = build_setup_profile().await;
+        let responder_key = setup
+            .wallet
+            .create_and_store_my_did(None, None)
+            .await
+            .unwrap()
+            .verkey()
+            .base58();
+        let mut questioner = _questioner(true);
+        let mut unsigned_question = questioner.get_question().clone();
+        unsigned_question.content.signature_required = false;
+
+        let answer = _answer(
+            &setup.wallet,
+            &unsigned_question,
+            "Yes, it's me",
+            &responder_key,
+        )
+        .await
+        .unwrap();
+
+        questioner
+            .handle_answer(&setup.wallet, answer, &responder_key)
+            .await
+            .unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_should_reject_invalid_or_late_responses() {
+        let setup = build_setup_profile().await;
+        let questioner = _questioner(false);
+        _answer(&setup.wallet, questioner.get_question(), "Maybe", "")
+            .await
+            .unwrap_err();
+
+        let expired = QuestionAnswerQuestioner::build(
+            "Are you there?".to_owned(),
+            None,
+            vec!["Yes".to_owned()],
+            false,
+            Some(Utc::now() - Duration::minutes(1)),
+        );
+        _answer(&setup.wallet, expired.get_question(), "Yes", "")
+            .await
+            .unwrap_err();
+    }
+}
@@ -1,8 +1,9 @@
 use anoncreds_types::data_types::identifiers::cred_def_id::CredentialDefinitionId;
 use messages::{
     msg_fields::protocols::{
+        action_menu::ActionMenu,
         connection::{invitation::Invitation, Connection},
-        coordinate_mediation::CoordinateMediation,
+        coordinate_mediation::{v2::CoordinateMediationV2, CoordinateMediation},
         cred_issuance::{v1::CredentialIssuanceV1, v2::CredentialIssuanceV2, CredentialIssuance},
         did_exchange::{v1_0::DidExchangeV1_0, v1_1::DidExchangeV1_1, DidExchange},
         discover_features::DiscoverFeatures,
@@ -17,8 +18,10 @@ use messages::{
             v2::PresentProofV2,
             PresentProof,
         },
+        question_answer::QuestionAnswer,
         report_problem::ProblemReport,
         revocation::Revocation,
+        transactions::Transactions,
         trust_ping::TrustPing,
     },
     AriesMessage,
@@ -243,6 +246,27 @@ pub fn verify_thread_id(thread_id: &str, message: &AriesMessage) -> VcxResult<()
         AriesMessage::CoordinateMediation(CoordinateMediation::Keylist(msg)) => {
             matches_opt_thread_id!(msg, thread_id)
         }
+        AriesMessage::CoordinateMediationV2(CoordinateMediationV2::MediateRequest(msg)) => {
+            msg.id == thread_id
+        }
+        AriesMessage::CoordinateMediationV2(CoordinateMediationV2::MediateDeny(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::CoordinateMediationV2(CoordinateMediationV2::MediateGrant(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::CoordinateMediationV2(CoordinateMediationV2::RecipientUpdate(msg)) => {
+            msg.id == thread_id
+        }
+        AriesMessage::CoordinateMediationV2(CoordinateMediationV2::RecipientUpdateResponse(
+            msg,
+        )) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::CoordinateMediationV2(CoordinateMediationV2::RecipientQuery(msg)) => {
+            msg.id == thread_id
+        }
+        AriesMessage::CoordinateMediationV2(CoordinateMediationV2::Recipient(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
         AriesMessage::DidExchange(DidExchange::V1_0(DidExchangeV1_0::Request(msg)))
         | AriesMessage::DidExchange(DidExchange::V1_1(DidExchangeV1_1::Request(msg))) => {
             matches_opt_thread_id!(msg, thread_id)
@@ -261,6 +285,28 @@ pub fn verify_thread_id(thread_id: &str, message: &AriesMessage) -> VcxResult<()
         AriesMessage::DidExchange(DidExchange::V1_1(DidExchangeV1_1::Response(msg))) => {
             matches_thread_id!(msg, thread_id)
         }
+        AriesMessage::ActionMenu(ActionMenu::Menu(msg)) => matches_opt_thread_id!(msg, thread_id),
+        AriesMessage::ActionMenu(ActionMenu::MenuRequest(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::ActionMenu(ActionMenu::Perform(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::QuestionAnswer(QuestionAnswer::Question(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::QuestionAnswer(QuestionAnswer::Answer(msg)) => {
+            matches_thread_id!(msg, thread_id)
+        }
+        AriesMessage::Transactions(Transactions::Request(msg)) => {
+            matches_opt_thread_id!(msg, thread_id)
+        }
+        AriesMessage::Transactions(Transactions::Endorse(msg)) => {
+            matches_thread_id!(msg, thread_id)
+        }
+        AriesMessage::Transactions(Transactions::Refuse(msg)) => {
+            matches_thread_id!(msg, thread_id)
+        }
     };
 
     if !is_match {
@@ -38,4 +38,5 @@ pub mod protocols;
 
 pub mod common;
 pub mod errors;
+pub mod storage;
 pub mod transport;
@@ -0,0 +1,135 @@
+use std::collections::{HashMap, HashSet};
+
+use ::uuid::Uuid;
+use chrono::Utc;
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::action_menu::{
+        menu::{Menu, MenuContent, MenuDecorators, MenuOption},
+        menu_request::{MenuRequest, MenuRequestContent, MenuRequestDecorators},
+        perform::{Perform, PerformContent, PerformDecorators},
+    },
+};
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+pub fn build_menu_request() -> MenuRequest {
+    let decorators = MenuRequestDecorators::builder()
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+
+    MenuRequest::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(MenuRequestContent::default())
+        .decorators(decorators)
+        .build()
+}
+
+pub fn build_menu(content: MenuContent, thread_id: Option<String>) -> Menu {
+    let decorators = MenuDecorators {
+        thread: thread_id.map(|thid| Thread::builder().thid(thid).build()),
+        timing: Some(Timing::builder().out_time(Utc::now()).build()),
+    };
+
+    Menu::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build()
+}
+
+pub fn build_perform(
+    name: String,
+    params: HashMap<String, String>,
+    thread_id: Option<String>,
+) -> Perform {
+    let decorators = PerformDecorators {
+        thread: thread_id.map(|thid| Thread::builder().thid(thid).build()),
+        timing: Some(Timing::builder().out_time(Utc::now()).build()),
+    };
+
+    Perform::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(PerformContent::builder().name(name).params(params).build())
+        .decorators(decorators)
+        .build()
+}
+
+/// Checks that the option names of a menu, and the parameter names of each option's form, are
+/// unique and non-empty.
+pub fn validate_menu(menu: &MenuContent) -> VcxResult<()> {
+    let mut names = HashSet::new();
+    for option in menu.options.iter() {
+        if option.name.is_empty() || !names.insert(option.name.as_str()) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!("Menu option name {:?} is empty or not unique", option.name),
+            ));
+        }
+        let mut param_names = HashSet::new();
+        for param in option.form.iter().flat_map(|form| form.params.iter()) {
+            if param.name.is_empty() || !param_names.insert(param.name.as_str()) {
+                return Err(AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    format!(
+                        "Parameter name {:?} of menu option {} is empty or not unique",
+                        param.name, option.name
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that `name` selects an enabled option of the menu and that `params` fill in all of
+/// the option's required form parameters and nothing else.
+pub fn validate_selection<'a>(
+    menu: &'a MenuContent,
+    name: &str,
+    params: &HashMap<String, String>,
+) -> VcxResult<&'a MenuOption> {
+    let option = menu
+        .options
+        .iter()
+        .find(|option| option.name == name)
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!("Menu has no option named {}", name),
+            )
+        })?;
+    if option.disabled {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            format!("Menu option {} is disabled", name),
+        ));
+    }
+
+    let form_params = option
+        .form
+        .as_ref()
+        .map(|form| form.params.as_slice())
+        .unwrap_or_default();
+    if let Some(unknown) = params
+        .keys()
+        .find(|key| !form_params.iter().any(|param| &param.name == *key))
+    {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            format!("Menu option {} has no parameter named {}", name, unknown),
+        ));
+    }
+    if let Some(missing) = form_params.iter().find(|param| {
+        param.required && params.get(&param.name).is_none_or(|value| value.is_empty())
+    }) {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            format!(
+                "Required parameter {} of menu option {} is missing",
+                missing.name, name
+            ),
+        ));
+    }
+    Ok(option)
+}
@@ -0,0 +1,76 @@
+use chrono::Utc;
+use messages::{
+    decorators::{
+        localization::{Locale, MsgLocalization},
+        thread::Thread,
+        timing::Timing,
+    },
+    msg_fields::protocols::basic_message::{
+        BasicMessage, BasicMessageContent, BasicMessageDecorators,
+    },
+};
+use uuid::Uuid;
+
+/// Builds a basic message, optionally announcing the locale its content is written in and
+/// replying on an existing thread.
+pub fn build_basic_message(
+    content: String,
+    locale: Option<Locale>,
+    thread_id: Option<String>,
+) -> BasicMessage {
+    let sent_time = Utc::now();
+    let content = BasicMessageContent::builder()
+        .content(content)
+        .sent_time(sent_time)
+        .build();
+
+    let mut decorators = BasicMessageDecorators::builder()
+        .timing(Timing::builder().out_time(sent_time).build())
+        .build();
+    decorators.l10n = locale.map(|locale| MsgLocalization::builder().locale(locale).build());
+    decorators.thread = thread_id.map(|thid| Thread::builder().thid(thid).build());
+
+    BasicMessage::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build()
+}
+
+/// Locale announced in the `~l10n` decorator of the message, if any.
+pub fn get_locale(message: &BasicMessage) -> Option<Locale> {
+    message
+        .decorators
+        .l10n
+        .as_ref()
+        .and_then(|l10n| l10n.locale)
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_build_basic_message_with_locale() {
+        let locale: Locale = serde_json::from_value(json!("de")).unwrap();
+        let message = build_basic_message("Hallo".to_owned(), Some(locale), None);
+
+        assert_eq!(message.content.content, "Hallo");
+        assert_eq!(get_locale(&message), Some(locale));
+        assert!(message.decorators.thread.is_none());
+        assert_eq!(
+            message.decorators.timing.unwrap().out_time,
+            Some(message.content.sent_time)
+        );
+    }
+
+    #[test]
+    fn test_build_basic_message_reply() {
+        let message = build_basic_message("hi".to_owned(), None, Some("thread-1".to_owned()));
+
+        assert_eq!(get_locale(&message), None);
+        assert_eq!(message.decorators.thread.unwrap().thid, "thread-1");
+    }
+}
@@ -20,12 +20,15 @@ pub use thin_state::ThinState;
 use super::{requester::DidExchangeRequester, responder::DidExchangeResponder};
 use crate::{
     errors::error::{AriesVcxError, AriesVcxErrorKind},
-    protocols::did_exchange::{
-        states::{
-            abandoned::Abandoned, completed::Completed, requester::request_sent::RequestSent,
-            responder::response_sent::ResponseSent,
+    protocols::{
+        did_exchange::{
+            states::{
+                abandoned::Abandoned, completed::Completed, requester::request_sent::RequestSent,
+                responder::response_sent::ResponseSent,
+            },
+            transition::{transition_error::TransitionError, transition_result::TransitionResult},
         },
-        transition::{transition_error::TransitionError, transition_result::TransitionResult},
+        problem_report::ReceivedProblem,
     },
 };
 
@@ -166,13 +169,16 @@ impl GenericDidExchange {
                         "Attempted to handle response in completed state",
                     ),
                 )),
-                RequesterState::Abandoned(abandoned_state) => Err((
-                    GenericDidExchange::Requester(RequesterState::Abandoned(abandoned_state)),
-                    AriesVcxError::from_msg(
-                        AriesVcxErrorKind::InvalidState,
+                RequesterState::Abandoned(abandoned_state) => {
+                    let error = abandoned_error(
+                        abandoned_state.received_problem(),
                         "Attempted to handle response in abandoned state",
-                    ),
-                )),
+                    );
+                    Err((
+                        GenericDidExchange::Requester(RequesterState::Abandoned(abandoned_state)),
+                        error,
+                    ))
+                }
             },
             GenericDidExchange::Responder(responder) => Err((
                 GenericDidExchange::Responder(responder),
@@ -205,13 +211,16 @@ impl GenericDidExchange {
                         "Attempted to handle complete in completed state",
                     ),
                 )),
-                ResponderState::Abandoned(_) => Err((
-                    GenericDidExchange::Responder(responder_state),
-                    AriesVcxError::from_msg(
-                        AriesVcxErrorKind::InvalidState,
+                ResponderState::Abandoned(abandoned_state) => {
+                    let error = abandoned_error(
+                        abandoned_state.received_problem(),
                         "Attempted to handle complete in abandoned state",
-                    ),
-                )),
+                    );
+                    Err((
+                        GenericDidExchange::Responder(ResponderState::Abandoned(abandoned_state)),
+                        error,
+                    ))
+                }
             },
             GenericDidExchange::Requester(requester_state) => Err((
                 GenericDidExchange::Requester(requester_state),
@@ -265,6 +274,20 @@ impl GenericDidExchange {
         }
     }
 
+    /// The problem the counterparty reported, if it abandoned the exchange with a problem
+    /// report.
+    pub fn get_problem(&self) -> Option<ReceivedProblem> {
+        match self {
+            GenericDidExchange::Requester(RequesterState::Abandoned(abandoned_state)) => {
+                abandoned_state.received_problem().cloned()
+            }
+            GenericDidExchange::Responder(ResponderState::Abandoned(abandoned_state)) => {
+                abandoned_state.received_problem().cloned()
+            }
+            _ => None,
+        }
+    }
+
     pub fn get_state(&self) -> ThinState {
         match self {
             GenericDidExchange::Requester(requester_state) => match requester_state {
@@ -280,3 +303,11 @@ impl GenericDidExchange {
         }
     }
 }
+
+// Progressing an exchange abandoned by the counterparty fails with the problem it reported
+fn abandoned_error(problem: Option<&ReceivedProblem>, msg: &'static str) -> AriesVcxError {
+    match problem {
+        Some(problem) => AriesVcxError::from(problem.clone()).extend(msg),
+        None => AriesVcxError::from_msg(AriesVcxErrorKind::InvalidState, msg),
+    }
+}
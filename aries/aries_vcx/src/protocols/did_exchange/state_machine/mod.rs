@@ -20,6 +20,7 @@ use super::{
     states::{abandoned::Abandoned, traits::ThreadId},
     transition::transition_result::TransitionResult,
 };
+use crate::protocols::problem_report::{Problem, ReceivedProblem};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DidExchange<I, S> {
@@ -34,11 +35,18 @@ impl<I, S: ThreadId> DidExchange<I, S> {
         self.state.thread_id()
     }
 
+    /// Abandons the exchange, reporting the problem with the DID exchange specific
+    /// `problem_code`. The explanation of the problem, or else its code, is the reason the
+    /// exchange was abandoned.
     pub fn fail(
         self,
-        reason: String,
+        problem: Problem,
         problem_code: Option<ProblemCode>,
     ) -> TransitionResult<DidExchange<I, Abandoned>, AnyProblemReport> {
+        let reason = problem
+            .explanation()
+            .map(ToOwned::to_owned)
+            .unwrap_or_else(|| problem.code().to_string());
         let content = ProblemReportContent::builder()
             .problem_code(problem_code)
             .explain(Some(reason.clone()))
@@ -62,6 +70,7 @@ impl<I, S: ThreadId> DidExchange<I, S> {
                 state: Abandoned {
                     reason,
                     request_id: self.state.thread_id().to_string(),
+                    problem: None,
                 },
                 initiation_type: PhantomData,
                 our_did_document: self.our_did_document,
@@ -77,6 +86,7 @@ impl<I, S: ThreadId> DidExchange<I, S> {
     ) -> DidExchange<I, Abandoned> {
         DidExchange {
             state: Abandoned {
+                problem: Some(ReceivedProblem::from(&problem_report)),
                 reason: problem_report.content.explain.unwrap_or_default(),
                 request_id: self.state.thread_id().to_string(),
             },
@@ -87,6 +97,13 @@ impl<I, S: ThreadId> DidExchange<I, S> {
     }
 }
 
+impl<I> DidExchange<I, Abandoned> {
+    /// The problem the counterparty reported, if it abandoned the exchange.
+    pub fn received_problem(&self) -> Option<&ReceivedProblem> {
+        self.state.problem.as_ref()
+    }
+}
+
 impl<I, S> DidExchange<I, S> {
     pub fn from_parts(
         state: S,
@@ -19,10 +19,11 @@ use public_key::Key;
 
 use super::DidExchangeRequester;
 use crate::{
+    common::attachments::jws_verify_attachment,
     errors::error::{AriesVcxError, AriesVcxErrorKind},
     protocols::did_exchange::{
         state_machine::{
-            helpers::{attachment_to_diddoc, jws_verify_attachment, to_transition_error},
+            helpers::{attachment_to_diddoc, to_transition_error},
             requester::helpers::{construct_didexchange_complete, construct_request},
         },
         states::{completed::Completed, requester::request_sent::RequestSent},
@@ -16,11 +16,12 @@ use public_key::Key;
 
 use super::DidExchangeResponder;
 use crate::{
+    common::attachments::{jws_resolve_and_verify_attachment, jws_sign_attach},
     errors::error::{AriesVcxError, AriesVcxErrorKind},
     protocols::did_exchange::{
         state_machine::helpers::{
             assemble_did_rotate_attachment, attachment_to_diddoc, construct_response_v1_0,
-            construct_response_v1_1, ddo_to_attach, jws_sign_attach,
+            construct_response_v1_1, ddo_to_attach,
         },
         states::{completed::Completed, responder::response_sent::ResponseSent},
         transition::{transition_error::TransitionError, transition_result::TransitionResult},
@@ -44,7 +45,7 @@ impl DidExchangeResponder<ResponseSent> {
         let version = request.get_version();
         let request = request.into_inner();
 
-        let their_ddo = resolve_ddo_from_request(resolver_registry, &request).await?;
+        let their_ddo = resolve_ddo_from_request(wallet, resolver_registry, &request).await?;
         let our_did_document = our_peer_did.resolve_did_doc()?;
 
         let unsigned_attachment = match version {
@@ -110,20 +111,51 @@ impl DidExchangeResponder<ResponseSent> {
     }
 }
 
+/// An attached DID document must be signed with one of its own keys, as nothing else ties it to
+/// the requester's DID.
 async fn resolve_ddo_from_request(
+    wallet: &impl BaseWallet,
     resolver_registry: &Arc<ResolverRegistry>,
     request: &Request,
 ) -> Result<DidDocument, AriesVcxError> {
-    Ok(request
-        .content
-        .did_doc
-        .clone()
-        .map(attachment_to_diddoc)
-        .transpose()?
-        .unwrap_or(
-            resolver_registry
-                .resolve(&request.content.did.parse()?, &Default::default())
-                .await?
-                .did_document,
-        ))
+    let Some(did_doc_attach) = request.content.did_doc.clone() else {
+        return Ok(resolver_registry
+            .resolve(&request.content.did.parse()?, &Default::default())
+            .await?
+            .did_document);
+    };
+
+    let signer = jws_resolve_and_verify_attachment(&did_doc_attach, resolver_registry, wallet)
+        .await
+        .map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!("DIDExchange request did not have a validly signed DIDDoc: {err}"),
+            )
+        })?;
+    let did_doc = attachment_to_diddoc(did_doc_attach)?;
+    if did_doc.id().to_string() != request.content.did {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "DIDExchange request had a DIDDoc which did not match the request DID",
+        ));
+    }
+    let signed_by_doc_key = did_doc
+        .verification_method()
+        .iter()
+        .chain(
+            did_doc
+                .authentication()
+                .iter()
+                .filter_map(|vm| vm.resolved()),
+        )
+        .filter_map(|vm| vm.public_key().ok())
+        .any(|key| key.key() == signer.key());
+    if !signed_by_doc_key {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "DIDExchange request had a DIDDoc which was not signed by one of its own keys",
+        ));
+    }
+    Ok(did_doc)
 }
@@ -1,9 +1,13 @@
 use super::traits::ThreadId;
+use crate::protocols::problem_report::ReceivedProblem;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Abandoned {
     pub reason: String,
     pub request_id: String,
+    /// Set when the exchange was abandoned by the counterparty with a problem report.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub problem: Option<ReceivedProblem>,
 }
 
 impl ThreadId for Abandoned {
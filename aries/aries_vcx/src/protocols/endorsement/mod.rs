@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+
+use ::uuid::Uuid;
+use chrono::Utc;
+use did_parser_nom::Did;
+use messages::{
+    decorators::{
+        attachment::{Attachment, AttachmentData, AttachmentType},
+        thread::Thread,
+        timing::Timing,
+    },
+    misc::MimeType,
+    msg_fields::protocols::transactions::{
+        endorse::{
+            EndorseTransaction, EndorseTransactionContent, EndorseTransactionDecorators,
+            SignatureResponse,
+        },
+        refuse::{RefuseTransaction, RefuseTransactionContent, RefuseTransactionDecorators},
+        request::{
+            SignatureRequest, TransactionRequest, TransactionRequestContent,
+            TransactionRequestDecorators,
+        },
+    },
+};
+
+use serde_json::Value;
+
+use crate::{
+    common::ledger::endorsement::{EndorsedTransactionType, EndorsementRequest},
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+};
+
+const SIGNATURE_CONTEXT: &str = "did:sov";
+const SIGNATURE_METHOD: &str = "add-signature";
+const SIGNATURE_TYPE: &str = "default";
+const ENDORSE_GOAL_CODE: &str = "aries.transaction.endorse";
+const LEDGER_WRITE_GOAL_CODE: &str = "aries.transaction.ledger.write";
+
+/// Builds a `transactions` request message carrying the author-signed ledger request, attached
+/// as is so that its signature stays intact.
+pub fn build_transaction_request(
+    endorsement_request: &EndorsementRequest,
+) -> VcxResult<TransactionRequest> {
+    let signature_request = SignatureRequest::builder()
+        .context(SIGNATURE_CONTEXT.to_owned())
+        .method(SIGNATURE_METHOD.to_owned())
+        .signature_type(SIGNATURE_TYPE.to_owned())
+        .signer_goal_code(ENDORSE_GOAL_CODE.to_owned())
+        .author_goal_code(LEDGER_WRITE_GOAL_CODE.to_owned())
+        .build();
+    let attachment = Attachment::builder()
+        .id(Uuid::new_v4().to_string())
+        .mime_type(MimeType::Json)
+        .data(
+            AttachmentData::builder()
+                .content(AttachmentType::Json(Value::String(
+                    endorsement_request.request.clone(),
+                )))
+                .build(),
+        )
+        .build();
+    let content = TransactionRequestContent::builder()
+        .signature_request(vec![signature_request])
+        .messages_attach(vec![attachment])
+        .endorser_write_txn(true)
+        .build();
+    let decorators = TransactionRequestDecorators::builder()
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+
+    Ok(TransactionRequest::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build())
+}
+
+/// Extracts the author-signed ledger request attached to a transaction request message, reading
+/// its author, endorser and type from the request itself. The request may be attached either as
+/// a JSON string or as a JSON object.
+pub fn extract_endorsement_request(request: &TransactionRequest) -> VcxResult<EndorsementRequest> {
+    let attachment = request.content.messages_attach.first().ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Transaction request has no attached transaction",
+        )
+    })?;
+    let AttachmentType::Json(value) = &attachment.data.content else {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Attached transaction must be embedded as json",
+        ));
+    };
+    let (ledger_request, request) = match value {
+        Value::String(request) => (
+            serde_json::from_str::<Value>(request).map_err(|err| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidJson,
+                    format!("Cannot deserialize attached transaction: {}", err),
+                )
+            })?,
+            request.clone(),
+        ),
+        value => (value.clone(), value.to_string()),
+    };
+    let field = |pointer: &str| {
+        ledger_request
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                AriesVcxError::from_msg(
+                    AriesVcxErrorKind::InvalidInput,
+                    format!("Attached transaction has no {} field", pointer),
+                )
+            })
+    };
+    Ok(EndorsementRequest {
+        author_did: Did::parse(field("/identifier")?.to_owned())?,
+        endorser_did: Did::parse(field("/endorser")?.to_owned())?,
+        transaction_type: EndorsedTransactionType::from_ledger_txn_type(field("/operation/type")?)?,
+        request,
+    })
+}
+
+fn thread_id(request: &TransactionRequest) -> &str {
+    request
+        .decorators
+        .thread
+        .as_ref()
+        .map(|t| t.thid.as_str())
+        .unwrap_or(request.id.as_str())
+}
+
+/// Builds the confirmation that the endorser signed the transaction and wrote it to the ledger,
+/// handing back the request with the endorser's signature keyed by the endorser DID.
+pub fn build_endorse_transaction(
+    request: &TransactionRequest,
+    endorser_did: &str,
+    endorsed_request: &str,
+) -> EndorseTransaction {
+    let signature_response = SignatureResponse::builder()
+        .message_id(request.id.clone())
+        .context(SIGNATURE_CONTEXT.to_owned())
+        .method(SIGNATURE_METHOD.to_owned())
+        .signer_goal_code(ENDORSE_GOAL_CODE.to_owned())
+        .signature_type(SIGNATURE_TYPE.to_owned())
+        .signature(HashMap::from([(
+            endorser_did.to_owned(),
+            endorsed_request.to_owned(),
+        )]))
+        .build();
+    let content = EndorseTransactionContent::builder()
+        .endorser_did(endorser_did.to_owned())
+        .signature_response(vec![signature_response])
+        .build();
+    let decorators = EndorseTransactionDecorators::builder()
+        .thread(
+            Thread::builder()
+                .thid(thread_id(request).to_owned())
+                .build(),
+        )
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+
+    EndorseTransaction::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build()
+}
+
+pub fn build_refuse_transaction(
+    request: &TransactionRequest,
+    endorser_did: &str,
+    comment: Option<String>,
+) -> RefuseTransaction {
+    let content = RefuseTransactionContent {
+        endorser_did: endorser_did.to_owned(),
+        comment,
+    };
+    let decorators = RefuseTransactionDecorators::builder()
+        .thread(
+            Thread::builder()
+                .thid(thread_id(request).to_owned())
+                .build(),
+        )
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+
+    RefuseTransaction::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build()
+}
@@ -37,12 +37,12 @@ use crate::{
         get_attach_as_string, make_attach_from_str, verify_thread_id, AttachmentId, Status,
     },
     protocols::{
-        common::build_problem_report_msg,
         issuance::holder::states::{
             finished::FinishedHolderState, initial::InitialHolderState,
             offer_received::OfferReceivedState, proposal_set::ProposalSetState,
             request_set::RequestSetState,
         },
+        problem_report::{build_rejection_msg, Problem},
     },
 };
 
@@ -236,8 +236,7 @@ impl HolderSM {
                         })
                     }
                     Err(err) => {
-                        let problem_report =
-                            build_problem_report_msg(Some(err.to_string()), &self.thread_id);
+                        let problem_report = Problem::from(&err).build(&self.thread_id);
                         error!(
                             "Failed to create credential request with error {err}, generating \
                              problem report: {:?}",
@@ -259,7 +258,7 @@ impl HolderSM {
         trace!("HolderSM::decline_offer >>");
         let state = match self.state {
             HolderFullState::OfferReceived(_) => {
-                let problem_report = build_problem_report_msg(comment, &self.thread_id);
+                let problem_report = build_rejection_msg(comment, &self.thread_id);
                 HolderFullState::Finished(FinishedHolderState::new(problem_report))
             }
             s => {
@@ -297,8 +296,7 @@ impl HolderSM {
                         (state_data, cred_id, credential, rev_reg_def_json).into(),
                     ),
                     Err(err) => {
-                        let problem_report =
-                            build_problem_report_msg(Some(err.to_string()), &self.thread_id);
+                        let problem_report = Problem::from(&err).build(&self.thread_id);
                         error!("Failed to process or save received credential: {problem_report:?}");
                         HolderFullState::Finished(FinishedHolderState::new(problem_report))
                     }
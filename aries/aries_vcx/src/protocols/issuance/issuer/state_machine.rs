@@ -37,12 +37,12 @@ use crate::{
         AttachmentId, OfferInfo, Status,
     },
     protocols::{
-        common::build_problem_report_msg,
         issuance::issuer::states::{
             credential_set::CredentialSetState, finished::FinishedState,
             initial::InitialIssuerState, offer_set::OfferSetState,
             proposal_received::ProposalReceivedState, requested_received::RequestReceivedState,
         },
+        problem_report::Problem,
     },
 };
 
@@ -455,8 +455,7 @@ impl IssuerSM {
                     // SM consumer can call       2. Also create separate
                     // "Failed" state
                     Err(err) => {
-                        let problem_report =
-                            build_problem_report_msg(Some(err.to_string()), &self.thread_id);
+                        let problem_report = Problem::from(&err).build(&self.thread_id);
                         error!(
                             "Failed to create credential, generated problem report \
                              {problem_report:?}",
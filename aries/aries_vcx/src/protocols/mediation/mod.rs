@@ -0,0 +1,133 @@
+use ::uuid::Uuid;
+use messages::{
+    decorators::{
+        thread::Thread,
+        transport::{ReturnRoute, Transport},
+    },
+    msg_fields::protocols::{
+        coordinate_mediation::{
+            keylist_query::KeylistQueryPaginateParams,
+            keylist_update::{KeylistUpdateItem, KeylistUpdateItemAction},
+            KeylistQuery, KeylistQueryContent, KeylistUpdate, KeylistUpdateContent, MediateRequest,
+            MediateRequestContent,
+        },
+        pickup::{
+            DeliveryRequest, DeliveryRequestContent, DeliveryRequestDecorators, MessagesReceived,
+            MessagesReceivedContent, MessagesReceivedDecorators, StatusRequest,
+            StatusRequestContent, StatusRequestDecorators,
+        },
+    },
+};
+
+// Pickup responses are only ever returned on the inbound exchange that requested them,
+// mediators never push them to the recipient's endpoint.
+fn return_route_all() -> Transport {
+    Transport::builder().return_route(ReturnRoute::All).build()
+}
+
+pub fn build_mediate_request() -> MediateRequest {
+    MediateRequest::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(MediateRequestContent::builder().build())
+        .build()
+}
+
+pub fn build_keylist_update_item(
+    recipient_key: String,
+    action: KeylistUpdateItemAction,
+) -> KeylistUpdateItem {
+    KeylistUpdateItem::builder()
+        .recipient_key(recipient_key)
+        .action(action)
+        .build()
+}
+
+pub fn build_keylist_update(updates: Vec<KeylistUpdateItem>) -> KeylistUpdate {
+    KeylistUpdate::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(KeylistUpdateContent::builder().updates(updates).build())
+        .build()
+}
+
+pub fn build_keylist_query(limit: Option<u64>, offset: Option<u64>) -> KeylistQuery {
+    let paginate = match (limit, offset) {
+        (None, None) => None,
+        (Some(limit), None) => Some(KeylistQueryPaginateParams::builder().limit(limit).build()),
+        (None, Some(offset)) => Some(KeylistQueryPaginateParams::builder().offset(offset).build()),
+        (Some(limit), Some(offset)) => Some(
+            KeylistQueryPaginateParams::builder()
+                .limit(limit)
+                .offset(offset)
+                .build(),
+        ),
+    };
+    let content = match paginate {
+        Some(paginate) => KeylistQueryContent::builder().paginate(paginate).build(),
+        None => KeylistQueryContent::builder().build(),
+    };
+    KeylistQuery::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .build()
+}
+
+pub fn build_status_request(recipient_key: Option<String>) -> StatusRequest {
+    let content = match recipient_key {
+        Some(recipient_key) => StatusRequestContent::builder()
+            .recipient_key(recipient_key)
+            .build(),
+        None => StatusRequestContent::builder().build(),
+    };
+    StatusRequest::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(
+            StatusRequestDecorators::builder()
+                .transport(return_route_all())
+                .build(),
+        )
+        .build()
+}
+
+pub fn build_delivery_request(limit: u32, recipient_key: Option<String>) -> DeliveryRequest {
+    let content = match recipient_key {
+        Some(recipient_key) => DeliveryRequestContent::builder()
+            .limit(limit)
+            .recipient_key(recipient_key)
+            .build(),
+        None => DeliveryRequestContent::builder().limit(limit).build(),
+    };
+    DeliveryRequest::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(
+            DeliveryRequestDecorators::builder()
+                .transport(return_route_all())
+                .build(),
+        )
+        .build()
+}
+
+pub fn build_messages_received(
+    message_id_list: Vec<String>,
+    delivery_thread_id: Option<String>,
+) -> MessagesReceived {
+    let decorators = match delivery_thread_id {
+        Some(thid) => MessagesReceivedDecorators::builder()
+            .transport(return_route_all())
+            .thread(Thread::builder().thid(thid).build())
+            .build(),
+        None => MessagesReceivedDecorators::builder()
+            .transport(return_route_all())
+            .build(),
+    };
+    MessagesReceived::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(
+            MessagesReceivedContent::builder()
+                .message_id_list(message_id_list)
+                .build(),
+        )
+        .decorators(decorators)
+        .build()
+}
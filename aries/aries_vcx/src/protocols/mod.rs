@@ -4,13 +4,19 @@ use messages::AriesMessage;
 
 use crate::errors::error::VcxResult;
 
+pub mod action_menu;
+pub mod basic_message;
 pub mod common;
 pub mod connection;
 pub mod did_exchange;
+pub mod endorsement;
 pub mod issuance;
 pub mod mediated_connection;
+pub mod mediation;
 pub mod oob;
+pub mod problem_report;
 pub mod proof_presentation;
+pub mod question_answer;
 pub mod revocation_notification;
 pub mod trustping;
 
@@ -22,3 +28,42 @@ pub type SendClosureConnection<'a> = Box<
         + Sync
         + 'a,
 >;
+
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use std::sync::{Arc, Mutex};
+
+    use messages::AriesMessage;
+
+    use super::SendClosure;
+    use crate::errors::error::VcxResult;
+
+    /// Records every message sent through the [`SendClosure`]s it hands out, so tests can
+    /// inspect them and pass them on to the other party.
+    #[derive(Clone, Default)]
+    pub struct SentMessages(Arc<Mutex<Vec<AriesMessage>>>);
+
+    impl SentMessages {
+        pub fn send_closure(&self) -> SendClosure<'static> {
+            let sent = self.0.clone();
+            Box::new(move |message: AriesMessage| {
+                Box::pin(async move {
+                    sent.lock().unwrap().push(message);
+                    VcxResult::Ok(())
+                })
+            })
+        }
+
+        pub fn last(&self) -> Option<AriesMessage> {
+            self.0.lock().unwrap().last().cloned()
+        }
+
+        pub fn len(&self) -> usize {
+            self.0.lock().unwrap().len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+    }
+}
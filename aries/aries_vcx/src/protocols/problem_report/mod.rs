@@ -0,0 +1,550 @@
+//! Problem reporting as defined in [Aries RFC 0035](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0035-report-problem/README.md>),
+//! shared by all protocols.
+//!
+//! [`Problem`] describes a problem to report to the counterparty, typically derived from an
+//! [`AriesVcxError`], and renders it as a problem report which can be converted into the problem
+//! report of any protocol reusing the RFC 0035 content. [`ReceivedProblem`] goes the other way,
+//! extracting the problem from an inbound problem report of any protocol.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{SecondsFormat, Utc};
+use messages::{
+    decorators::{
+        localization::{FieldLocalization, Locale},
+        thread::Thread,
+        timing::Timing,
+    },
+    msg_fields::protocols::{
+        connection::Connection,
+        cred_issuance::{v1::CredentialIssuanceV1, v2::CredentialIssuanceV2, CredentialIssuance},
+        did_exchange::{
+            v1_0::DidExchangeV1_0, v1_1::DidExchangeV1_1,
+            v1_x::problem_report::ProblemReport as DidExchangeProblemReport, DidExchange,
+        },
+        notification::Notification,
+        present_proof::{v1::PresentProofV1, v2::PresentProofV2, PresentProof},
+        report_problem::{
+            Description, Impact, ProblemReport, ProblemReportContent, ProblemReportDecorators,
+            Where, WhereParty, WhoRetries,
+        },
+    },
+    AriesMessage,
+};
+use serde::Serialize;
+use url::Url;
+use uuid::Uuid;
+
+use crate::errors::error::prelude::*;
+
+/// Descriptors of the problem codes used by this library. They describe the problem within
+/// the [`ProblemScope`] of a [`ProblemCode`].
+pub mod descriptors {
+    pub const UNSUPPORTED_MESSAGE_TYPE: &str = "unsupported-message-type";
+    pub const MESSAGE_EXPIRED: &str = "message-expired";
+    pub const MESSAGE_PARSE_FAILURE: &str = "message-parse-failure";
+    pub const INVALID_MESSAGE: &str = "invalid-message";
+    pub const INVALID_STATE: &str = "invalid-state";
+    pub const TRUST_CRYPTO: &str = "trust.crypto";
+    pub const UNSUPPORTED: &str = "unsupported";
+    pub const REJECTED: &str = "rejected";
+    pub const CANT_USE_ENDPOINT: &str = "xfer.cant-use-endpoint";
+    pub const INTERNAL_ERROR: &str = "internal-error";
+}
+
+/// Whether the problem ends the protocol (`e`) or is merely a warning (`w`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProblemSorter {
+    Error,
+    Warning,
+}
+
+/// What the problem affects: the whole protocol instance (`p`), a single message (`m`), or the
+/// named state of the protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProblemScope {
+    Protocol,
+    Message,
+    State(String),
+}
+
+/// Problem code in the `sorter.scope.descriptors` format of RFC 0035, such as
+/// `e.p.xfer.cant-use-endpoint`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemCode {
+    pub sorter: ProblemSorter,
+    pub scope: ProblemScope,
+    pub descriptor: String,
+}
+
+impl ProblemCode {
+    pub fn error(scope: ProblemScope, descriptor: &str) -> Self {
+        Self {
+            sorter: ProblemSorter::Error,
+            scope,
+            descriptor: descriptor.to_owned(),
+        }
+    }
+
+    pub fn warning(scope: ProblemScope, descriptor: &str) -> Self {
+        Self {
+            sorter: ProblemSorter::Warning,
+            scope,
+            descriptor: descriptor.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for ProblemCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sorter = match self.sorter {
+            ProblemSorter::Error => "e",
+            ProblemSorter::Warning => "w",
+        };
+        let scope = match &self.scope {
+            ProblemScope::Protocol => "p",
+            ProblemScope::Message => "m",
+            ProblemScope::State(state) => state,
+        };
+        write!(f, "{}.{}.{}", sorter, scope, self.descriptor)
+    }
+}
+
+impl FromStr for ProblemCode {
+    type Err = AriesVcxError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidInput,
+                format!(
+                    "Problem code {} is not in the sorter.scope.descriptors format",
+                    code
+                ),
+            )
+        };
+        let mut parts = code.splitn(3, '.');
+        let sorter = match parts.next() {
+            Some("e") => ProblemSorter::Error,
+            Some("w") => ProblemSorter::Warning,
+            _ => return Err(invalid()),
+        };
+        let scope = match parts.next() {
+            Some("p") => ProblemScope::Protocol,
+            Some("m") => ProblemScope::Message,
+            Some(state) if !state.is_empty() => ProblemScope::State(state.to_owned()),
+            _ => return Err(invalid()),
+        };
+        let descriptor = parts
+            .next()
+            .filter(|descriptor| !descriptor.is_empty())
+            .ok_or_else(invalid)?;
+        Ok(Self {
+            sorter,
+            scope,
+            descriptor: descriptor.to_owned(),
+        })
+    }
+}
+
+/// A problem to report to the counterparty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    code: ProblemCode,
+    explain: Option<String>,
+    locale: Locale,
+    impact: Option<Impact>,
+    location: Option<Where>,
+    who_retries: Option<WhoRetries>,
+    fix_hint: Option<String>,
+    escalation_uri: Option<Url>,
+    catalogs: Option<Vec<Url>>,
+}
+
+impl Problem {
+    pub fn new(code: ProblemCode) -> Self {
+        Self {
+            code,
+            explain: None,
+            locale: Locale::default(),
+            impact: None,
+            location: None,
+            who_retries: None,
+            fix_hint: None,
+            escalation_uri: None,
+            catalogs: None,
+        }
+    }
+
+    pub fn code(&self) -> &ProblemCode {
+        &self.code
+    }
+
+    /// The explanation set by [`Self::explain`], if any.
+    pub fn explanation(&self) -> Option<&str> {
+        self.explain.as_deref()
+    }
+
+    /// Human readable explanation of the problem, in the locale set by [`Self::locale`].
+    pub fn explain(mut self, explain: impl Into<String>) -> Self {
+        self.explain = Some(explain.into());
+        self
+    }
+
+    /// Locale of the explanation and fix hint, English by default.
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    pub fn impact(mut self, impact: Impact) -> Self {
+        self.impact = Some(impact);
+        self
+    }
+
+    pub fn location(mut self, party: WhereParty, location: impl Into<String>) -> Self {
+        self.location = Some(Where::new(party, location.into()));
+        self
+    }
+
+    pub fn who_retries(mut self, who_retries: WhoRetries) -> Self {
+        self.who_retries = Some(who_retries);
+        self
+    }
+
+    pub fn fix_hint(mut self, fix_hint: impl Into<String>) -> Self {
+        self.fix_hint = Some(fix_hint.into());
+        self
+    }
+
+    pub fn escalation_uri(mut self, escalation_uri: Url) -> Self {
+        self.escalation_uri = Some(escalation_uri);
+        self
+    }
+
+    /// Message catalogs in which the counterparty can look up translations of the problem
+    /// code, see [Aries RFC 0043](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0043-l10n/README.md>).
+    pub fn catalogs(mut self, catalogs: Vec<Url>) -> Self {
+        self.catalogs = Some(catalogs);
+        self
+    }
+
+    pub fn to_content(&self) -> ProblemReportContent {
+        let mut description = Description::builder().code(self.code.to_string()).build();
+        if let Some(explain) = &self.explain {
+            description
+                .translations
+                .insert(self.locale, explain.clone());
+        }
+        let mut content: ProblemReportContent = ProblemReportContent::builder()
+            .description(description)
+            .noticed_time(Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true))
+            .build();
+        content.impact = self.impact;
+        content.location.clone_from(&self.location);
+        content.who_retries = self.who_retries;
+        content.fix_hint.clone_from(&self.fix_hint);
+        content.escalation_uri.clone_from(&self.escalation_uri);
+        content
+    }
+
+    pub fn to_decorators(&self, thread_id: &str) -> ProblemReportDecorators {
+        let mut decorators = ProblemReportDecorators::builder()
+            .thread(Thread::builder().thid(thread_id.to_owned()).build())
+            .timing(Timing::builder().out_time(Utc::now()).build())
+            .build();
+        if let Some(catalogs) = &self.catalogs {
+            decorators.description_locale = Some(
+                FieldLocalization::builder()
+                    .code(self.code.to_string())
+                    .locale(self.locale)
+                    .catalogs(catalogs.clone())
+                    .build(),
+            );
+        }
+        if self.fix_hint.is_some() {
+            decorators.fix_hint_locale =
+                Some(FieldLocalization::builder().locale(self.locale).build());
+        }
+        decorators
+    }
+
+    /// Builds the problem report for the given thread. Protocols with their own problem report
+    /// message can convert its content, e.g. into a `CredIssuanceV1ProblemReportContent`.
+    pub fn build(&self, thread_id: &str) -> ProblemReport {
+        ProblemReport::builder()
+            .id(Uuid::new_v4().to_string())
+            .content(self.to_content())
+            .decorators(self.to_decorators(thread_id))
+            .build()
+    }
+}
+
+impl From<&AriesVcxError> for Problem {
+    /// Maps the kind of the error to a standard problem code, explaining the problem with the
+    /// error message.
+    fn from(err: &AriesVcxError) -> Self {
+        use descriptors::*;
+        use AriesVcxErrorKind::*;
+
+        let (scope, descriptor, impact) = match err.kind() {
+            InvalidJson | InvalidMessagePack | InvalidMessageFormat | SerializationError
+            | ParsingError | NotBase58 => (
+                ProblemScope::Message,
+                MESSAGE_PARSE_FAILURE,
+                Impact::MessageContent,
+            ),
+            InvalidInput
+            | InvalidAttributesStructure
+            | InvalidProof
+            | InvalidProofCredentialData
+            | InvalidProofRequest
+            | InvalidSchema
+            | InvalidDid
+            | InvalidVerkey
+            | InvalidNonce
+            | InvalidUrl
+            | InvalidRevocationDetails
+            | InvalidRevocationTimestamp => (
+                ProblemScope::Message,
+                INVALID_MESSAGE,
+                Impact::MessageContent,
+            ),
+            AuthenticationError => (ProblemScope::Message, TRUST_CRYPTO, Impact::MessageContent),
+            InvalidState | NotReady => (ProblemScope::Protocol, INVALID_STATE, Impact::Thread),
+            ProofRejected => (ProblemScope::Protocol, REJECTED, Impact::Thread),
+            ActionNotSupported | UnimplementedFeature => {
+                (ProblemScope::Protocol, UNSUPPORTED, Impact::Thread)
+            }
+            PostMessageFailed | InvalidHttpResponse => (
+                ProblemScope::Protocol,
+                CANT_USE_ENDPOINT,
+                Impact::Connection,
+            ),
+            _ => (ProblemScope::Protocol, INTERNAL_ERROR, Impact::Thread),
+        };
+        Problem::new(ProblemCode::error(scope, descriptor))
+            .impact(impact)
+            .explain(err.msg())
+    }
+}
+
+impl From<AriesVcxError> for Problem {
+    fn from(err: AriesVcxError) -> Self {
+        Self::from(&err)
+    }
+}
+
+/// Problem report declining the protocol on the given thread, e.g. an offer or a presentation
+/// request, optionally explaining why.
+pub fn build_rejection_msg(reason: Option<String>, thread_id: &str) -> ProblemReport {
+    let problem = Problem::new(ProblemCode::error(
+        ProblemScope::Protocol,
+        descriptors::REJECTED,
+    ))
+    .impact(Impact::Thread);
+    match reason {
+        Some(reason) => problem.explain(reason),
+        None => problem,
+    }
+    .build(thread_id)
+}
+
+/// A problem reported by the counterparty, as read from the problem report of any protocol.
+///
+/// Protocol handlers keep the problem that ended their protocol instance, see e.g.
+/// `Issuer::get_problem`. It converts into an [`AriesVcxError`] of kind
+/// [`AriesVcxErrorKind::ProblemReportReceived`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReceivedProblem {
+    /// Thread of the protocol instance the problem was reported for.
+    pub thread_id: Option<String>,
+    /// The code as sent, which may not follow the format of [`ProblemCode`].
+    pub code: Option<String>,
+    pub explain: Option<String>,
+    pub impact: Option<Impact>,
+    pub location: Option<Where>,
+    pub escalation_uri: Option<Url>,
+}
+
+impl ReceivedProblem {
+    /// Reads the problem reported by the message, if it is a problem report of any protocol.
+    pub fn from_message(message: &AriesMessage) -> Option<Self> {
+        let problem = match message {
+            AriesMessage::ReportProblem(msg) => msg.into(),
+            AriesMessage::Notification(Notification::ProblemReport(msg)) => {
+                (&ProblemReport::from(msg.clone())).into()
+            }
+            AriesMessage::CredentialIssuance(CredentialIssuance::V1(
+                CredentialIssuanceV1::ProblemReport(msg),
+            )) => (&ProblemReport::from(msg.clone())).into(),
+            AriesMessage::CredentialIssuance(CredentialIssuance::V2(
+                CredentialIssuanceV2::ProblemReport(msg),
+            )) => (&ProblemReport::from(msg.clone())).into(),
+            AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::ProblemReport(msg))) => {
+                (&ProblemReport::from(msg.clone())).into()
+            }
+            AriesMessage::PresentProof(PresentProof::V2(PresentProofV2::ProblemReport(msg))) => {
+                (&ProblemReport::from(msg.clone())).into()
+            }
+            AriesMessage::Connection(Connection::ProblemReport(msg)) => Self {
+                thread_id: Some(msg.decorators.thread.thid.clone()),
+                code: msg.content.problem_code.as_ref().and_then(enum_code),
+                explain: msg.content.explain.clone(),
+                ..Self::default()
+            },
+            AriesMessage::DidExchange(DidExchange::V1_0(DidExchangeV1_0::ProblemReport(msg)))
+            | AriesMessage::DidExchange(DidExchange::V1_1(DidExchangeV1_1::ProblemReport(msg))) => {
+                msg.into()
+            }
+            _ => return None,
+        };
+        Some(problem)
+    }
+
+    /// The code parsed in the format of RFC 0035, if it follows it.
+    pub fn problem_code(&self) -> Option<ProblemCode> {
+        self.code.as_deref()?.parse().ok()
+    }
+}
+
+impl From<&ProblemReport> for ReceivedProblem {
+    fn from(msg: &ProblemReport) -> Self {
+        let description = &msg.content.description;
+        let explain = description
+            .translations
+            .get(&Locale::default())
+            .or_else(|| description.translations.values().next())
+            .cloned();
+        Self {
+            thread_id: msg
+                .decorators
+                .thread
+                .as_ref()
+                .map(|thread| thread.thid.clone()),
+            code: Some(description.code.clone()).filter(|code| !code.is_empty()),
+            explain,
+            impact: msg.content.impact,
+            location: msg.content.location.clone(),
+            escalation_uri: msg.content.escalation_uri.clone(),
+        }
+    }
+}
+
+impl From<&DidExchangeProblemReport> for ReceivedProblem {
+    fn from(msg: &DidExchangeProblemReport) -> Self {
+        Self {
+            thread_id: Some(msg.decorators.thread.thid.clone()),
+            code: msg.content.problem_code.as_ref().and_then(enum_code),
+            explain: msg.content.explain.clone(),
+            ..Self::default()
+        }
+    }
+}
+
+impl From<ReceivedProblem> for AriesVcxError {
+    fn from(problem: ReceivedProblem) -> Self {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::ProblemReportReceived,
+            format!(
+                "Counterparty reported problem {} on thread {}: {}",
+                problem.code.as_deref().unwrap_or("without code"),
+                problem.thread_id.as_deref().unwrap_or("unknown"),
+                problem.explain.as_deref().unwrap_or("no explanation given")
+            ),
+        )
+    }
+}
+
+// The problem codes of the connection and DID exchange protocols are enums serialized as
+// strings.
+fn enum_code(code: &impl Serialize) -> Option<String> {
+    serde_json::to_value(code)
+        .ok()?
+        .as_str()
+        .map(ToOwned::to_owned)
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use messages::msg_fields::protocols::cred_issuance::v1::problem_report::CredIssuanceV1ProblemReport;
+
+    use super::*;
+
+    #[test]
+    fn test_problem_code_roundtrip() {
+        let code: ProblemCode = "e.p.xfer.cant-use-endpoint".parse().unwrap();
+        assert_eq!(
+            code,
+            ProblemCode::error(ProblemScope::Protocol, descriptors::CANT_USE_ENDPOINT)
+        );
+        assert_eq!(code.to_string(), "e.p.xfer.cant-use-endpoint");
+
+        let code: ProblemCode = "w.request-sent.slow".parse().unwrap();
+        assert_eq!(code.scope, ProblemScope::State("request-sent".to_owned()));
+        assert_eq!(code.sorter, ProblemSorter::Warning);
+
+        assert!("unsupported-message-type".parse::<ProblemCode>().is_err());
+        assert!("e.p.".parse::<ProblemCode>().is_err());
+    }
+
+    #[test]
+    fn test_problem_from_error() {
+        let err = AriesVcxError::from_msg(AriesVcxErrorKind::InvalidJson, "unexpected token");
+        let problem_report = Problem::from(&err)
+            .escalation_uri("https://example.org/support".parse().unwrap())
+            .catalogs(vec!["https://example.org/catalog.json".parse().unwrap()])
+            .build("thread-1");
+
+        let content = &problem_report.content;
+        assert_eq!(content.description.code, "e.m.message-parse-failure");
+        assert_eq!(
+            content.description.translations.get(&Locale::default()),
+            Some(&"unexpected token".to_owned())
+        );
+        assert_eq!(content.impact, Some(Impact::MessageContent));
+        assert!(content.escalation_uri.is_some());
+        let l10n = problem_report.decorators.description_locale.unwrap();
+        assert_eq!(l10n.code.as_deref(), Some("e.m.message-parse-failure"));
+        assert_eq!(l10n.catalogs.unwrap().len(), 1);
+        assert_eq!(
+            problem_report.decorators.thread.unwrap().thid,
+            "thread-1".to_owned()
+        );
+    }
+
+    #[test]
+    fn test_received_problem_from_protocol_problem_report() {
+        let problem_report = Problem::new(ProblemCode::error(
+            ProblemScope::Protocol,
+            descriptors::REJECTED,
+        ))
+        .explain("offer declined")
+        .location(WhereParty::Me, "holder")
+        .build("thread-1");
+        let message: AriesMessage = CredIssuanceV1ProblemReport::builder()
+            .id(problem_report.id)
+            .content(problem_report.content.into())
+            .decorators(problem_report.decorators)
+            .build()
+            .into();
+
+        let problem = ReceivedProblem::from_message(&message).unwrap();
+        assert_eq!(problem.thread_id.as_deref(), Some("thread-1"));
+        assert_eq!(problem.explain.as_deref(), Some("offer declined"));
+        assert_eq!(
+            problem.problem_code().unwrap().descriptor,
+            descriptors::REJECTED
+        );
+        assert_eq!(problem.location.unwrap().party, WhereParty::Me);
+
+        let err = AriesVcxError::from(ReceivedProblem::from_message(&message).unwrap());
+        assert_eq!(err.kind(), AriesVcxErrorKind::ProblemReportReceived);
+    }
+
+    #[test]
+    fn test_non_problem_report_is_ignored() {
+        let message: AriesMessage = crate::protocols::trustping::build_ping(false, None).into();
+        assert!(ReceivedProblem::from_message(&message).is_none());
+    }
+}
@@ -25,10 +25,11 @@ use messages::{
 use uuid::Uuid;
 
 use crate::{
+    common::primitives::tails_file_manager::TailsFileManager,
     errors::error::prelude::*,
     handlers::util::{make_attach_from_str, AttachmentId, PresentationProposalData, Status},
     protocols::{
-        common::build_problem_report_msg,
+        problem_report::Problem,
         proof_presentation::prover::states::{
             finished::FinishedState, initial::InitialProverState,
             presentation_preparation_failed::PresentationPreparationFailedState,
@@ -237,6 +238,7 @@ impl ProverSM {
         wallet: &impl BaseWallet,
         ledger: &impl AnoncredsLedgerRead,
         anoncreds: &impl BaseAnonCreds,
+        tails_file_manager: Option<&TailsFileManager>,
         credentials: SelectedCredentials,
         self_attested_attrs: HashMap<String, String>,
     ) -> VcxResult<Self> {
@@ -247,6 +249,7 @@ impl ProverSM {
                         wallet,
                         ledger,
                         anoncreds,
+                        tails_file_manager,
                         &credentials,
                         self_attested_attrs,
                     )
@@ -257,8 +260,7 @@ impl ProverSM {
                         ProverFullState::PresentationPrepared((state, presentation).into())
                     }
                     Err(err) => {
-                        let problem_report =
-                            build_problem_report_msg(Some(err.to_string()), &self.thread_id);
+                        let problem_report = Problem::from(&err).build(&self.thread_id);
                         error!(
                             "Failed bo build presentation, sending problem report: {:?}",
                             problem_report
@@ -16,7 +16,9 @@ use messages::msg_fields::protocols::{
 use uuid::Uuid;
 
 use crate::{
-    common::proofs::prover::generate_indy_proof,
+    common::{
+        primitives::tails_file_manager::TailsFileManager, proofs::prover::generate_indy_proof,
+    },
     errors::error::prelude::*,
     handlers::util::{get_attach_as_string, Status},
     protocols::proof_presentation::prover::states::{
@@ -59,6 +61,7 @@ impl PresentationRequestReceived {
         wallet: &impl BaseWallet,
         ledger: &impl AnoncredsLedgerRead,
         anoncreds: &impl BaseAnonCreds,
+        tails_file_manager: Option<&TailsFileManager>,
         credentials: &SelectedCredentials,
         self_attested_attrs: HashMap<String, String>,
     ) -> VcxResult<Presentation> {
@@ -73,6 +76,7 @@ impl PresentationRequestReceived {
             wallet,
             ledger,
             anoncreds,
+            tails_file_manager,
             credentials,
             self_attested_attrs,
             proof_req_data_json,
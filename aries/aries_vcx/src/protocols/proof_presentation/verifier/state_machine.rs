@@ -18,7 +18,7 @@ use messages::{
                 RequestPresentationV1Decorators,
             },
         },
-        report_problem::ProblemReport,
+        report_problem::{Impact, ProblemReport},
     },
     AriesMessage,
 };
@@ -28,7 +28,7 @@ use crate::{
     errors::error::prelude::*,
     handlers::util::{make_attach_from_str, verify_thread_id, AttachmentId, Status},
     protocols::{
-        common::build_problem_report_msg,
+        problem_report::{descriptors, Problem, ProblemCode, ProblemScope},
         proof_presentation::verifier::{
             states::{
                 finished::FinishedState, initial::InitialVerifierState,
@@ -267,8 +267,7 @@ impl VerifierSM {
                         (state, presentation, PresentationVerificationStatus::Valid).into(),
                     ),
                     Err(err) => {
-                        let problem_report =
-                            build_problem_report_msg(Some(err.to_string()), &self.thread_id);
+                        let problem_report = Problem::from(&err).build(&self.thread_id);
 
                         match err.kind() {
                             AriesVcxErrorKind::InvalidProof => VerifierFullState::Finished(
@@ -300,7 +299,17 @@ impl VerifierSM {
                         AriesVcxErrorKind::InvalidState,
                         "Cannot get final message in this state: finished, status undefined",
                     )),
-                    Status::Success => Ok(build_problem_report_msg(None, &self.thread_id).into()),
+                    Status::Success => Ok(Problem::new(ProblemCode::error(
+                        ProblemScope::Protocol,
+                        descriptors::INVALID_MESSAGE,
+                    ))
+                    .impact(Impact::Thread)
+                    .explain(format!(
+                        "Presentation verification status: {:?}",
+                        state.verification_status
+                    ))
+                    .build(&self.thread_id)
+                    .into()),
                     Status::Failed(problem_report) | Status::Declined(problem_report) => {
                         let problem_report = PresentProofV1ProblemReport::builder()
                             .id(problem_report.id.clone())
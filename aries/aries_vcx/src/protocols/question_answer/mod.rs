@@ -0,0 +1,110 @@
+use ::uuid::Uuid;
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use chrono::{DateTime, Utc};
+use messages::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::question_answer::{
+        answer::{Answer, AnswerContent, AnswerDecorators},
+        question::{Question, QuestionContent, QuestionDecorators, ValidResponse},
+    },
+};
+
+use crate::{
+    common::signing::{sign_data, verify_signed_data},
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+};
+
+pub fn build_question(
+    question_text: String,
+    question_detail: Option<String>,
+    valid_responses: Vec<String>,
+    signature_required: bool,
+    expires_time: Option<DateTime<Utc>>,
+) -> Question {
+    let content = QuestionContent {
+        question_text,
+        question_detail,
+        nonce: Uuid::new_v4().to_string(),
+        signature_required,
+        valid_responses: valid_responses
+            .into_iter()
+            .map(ValidResponse::new)
+            .collect(),
+    };
+    let timing = Timing {
+        out_time: Some(Utc::now()),
+        expires_time,
+        ..Default::default()
+    };
+
+    Question::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(QuestionDecorators::builder().timing(timing).build())
+        .build()
+}
+
+/// Builds the answer to a question, signed with `signer_key` if the question requires it.
+pub async fn build_answer(
+    wallet: &impl BaseWallet,
+    question: &Question,
+    response: String,
+    signer_key: &str,
+) -> VcxResult<Answer> {
+    let thread_id = question
+        .decorators
+        .thread
+        .as_ref()
+        .map(|t| t.thid.as_str())
+        .unwrap_or(question.id.as_str())
+        .to_owned();
+
+    let content = if question.content.signature_required {
+        let sig_data = answer_sig_data(&question.content, &response);
+        AnswerContent::builder()
+            .response_sig(sign_data(wallet, signer_key, sig_data).await?)
+            .response(response)
+            .build()
+    } else {
+        AnswerContent::builder().response(response).build()
+    };
+    let decorators = AnswerDecorators::builder()
+        .thread(Thread::builder().thid(thread_id).build())
+        .timing(Timing::builder().out_time(Utc::now()).build())
+        .build();
+
+    Ok(Answer::builder()
+        .id(Uuid::new_v4().to_string())
+        .content(content)
+        .decorators(decorators)
+        .build())
+}
+
+/// Verifies the signature of an answer, made by `their_vk`, against the question it answers.
+pub async fn verify_answer_signature(
+    wallet: &impl BaseWallet,
+    question: &QuestionContent,
+    answer: &AnswerContent,
+    their_vk: &str,
+) -> VcxResult<()> {
+    let signature = answer.response_sig.as_ref().ok_or_else(|| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Answer is not signed, but the question requires a signature",
+        )
+    })?;
+    let signed_data = verify_signed_data(wallet, signature, their_vk).await?;
+    if signed_data != answer_sig_data(question, &answer.response).as_bytes() {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidInput,
+            "Answer signature does not cover the question text, response and nonce",
+        ));
+    }
+    Ok(())
+}
+
+/// The signature of an answer covers the question text, the response and the question nonce,
+/// concatenated.
+fn answer_sig_data(question: &QuestionContent, response: &str) -> String {
+    format!("{}{}{}", question.question_text, response, question.nonce)
+}
@@ -0,0 +1,334 @@
+mod records;
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+use aries_vcx_wallet::wallet::{
+    base_wallet::{record::Record, record_category::RecordCategory, BaseWallet},
+    record_tags::{RecordTag, RecordTags},
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+const THREAD_ID_TAG: &str = "thread_id";
+const CONNECTION_ID_TAG: &str = "connection_id";
+const STATE_TAG: &str = "state";
+
+/// Protocol state which can be persisted in a [`ProtocolRecordStore`].
+pub trait ProtocolRecord: Serialize + DeserializeOwned + Send + Sync {
+    type State: Debug;
+
+    /// Wallet category the records of this type are stored in.
+    const CATEGORY: RecordCategory;
+
+    /// Thread of the protocol instance, if it has been established yet.
+    fn thread_id(&self) -> Option<String>;
+
+    fn state(&self) -> Self::State;
+
+    /// Name of `state` the records are tagged and queried with. It is persisted, so it must not
+    /// change once released.
+    fn state_tag(state: &Self::State) -> &'static str;
+}
+
+/// A protocol record as read from the store, along with its storage metadata.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredProtocolRecord<T> {
+    pub id: String,
+    /// Incremented on every update, see [`ProtocolRecordStore::update`].
+    pub version: u64,
+    pub connection_id: Option<String>,
+    pub record: T,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredValue<T> {
+    version: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    connection_id: Option<String>,
+    record: T,
+}
+
+/// Filter for [`ProtocolRecordStore::find`]; all the criteria that are set must match.
+#[derive(Debug)]
+pub struct ProtocolRecordQuery<T: ProtocolRecord> {
+    thread_id: Option<String>,
+    connection_id: Option<String>,
+    state: Option<T::State>,
+    _record: PhantomData<T>,
+}
+
+impl<T: ProtocolRecord> Default for ProtocolRecordQuery<T> {
+    fn default() -> Self {
+        Self {
+            thread_id: None,
+            connection_id: None,
+            state: None,
+            _record: PhantomData,
+        }
+    }
+}
+
+impl<T: ProtocolRecord> ProtocolRecordQuery<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn thread_id(mut self, thread_id: &str) -> Self {
+        self.thread_id = Some(thread_id.to_owned());
+        self
+    }
+
+    pub fn connection_id(mut self, connection_id: &str) -> Self {
+        self.connection_id = Some(connection_id.to_owned());
+        self
+    }
+
+    pub fn state(mut self, state: T::State) -> Self {
+        self.state = Some(state);
+        self
+    }
+
+    fn to_wql(&self) -> Option<String> {
+        let mut wql = serde_json::Map::new();
+        if let Some(thread_id) = &self.thread_id {
+            wql.insert(THREAD_ID_TAG.to_owned(), json!(thread_id));
+        }
+        if let Some(connection_id) = &self.connection_id {
+            wql.insert(CONNECTION_ID_TAG.to_owned(), json!(connection_id));
+        }
+        if let Some(state) = &self.state {
+            wql.insert(STATE_TAG.to_owned(), json!(T::state_tag(state)));
+        }
+        (!wql.is_empty()).then(|| serde_json::Value::Object(wql).to_string())
+    }
+}
+
+/// Persists the state of protocol handlers, such as [`crate::handlers::issuance::issuer::Issuer`]
+/// or [`crate::protocols::connection::GenericConnection`], in the wallet so that in-flight
+/// exchanges survive restarts.
+///
+/// Records are tagged with their thread id, connection id and state, which
+/// [`ProtocolRecordStore::find`] can filter on. Updates are versioned: an update based on a
+/// record which has been modified since it was read is rejected rather than silently
+/// overwriting the newer state.
+///
+/// The wallet offers no compare-and-swap, so the store serializes the updates of each record
+/// itself. Share a single store between the tasks updating the same records, rather than
+/// creating one per task.
+pub struct ProtocolRecordStore<W> {
+    wallet: W,
+    record_locks: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+
+impl<W> ProtocolRecordStore<W>
+where
+    W: BaseWallet,
+{
+    pub fn new(wallet: W) -> Self {
+        Self {
+            wallet,
+            record_locks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn record_lock(&self, key: &str) -> Arc<tokio::sync::Mutex<()>> {
+        self.record_locks
+            .lock()
+            .unwrap()
+            .entry(key.to_owned())
+            .or_default()
+            .clone()
+    }
+
+    fn release_record_lock(&self, key: &str) {
+        let mut locks = self.record_locks.lock().unwrap();
+        // Clones are only taken under this mutex, so nobody can be about to lock it
+        if locks
+            .get(key)
+            .is_some_and(|lock| Arc::strong_count(lock) == 1)
+        {
+            locks.remove(key);
+        }
+    }
+
+    /// Stores a new record under the given id, failing if one already exists.
+    pub async fn insert<T: ProtocolRecord>(
+        &self,
+        id: &str,
+        record: T,
+        connection_id: Option<&str>,
+    ) -> VcxResult<StoredProtocolRecord<T>> {
+        let stored = StoredProtocolRecord {
+            id: id.to_owned(),
+            version: 1,
+            connection_id: connection_id.map(ToOwned::to_owned),
+            record,
+        };
+        let wallet_record = Record::builder()
+            .category(T::CATEGORY)
+            .name(id.to_owned())
+            .value(serialize_value(&stored)?)
+            .tags(tags_of(&stored))
+            .build();
+        self.wallet.add_record(wallet_record).await?;
+        Ok(stored)
+    }
+
+    pub async fn get<T: ProtocolRecord>(&self, id: &str) -> VcxResult<StoredProtocolRecord<T>> {
+        let record = self.wallet.get_record(T::CATEGORY, id).await?;
+        deserialize_record(&record)
+    }
+
+    /// Replaces the record with `stored.record`, provided it is still at `stored.version`.
+    /// Returns the record as stored, with its version incremented.
+    pub async fn update<T: ProtocolRecord>(
+        &self,
+        stored: StoredProtocolRecord<T>,
+    ) -> VcxResult<StoredProtocolRecord<T>> {
+        let key = format!("{}:{}", T::CATEGORY, stored.id);
+        let lock = self.record_lock(&key);
+        let res = {
+            let _guard = lock.lock().await;
+            self.compare_and_update(stored).await
+        };
+        drop(lock);
+        self.release_record_lock(&key);
+        res
+    }
+
+    async fn compare_and_update<T: ProtocolRecord>(
+        &self,
+        stored: StoredProtocolRecord<T>,
+    ) -> VcxResult<StoredProtocolRecord<T>> {
+        let current_record = self.wallet.get_record(T::CATEGORY, &stored.id).await?;
+        let current: StoredProtocolRecord<serde_json::Value> = deserialize_record(&current_record)?;
+        if current.version != stored.version {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidState,
+                format!(
+                    "Protocol record {} was modified concurrently, expected version {} but found \
+                     {}",
+                    stored.id, stored.version, current.version
+                ),
+            ));
+        }
+        let updated = StoredProtocolRecord {
+            version: stored.version + 1,
+            ..stored
+        };
+        // The version is in the value, so writing it last commits the update
+        let value = serialize_value(&updated)?;
+        self.wallet
+            .update_record_tags(T::CATEGORY, &updated.id, tags_of(&updated))
+            .await?;
+        if let Err(err) = self
+            .wallet
+            .update_record_value(T::CATEGORY, &updated.id, &value)
+            .await
+        {
+            if let Err(restore_err) = self
+                .wallet
+                .update_record_tags(T::CATEGORY, &updated.id, current_record.tags().clone())
+                .await
+            {
+                warn!(
+                    "Failed to restore the tags of protocol record {} after a failed update: {}",
+                    updated.id, restore_err
+                );
+            }
+            return Err(err.into());
+        }
+        Ok(updated)
+    }
+
+    pub async fn delete<T: ProtocolRecord>(&self, id: &str) -> VcxResult<()> {
+        Ok(self.wallet.delete_record(T::CATEGORY, id).await?)
+    }
+
+    pub async fn find<T: ProtocolRecord>(
+        &self,
+        query: ProtocolRecordQuery<T>,
+    ) -> VcxResult<Vec<StoredProtocolRecord<T>>> {
+        self.wallet
+            .search_record(T::CATEGORY, query.to_wql())
+            .await?
+            .iter()
+            .map(deserialize_record)
+            .collect()
+    }
+
+    /// Finds the protocol instance running on the given thread.
+    pub async fn find_by_thread_id<T: ProtocolRecord>(
+        &self,
+        thread_id: &str,
+    ) -> VcxResult<Option<StoredProtocolRecord<T>>> {
+        let found = self
+            .find(ProtocolRecordQuery::new().thread_id(thread_id))
+            .await?;
+        if found.len() > 1 {
+            warn!(
+                "Found {} protocol records in {} for thread {}, using the first one",
+                found.len(),
+                T::CATEGORY,
+                thread_id
+            );
+        }
+        Ok(found.into_iter().next())
+    }
+
+    pub async fn find_by_connection_id<T: ProtocolRecord>(
+        &self,
+        connection_id: &str,
+    ) -> VcxResult<Vec<StoredProtocolRecord<T>>> {
+        self.find(ProtocolRecordQuery::new().connection_id(connection_id))
+            .await
+    }
+}
+
+fn tags_of<T: ProtocolRecord>(stored: &StoredProtocolRecord<T>) -> RecordTags {
+    let mut tags = RecordTags::new(vec![RecordTag::new(
+        STATE_TAG,
+        T::state_tag(&stored.record.state()),
+    )]);
+    if let Some(thread_id) = stored.record.thread_id() {
+        tags.add(RecordTag::new(THREAD_ID_TAG, &thread_id));
+    }
+    if let Some(connection_id) = &stored.connection_id {
+        tags.add(RecordTag::new(CONNECTION_ID_TAG, connection_id));
+    }
+    tags
+}
+
+fn serialize_value<T: Serialize>(stored: &StoredProtocolRecord<T>) -> VcxResult<String> {
+    Ok(serde_json::to_string(&StoredValue {
+        version: stored.version,
+        connection_id: stored.connection_id.clone(),
+        record: &stored.record,
+    })?)
+}
+
+fn deserialize_record<T: DeserializeOwned>(record: &Record) -> VcxResult<StoredProtocolRecord<T>> {
+    let value: StoredValue<T> = serde_json::from_str(record.value()).map_err(|err| {
+        AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidJson,
+            format!(
+                "Cannot deserialize protocol record {} of {}: {}",
+                record.name(),
+                record.category(),
+                err
+            ),
+        )
+    })?;
+    Ok(StoredProtocolRecord {
+        id: record.name().to_owned(),
+        version: value.version,
+        connection_id: value.connection_id,
+        record: value.record,
+    })
+}
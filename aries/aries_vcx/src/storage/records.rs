@@ -0,0 +1,148 @@
+use aries_vcx_wallet::wallet::base_wallet::record_category::RecordCategory;
+
+use super::ProtocolRecord;
+use crate::{
+    handlers::{
+        issuance::{holder::Holder, issuer::Issuer},
+        proof_presentation::{prover::Prover, verifier::Verifier},
+    },
+    protocols::{
+        connection::{GenericConnection, State, ThinState},
+        issuance::{holder::state_machine::HolderState, issuer::state_machine::IssuerState},
+        proof_presentation::{
+            prover::state_machine::ProverState, verifier::state_machine::VerifierState,
+        },
+    },
+};
+
+impl ProtocolRecord for GenericConnection {
+    type State = ThinState;
+
+    const CATEGORY: RecordCategory = RecordCategory::ProtocolConnection;
+
+    fn thread_id(&self) -> Option<String> {
+        GenericConnection::thread_id(self).map(ToOwned::to_owned)
+    }
+
+    fn state(&self) -> Self::State {
+        GenericConnection::state(self)
+    }
+
+    fn state_tag(state: &Self::State) -> &'static str {
+        match state {
+            ThinState::Invitee(State::Initial) => "Invitee(Initial)",
+            ThinState::Invitee(State::Invited) => "Invitee(Invited)",
+            ThinState::Invitee(State::Requested) => "Invitee(Requested)",
+            ThinState::Invitee(State::Responded) => "Invitee(Responded)",
+            ThinState::Invitee(State::Completed) => "Invitee(Completed)",
+            ThinState::Inviter(State::Initial) => "Inviter(Initial)",
+            ThinState::Inviter(State::Invited) => "Inviter(Invited)",
+            ThinState::Inviter(State::Requested) => "Inviter(Requested)",
+            ThinState::Inviter(State::Responded) => "Inviter(Responded)",
+            ThinState::Inviter(State::Completed) => "Inviter(Completed)",
+        }
+    }
+}
+
+impl ProtocolRecord for Issuer {
+    type State = IssuerState;
+
+    const CATEGORY: RecordCategory = RecordCategory::ProtocolIssuer;
+
+    fn thread_id(&self) -> Option<String> {
+        self.get_thread_id().ok()
+    }
+
+    fn state(&self) -> Self::State {
+        self.get_state()
+    }
+
+    fn state_tag(state: &Self::State) -> &'static str {
+        match state {
+            IssuerState::Initial => "Initial",
+            IssuerState::OfferSet => "OfferSet",
+            IssuerState::ProposalReceived => "ProposalReceived",
+            IssuerState::RequestReceived => "RequestReceived",
+            IssuerState::CredentialSet => "CredentialSet",
+            IssuerState::Finished => "Finished",
+            IssuerState::Failed => "Failed",
+        }
+    }
+}
+
+impl ProtocolRecord for Holder {
+    type State = HolderState;
+
+    const CATEGORY: RecordCategory = RecordCategory::ProtocolHolder;
+
+    fn thread_id(&self) -> Option<String> {
+        self.get_thread_id().ok()
+    }
+
+    fn state(&self) -> Self::State {
+        self.get_state()
+    }
+
+    fn state_tag(state: &Self::State) -> &'static str {
+        match state {
+            HolderState::Initial => "Initial",
+            HolderState::ProposalSet => "ProposalSet",
+            HolderState::OfferReceived => "OfferReceived",
+            HolderState::RequestSet => "RequestSet",
+            HolderState::Finished => "Finished",
+            HolderState::Failed => "Failed",
+        }
+    }
+}
+
+impl ProtocolRecord for Prover {
+    type State = ProverState;
+
+    const CATEGORY: RecordCategory = RecordCategory::ProtocolProver;
+
+    fn thread_id(&self) -> Option<String> {
+        self.get_thread_id().ok()
+    }
+
+    fn state(&self) -> Self::State {
+        self.get_state()
+    }
+
+    fn state_tag(state: &Self::State) -> &'static str {
+        match state {
+            ProverState::Initial => "Initial",
+            ProverState::PresentationProposalSent => "PresentationProposalSent",
+            ProverState::PresentationRequestReceived => "PresentationRequestReceived",
+            ProverState::PresentationPrepared => "PresentationPrepared",
+            ProverState::PresentationPreparationFailed => "PresentationPreparationFailed",
+            ProverState::PresentationSent => "PresentationSent",
+            ProverState::Finished => "Finished",
+            ProverState::Failed => "Failed",
+        }
+    }
+}
+
+impl ProtocolRecord for Verifier {
+    type State = VerifierState;
+
+    const CATEGORY: RecordCategory = RecordCategory::ProtocolVerifier;
+
+    fn thread_id(&self) -> Option<String> {
+        self.get_thread_id().ok()
+    }
+
+    fn state(&self) -> Self::State {
+        self.get_state()
+    }
+
+    fn state_tag(state: &Self::State) -> &'static str {
+        match state {
+            VerifierState::Initial => "Initial",
+            VerifierState::PresentationProposalReceived => "PresentationProposalReceived",
+            VerifierState::PresentationRequestSet => "PresentationRequestSet",
+            VerifierState::PresentationRequestSent => "PresentationRequestSent",
+            VerifierState::Finished => "Finished",
+            VerifierState::Failed => "Failed",
+        }
+    }
+}
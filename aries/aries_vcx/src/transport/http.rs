@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use shared::{
+    errors::http_error::HttpError,
+    http_client::{HttpClient, HttpClientConfig},
+};
+use url::Url;
+
+use super::{DuplexTransport, Transport};
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// Failures which may go away on a later attempt map to [`AriesVcxErrorKind::PostMessageFailed`],
+/// the ones the recipient would answer the same way again, such as a 4xx response, to
+/// [`AriesVcxErrorKind::InvalidHttpResponse`].
+fn transport_error(err: HttpError) -> AriesVcxError {
+    let kind = if err.is_transient() {
+        AriesVcxErrorKind::PostMessageFailed
+    } else {
+        AriesVcxErrorKind::InvalidHttpResponse
+    };
+    AriesVcxError::from_msg(kind, err.to_string())
+}
+
+/// Posts messages to HTTP(S) endpoints. Unlike [`shared::http_client::post_message`], which
+/// shares a single globally configured client, each instance carries its own timeouts.
+/// Wrap it in a [`super::retry::RetryTransport`] to retry failed deliveries.
+#[derive(Debug, Clone)]
+pub struct HttpTransport {
+    client: HttpClient,
+}
+
+impl HttpTransport {
+    pub fn new(config: HttpClientConfig) -> VcxResult<Self> {
+        Ok(Self {
+            client: HttpClient::new(config)?,
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for HttpTransport {
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()> {
+        self.client
+            .post_message(msg, service_endpoint)
+            .await
+            .map_err(transport_error)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DuplexTransport for HttpTransport {
+    async fn send_message_with_response(
+        &self,
+        msg: Vec<u8>,
+        service_endpoint: &Url,
+    ) -> VcxResult<Option<Vec<u8>>> {
+        let response = self
+            .client
+            .post_message(msg, service_endpoint)
+            .await
+            .map_err(transport_error)?;
+        Ok((!response.is_empty()).then_some(response))
+    }
+}
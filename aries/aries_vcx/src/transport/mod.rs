@@ -0,0 +1,60 @@
+use async_trait::async_trait;
+use url::Url;
+
+use crate::errors::error::VcxResult;
+
+pub mod http;
+pub mod queue;
+pub mod retry;
+pub mod return_route;
+#[cfg(feature = "websocket")]
+pub mod websocket;
+
+/// Trait used for implementing a mechanism to send a message, used by
+/// [`crate::protocols::connection::Connection`].
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()>;
+}
+
+/// A [`Transport`] over which the recipient can answer on the same exchange, such as the
+/// response of an HTTP request. Recipients only do so for messages carrying a `~transport`
+/// decorator requesting return routing.
+#[async_trait]
+pub trait DuplexTransport: Transport {
+    /// Sends the message and returns the envelope sent back by the recipient, if any.
+    async fn send_message_with_response(
+        &self,
+        msg: Vec<u8>,
+        service_endpoint: &Url,
+    ) -> VcxResult<Option<Vec<u8>>>;
+}
+
+// While in many cases the auto-dereferencing does the trick,
+// this implementation aids in using things such as a trait object
+// when a generic parameter is expected.
+#[async_trait]
+impl<T> Transport for &T
+where
+    T: Transport + ?Sized,
+{
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()> {
+        self.send_message(msg, service_endpoint).await
+    }
+}
+
+#[async_trait]
+impl<T> DuplexTransport for &T
+where
+    T: DuplexTransport + ?Sized,
+{
+    async fn send_message_with_response(
+        &self,
+        msg: Vec<u8>,
+        service_endpoint: &Url,
+    ) -> VcxResult<Option<Vec<u8>>> {
+        (**self)
+            .send_message_with_response(msg, service_endpoint)
+            .await
+    }
+}
@@ -0,0 +1,212 @@
+use aries_vcx_wallet::wallet::{
+    base_wallet::{record::Record, record_category::RecordCategory, BaseWallet},
+    record_tags::{RecordTag, RecordTags},
+};
+use async_trait::async_trait;
+use base64::Engine;
+use chrono::Utc;
+use tokio::sync::Mutex;
+use url::Url;
+use uuid::Uuid;
+
+use super::{
+    retry::{is_transient, RetryPolicy},
+    Transport,
+};
+use crate::{errors::error::VcxResult, utils::base64::URL_SAFE_LENIENT};
+
+const SERVICE_ENDPOINT_TAG: &str = "service_endpoint";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QueuedMessage {
+    payload: String,
+    service_endpoint: Url,
+    attempts: u32,
+    // unix timestamp in milliseconds
+    next_attempt_at: i64,
+}
+
+/// Outcome of an [`OutboundQueue::flush`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlushReport {
+    pub delivered: usize,
+    pub rescheduled: usize,
+    /// Messages dropped after exhausting [`RetryPolicy::max_retries`] or being rejected by the
+    /// recipient.
+    pub dropped: usize,
+    /// Records which could not be read as a queued message; they are left in the wallet.
+    pub skipped: usize,
+}
+
+/// Persists messages the wrapped transport failed to deliver, e.g. because the recipient is
+/// offline, in the wallet. Queued messages are retried on [`OutboundQueue::flush`], which the
+/// owner is expected to call periodically; the retry schedule follows the [`RetryPolicy`].
+///
+/// Flushes and enqueues on the same instance are serialized, so concurrent flushes never
+/// deliver a message twice. Separate instances over the same wallet are not coordinated.
+pub struct OutboundQueue<W, T> {
+    wallet: W,
+    inner: T,
+    policy: RetryPolicy,
+    lock: Mutex<()>,
+}
+
+impl<W, T> OutboundQueue<W, T>
+where
+    W: BaseWallet,
+    T: Transport,
+{
+    pub fn new(wallet: W, inner: T, policy: RetryPolicy) -> Self {
+        Self {
+            wallet,
+            inner,
+            policy,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Queues a message without attempting to send it.
+    pub async fn enqueue(&self, msg: &[u8], service_endpoint: &Url) -> VcxResult<String> {
+        let _guard = self.lock.lock().await;
+        let id = Uuid::new_v4().to_string();
+        let queued = QueuedMessage {
+            payload: URL_SAFE_LENIENT.encode(msg),
+            service_endpoint: service_endpoint.clone(),
+            attempts: 0,
+            next_attempt_at: Utc::now().timestamp_millis(),
+        };
+        let record = Record::builder()
+            .category(RecordCategory::OutboundMessage)
+            .name(id.clone())
+            .value(serde_json::to_string(&queued)?)
+            .tags(RecordTags::new(vec![RecordTag::new(
+                SERVICE_ENDPOINT_TAG,
+                service_endpoint.as_str(),
+            )]))
+            .build();
+        self.wallet.add_record(record).await?;
+        Ok(id)
+    }
+
+    pub async fn pending_count(&self) -> VcxResult<usize> {
+        Ok(self
+            .wallet
+            .search_record(RecordCategory::OutboundMessage, None)
+            .await?
+            .len())
+    }
+
+    /// Attempts delivery of every queued message which is due.
+    pub async fn flush(&self) -> VcxResult<FlushReport> {
+        let _guard = self.lock.lock().await;
+        let records = self
+            .wallet
+            .search_record(RecordCategory::OutboundMessage, None)
+            .await?;
+        let mut report = FlushReport::default();
+        let now = Utc::now().timestamp_millis();
+        for record in records {
+            let mut queued: QueuedMessage = match serde_json::from_str(record.value()) {
+                Ok(queued) => queued,
+                Err(err) => {
+                    warn!(
+                        "Skipping queued message {} which could not be parsed; err: {}",
+                        record.name(),
+                        err
+                    );
+                    report.skipped += 1;
+                    continue;
+                }
+            };
+            if queued.next_attempt_at > now {
+                continue;
+            }
+            let payload = match URL_SAFE_LENIENT.decode(&queued.payload) {
+                Ok(payload) => payload,
+                Err(err) => {
+                    warn!(
+                        "Skipping queued message {} with a payload which is not valid base64; \
+                         err: {}",
+                        record.name(),
+                        err
+                    );
+                    report.skipped += 1;
+                    continue;
+                }
+            };
+            match self
+                .inner
+                .send_message(payload, &queued.service_endpoint)
+                .await
+            {
+                Ok(()) => {
+                    self.delete(record.name()).await?;
+                    report.delivered += 1;
+                }
+                Err(err) if !is_transient(&err) || queued.attempts >= self.policy.max_retries => {
+                    warn!(
+                        "Dropping queued message {} for {} after {} retries; err: {}",
+                        record.name(),
+                        queued.service_endpoint,
+                        queued.attempts,
+                        err
+                    );
+                    self.delete(record.name()).await?;
+                    report.dropped += 1;
+                }
+                Err(err) => {
+                    queued.attempts += 1;
+                    let delay = self.policy.delay_for(queued.attempts);
+                    debug!(
+                        "Queued message {} for {} is still undeliverable, next attempt in {:?}; \
+                         err: {}",
+                        record.name(),
+                        queued.service_endpoint,
+                        delay,
+                        err
+                    );
+                    queued.next_attempt_at = now.saturating_add(delay.as_millis() as i64);
+                    self.wallet
+                        .update_record_value(
+                            RecordCategory::OutboundMessage,
+                            record.name(),
+                            &serde_json::to_string(&queued)?,
+                        )
+                        .await?;
+                    report.rescheduled += 1;
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    async fn delete(&self, name: &str) -> VcxResult<()> {
+        Ok(self
+            .wallet
+            .delete_record(RecordCategory::OutboundMessage, name)
+            .await?)
+    }
+}
+
+#[async_trait]
+impl<W, T> Transport for OutboundQueue<W, T>
+where
+    W: BaseWallet,
+    T: Transport,
+{
+    /// Tries to deliver the message right away and queues it if that fails transiently, in which
+    /// case the message counts as accepted for delivery.
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()> {
+        match self.inner.send_message(msg.clone(), service_endpoint).await {
+            Ok(()) => Ok(()),
+            Err(err) if !is_transient(&err) => Err(err),
+            Err(err) => {
+                info!(
+                    "Delivery to {} failed, queueing message for retry; err: {}",
+                    service_endpoint, err
+                );
+                self.enqueue(&msg, service_endpoint).await.map(|_| ())
+            }
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use std::{future::Future, time::Duration};
+
+use async_trait::async_trait;
+use url::Url;
+
+use super::{DuplexTransport, Transport};
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+/// Whether a failed delivery is worth retrying. Transports report failures to reach the
+/// recipient, such as connection errors, timeouts and 5xx responses, as
+/// [`AriesVcxErrorKind::PostMessageFailed`]; any other error, e.g. a rejected message or an
+/// invalid endpoint, would fail the same way again.
+pub(crate) fn is_transient(err: &AriesVcxError) -> bool {
+    err.kind() == AriesVcxErrorKind::PostMessageFailed
+}
+
+/// Exponential backoff schedule: the n-th retry waits `initial_delay * multiplier^(n-1)`,
+/// capped at `max_delay`. Only transient failures are retried.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    pub multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            multiplier: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn no_retries() -> Self {
+        Self {
+            max_retries: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Delay to wait before the given retry, counted from 1.
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .checked_pow(retry.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay))
+    }
+
+    pub(crate) async fn run<F, Fut, T>(&self, mut attempt: F) -> VcxResult<T>
+    where
+        F: FnMut() -> Fut + Send,
+        Fut: Future<Output = VcxResult<T>> + Send,
+    {
+        let mut retry = 0;
+        loop {
+            match attempt().await {
+                Ok(result) => return Ok(result),
+                Err(err) if retry < self.max_retries && is_transient(&err) => {
+                    retry += 1;
+                    let delay = self.delay_for(retry);
+                    warn!(
+                        "Sending message failed, retry {} of {} in {:?}; err: {}",
+                        retry, self.max_retries, delay, err
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
+
+/// Retries messages the wrapped transport failed to send, waiting between attempts as defined
+/// by the [`RetryPolicy`].
+pub struct RetryTransport<T> {
+    inner: T,
+    policy: RetryPolicy,
+}
+
+impl<T> RetryTransport<T> {
+    pub fn new(inner: T, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl<T> Transport for RetryTransport<T>
+where
+    T: Transport,
+{
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()> {
+        self.policy
+            .run(|| self.inner.send_message(msg.clone(), service_endpoint))
+            .await
+    }
+}
+
+#[async_trait]
+impl<T> DuplexTransport for RetryTransport<T>
+where
+    T: DuplexTransport,
+{
+    async fn send_message_with_response(
+        &self,
+        msg: Vec<u8>,
+        service_endpoint: &Url,
+    ) -> VcxResult<Option<Vec<u8>>> {
+        self.policy
+            .run(|| {
+                self.inner
+                    .send_message_with_response(msg.clone(), service_endpoint)
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    /// Fails the first `failures` sends with an error of the given kind, then delivers.
+    struct FlakyTransport {
+        failures: u32,
+        error_kind: AriesVcxErrorKind,
+        attempts: AtomicU32,
+    }
+
+    impl FlakyTransport {
+        fn new(failures: u32) -> Self {
+            Self::failing_with(failures, AriesVcxErrorKind::PostMessageFailed)
+        }
+
+        fn failing_with(failures: u32, error_kind: AriesVcxErrorKind) -> Self {
+            Self {
+                failures,
+                error_kind,
+                attempts: AtomicU32::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Transport for FlakyTransport {
+        async fn send_message(&self, _msg: Vec<u8>, _service_endpoint: &Url) -> VcxResult<()> {
+            if self.attempts.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(AriesVcxError::from_msg(
+                    self.error_kind,
+                    "recipient is unavailable",
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    fn fast_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            multiplier: 2,
+        }
+    }
+
+    #[test]
+    fn test_delay_grows_exponentially_up_to_max() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+            multiplier: 2,
+        };
+        assert_eq!(policy.delay_for(1), Duration::from_millis(100));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(200));
+        assert_eq!(policy.delay_for(4), Duration::from_millis(800));
+        assert_eq!(policy.delay_for(5), Duration::from_secs(1));
+        assert_eq!(policy.delay_for(64), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_retry_transport_recovers_from_transient_failures() {
+        let url: Url = "http://example.org".parse().unwrap();
+        let transport = RetryTransport::new(FlakyTransport::new(2), fast_policy(3));
+        transport.send_message(vec![], &url).await.unwrap();
+        assert_eq!(transport.inner().attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transport_gives_up_after_max_retries() {
+        let url: Url = "http://example.org".parse().unwrap();
+        let transport = RetryTransport::new(FlakyTransport::new(5), fast_policy(2));
+        transport.send_message(vec![], &url).await.unwrap_err();
+        assert_eq!(transport.inner().attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_retry_transport_does_not_retry_permanent_failures() {
+        let url: Url = "http://example.org".parse().unwrap();
+        let transport = RetryTransport::new(
+            FlakyTransport::failing_with(1, AriesVcxErrorKind::InvalidHttpResponse),
+            fast_policy(3),
+        );
+        let err = transport.send_message(vec![], &url).await.unwrap_err();
+        assert_eq!(err.kind(), AriesVcxErrorKind::InvalidHttpResponse);
+        assert_eq!(transport.inner().attempts.load(Ordering::SeqCst), 1);
+    }
+}
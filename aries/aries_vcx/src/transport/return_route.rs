@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use url::Url;
+
+use super::{DuplexTransport, Transport};
+use crate::errors::error::VcxResult;
+
+/// Envelope returned by a recipient on the exchange a message was sent over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReturnedEnvelope {
+    pub service_endpoint: Url,
+    pub envelope: Vec<u8>,
+}
+
+/// Makes the responses of a [`DuplexTransport`] available to code which only deals with a plain
+/// [`Transport`], such as [`crate::protocols::connection::Connection::send_message`]. Every
+/// envelope returned by a recipient is handed over through the receiver obtained on
+/// construction, to be unpacked and processed like any other inbound message.
+///
+/// Recipients only answer on the same exchange if the sent message asked for it through a
+/// `~transport` decorator with `return_route` set.
+pub struct ReturnRouteTransport<T> {
+    inner: T,
+    returned: UnboundedSender<ReturnedEnvelope>,
+}
+
+impl<T> ReturnRouteTransport<T>
+where
+    T: DuplexTransport,
+{
+    pub fn new(inner: T) -> (Self, UnboundedReceiver<ReturnedEnvelope>) {
+        let (returned, receiver) = unbounded_channel();
+        (Self { inner, returned }, receiver)
+    }
+}
+
+#[async_trait]
+impl<T> Transport for ReturnRouteTransport<T>
+where
+    T: DuplexTransport,
+{
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()> {
+        if let Some(envelope) = self
+            .send_message_with_response(msg, service_endpoint)
+            .await?
+        {
+            let returned = ReturnedEnvelope {
+                service_endpoint: service_endpoint.clone(),
+                envelope,
+            };
+            if self.returned.send(returned).is_err() {
+                warn!(
+                    "Dropping envelope returned by {}, its receiver is gone",
+                    service_endpoint
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<T> DuplexTransport for ReturnRouteTransport<T>
+where
+    T: DuplexTransport,
+{
+    async fn send_message_with_response(
+        &self,
+        msg: Vec<u8>,
+        service_endpoint: &Url,
+    ) -> VcxResult<Option<Vec<u8>>> {
+        self.inner
+            .send_message_with_response(msg, service_endpoint)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    /// Echoes every message back, as a recipient honouring return routing would.
+    struct EchoTransport;
+
+    #[async_trait]
+    impl Transport for EchoTransport {
+        async fn send_message(&self, _msg: Vec<u8>, _service_endpoint: &Url) -> VcxResult<()> {
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl DuplexTransport for EchoTransport {
+        async fn send_message_with_response(
+            &self,
+            msg: Vec<u8>,
+            _service_endpoint: &Url,
+        ) -> VcxResult<Option<Vec<u8>>> {
+            Ok((!msg.is_empty()).then_some(msg))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_returned_envelopes_are_handed_to_receiver() {
+        let url: Url = "http://example.org".parse().unwrap();
+        let (transport, mut receiver) = ReturnRouteTransport::new(EchoTransport);
+
+        transport
+            .send_message(b"envelope".to_vec(), &url)
+            .await
+            .unwrap();
+        transport.send_message(vec![], &url).await.unwrap();
+        drop(transport);
+
+        assert_eq!(
+            receiver.recv().await,
+            Some(ReturnedEnvelope {
+                service_endpoint: url,
+                envelope: b"envelope".to_vec(),
+            })
+        );
+        assert_eq!(receiver.recv().await, None);
+    }
+}
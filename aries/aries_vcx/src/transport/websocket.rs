@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use tokio::time::timeout;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use url::Url;
+
+use super::{DuplexTransport, Transport};
+use crate::errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult};
+
+fn ws_error(msg: impl std::fmt::Display) -> AriesVcxError {
+    AriesVcxError::from_msg(AriesVcxErrorKind::PostMessageFailed, msg.to_string())
+}
+
+/// Sends messages to `ws://` and `wss://` endpoints, opening a connection per message.
+///
+/// When a response is expected, the first binary or text frame the recipient sends back on the
+/// connection is returned; recipients which have nothing to return are expected to close the
+/// connection, otherwise the call waits until `response_timeout` elapses.
+#[derive(Debug, Clone)]
+pub struct WebSocketTransport {
+    connect_timeout: Duration,
+    response_timeout: Duration,
+}
+
+impl Default for WebSocketTransport {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(10),
+            response_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl WebSocketTransport {
+    pub fn new(connect_timeout: Duration, response_timeout: Duration) -> Self {
+        Self {
+            connect_timeout,
+            response_timeout,
+        }
+    }
+
+    async fn exchange(
+        &self,
+        msg: Vec<u8>,
+        service_endpoint: &Url,
+        await_response: bool,
+    ) -> VcxResult<Option<Vec<u8>>> {
+        if !matches!(service_endpoint.scheme(), "ws" | "wss") {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidUrl,
+                format!("Not a websocket endpoint: {}", service_endpoint),
+            ));
+        }
+        debug!(
+            "WebSocketTransport >> sending message to {}",
+            service_endpoint
+        );
+        let (mut stream, _) = timeout(
+            self.connect_timeout,
+            connect_async(service_endpoint.as_str()),
+        )
+        .await
+        .map_err(|_| ws_error(format!("Connecting to {} timed out", service_endpoint)))?
+        .map_err(|err| {
+            ws_error(format!(
+                "Connecting to {} failed: {}",
+                service_endpoint, err
+            ))
+        })?;
+
+        stream
+            .send(Message::Binary(msg.into()))
+            .await
+            .map_err(|err| ws_error(format!("Sending to {} failed: {}", service_endpoint, err)))?;
+
+        let response = if await_response {
+            timeout(self.response_timeout, async {
+                while let Some(frame) = stream.next().await {
+                    match frame {
+                        Ok(Message::Binary(data)) => return Ok(Some(data.to_vec())),
+                        Ok(Message::Text(text)) => {
+                            return Ok(Some(text.as_str().as_bytes().to_vec()))
+                        }
+                        Ok(Message::Close(_)) => return Ok(None),
+                        Ok(_) => continue,
+                        Err(err) => return Err(ws_error(err)),
+                    }
+                }
+                Ok(None)
+            })
+            .await
+            .map_err(|_| {
+                ws_error(format!(
+                    "Waiting for response from {} timed out",
+                    service_endpoint
+                ))
+            })??
+        } else {
+            None
+        };
+
+        if let Err(err) = stream.close(None).await {
+            debug!("Closing websocket to {} failed: {}", service_endpoint, err);
+        }
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl Transport for WebSocketTransport {
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()> {
+        self.exchange(msg, service_endpoint, false).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl DuplexTransport for WebSocketTransport {
+    async fn send_message_with_response(
+        &self,
+        msg: Vec<u8>,
+        service_endpoint: &Url,
+    ) -> VcxResult<Option<Vec<u8>>> {
+        self.exchange(msg, service_endpoint, true).await
+    }
+}
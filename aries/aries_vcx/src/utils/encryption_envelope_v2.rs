@@ -0,0 +1,643 @@
+use aries_vcx_wallet::{
+    errors::error::VcxWalletError,
+    wallet::{
+        base_wallet::BaseWallet,
+        structs_io::{JweContentEncryption, KeyAgreementKey},
+    },
+};
+use base64::Engine;
+use did_doc::schema::{did_doc::DidDocument, verification_method::VerificationMethodKind};
+use did_parser_nom::{Did, DidUrl};
+use did_resolver_registry::ResolverRegistry;
+use public_key::{Key, KeyType};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    utils::{base64::URL_SAFE_LENIENT, encryption_envelope::EncryptionEnvelope},
+};
+
+pub const MEDIA_TYPE_ENCRYPTED_V1: &str = "application/didcomm-envelope-enc";
+pub const MEDIA_TYPE_ENCRYPTED_V2: &str = "application/didcomm-encrypted+json";
+pub const MEDIA_TYPE_SIGNED_V2: &str = "application/didcomm-signed+json";
+pub const MEDIA_TYPE_PLAIN_V2: &str = "application/didcomm-plain+json";
+
+const JWS_ALG_EDDSA: &str = "EdDSA";
+
+/// Media type of a received DIDComm message, as told apart by the shape of its JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DidCommMediaType {
+    /// DIDComm v1 `JWM/1.0` envelope.
+    EncryptedV1,
+    /// DIDComm v2 JWE envelope.
+    EncryptedV2,
+    /// DIDComm v2 JWS in the general JSON serialization.
+    SignedV2,
+    /// Plaintext message, which is not wrapped in any envelope.
+    Plain,
+}
+
+impl DidCommMediaType {
+    pub fn detect(data: &[u8]) -> VcxResult<Self> {
+        let value: Value = serde_json::from_slice(data).map_err(|err| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidJson,
+                format!("Cannot parse DIDComm message: {}", err),
+            )
+        })?;
+        let has = |field: &str| value.get(field).is_some();
+        if has("payload") && has("signatures") {
+            Ok(Self::SignedV2)
+        } else if has("protected") && has("recipients") {
+            Ok(Self::EncryptedV2)
+        } else if has("protected") && has("ciphertext") {
+            Ok(Self::EncryptedV1)
+        } else if has("type") || has("@type") {
+            Ok(Self::Plain)
+        } else {
+            Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                "Unrecognized DIDComm message format",
+            ))
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::EncryptedV1 => MEDIA_TYPE_ENCRYPTED_V1,
+            Self::EncryptedV2 => MEDIA_TYPE_ENCRYPTED_V2,
+            Self::SignedV2 => MEDIA_TYPE_SIGNED_V2,
+            Self::Plain => MEDIA_TYPE_PLAIN_V2,
+        }
+    }
+}
+
+/// Resolves the X25519 and Ed25519 keys of the key agreement verification methods of
+/// `did_document`, identified by absolute DID URLs as expected in DIDComm v2 `kid` headers.
+pub fn resolve_key_agreement_keys(did_document: &DidDocument) -> VcxResult<Vec<KeyAgreementKey>> {
+    let mut keys = Vec::new();
+    for vm in did_document.key_agreement() {
+        let vm = match vm {
+            VerificationMethodKind::Resolved(vm) => vm,
+            VerificationMethodKind::Resolvable(reference) => {
+                did_document.dereference_key(reference).ok_or_else(|| {
+                    AriesVcxError::from_msg(
+                        AriesVcxErrorKind::InvalidState,
+                        format!("Unable to dereference key agreement key: {}", reference),
+                    )
+                })?
+            }
+        };
+        let key = vm.public_key()?;
+        match key.key_type() {
+            KeyType::X25519 | KeyType::Ed25519 => keys.push(KeyAgreementKey::new(
+                absolute_kid(did_document, vm.id()),
+                key,
+            )),
+            other => warn!(
+                "Skipping key agreement key {} with unsupported type {}",
+                vm.id(),
+                other
+            ),
+        }
+    }
+    if keys.is_empty() {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            format!(
+                "No X25519 or Ed25519 key agreement key found in DID document {}",
+                did_document.id()
+            ),
+        ));
+    }
+    Ok(keys)
+}
+
+fn absolute_kid(did_document: &DidDocument, id: &DidUrl) -> String {
+    match id.did() {
+        Some(_) => id.to_string(),
+        None => format!("{}{}", did_document.id(), id),
+    }
+}
+
+/// DIDComm v2 JWE envelope.
+#[derive(Debug)]
+pub struct EncryptionEnvelopeV2(pub Vec<u8>);
+
+impl EncryptionEnvelopeV2 {
+    /// Encrypts `data` for all key agreement keys of the recipient, without authenticating the
+    /// sender (ECDH-ES+A256KW).
+    pub async fn anoncrypt(
+        wallet: &impl BaseWallet,
+        data: &[u8],
+        recipient_did_doc: &DidDocument,
+        enc: JweContentEncryption,
+    ) -> VcxResult<Self> {
+        let recipients = resolve_key_agreement_keys(recipient_did_doc)?;
+        Ok(Self(
+            wallet.pack_message_v2(None, recipients, enc, data).await?,
+        ))
+    }
+
+    /// Encrypts `data` for all key agreement keys of the recipient, authenticated with the first
+    /// key agreement key of the sender held by the wallet (ECDH-1PU+A256KW with A256CBC-HS512).
+    pub async fn authcrypt(
+        wallet: &impl BaseWallet,
+        data: &[u8],
+        sender_did_doc: &DidDocument,
+        recipient_did_doc: &DidDocument,
+    ) -> VcxResult<Self> {
+        let recipients = resolve_key_agreement_keys(recipient_did_doc)?;
+        for sender in resolve_key_agreement_keys(sender_did_doc)? {
+            match wallet
+                .pack_message_v2(
+                    Some(sender),
+                    recipients.clone(),
+                    JweContentEncryption::A256CbcHs512,
+                    data,
+                )
+                .await
+            {
+                Ok(envelope) => return Ok(Self(envelope)),
+                Err(VcxWalletError::RecordNotFound(_)) => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidState,
+            format!(
+                "No key agreement key of sender DID document {} is held by the wallet",
+                sender_did_doc.id()
+            ),
+        ))
+    }
+
+    /// Decrypts an envelope addressed to one of the key agreement keys of `recipient_did_doc`.
+    /// The DID document of the sender of an authcrypted envelope is resolved from its `skid`.
+    pub async fn unpack(
+        wallet: &impl BaseWallet,
+        envelope: &[u8],
+        recipient_did_doc: &DidDocument,
+        resolver_registry: &ResolverRegistry,
+    ) -> VcxResult<UnpackedEnvelope> {
+        let sender = match sender_kid(envelope)? {
+            Some(skid) => {
+                let skid = DidUrl::parse(skid)?;
+                let sender_did_doc = resolver_registry
+                    .resolve(&Did::try_from(&skid)?, &Default::default())
+                    .await?
+                    .did_document;
+                let skid = skid.to_string();
+                Some(
+                    resolve_key_agreement_keys(&sender_did_doc)?
+                        .into_iter()
+                        .find(|key| key.kid == skid)
+                        .ok_or_else(|| {
+                            AriesVcxError::from_msg(
+                                AriesVcxErrorKind::InvalidState,
+                                format!("Sender key {} not found in its DID document", skid),
+                            )
+                        })?,
+                )
+            }
+            None => None,
+        };
+        let unpacked = wallet
+            .unpack_message_v2(
+                envelope,
+                resolve_key_agreement_keys(recipient_did_doc)?,
+                sender,
+            )
+            .await?;
+        Ok(UnpackedEnvelope::V2 {
+            message: unpacked.message,
+            sender_kid: unpacked.sender_kid,
+            recipient_kid: unpacked.recipient_kid,
+            signer_kid: None,
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct ProtectedSender {
+    skid: Option<String>,
+    apu: Option<String>,
+}
+
+fn sender_kid(envelope: &[u8]) -> VcxResult<Option<String>> {
+    #[derive(Deserialize)]
+    struct Envelope {
+        protected: String,
+    }
+    let envelope: Envelope = serde_json::from_slice(envelope)?;
+    let protected: ProtectedSender =
+        serde_json::from_slice(&URL_SAFE_LENIENT.decode(envelope.protected)?)?;
+    match (protected.skid, protected.apu) {
+        (Some(skid), _) => Ok(Some(skid)),
+        (None, Some(apu)) => Ok(Some(String::from_utf8(URL_SAFE_LENIENT.decode(apu)?)?)),
+        (None, None) => Ok(None),
+    }
+}
+
+/// A DIDComm v2 JWS, in the general JSON serialization.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SignedMessageV2 {
+    pub payload: String,
+    pub signatures: Vec<JwsSignature>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JwsSignature {
+    pub protected: String,
+    pub signature: String,
+    pub header: JwsHeader,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct JwsHeader {
+    pub kid: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JwsProtected {
+    typ: String,
+    alg: String,
+}
+
+impl SignedMessageV2 {
+    /// Signs `payload` with the Ed25519 `key` held by the wallet, referenced as `kid`.
+    pub async fn sign(
+        wallet: &impl BaseWallet,
+        payload: &[u8],
+        kid: &str,
+        key: &Key,
+    ) -> VcxResult<Self> {
+        key.validate_key_type(KeyType::Ed25519)?;
+        let protected = URL_SAFE_LENIENT.encode(serde_json::to_vec(&JwsProtected {
+            typ: MEDIA_TYPE_SIGNED_V2.to_owned(),
+            alg: JWS_ALG_EDDSA.to_owned(),
+        })?);
+        let payload = URL_SAFE_LENIENT.encode(payload);
+        let signature = wallet
+            .sign(key, format!("{}.{}", protected, payload).as_bytes())
+            .await?;
+        Ok(Self {
+            payload,
+            signatures: vec![JwsSignature {
+                protected,
+                signature: URL_SAFE_LENIENT.encode(signature),
+                header: JwsHeader {
+                    kid: kid.to_owned(),
+                },
+            }],
+        })
+    }
+
+    /// Verifies that the message is signed by a key of `signer_did_doc`, returning the payload
+    /// and the id of the key which signed it.
+    pub async fn verify(
+        &self,
+        wallet: &impl BaseWallet,
+        signer_did_doc: &DidDocument,
+    ) -> VcxResult<(Vec<u8>, String)> {
+        for signature in self.signatures.iter() {
+            let protected: JwsProtected =
+                serde_json::from_slice(&URL_SAFE_LENIENT.decode(&signature.protected)?)?;
+            if protected.alg != JWS_ALG_EDDSA {
+                warn!(
+                    "Skipping JWS signature with unsupported alg {}",
+                    protected.alg
+                );
+                continue;
+            }
+            let Some(vm) =
+                signer_did_doc.dereference_key(&DidUrl::parse(signature.header.kid.clone())?)
+            else {
+                continue;
+            };
+            let key = vm.public_key()?;
+            let signing_input = format!("{}.{}", signature.protected, self.payload);
+            if wallet
+                .verify(
+                    &key,
+                    signing_input.as_bytes(),
+                    &URL_SAFE_LENIENT.decode(&signature.signature)?,
+                )
+                .await?
+            {
+                return Ok((
+                    URL_SAFE_LENIENT.decode(&self.payload)?,
+                    signature.header.kid.clone(),
+                ));
+            }
+        }
+        Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::AuthenticationError,
+            format!(
+                "Message is not signed by any key of {}",
+                signer_did_doc.id()
+            ),
+        ))
+    }
+}
+
+/// Message unpacked by [`unpack_any`], along with the keys it was exchanged with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnpackedEnvelope {
+    V1 {
+        message: String,
+        sender_vk: Option<Key>,
+        recipient_vk: Key,
+    },
+    V2 {
+        message: String,
+        /// Key agreement key the envelope was authcrypted with.
+        sender_kid: Option<String>,
+        recipient_kid: String,
+        /// Key the message nested in the envelope was signed with.
+        signer_kid: Option<String>,
+    },
+    /// DIDComm v2 message which was signed, but not encrypted.
+    Signed { message: String, signer_kid: String },
+}
+
+impl UnpackedEnvelope {
+    pub fn message(&self) -> &str {
+        match self {
+            Self::V1 { message, .. } | Self::V2 { message, .. } | Self::Signed { message, .. } => {
+                message
+            }
+        }
+    }
+}
+
+/// Unpacks a DIDComm v1 or v2 envelope, as told apart by [`DidCommMediaType::detect`].
+///
+/// DIDComm v2 envelopes are decrypted with the key agreement keys of `recipient_did_doc`, and
+/// signed messages, whether nested in an encryption envelope or not, are verified against the DID
+/// document of their signer. A signed message nested in an authcrypted envelope must be signed by
+/// the DID of the sender.
+pub async fn unpack_any(
+    wallet: &impl BaseWallet,
+    envelope: &[u8],
+    recipient_did_doc: &DidDocument,
+    resolver_registry: &ResolverRegistry,
+) -> VcxResult<UnpackedEnvelope> {
+    match DidCommMediaType::detect(envelope)? {
+        DidCommMediaType::EncryptedV1 => {
+            let (message, sender_vk, recipient_vk) =
+                EncryptionEnvelope::unpack(wallet, envelope, &None).await?;
+            Ok(UnpackedEnvelope::V1 {
+                message,
+                sender_vk,
+                recipient_vk,
+            })
+        }
+        DidCommMediaType::EncryptedV2 => {
+            let unpacked = EncryptionEnvelopeV2::unpack(
+                wallet,
+                envelope,
+                recipient_did_doc,
+                resolver_registry,
+            )
+            .await?;
+            let UnpackedEnvelope::V2 {
+                message,
+                sender_kid,
+                recipient_kid,
+                ..
+            } = unpacked
+            else {
+                return Ok(unpacked);
+            };
+            if DidCommMediaType::detect(message.as_bytes())? != DidCommMediaType::SignedV2 {
+                return Ok(UnpackedEnvelope::V2 {
+                    message,
+                    sender_kid,
+                    recipient_kid,
+                    signer_kid: None,
+                });
+            }
+            let (message, signer_kid) =
+                verify_signed(wallet, message.as_bytes(), resolver_registry).await?;
+            if let Some(sender_kid) = &sender_kid {
+                check_same_did(sender_kid, &signer_kid)?;
+            }
+            Ok(UnpackedEnvelope::V2 {
+                message,
+                sender_kid,
+                recipient_kid,
+                signer_kid: Some(signer_kid),
+            })
+        }
+        DidCommMediaType::SignedV2 => {
+            let (message, signer_kid) = verify_signed(wallet, envelope, resolver_registry).await?;
+            Ok(UnpackedEnvelope::Signed {
+                message,
+                signer_kid,
+            })
+        }
+        DidCommMediaType::Plain => Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::InvalidMessageFormat,
+            "Plaintext messages are not wrapped in an envelope",
+        )),
+    }
+}
+
+/// Rejects a signed message whose signer is another party than the sender of the authcrypted
+/// envelope it came in.
+fn check_same_did(sender_kid: &str, signer_kid: &str) -> VcxResult<()> {
+    let sender = Did::try_from(&DidUrl::parse(sender_kid.to_owned())?)?;
+    let signer = Did::try_from(&DidUrl::parse(signer_kid.to_owned())?)?;
+    if sender != signer {
+        return Err(AriesVcxError::from_msg(
+            AriesVcxErrorKind::AuthenticationError,
+            format!(
+                "Message signed by {} was authcrypted by another sender {}",
+                signer_kid, sender_kid
+            ),
+        ));
+    }
+    Ok(())
+}
+
+async fn verify_signed(
+    wallet: &impl BaseWallet,
+    data: &[u8],
+    resolver_registry: &ResolverRegistry,
+) -> VcxResult<(String, String)> {
+    let signed: SignedMessageV2 = serde_json::from_slice(data)?;
+    let kid = signed
+        .signatures
+        .first()
+        .map(|signature| DidUrl::parse(signature.header.kid.clone()))
+        .transpose()?
+        .ok_or_else(|| {
+            AriesVcxError::from_msg(
+                AriesVcxErrorKind::InvalidMessageFormat,
+                "Signed message has no signatures",
+            )
+        })?;
+    let signer_did_doc = resolver_registry
+        .resolve(&Did::try_from(&kid)?, &Default::default())
+        .await?
+        .did_document;
+    let (payload, signer_kid) = signed.verify(wallet, &signer_did_doc).await?;
+    Ok((String::from_utf8(payload)?, signer_kid))
+}
+
+#[cfg(test)]
+pub mod unit_tests {
+    use aries_vcx_wallet::wallet::base_wallet::did_wallet::DidWallet;
+    use serde_json::json;
+    use test_utils::devsetup::build_setup_profile;
+
+    use super::*;
+
+    fn did_doc_with_key_agreement(verkey: &Key) -> DidDocument {
+        serde_json::from_value(json!({
+            "id": "did:example:bob",
+            "verificationMethod": [{
+                "id": "did:example:bob#key-1",
+                "type": "Ed25519VerificationKey2018",
+                "controller": "did:example:bob",
+                "publicKeyBase58": verkey.base58()
+            }],
+            "keyAgreement": [
+                "did:example:bob#key-1",
+                {
+                    "id": "#key-2",
+                    "type": "X25519KeyAgreementKey2019",
+                    "controller": "did:example:bob",
+                    "publicKeyBase58": "CaSHXEvLKS6SfN9aBfkVGBpp15jSnaHazqHgLHp8KZ3Y"
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_detect_media_type() {
+        let detect = |value: Value| DidCommMediaType::detect(value.to_string().as_bytes()).unwrap();
+        assert_eq!(
+            detect(json!({"protected": "", "iv": "", "ciphertext": "", "tag": ""})),
+            DidCommMediaType::EncryptedV1
+        );
+        assert_eq!(
+            detect(
+                json!({"protected": "", "recipients": [], "iv": "", "ciphertext": "", "tag": ""})
+            ),
+            DidCommMediaType::EncryptedV2
+        );
+        assert_eq!(
+            detect(json!({"payload": "", "signatures": []})),
+            DidCommMediaType::SignedV2
+        );
+        assert_eq!(
+            detect(json!({"id": "1", "type": "https://didcomm.org/basicmessage/2.0/message"})),
+            DidCommMediaType::Plain
+        );
+        assert!(DidCommMediaType::detect(br#"{"foo": "bar"}"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_key_agreement_keys() {
+        let setup = build_setup_profile().await;
+        let did_data = setup
+            .wallet
+            .create_and_store_my_did(None, None)
+            .await
+            .unwrap();
+
+        let keys =
+            resolve_key_agreement_keys(&did_doc_with_key_agreement(did_data.verkey())).unwrap();
+
+        assert_eq!(keys.len(), 2);
+        assert_eq!(keys[0].kid, "did:example:bob#key-1");
+        assert_eq!(&keys[0].key, did_data.verkey());
+        assert_eq!(keys[1].kid, "did:example:bob#key-2");
+        assert_eq!(keys[1].key.key_type(), &KeyType::X25519);
+    }
+
+    #[tokio::test]
+    async fn test_anoncrypt_unpack_any() {
+        let setup = build_setup_profile().await;
+        let did_data = setup
+            .wallet
+            .create_and_store_my_did(None, None)
+            .await
+            .unwrap();
+        let did_doc = did_doc_with_key_agreement(did_data.verkey());
+
+        let envelope = EncryptionEnvelopeV2::anoncrypt(
+            &setup.wallet,
+            b"foobar",
+            &did_doc,
+            JweContentEncryption::Xc20P,
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            DidCommMediaType::detect(&envelope.0).unwrap(),
+            DidCommMediaType::EncryptedV2
+        );
+
+        let unpacked = unpack_any(
+            &setup.wallet,
+            &envelope.0,
+            &did_doc,
+            &ResolverRegistry::new(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            unpacked,
+            UnpackedEnvelope::V2 {
+                message: "foobar".to_owned(),
+                sender_kid: None,
+                recipient_kid: "did:example:bob#key-1".to_owned(),
+                signer_kid: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_signer_must_be_authcrypt_sender() {
+        assert!(check_same_did("did:example:bob#key-x25519", "did:example:bob#key-1").is_ok());
+        assert_eq!(
+            check_same_did("did:example:mallory#key-x25519", "did:example:bob#key-1")
+                .unwrap_err()
+                .kind(),
+            AriesVcxErrorKind::AuthenticationError
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_verify_jws() {
+        let setup = build_setup_profile().await;
+        let did_data = setup
+            .wallet
+            .create_and_store_my_did(None, None)
+            .await
+            .unwrap();
+        let did_doc = did_doc_with_key_agreement(did_data.verkey());
+
+        let signed = SignedMessageV2::sign(
+            &setup.wallet,
+            b"foobar",
+            "did:example:bob#key-1",
+            did_data.verkey(),
+        )
+        .await
+        .unwrap();
+
+        let (payload, kid) = signed.verify(&setup.wallet, &did_doc).await.unwrap();
+        assert_eq!(payload, b"foobar");
+        assert_eq!(kid, "did:example:bob#key-1");
+
+        let mut tampered = signed.clone();
+        tampered.payload = URL_SAFE_LENIENT.encode(b"barfoo");
+        assert!(tampered.verify(&setup.wallet, &did_doc).await.is_err());
+    }
+}
@@ -21,5 +21,6 @@ pub mod qualifier;
 #[macro_use]
 pub mod encryption_envelope;
 pub mod didcomm_utils;
+pub mod encryption_envelope_v2;
 pub mod serialization;
 pub mod validation;
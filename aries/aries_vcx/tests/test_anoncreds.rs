@@ -1,8 +1,13 @@
 use std::error::Error;
 
+use anoncreds_types::utils::conversions::from_revocation_status_list_to_revocation_registry_delta;
 use aries_vcx::common::credentials::get_cred_rev_id;
 use aries_vcx_anoncreds::anoncreds::base_anoncreds::BaseAnonCreds;
 use aries_vcx_ledger::ledger::base_ledger::AnoncredsLedgerRead;
+use aries_vcx_wallet::wallet::base_wallet::{
+    record::Record, record_category::RecordCategory, BaseWallet,
+};
+use chrono::Utc;
 use serde_json::json;
 use test_utils::{constants::DEFAULT_SCHEMA_ATTRS, devsetup::build_setup_profile};
 
@@ -132,9 +137,13 @@ async fn test_pool_revoke_credential() -> Result<(), Box<dyn Error>> {
 
     let anoncreds = &setup.anoncreds;
 
-    let rev_reg_delta_json = setup
+    let rev_status_list = setup
         .ledger_read
-        .get_rev_reg_delta_json(&rev_reg.rev_reg_id.to_owned().try_into()?, None, None)
+        .get_rev_status_list(
+            &rev_reg.rev_reg_id.to_owned().try_into()?,
+            first_timestamp,
+            None,
+        )
         .await?
         .0;
     anoncreds
@@ -142,7 +151,7 @@ async fn test_pool_revoke_credential() -> Result<(), Box<dyn Error>> {
             &setup.wallet,
             &rev_reg.rev_reg_id.to_owned().try_into()?,
             cred_rev_id,
-            rev_reg_delta_json,
+            rev_status_list,
         )
         .await?;
 
@@ -150,6 +159,7 @@ async fn test_pool_revoke_credential() -> Result<(), Box<dyn Error>> {
         .publish_local_revocations(
             &setup.wallet,
             &setup.anoncreds,
+            &setup.ledger_read,
             &setup.ledger_write,
             &setup.institution_did,
         )
@@ -167,3 +177,120 @@ async fn test_pool_revoke_credential() -> Result<(), Box<dyn Error>> {
     assert_ne!(first_rev_reg_delta, second_rev_reg_delta);
     Ok(())
 }
+
+#[tokio::test]
+#[ignore]
+async fn test_pool_migrate_legacy_pending_revocations() -> Result<(), Box<dyn Error>> {
+    let setup = build_setup_profile().await;
+    let schema = create_and_write_test_schema(
+        &setup.wallet,
+        &setup.anoncreds,
+        &setup.ledger_write,
+        &setup.institution_did,
+        DEFAULT_SCHEMA_ATTRS,
+    )
+    .await;
+    let cred_def = create_and_write_test_cred_def(
+        &setup.wallet,
+        &setup.anoncreds,
+        &setup.ledger_read,
+        &setup.ledger_write,
+        &setup.institution_did,
+        &schema.schema_id,
+        true,
+    )
+    .await;
+    let rev_reg = create_and_publish_test_rev_reg(
+        &setup.wallet,
+        &setup.anoncreds,
+        &setup.ledger_write,
+        &setup.institution_did,
+        cred_def.get_cred_def_id(),
+    )
+    .await;
+    let cred_id = create_and_write_credential(
+        &setup.wallet,
+        &setup.wallet,
+        &setup.anoncreds,
+        &setup.anoncreds,
+        &setup.institution_did,
+        &schema,
+        &cred_def,
+        Some(&rev_reg),
+    )
+    .await;
+    let cred_rev_id = get_cred_rev_id(&setup.wallet, &setup.anoncreds, &cred_id).await?;
+    let rev_reg_id = rev_reg.rev_reg_id.to_owned().try_into()?;
+
+    let (ledger_rev_status_list, _) = setup
+        .ledger_read
+        .get_rev_status_list(&rev_reg_id, Utc::now().timestamp() as u64, None)
+        .await?;
+    setup
+        .anoncreds
+        .revoke_credential_local(
+            &setup.wallet,
+            &rev_reg_id,
+            cred_rev_id,
+            ledger_rev_status_list,
+        )
+        .await?;
+    let pending = setup
+        .anoncreds
+        .get_pending_rev_status_list(&setup.wallet, &rev_reg_id)
+        .await?
+        .unwrap();
+
+    // Store the pending revocation the way earlier versions did: a delta of every revocation
+    setup
+        .wallet
+        .delete_record(RecordCategory::RevStatusList, &rev_reg_id.to_string())
+        .await?;
+    let legacy_delta = from_revocation_status_list_to_revocation_registry_delta(&pending, None)?;
+    setup
+        .wallet
+        .add_record(
+            Record::builder()
+                .name(rev_reg_id.to_string())
+                .category(RecordCategory::RevRegDelta)
+                .value(serde_json::to_string(&legacy_delta)?)
+                .build(),
+        )
+        .await?;
+
+    assert_eq!(
+        setup
+            .anoncreds
+            .get_rev_reg_ids_with_pending_revocations(&setup.wallet)
+            .await?,
+        vec![rev_reg_id.clone()]
+    );
+    let migrated = setup
+        .anoncreds
+        .get_pending_rev_status_list(&setup.wallet, &rev_reg_id)
+        .await?
+        .unwrap();
+    assert_eq!(migrated.state(), pending.state());
+    assert_eq!(migrated.accum(), pending.accum());
+    assert!(setup
+        .wallet
+        .get_record(RecordCategory::RevRegDelta, &rev_reg_id.to_string())
+        .await
+        .is_err());
+
+    rev_reg
+        .publish_local_revocations(
+            &setup.wallet,
+            &setup.anoncreds,
+            &setup.ledger_read,
+            &setup.ledger_write,
+            &setup.institution_did,
+        )
+        .await?;
+    let (published, _) = setup
+        .ledger_read
+        .get_rev_status_list(&rev_reg_id, Utc::now().timestamp() as u64, None)
+        .await?;
+    assert!(published.state()[cred_rev_id as usize]);
+    Ok(())
+}
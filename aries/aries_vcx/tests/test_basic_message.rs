@@ -0,0 +1,89 @@
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+};
+
+use aries_vcx::{
+    errors::error::VcxResult,
+    handlers::basic_message::{BasicMessageService, HistoryQuery, MessageDirection},
+    protocols::basic_message::build_basic_message,
+};
+use messages::{decorators::localization::Locale, AriesMessage};
+use test_utils::devsetup::dev_build_featured_wallet;
+
+#[tokio::test]
+#[ignore]
+async fn test_basic_message_history_is_stored_and_paged() -> Result<(), Box<dyn Error>> {
+    let (_, wallet) = dev_build_featured_wallet("00000000000000000000BasicMessage").await;
+    let service = BasicMessageService::new(wallet);
+    let outbox: Arc<Mutex<Vec<AriesMessage>>> = Default::default();
+
+    let locale: Locale = serde_json::from_value(serde_json::json!("fr"))?;
+    for content in ["first", "second", "third"] {
+        let outbox = outbox.clone();
+        service
+            .send_message(
+                "connection-1",
+                content.to_owned(),
+                Some(locale),
+                Box::new(move |message| {
+                    Box::pin(async move {
+                        outbox.lock().unwrap().push(message);
+                        VcxResult::Ok(())
+                    })
+                }),
+            )
+            .await?;
+    }
+    let sent = outbox.lock().unwrap().clone();
+    assert_eq!(sent.len(), 3);
+
+    let reply = build_basic_message("hello".to_owned(), None, None);
+    let received = service.handle_message("connection-2", &reply).await?;
+    assert_eq!(received.direction, MessageDirection::Received);
+    assert_eq!(received.connection_id, "connection-2");
+    // A redelivered message is not stored twice
+    service.handle_message("connection-2", &reply).await?;
+    let history = service
+        .get_history(&HistoryQuery::new().connection_id("connection-2"))
+        .await?;
+    assert_eq!(history.messages, vec![received]);
+    // Message ids are only unique per sender, the same id over another connection is a new message
+    let other = service.handle_message("connection-3", &reply).await?;
+    assert_eq!(other.connection_id, "connection-3");
+    assert_eq!(
+        service
+            .get_history(&HistoryQuery::new().connection_id("connection-2"))
+            .await?
+            .total,
+        1
+    );
+    service.delete_history("connection-3").await?;
+
+    let first_page = service
+        .get_history(&HistoryQuery::new().connection_id("connection-1").limit(2))
+        .await?;
+    assert_eq!(first_page.total, 3);
+    assert_eq!(first_page.messages.len(), 2);
+    assert_eq!(first_page.messages[0].content, "third");
+    assert!(first_page.messages.iter().all(
+        |message| message.direction == MessageDirection::Sent && message.locale == Some(locale)
+    ));
+
+    let query = HistoryQuery::new().connection_id("connection-1").limit(2);
+    assert!(first_page.has_more(&query));
+    let second_page = service.get_history(&query.next_page()).await?;
+    assert_eq!(second_page.messages.len(), 1);
+    assert_eq!(second_page.messages[0].content, "first");
+    assert!(!second_page.has_more(&query.next_page()));
+
+    assert_eq!(service.delete_history("connection-1").await?, 3);
+    assert_eq!(
+        service
+            .get_history(&HistoryQuery::new().connection_id("connection-1"))
+            .await?
+            .total,
+        0
+    );
+    Ok(())
+}
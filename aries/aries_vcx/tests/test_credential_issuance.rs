@@ -1,8 +1,11 @@
 use std::error::Error;
 
-use aries_vcx::protocols::{
-    issuance::issuer::state_machine::IssuerState,
-    proof_presentation::verifier::verification_status::PresentationVerificationStatus,
+use aries_vcx::{
+    errors::error::{AriesVcxError, AriesVcxErrorKind},
+    protocols::{
+        issuance::issuer::state_machine::IssuerState, problem_report::descriptors,
+        proof_presentation::verifier::verification_status::PresentationVerificationStatus,
+    },
 };
 use test_utils::devsetup::*;
 
@@ -201,6 +204,15 @@ async fn test_agency_pool_credential_exchange_via_proposal_failed() -> Result<()
     assert_eq!(IssuerState::OfferSet, issuer.get_state());
     issuer.process_aries_msg(problem_report.into()).await?;
     assert_eq!(IssuerState::Failed, issuer.get_state());
+    let problem = issuer.get_problem().unwrap();
+    assert_eq!(
+        problem.problem_code().unwrap().descriptor,
+        descriptors::REJECTED
+    );
+    assert_eq!(
+        AriesVcxError::from(problem).kind(),
+        AriesVcxErrorKind::ProblemReportReceived
+    );
     Ok(())
 }
 
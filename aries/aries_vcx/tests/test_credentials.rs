@@ -112,25 +112,19 @@ async fn test_pool_is_cred_revoked() -> Result<(), Box<dyn Error>> {
 
     assert!(!is_cred_revoked(&setup.ledger_read, &rev_reg.rev_reg_id, cred_rev_id).await?);
 
-    #[allow(deprecated)] // TODO - https://github.com/hyperledger/aries-vcx/issues/1309
-    let rev_reg_delta_json = setup
-        .ledger_read
-        .get_rev_reg_delta_json(&rev_reg.rev_reg_id.to_owned().try_into()?, None, None)
-        .await?
-        .0;
-    setup
-        .anoncreds
+    rev_reg
         .revoke_credential_local(
             &setup.wallet,
-            &rev_reg.rev_reg_id.to_owned().try_into()?,
+            &setup.anoncreds,
+            &setup.ledger_read,
             cred_rev_id,
-            rev_reg_delta_json,
         )
         .await?;
     rev_reg
         .publish_local_revocations(
             &setup.wallet,
             &setup.anoncreds,
+            &setup.ledger_read,
             &setup.ledger_write,
             &setup.institution_did,
         )
@@ -0,0 +1,367 @@
+use std::{error::Error, sync::Mutex};
+
+use aries_vcx::{
+    errors::error::VcxResult,
+    handlers::mediation::{pickup::MessagePickup, recipient::MediationRecipient},
+    protocols::{trustping::build_ping, SendClosure},
+    utils::{
+        didcomm_utils::{get_ed25519_recipient_keys, get_ed25519_routing_keys},
+        encryption_envelope::EncryptionEnvelope,
+    },
+};
+use aries_vcx_wallet::wallet::base_wallet::{did_wallet::DidWallet, BaseWallet};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use did_doc::schema::service::typed::ServiceType;
+use did_key::DidKey;
+use messages::{
+    decorators::{
+        attachment::{Attachment, AttachmentData, AttachmentType},
+        thread::Thread,
+    },
+    msg_fields::protocols::{
+        coordinate_mediation::{
+            keylist::KeylistItem,
+            keylist_update::KeylistUpdateItemAction,
+            keylist_update_response::{KeylistUpdateItemResult, KeylistUpdateResponseItem},
+            CoordinateMediation, Keylist, KeylistContent, KeylistDecorators, KeylistUpdateResponse,
+            KeylistUpdateResponseContent, KeylistUpdateResponseDecorators, MediateGrant,
+            MediateGrantContent, MediateGrantDecorators,
+        },
+        pickup::{Delivery, DeliveryContent, Pickup, Status, StatusContent},
+        trust_ping::TrustPing,
+    },
+    AriesMessage,
+};
+use public_key::Key;
+use test_utils::devsetup::dev_build_featured_wallet;
+use url::Url;
+use uuid::Uuid;
+
+/// Minimal in-process mediator: routes forward messages for the keys registered with it and
+/// answers coordinate-mediation and pickup requests on the same exchange, as if return routing
+/// was in place.
+struct LocalMediator<W: BaseWallet> {
+    wallet: W,
+    verkey: Key,
+    endpoint: Url,
+    keylist: Mutex<Vec<String>>,
+    queue: Mutex<Vec<(String, Vec<u8>)>>,
+}
+
+impl<W: BaseWallet> LocalMediator<W> {
+    async fn new(wallet: W) -> Self {
+        let verkey = wallet
+            .create_and_store_my_did(None, None)
+            .await
+            .unwrap()
+            .verkey()
+            .clone();
+        Self {
+            wallet,
+            verkey,
+            endpoint: "http://mediator.local/didcomm".parse().unwrap(),
+            keylist: Mutex::new(Vec::new()),
+            queue: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn queued(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    /// Processes an inbound packed message, returning the packed response if there is one.
+    async fn receive(&self, packed: &[u8]) -> Option<Vec<u8>> {
+        let (message, sender_vk, _) =
+            EncryptionEnvelope::unpack_aries_msg(&self.wallet, packed, &None)
+                .await
+                .unwrap();
+        let response = match message {
+            AriesMessage::Routing(forward) => {
+                assert!(self.keylist.lock().unwrap().contains(&forward.content.to));
+                let payload = serde_json::to_vec(&forward.content.msg).unwrap();
+                self.queue
+                    .lock()
+                    .unwrap()
+                    .push((Uuid::new_v4().to_string(), payload));
+                return None;
+            }
+            AriesMessage::CoordinateMediation(message) => self.handle_coordination(message),
+            AriesMessage::Pickup(message) => self.handle_pickup(message)?,
+            message => panic!("Local mediator received unexpected message: {message:?}"),
+        };
+        let response = serde_json::to_vec(&response).unwrap();
+        let envelope = EncryptionEnvelope::create_from_keys(
+            &self.wallet,
+            &response,
+            Some(self.verkey.clone()),
+            sender_vk.expect("mediation clients must authcrypt"),
+            vec![],
+        )
+        .await
+        .unwrap();
+        Some(envelope.0)
+    }
+
+    fn handle_coordination(&self, message: CoordinateMediation) -> AriesMessage {
+        match message {
+            CoordinateMediation::MediateRequest(request) => {
+                let routing_key = DidKey::try_from(self.verkey.clone()).unwrap();
+                MediateGrant::builder()
+                    .id(Uuid::new_v4().to_string())
+                    .content(
+                        MediateGrantContent::builder()
+                            .endpoint(self.endpoint.to_string())
+                            .routing_keys(vec![routing_key.to_string()])
+                            .build(),
+                    )
+                    .decorators(
+                        MediateGrantDecorators::builder()
+                            .thread(Thread::builder().thid(request.id).build())
+                            .build(),
+                    )
+                    .build()
+            }
+            CoordinateMediation::KeylistUpdate(update) => {
+                let mut keylist = self.keylist.lock().unwrap();
+                let updated = update
+                    .content
+                    .updates
+                    .into_iter()
+                    .map(|item| {
+                        match item.action {
+                            KeylistUpdateItemAction::Add => {
+                                keylist.push(item.recipient_key.clone())
+                            }
+                            KeylistUpdateItemAction::Remove => {
+                                keylist.retain(|key| key != &item.recipient_key)
+                            }
+                        }
+                        KeylistUpdateResponseItem::builder()
+                            .recipient_key(item.recipient_key)
+                            .action(item.action)
+                            .result(KeylistUpdateItemResult::Success)
+                            .build()
+                    })
+                    .collect();
+                KeylistUpdateResponse::builder()
+                    .id(Uuid::new_v4().to_string())
+                    .content(
+                        KeylistUpdateResponseContent::builder()
+                            .updated(updated)
+                            .build(),
+                    )
+                    .decorators(
+                        KeylistUpdateResponseDecorators::builder()
+                            .thread(Thread::builder().thid(update.id).build())
+                            .build(),
+                    )
+                    .build()
+            }
+            CoordinateMediation::KeylistQuery(query) => {
+                let keys = self
+                    .keylist
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .map(|key| KeylistItem::builder().recipient_key(key.clone()).build())
+                    .collect();
+                Keylist::builder()
+                    .id(Uuid::new_v4().to_string())
+                    .content(KeylistContent::builder().keys(keys).build())
+                    .decorators(
+                        KeylistDecorators::builder()
+                            .thread(Thread::builder().thid(query.id).build())
+                            .build(),
+                    )
+                    .build()
+            }
+            message => panic!("Local mediator received unexpected message: {message:?}"),
+        }
+    }
+
+    fn handle_pickup(&self, message: Pickup) -> Option<AriesMessage> {
+        let mut queue = self.queue.lock().unwrap();
+        match message {
+            Pickup::StatusRequest(_) => Some(
+                Status::builder()
+                    .id(Uuid::new_v4().to_string())
+                    .content(
+                        StatusContent::builder()
+                            .message_count(queue.len() as u32)
+                            .build(),
+                    )
+                    .build(),
+            ),
+            Pickup::DeliveryRequest(request) => {
+                let attach = queue
+                    .iter()
+                    .take(request.content.limit as usize)
+                    .map(|(id, payload)| {
+                        Attachment::builder()
+                            .id(id.clone())
+                            .data(
+                                AttachmentData::builder()
+                                    .content(AttachmentType::Base64(
+                                        URL_SAFE_NO_PAD.encode(payload),
+                                    ))
+                                    .build(),
+                            )
+                            .build()
+                    })
+                    .collect();
+                Some(
+                    Delivery::builder()
+                        .id(Uuid::new_v4().to_string())
+                        .content(DeliveryContent::builder().attach(attach).build())
+                        .build(),
+                )
+            }
+            Pickup::MessagesReceived(received) => {
+                queue.retain(|(id, _)| !received.content.message_id_list.contains(id));
+                None
+            }
+            message => panic!("Local mediator received unexpected message: {message:?}"),
+        }
+    }
+}
+
+/// Packs outbound messages of the recipient for the mediator and stores the mediator's
+/// response, if any, in `inbox`.
+fn send_via_mediator<'a, W: BaseWallet, M: BaseWallet>(
+    wallet: &'a W,
+    verkey: &'a Key,
+    mediator: &'a LocalMediator<M>,
+    inbox: &'a Mutex<Option<AriesMessage>>,
+) -> SendClosure<'a> {
+    Box::new(move |message: AriesMessage| {
+        Box::pin(async move {
+            let envelope = EncryptionEnvelope::create_from_keys(
+                wallet,
+                &serde_json::to_vec(&message)?,
+                Some(verkey.clone()),
+                mediator.verkey.clone(),
+                vec![],
+            )
+            .await?;
+            if let Some(response) = mediator.receive(&envelope.0).await {
+                let (response, _, _) = EncryptionEnvelope::unpack_aries_msg(
+                    wallet,
+                    &response,
+                    &Some(mediator.verkey.clone()),
+                )
+                .await?;
+                *inbox.lock().unwrap() = Some(response);
+            }
+            VcxResult::Ok(())
+        })
+    })
+}
+
+fn take_response(inbox: &Mutex<Option<AriesMessage>>) -> AriesMessage {
+    inbox
+        .lock()
+        .unwrap()
+        .take()
+        .expect("mediator should have responded")
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_mediated_peer_did_receives_messages_through_pickup() -> Result<(), Box<dyn Error>> {
+    let (_, mediator_wallet) = dev_build_featured_wallet("000000000000000000000000Mediator").await;
+    let mediator = LocalMediator::new(mediator_wallet).await;
+    let (_, recipient_wallet) = dev_build_featured_wallet("00000000000000000000000Recipient").await;
+    let recipient_verkey = recipient_wallet
+        .create_and_store_my_did(None, None)
+        .await?
+        .verkey()
+        .clone();
+    let inbox = Mutex::new(None);
+    let send = || send_via_mediator(&recipient_wallet, &recipient_verkey, &mediator, &inbox);
+
+    // request mediation
+    let mut mediation = MediationRecipient::new();
+    mediation.send_mediate_request(send()).await?;
+    let AriesMessage::CoordinateMediation(CoordinateMediation::MediateGrant(grant)) =
+        take_response(&inbox)
+    else {
+        panic!("Expected mediation to be granted");
+    };
+    mediation.handle_mediate_grant(grant)?;
+    assert!(mediation.is_granted());
+    assert_eq!(mediation.get_endpoint()?, &mediator.endpoint);
+
+    // publish a did:peer:4 routed through the mediator and register its key
+    let (peer_did, peer_did_key) = mediation.create_peer_did(&recipient_wallet).await?;
+    mediation
+        .add_recipient_key(peer_did_key.base58(), send())
+        .await?;
+    let AriesMessage::CoordinateMediation(CoordinateMediation::KeylistUpdateResponse(response)) =
+        take_response(&inbox)
+    else {
+        panic!("Expected a keylist update response");
+    };
+    assert!(mediation
+        .handle_keylist_update_response(&response)?
+        .is_empty());
+
+    mediation.send_keylist_query(None, None, send()).await?;
+    let AriesMessage::CoordinateMediation(CoordinateMediation::Keylist(keylist)) =
+        take_response(&inbox)
+    else {
+        panic!("Expected a keylist");
+    };
+    assert_eq!(
+        mediation.handle_keylist(&keylist)?,
+        vec![peer_did_key.base58()]
+    );
+
+    // a third party packs a message for the did:peer:4, which lands at the mediator
+    let did_doc = peer_did.resolve_did_doc()?;
+    let service = did_doc.get_service_of_type(&ServiceType::DIDCommV1)?;
+    assert_eq!(service.service_endpoint(), &mediator.endpoint);
+    let recipient_key = get_ed25519_recipient_keys(&did_doc, service.id())?.remove(0);
+    let routing_keys = get_ed25519_routing_keys(&did_doc, service.id())?;
+    assert_eq!(routing_keys, vec![mediator.verkey.clone()]);
+
+    let (_, sender_wallet) = dev_build_featured_wallet("00000000000000000000000000Sender").await;
+    let ping = build_ping(false, Some("hello via mediator".to_owned()));
+    let envelope = EncryptionEnvelope::create_from_keys(
+        &sender_wallet,
+        &serde_json::to_vec(&AriesMessage::from(ping.clone()))?,
+        None,
+        recipient_key,
+        routing_keys,
+    )
+    .await?;
+    assert!(mediator.receive(&envelope.0).await.is_none());
+    assert_eq!(mediator.queued(), 1);
+
+    // pick the message up
+    let mut pickup = MessagePickup::new(None);
+    pickup.send_status_request(send()).await?;
+    let AriesMessage::Pickup(Pickup::Status(status)) = take_response(&inbox) else {
+        panic!("Expected a pickup status");
+    };
+    assert_eq!(pickup.handle_status(&status)?, 1);
+
+    pickup.send_delivery_request(10, send()).await?;
+    let AriesMessage::Pickup(Pickup::Delivery(delivery)) = take_response(&inbox) else {
+        panic!("Expected a pickup delivery");
+    };
+    let delivered = pickup.handle_delivery(&delivery)?;
+    assert_eq!(delivered.len(), 1);
+    let (message, _, unpacked_for) =
+        EncryptionEnvelope::unpack_aries_msg(&recipient_wallet, &delivered[0].packed, &None)
+            .await?;
+    assert_eq!(unpacked_for, peer_did_key);
+    let AriesMessage::TrustPing(TrustPing::Ping(delivered_ping)) = message else {
+        panic!("Expected the forwarded ping");
+    };
+    assert_eq!(delivered_ping.id, ping.id);
+
+    pickup.send_messages_received(send()).await?;
+    assert_eq!(mediator.queued(), 0);
+
+    Ok(())
+}
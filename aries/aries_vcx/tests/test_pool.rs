@@ -7,6 +7,7 @@ use aries_vcx::{
     common::{
         keys::{get_verkey_from_ledger, rotate_verkey},
         ledger::{
+            endorsement::{endorse_and_submit, prepare_schema_for_endorsement},
             service_didsov::EndpointDidSov,
             transactions::{
                 add_attr, add_new_did, clear_attr, get_attr, get_service, write_endorser_did,
@@ -122,7 +123,7 @@ async fn create_and_store_revocable_credential_def(
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_rotate_verkey() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let (did, verkey) = add_new_did(
@@ -143,7 +144,7 @@ async fn test_pool_rotate_verkey() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_add_get_service() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let endorser = create_test_agent_endorser(
@@ -178,7 +179,7 @@ async fn test_pool_add_get_service() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_write_new_endorser_did() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
     let faber = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
@@ -218,7 +219,7 @@ async fn test_pool_write_new_endorser_did() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_add_get_service_public() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let endorser = create_test_agent_endorser(
@@ -261,7 +262,7 @@ async fn test_pool_add_get_service_public() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_add_get_service_public_none_routing_keys() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let did = setup.institution_did.clone();
@@ -291,7 +292,7 @@ async fn test_pool_add_get_service_public_none_routing_keys() -> Result<(), Box<
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_multiple_service_formats() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let did = setup.institution_did.clone();
@@ -351,7 +352,7 @@ async fn test_pool_multiple_service_formats() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_add_get_attr() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let did = setup.institution_did.clone();
@@ -383,7 +384,7 @@ async fn test_pool_add_get_attr() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_agency_pool_get_credential_def() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let (_, _, cred_def_id, cred_def_json, _) = create_and_store_nonrevocable_credential_def(
@@ -406,7 +407,7 @@ async fn test_agency_pool_get_credential_def() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_rev_reg_def_fails_for_cred_def_created_without_revocation(
 ) -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
@@ -438,7 +439,7 @@ async fn test_pool_rev_reg_def_fails_for_cred_def_created_without_revocation(
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_get_rev_reg_def_json() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let attrs = format!("{:?}", attr_names_address_list());
@@ -460,7 +461,7 @@ async fn test_pool_get_rev_reg_def_json() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_get_rev_reg_delta_json() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let attrs = format!("{:?}", attr_names_address_list());
@@ -484,7 +485,7 @@ async fn test_pool_get_rev_reg_delta_json() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_get_rev_reg() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let attrs = format!("{:?}", attr_names_address_list());
@@ -514,7 +515,7 @@ async fn test_pool_get_rev_reg() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_pool_create_and_get_schema() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let schema = create_and_write_test_schema(
@@ -532,3 +533,57 @@ async fn test_pool_create_and_get_schema() -> Result<(), Box<dyn Error>> {
     assert!(retrieved_schema.contains(&schema.schema_id.to_string()));
     Ok(())
 }
+
+#[tokio::test]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
+async fn test_pool_endorse_schema_of_author() -> Result<(), Box<dyn Error>> {
+    let setup = SetupPoolDirectory::init().await;
+    let faber = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let acme = create_test_agent(setup.genesis_file_path.clone()).await;
+    let acme_vk = acme
+        .wallet
+        .key_for_did(&acme.institution_did.to_string())
+        .await?;
+    faber
+        .ledger_write
+        .publish_nym(
+            &faber.wallet,
+            &faber.institution_did,
+            &acme.institution_did,
+            Some(&acme_vk),
+            None,
+            None,
+        )
+        .await?;
+    thread::sleep(Duration::from_millis(50));
+
+    let schema = Schema::create(
+        &acme.anoncreds,
+        "source_id",
+        &acme.institution_did,
+        "endorsed_schema",
+        "1.0",
+        vec!["name".to_owned()],
+    )
+    .await?;
+    let endorsement_request = prepare_schema_for_endorsement(
+        &acme.wallet,
+        &acme.ledger_write,
+        &schema,
+        &acme.institution_did,
+        &faber.institution_did,
+    )
+    .await?;
+    endorse_and_submit(
+        &faber.wallet,
+        &faber.ledger_write,
+        &faber.institution_did,
+        &endorsement_request,
+    )
+    .await?;
+    thread::sleep(Duration::from_millis(50));
+
+    let ledger_schema = acme.ledger_read.get_schema(&schema.schema_id, None).await?;
+    assert_eq!(ledger_schema.id, schema.schema_id);
+    Ok(())
+}
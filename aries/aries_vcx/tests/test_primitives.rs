@@ -103,7 +103,7 @@ async fn test_pool_create_rev_reg_def() -> Result<(), Box<dyn Error>> {
 
     let path = get_temp_dir_path();
 
-    let (rev_reg_def_id, rev_reg_def_json, rev_reg_entry_json) = generate_rev_reg(
+    let (_, rev_reg_def_json, rev_status_list_json) = generate_rev_reg(
         &setup.wallet,
         &setup.anoncreds,
         &setup.institution_did,
@@ -121,10 +121,10 @@ async fn test_pool_create_rev_reg_def() -> Result<(), Box<dyn Error>> {
         )
         .await?;
     ledger_write
-        .publish_rev_reg_delta(
+        .publish_rev_status_list(
             &setup.wallet,
-            &rev_reg_def_id.try_into()?,
-            serde_json::from_str(&rev_reg_entry_json)?,
+            serde_json::from_str(&rev_status_list_json)?,
+            None,
             &setup.institution_did,
         )
         .await?;
@@ -1,17 +1,24 @@
 #![allow(clippy::diverging_sub_expression)]
 
-use std::error::Error;
+use std::{collections::HashMap, error::Error, sync::Mutex};
 
 use anoncreds_types::data_types::messages::pres_request::PresentationRequest;
 use aries_vcx::{
-    handlers::proof_presentation::{prover::Prover, verifier::Verifier},
+    errors::error::VcxResult,
+    handlers::{
+        connectionless::{ConnectionlessExchange, EphemeralService},
+        out_of_band::{receiver::OutOfBandReceiver, sender::OutOfBandSender},
+        proof_presentation::{prover::Prover, verifier::Verifier},
+    },
     protocols::proof_presentation::{
         prover::state_machine::ProverState,
         verifier::{
             state_machine::VerifierState, verification_status::PresentationVerificationStatus,
         },
     },
+    transport::Transport,
 };
+use async_trait::async_trait;
 use messages::{
     msg_fields::protocols::present_proof::{v1::PresentProofV1, PresentProof},
     AriesMessage,
@@ -21,22 +28,50 @@ use test_utils::{
     constants::DEFAULT_SCHEMA_ATTRS,
     devsetup::{build_setup_profile, SetupPoolDirectory},
 };
+use url::Url;
 
 use crate::utils::{
     create_and_publish_test_rev_reg, create_and_write_credential, create_and_write_test_cred_def,
     create_and_write_test_schema,
     scenarios::{
         accept_proof_proposal, create_address_schema_creddef_revreg, create_proof_proposal,
-        exchange_credential_with_proposal, generate_and_send_proof, prover_select_credentials,
-        receive_proof_proposal_rejection, reject_proof_proposal, verify_proof,
+        create_proof_request_data, create_prover_from_request, exchange_credential_with_proposal,
+        generate_and_send_proof, issue_address_credential, prover_select_credentials,
+        receive_proof_proposal_rejection, reject_proof_proposal, requested_attrs_address,
+        verify_proof,
     },
     test_agent::{create_test_agent, create_test_agent_trustee},
 };
 
 pub mod utils;
 
+/// Stands in for the endpoints of both parties, keeping the messages sent to them.
+#[derive(Default)]
+struct Mailbox {
+    sent: Mutex<Vec<(Vec<u8>, Url)>>,
+}
+
+impl Mailbox {
+    fn take(&self, service_endpoint: &Url) -> Vec<u8> {
+        let (msg, endpoint) = self.sent.lock().unwrap().remove(0);
+        assert_eq!(&endpoint, service_endpoint);
+        msg
+    }
+}
+
+#[async_trait]
+impl Transport for Mailbox {
+    async fn send_message(&self, msg: Vec<u8>, service_endpoint: &Url) -> VcxResult<()> {
+        self.sent
+            .lock()
+            .unwrap()
+            .push((msg, service_endpoint.clone()));
+        Ok(())
+    }
+}
+
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_agency_pool_generate_proof_with_predicates() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
     let schema = create_and_write_test_schema(
@@ -171,7 +206,7 @@ async fn test_agency_pool_generate_proof_with_predicates() -> Result<(), Box<dyn
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 #[allow(unused_mut)]
 async fn test_agency_pool_presentation_via_proposal() -> Result<(), Box<dyn Error>> {
     let setup = build_setup_profile().await;
@@ -215,7 +250,7 @@ async fn test_agency_pool_presentation_via_proposal() -> Result<(), Box<dyn Erro
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 #[allow(unused_mut)]
 async fn test_agency_pool_presentation_via_proposal_with_rejection() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
@@ -251,7 +286,7 @@ async fn test_agency_pool_presentation_via_proposal_with_rejection() -> Result<(
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 #[allow(unused_mut)]
 async fn test_agency_pool_presentation_via_proposal_with_negotiation() -> Result<(), Box<dyn Error>>
 {
@@ -294,3 +329,97 @@ async fn test_agency_pool_presentation_via_proposal_with_negotiation() -> Result
     verify_proof(&mut institution, &mut verifier, presentation).await;
     Ok(())
 }
+
+#[tokio::test]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
+async fn test_agency_pool_connectionless_presentation_via_oob() -> Result<(), Box<dyn Error>> {
+    let setup = SetupPoolDirectory::init().await;
+    let mut institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let mut consumer = create_test_agent(setup.genesis_file_path).await;
+    let mailbox = Mailbox::default();
+
+    let (schema, cred_def, _rev_reg, _issuer) =
+        issue_address_credential(&mut consumer, &mut institution).await;
+
+    let verifier_endpoint: Url = "https://kiosk.example.org/didcomm".parse()?;
+    let mut verifier_exchange = ConnectionlessExchange::new(
+        EphemeralService::create(&institution.wallet, verifier_endpoint.clone(), vec![]).await?,
+    );
+    let requested_attrs = requested_attrs_address(
+        &institution.institution_did,
+        &schema.schema_id,
+        cred_def.get_cred_def_id(),
+        None,
+        None,
+    );
+    let presentation_request_data = create_proof_request_data(
+        &mut institution,
+        requested_attrs,
+        Default::default(),
+        Default::default(),
+        None,
+    )
+    .await;
+    let mut verifier = Verifier::create_from_request("1".to_string(), &presentation_request_data)?;
+    let request = verifier.mark_presentation_request_sent_connectionless(&verifier_exchange)?;
+    let invitation_url = OutOfBandSender::create()
+        .append_a2a_message(request)?
+        .invitation_to_url(verifier_endpoint.as_str())?;
+
+    let prover_endpoint: Url = "https://wallet.example.org/didcomm".parse()?;
+    let mut prover_exchange = ConnectionlessExchange::new(
+        EphemeralService::create(&consumer.wallet, prover_endpoint.clone(), vec![]).await?,
+    );
+    let request = OutOfBandReceiver::create_from_url_encoded_oob(invitation_url.as_str())?
+        .extract_a2a_message()?
+        .unwrap();
+    prover_exchange.receive(&request);
+    let request = match request {
+        AriesMessage::PresentProof(PresentProof::V1(PresentProofV1::RequestPresentation(
+            request,
+        ))) => request,
+        _ => panic!("Unexpected message type {:?}", request),
+    };
+    let mut prover = create_prover_from_request(request.clone()).await;
+    let selected_credentials =
+        prover_select_credentials(&mut prover, &mut consumer, request, None).await;
+    prover
+        .generate_presentation(
+            &consumer.wallet,
+            &consumer.ledger_read,
+            &consumer.anoncreds,
+            selected_credentials,
+            HashMap::new(),
+        )
+        .await?;
+    prover
+        .send_presentation_connectionless(&consumer.wallet, &prover_exchange, &mailbox)
+        .await?;
+    assert_eq!(prover.get_state(), ProverState::PresentationSent);
+
+    let presentation = verifier_exchange
+        .unpack(&institution.wallet, &mailbox.take(&verifier_endpoint))
+        .await?;
+    verifier
+        .process_connectionless_msg(
+            &institution.wallet,
+            &institution.ledger_read,
+            &institution.anoncreds,
+            &mut verifier_exchange,
+            presentation,
+            &mailbox,
+        )
+        .await?;
+    assert_eq!(verifier.get_state(), VerifierState::Finished);
+    assert_eq!(
+        verifier.get_verification_status(),
+        PresentationVerificationStatus::Valid
+    );
+
+    let ack = prover_exchange
+        .unpack(&consumer.wallet, &mailbox.take(&prover_endpoint))
+        .await?;
+    prover.process_aries_msg(ack).await?;
+    assert_eq!(prover.get_state(), ProverState::Finished);
+    Ok(())
+}
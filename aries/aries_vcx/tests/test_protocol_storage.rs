@@ -0,0 +1,101 @@
+use std::error::Error;
+
+use aries_vcx::{
+    errors::error::AriesVcxErrorKind,
+    handlers::issuance::issuer::Issuer,
+    protocols::issuance::issuer::state_machine::IssuerState,
+    storage::{ProtocolRecordQuery, ProtocolRecordStore},
+};
+use test_utils::devsetup::dev_build_featured_wallet;
+
+#[tokio::test]
+#[ignore]
+async fn test_protocol_records_survive_in_wallet() -> Result<(), Box<dyn Error>> {
+    let (_, wallet) = dev_build_featured_wallet("000000000000000000000000Protocol").await;
+    let store = ProtocolRecordStore::new(wallet);
+
+    let issuer = Issuer::create("issuer-1")?;
+    let thread_id = issuer.get_thread_id()?;
+    store
+        .insert("issuer-1", issuer, Some("connection-1"))
+        .await?;
+    store
+        .insert(
+            "issuer-2",
+            Issuer::create("issuer-2")?,
+            Some("connection-2"),
+        )
+        .await?;
+
+    let stored = store.get::<Issuer>("issuer-1").await?;
+    assert_eq!(stored.version, 1);
+    assert_eq!(stored.connection_id.as_deref(), Some("connection-1"));
+    assert_eq!(stored.record.get_thread_id()?, thread_id);
+
+    let found = store
+        .find::<Issuer>(
+            ProtocolRecordQuery::new()
+                .state(IssuerState::Initial)
+                .connection_id("connection-1"),
+        )
+        .await?;
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, "issuer-1");
+
+    let by_thread = store.find_by_thread_id::<Issuer>(&thread_id).await?;
+    assert_eq!(
+        by_thread.map(|stored| stored.id).as_deref(),
+        Some("issuer-1")
+    );
+
+    let in_offer_set = store
+        .find::<Issuer>(ProtocolRecordQuery::new().state(IssuerState::OfferSet))
+        .await?;
+    assert!(in_offer_set.is_empty());
+
+    store.delete::<Issuer>("issuer-2").await?;
+    assert!(store
+        .find_by_connection_id::<Issuer>("connection-2")
+        .await?
+        .is_empty());
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_protocol_record_update_rejects_stale_version() -> Result<(), Box<dyn Error>> {
+    let (_, wallet) = dev_build_featured_wallet("00000000000000000000000Versioned").await;
+    let store = ProtocolRecordStore::new(wallet);
+    store
+        .insert("issuer-1", Issuer::create("issuer-1")?, None)
+        .await?;
+
+    let first_read = store.get::<Issuer>("issuer-1").await?;
+    let second_read = store.get::<Issuer>("issuer-1").await?;
+
+    let updated = store.update(first_read).await?;
+    assert_eq!(updated.version, 2);
+
+    let err = store.update(second_read).await.unwrap_err();
+    assert_eq!(err.kind(), AriesVcxErrorKind::InvalidState);
+    assert_eq!(store.get::<Issuer>("issuer-1").await?.version, 2);
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_protocol_record_concurrent_updates_of_same_version() -> Result<(), Box<dyn Error>> {
+    let (_, wallet) = dev_build_featured_wallet("0000000000000000000000Concurrent").await;
+    let store = ProtocolRecordStore::new(wallet);
+    store
+        .insert("issuer-1", Issuer::create("issuer-1")?, None)
+        .await?;
+
+    let first_read = store.get::<Issuer>("issuer-1").await?;
+    let second_read = store.get::<Issuer>("issuer-1").await?;
+    let (first, second) = tokio::join!(store.update(first_read), store.update(second_read));
+
+    assert!(first.is_ok() != second.is_ok());
+    assert_eq!(store.get::<Issuer>("issuer-1").await?.version, 2);
+    Ok(())
+}
@@ -1,19 +1,59 @@
-use std::{error::Error, thread, time::Duration};
+use std::{
+    error::Error,
+    path::Path,
+    pin::pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
 
-use anoncreds_types::data_types::messages::pres_request::NonRevokedInterval;
-use aries_vcx::protocols::proof_presentation::verifier::{
-    state_machine::VerifierState, verification_status::PresentationVerificationStatus,
+use anoncreds_types::data_types::{
+    identifiers::rev_reg_def_id::RevocationRegistryDefinitionId,
+    ledger::{
+        cred_def::CredentialDefinition, rev_reg_def::RevocationRegistryDefinition,
+        rev_reg_delta::RevocationRegistryDelta, rev_status_list::RevocationStatusList,
+        schema::Schema,
+    },
+    messages::pres_request::NonRevokedInterval,
 };
-use test_utils::devsetup::*;
+use aries_vcx::{
+    common::primitives::{
+        revocation_registry_manager::{RegistryStatus, RevocationRegistryManager},
+        revocation_service::{PublishReport, RevocationNotifier, RevocationService},
+        tails_file_manager::{HttpTailsFileFetcher, TailsFileManager},
+        tails_server::{HttpTailsServerClient, TailsServerClient},
+    },
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    protocols::proof_presentation::verifier::{
+        state_machine::VerifierState, verification_status::PresentationVerificationStatus,
+    },
+};
+use aries_vcx_ledger::{
+    errors::error::VcxLedgerResult,
+    ledger::{base_ledger::AnoncredsLedgerWrite, indy::pool::test_utils::get_temp_dir_path},
+};
+use aries_vcx_wallet::wallet::base_wallet::{
+    record_category::RecordCategory, record_wallet::RecordWallet, BaseWallet,
+};
+use async_trait::async_trait;
+use did_parser_nom::Did;
+use messages::AriesMessage;
+use serde_json::json;
+use test_utils::{devsetup::*, tails_server::LocalTailsServer};
+use tokio::sync::Notify;
 
 use crate::utils::{
+    create_and_write_test_cred_def, create_and_write_test_schema,
     scenarios::{
-        create_address_schema_creddef_revreg, create_proof_request_data,
+        attr_names_address_list, create_address_schema_creddef_revreg, create_proof_request_data,
         create_verifier_from_request_data, credential_data_address_1, credential_data_address_2,
-        credential_data_address_3, exchange_credential, exchange_proof, issue_address_credential,
-        prover_select_credentials_and_send_proof, publish_revocation, requested_attrs_address,
-        revoke_credential_and_publish_accumulator, revoke_credential_local, rotate_rev_reg,
-        verifier_create_proof_and_send_request,
+        credential_data_address_3, exchange_credential, exchange_credential_with_rev_reg_manager,
+        exchange_proof, issue_address_credential, prover_select_credentials_and_send_proof,
+        publish_revocation, requested_attrs_address, revoke_credential_and_publish_accumulator,
+        revoke_credential_local, rotate_rev_reg, verifier_create_proof_and_send_request,
     },
     test_agent::{create_test_agent, create_test_agent_trustee},
 };
@@ -21,7 +61,7 @@ use crate::utils::{
 pub mod utils;
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_agency_pool_basic_revocation() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
     let mut institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
@@ -84,7 +124,7 @@ async fn test_agency_pool_basic_revocation() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_agency_pool_revoked_credential_might_still_work() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
     let mut institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
@@ -145,7 +185,7 @@ async fn test_agency_pool_revoked_credential_might_still_work() -> Result<(), Bo
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_agency_pool_local_revocation() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
     let mut institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
@@ -193,7 +233,7 @@ async fn test_agency_pool_local_revocation() -> Result<(), Box<dyn Error>> {
 
 // TODO - re-enable after https://github.com/hyperledger/aries-vcx/issues/1309
 // #[tokio::test]
-// #[ignore]
+// #[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 #[allow(unused)]
 async fn test_agency_batch_revocation() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
@@ -358,7 +398,7 @@ async fn test_agency_batch_revocation() -> Result<(), Box<dyn Error>> {
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 #[allow(unused_mut)]
 async fn test_agency_pool_two_creds_one_rev_reg_revoke_first() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
@@ -453,7 +493,7 @@ async fn test_agency_pool_two_creds_one_rev_reg_revoke_first() -> Result<(), Box
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 #[allow(unused_mut)]
 async fn test_agency_pool_two_creds_one_rev_reg_revoke_second() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
@@ -546,7 +586,7 @@ async fn test_agency_pool_two_creds_one_rev_reg_revoke_second() -> Result<(), Bo
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_agency_pool_two_creds_two_rev_reg_id() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
     let mut issuer = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
@@ -635,7 +675,7 @@ async fn test_agency_pool_two_creds_two_rev_reg_id() -> Result<(), Box<dyn Error
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 #[allow(unused_mut)]
 async fn test_agency_pool_two_creds_two_rev_reg_id_revoke_first() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
@@ -729,7 +769,7 @@ async fn test_agency_pool_two_creds_two_rev_reg_id_revoke_first() -> Result<(),
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_agency_pool_two_creds_two_rev_reg_id_revoke_second() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
     let mut issuer = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
@@ -824,7 +864,7 @@ async fn test_agency_pool_two_creds_two_rev_reg_id_revoke_second() -> Result<(),
 }
 
 #[tokio::test]
-#[ignore]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
 async fn test_agency_pool_three_creds_one_rev_reg_revoke_all() -> Result<(), Box<dyn Error>> {
     let setup = SetupPoolDirectory::init().await;
     let mut issuer = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
@@ -884,3 +924,643 @@ async fn test_agency_pool_three_creds_one_rev_reg_revoke_all() -> Result<(), Box
     assert!(issuer_credential3.is_revoked(&issuer.ledger_read).await?);
     Ok(())
 }
+
+#[tokio::test]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
+async fn test_agency_pool_rev_reg_manager_rotates_full_registries() -> Result<(), Box<dyn Error>> {
+    let setup = SetupPoolDirectory::init().await;
+    let mut institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let mut consumer = create_test_agent(setup.genesis_file_path).await;
+    let tails_server = LocalTailsServer::start().await;
+    let tails_client = HttpTailsServerClient::new(tails_server.url().parse()?, Default::default())?;
+
+    let schema = create_and_write_test_schema(
+        &institution.wallet,
+        &institution.anoncreds,
+        &institution.ledger_write,
+        &institution.institution_did,
+        &json!(attr_names_address_list()).to_string(),
+    )
+    .await;
+    let cred_def = create_and_write_test_cred_def(
+        &institution.wallet,
+        &institution.anoncreds,
+        &institution.ledger_read,
+        &institution.ledger_write,
+        &institution.institution_did,
+        &schema.schema_id,
+        true,
+    )
+    .await;
+    let tails_dir = get_temp_dir_path().to_str().unwrap().to_string();
+    let mut rev_reg_manager = RevocationRegistryManager::new(
+        institution.institution_did.clone(),
+        cred_def.get_cred_def_id().to_owned(),
+        &tails_dir,
+        3,
+        1,
+    )?;
+
+    let mut issuers = Vec::new();
+    for credential_data in [
+        credential_data_address_1(),
+        credential_data_address_2(),
+        credential_data_address_3(),
+        credential_data_address_1(),
+    ] {
+        let issuer = exchange_credential_with_rev_reg_manager(
+            &mut consumer,
+            &mut institution,
+            &mut rev_reg_manager,
+            &tails_client,
+            credential_data.to_string(),
+        )
+        .await;
+        issuers.push(issuer);
+    }
+
+    // the second registry was published when only one index of the first was left
+    let registries = rev_reg_manager.registries();
+    assert_eq!(registries.len(), 2);
+    assert_eq!(registries[0].status(), RegistryStatus::Full);
+    assert_eq!(registries[1].status(), RegistryStatus::Active);
+    assert_eq!(registries[1].issued_indices().len(), 1);
+
+    let first_rev_reg_id = registries[0].registry().get_rev_reg_id();
+    let second_rev_reg_id = registries[1].registry().get_rev_reg_id();
+    assert_eq!(issuers[2].get_rev_reg_id()?, first_rev_reg_id);
+    assert_eq!(issuers[3].get_rev_reg_id()?, second_rev_reg_id);
+
+    assert_eq!(tails_server.uploaded_count(), 2);
+    let tails_location = registries[1]
+        .registry()
+        .get_rev_reg_def()
+        .value
+        .tails_location;
+    assert_eq!(
+        tails_location,
+        tails_client.tails_url(&second_rev_reg_id)?.to_string()
+    );
+    let tails_file =
+        Path::new(&tails_dir).join(registries[1].registry().get_rev_reg_def().value.tails_hash);
+    assert_eq!(
+        tails_server.tails_file(&second_rev_reg_id),
+        Some(std::fs::read(tails_file)?)
+    );
+    Ok(())
+}
+
+/// Tails server client whose uploads fail while `unavailable` is set.
+struct FlakyTailsServerClient {
+    inner: HttpTailsServerClient,
+    unavailable: AtomicBool,
+}
+
+#[async_trait]
+impl TailsServerClient for FlakyTailsServerClient {
+    async fn upload_tails_file(&self, rev_reg_id: &str, tails_file: &Path) -> VcxResult<String> {
+        if self.unavailable.load(Ordering::SeqCst) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::PostMessageFailed,
+                "Tails server unavailable",
+            ));
+        }
+        self.inner.upload_tails_file(rev_reg_id, tails_file).await
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
+async fn test_agency_pool_rev_reg_manager_retries_failed_standby_registry(
+) -> Result<(), Box<dyn Error>> {
+    let setup = SetupPoolDirectory::init().await;
+    let mut institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let mut consumer = create_test_agent(setup.genesis_file_path).await;
+    let tails_server = LocalTailsServer::start().await;
+    let tails_client = FlakyTailsServerClient {
+        inner: HttpTailsServerClient::new(tails_server.url().parse()?, Default::default())?,
+        unavailable: AtomicBool::new(false),
+    };
+
+    let schema = create_and_write_test_schema(
+        &institution.wallet,
+        &institution.anoncreds,
+        &institution.ledger_write,
+        &institution.institution_did,
+        &json!(attr_names_address_list()).to_string(),
+    )
+    .await;
+    let cred_def = create_and_write_test_cred_def(
+        &institution.wallet,
+        &institution.anoncreds,
+        &institution.ledger_read,
+        &institution.ledger_write,
+        &institution.institution_did,
+        &schema.schema_id,
+        true,
+    )
+    .await;
+    let tails_dir = get_temp_dir_path().to_str().unwrap().to_string();
+    let mut rev_reg_manager = RevocationRegistryManager::new(
+        institution.institution_did.clone(),
+        cred_def.get_cred_def_id().to_owned(),
+        &tails_dir,
+        3,
+        1,
+    )?;
+
+    let mut issuers = Vec::new();
+    for (credential_data, unavailable) in [
+        (credential_data_address_1(), false),
+        (credential_data_address_2(), true),
+        (credential_data_address_3(), true),
+        (credential_data_address_1(), false),
+    ] {
+        tails_client
+            .unavailable
+            .store(unavailable, Ordering::SeqCst);
+        let issuer = exchange_credential_with_rev_reg_manager(
+            &mut consumer,
+            &mut institution,
+            &mut rev_reg_manager,
+            &tails_client,
+            credential_data.to_string(),
+        )
+        .await;
+        issuers.push(issuer);
+    }
+
+    // the standby registry couldn't be set up while the tails server was down, yet issuance
+    // carried on with the active registry until the standby one could be published
+    let registries = rev_reg_manager.registries();
+    assert_eq!(registries.len(), 2);
+    assert_eq!(registries[0].status(), RegistryStatus::Full);
+    assert_eq!(registries[1].status(), RegistryStatus::Active);
+    assert_eq!(
+        issuers[2].get_rev_reg_id()?,
+        registries[0].registry().get_rev_reg_id()
+    );
+    assert_eq!(
+        issuers[3].get_rev_reg_id()?,
+        registries[1].registry().get_rev_reg_id()
+    );
+    assert!(registries[1].registry().get_rev_reg_id().ends_with(":tag2"));
+    assert_eq!(tails_server.uploaded_count(), 2);
+    Ok(())
+}
+
+/// Records the revocation notifications the service asks to send to holders.
+#[derive(Default)]
+struct RecordingNotifier {
+    sent: Mutex<Vec<(String, u32, AriesMessage)>>,
+}
+
+#[async_trait]
+impl RevocationNotifier for RecordingNotifier {
+    async fn send_to_holder(
+        &self,
+        rev_reg_id: &str,
+        cred_rev_id: u32,
+        message: AriesMessage,
+    ) -> VcxResult<()> {
+        self.sent
+            .lock()
+            .unwrap()
+            .push((rev_reg_id.to_owned(), cred_rev_id, message));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
+async fn test_agency_pool_revocation_service_batch() -> Result<(), Box<dyn Error>> {
+    let setup = SetupPoolDirectory::init().await;
+    let mut institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let mut consumer = create_test_agent(setup.genesis_file_path).await;
+
+    let (_, cred_def, rev_reg) = create_address_schema_creddef_revreg(
+        &institution.wallet,
+        &institution.ledger_read,
+        &institution.ledger_write,
+        &institution.anoncreds,
+        &institution.institution_did,
+    )
+    .await;
+    let rev_reg_2 = rotate_rev_reg(&mut institution, &cred_def, &rev_reg).await;
+    let issuer_credential1 = exchange_credential(
+        &mut consumer,
+        &mut institution,
+        credential_data_address_1().to_string(),
+        &cred_def,
+        &rev_reg,
+        None,
+    )
+    .await;
+    let issuer_credential2 = exchange_credential(
+        &mut consumer,
+        &mut institution,
+        credential_data_address_2().to_string(),
+        &cred_def,
+        &rev_reg,
+        None,
+    )
+    .await;
+    let issuer_credential3 = exchange_credential(
+        &mut consumer,
+        &mut institution,
+        credential_data_address_3().to_string(),
+        &cred_def,
+        &rev_reg_2,
+        None,
+    )
+    .await;
+
+    let notifier = Arc::new(RecordingNotifier::default());
+    let service = RevocationService::new(institution.institution_did.clone())
+        .with_notifier(notifier.clone(), Some("revoked in batch".to_owned()));
+    let revocations = [
+        &issuer_credential1,
+        &issuer_credential2,
+        &issuer_credential3,
+    ]
+    .into_iter()
+    .map(|issuer| Ok((issuer.get_rev_reg_id()?, issuer.get_rev_id()?)))
+    .collect::<VcxResult<Vec<_>>>()?;
+    let revoked = service
+        .revoke_batch(
+            &institution.wallet,
+            &institution.anoncreds,
+            &institution.ledger_read,
+            revocations,
+        )
+        .await?;
+    assert_eq!(revoked.get(&rev_reg.rev_reg_id), Some(&2));
+    assert_eq!(revoked.get(&rev_reg_2.rev_reg_id), Some(&1));
+
+    let mut pending = service
+        .pending_registries(&institution.wallet, &institution.anoncreds)
+        .await?;
+    pending.sort();
+    let mut expected = vec![rev_reg.rev_reg_id.clone(), rev_reg_2.rev_reg_id.clone()];
+    expected.sort();
+    assert_eq!(pending, expected);
+    assert!(
+        !issuer_credential1
+            .is_revoked(&institution.ledger_read)
+            .await?
+    );
+
+    let report = service
+        .publish_pending(
+            &institution.wallet,
+            &institution.anoncreds,
+            &institution.ledger_read,
+            &institution.ledger_write,
+        )
+        .await?;
+    assert!(report.failed.is_empty());
+    assert_eq!(report.published_count(), 3);
+    assert_eq!(report.notifications_sent, 3);
+    assert_eq!(notifier.sent.lock().unwrap().len(), 3);
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+
+    for issuer in [
+        &issuer_credential1,
+        &issuer_credential2,
+        &issuer_credential3,
+    ] {
+        assert!(issuer.is_revoked(&institution.ledger_read).await?);
+    }
+    assert!(service
+        .pending_registries(&institution.wallet, &institution.anoncreds)
+        .await?
+        .is_empty());
+
+    // nothing left to publish
+    let report = service
+        .publish_pending(
+            &institution.wallet,
+            &institution.anoncreds,
+            &institution.ledger_read,
+            &institution.ledger_write,
+        )
+        .await?;
+    assert_eq!(report, PublishReport::default());
+    Ok(())
+}
+
+/// Ledger writer pausing every status list publish until released.
+#[derive(Debug)]
+struct PausingLedgerWrite<'a, L> {
+    inner: &'a L,
+    publishing: Notify,
+    release: Notify,
+}
+
+#[async_trait]
+impl<L: AnoncredsLedgerWrite> AnoncredsLedgerWrite for PausingLedgerWrite<'_, L> {
+    async fn publish_schema(
+        &self,
+        wallet: &impl BaseWallet,
+        schema_json: Schema,
+        submitter_did: &Did,
+        endorser_did: Option<&Did>,
+    ) -> VcxLedgerResult<()> {
+        self.inner
+            .publish_schema(wallet, schema_json, submitter_did, endorser_did)
+            .await
+    }
+
+    async fn publish_cred_def(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_def_json: CredentialDefinition,
+        submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        self.inner
+            .publish_cred_def(wallet, cred_def_json, submitter_did)
+            .await
+    }
+
+    async fn publish_rev_reg_def(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_def: RevocationRegistryDefinition,
+        submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        self.inner
+            .publish_rev_reg_def(wallet, rev_reg_def, submitter_did)
+            .await
+    }
+
+    async fn publish_rev_reg_delta(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+        rev_reg_entry_json: RevocationRegistryDelta,
+        submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        self.inner
+            .publish_rev_reg_delta(wallet, rev_reg_id, rev_reg_entry_json, submitter_did)
+            .await
+    }
+
+    async fn publish_rev_status_list(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_status_list: RevocationStatusList,
+        prev_rev_status_list: Option<&RevocationStatusList>,
+        submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        self.publishing.notify_one();
+        self.release.notified().await;
+        self.inner
+            .publish_rev_status_list(wallet, rev_status_list, prev_rev_status_list, submitter_did)
+            .await
+    }
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
+async fn test_agency_pool_revocation_service_keeps_revocation_made_while_publishing(
+) -> Result<(), Box<dyn Error>> {
+    let setup = SetupPoolDirectory::init().await;
+    let mut institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let mut consumer = create_test_agent(setup.genesis_file_path).await;
+
+    let (_, cred_def, rev_reg) = create_address_schema_creddef_revreg(
+        &institution.wallet,
+        &institution.ledger_read,
+        &institution.ledger_write,
+        &institution.anoncreds,
+        &institution.institution_did,
+    )
+    .await;
+    let issuer_credential1 = exchange_credential(
+        &mut consumer,
+        &mut institution,
+        credential_data_address_1().to_string(),
+        &cred_def,
+        &rev_reg,
+        None,
+    )
+    .await;
+    let issuer_credential2 = exchange_credential(
+        &mut consumer,
+        &mut institution,
+        credential_data_address_2().to_string(),
+        &cred_def,
+        &rev_reg,
+        None,
+    )
+    .await;
+
+    let service = RevocationService::new(institution.institution_did.clone());
+    service
+        .revoke_batch(
+            &institution.wallet,
+            &institution.anoncreds,
+            &institution.ledger_read,
+            [(
+                issuer_credential1.get_rev_reg_id()?,
+                issuer_credential1.get_rev_id()?,
+            )],
+        )
+        .await?;
+
+    let pausing_ledger_write = PausingLedgerWrite {
+        inner: &institution.ledger_write,
+        publishing: Notify::new(),
+        release: Notify::new(),
+    };
+    let (report, revoked) = tokio::join!(
+        service.publish_pending(
+            &institution.wallet,
+            &institution.anoncreds,
+            &institution.ledger_read,
+            &pausing_ledger_write,
+        ),
+        async {
+            pausing_ledger_write.publishing.notified().await;
+            let mut revoke = pin!(service.revoke_batch(
+                &institution.wallet,
+                &institution.anoncreds,
+                &institution.ledger_read,
+                [(
+                    issuer_credential2.get_rev_reg_id().unwrap(),
+                    issuer_credential2.get_rev_id().unwrap(),
+                )],
+            ));
+            // the revocation waits for the ongoing publish of its registry
+            assert!(
+                tokio::time::timeout(Duration::from_millis(500), revoke.as_mut())
+                    .await
+                    .is_err()
+            );
+            pausing_ledger_write.release.notify_one();
+            revoke.await
+        }
+    );
+    assert_eq!(report?.published_count(), 1);
+    revoked?;
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+    assert!(
+        issuer_credential1
+            .is_revoked(&institution.ledger_read)
+            .await?
+    );
+    assert!(
+        !issuer_credential2
+            .is_revoked(&institution.ledger_read)
+            .await?
+    );
+
+    // the second revocation is still pending
+    assert_eq!(
+        service
+            .pending_registries(&institution.wallet, &institution.anoncreds)
+            .await?,
+        vec![rev_reg.rev_reg_id.clone()]
+    );
+    let report = service
+        .publish_pending(
+            &institution.wallet,
+            &institution.anoncreds,
+            &institution.ledger_read,
+            &institution.ledger_write,
+        )
+        .await?;
+    assert_eq!(report.published_count(), 1);
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+    assert!(
+        issuer_credential2
+            .is_revoked(&institution.ledger_read)
+            .await?
+    );
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
+async fn test_agency_pool_prover_caches_revocation_states() -> Result<(), Box<dyn Error>> {
+    let setup = SetupPoolDirectory::init().await;
+    let mut institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let mut consumer = create_test_agent(setup.genesis_file_path).await;
+
+    let (schema, cred_def, rev_reg, issuer) =
+        issue_address_credential(&mut consumer, &mut institution).await;
+
+    let verifier = exchange_proof(
+        &mut institution,
+        &mut consumer,
+        &schema.schema_id,
+        cred_def.get_cred_def_id(),
+        Some("request1"),
+    )
+    .await;
+    assert_eq!(
+        verifier.get_verification_status(),
+        PresentationVerificationStatus::Valid
+    );
+    let cached = consumer
+        .wallet
+        .search_record(RecordCategory::RevState, None)
+        .await?;
+    assert_eq!(cached.len(), 1);
+    assert_eq!(
+        cached[0].name(),
+        format!("{}::{}", rev_reg.rev_reg_id, issuer.get_rev_id()?)
+    );
+
+    // the cached state gets updated to the status list with the revocation
+    revoke_credential_and_publish_accumulator(&mut institution, &issuer, &rev_reg).await;
+    tokio::time::sleep(Duration::from_millis(1000)).await;
+    let verifier = exchange_proof(
+        &mut institution,
+        &mut consumer,
+        &schema.schema_id,
+        cred_def.get_cred_def_id(),
+        Some("request2"),
+    )
+    .await;
+    assert_eq!(
+        verifier.get_verification_status(),
+        PresentationVerificationStatus::Invalid
+    );
+    assert_eq!(
+        consumer
+            .wallet
+            .search_record(RecordCategory::RevState, None)
+            .await?
+            .len(),
+        1
+    );
+    Ok(())
+}
+
+#[tokio::test]
+#[cfg_attr(not(feature = "in_memory_ledger"), ignore)]
+async fn test_agency_pool_holder_downloads_tails_files() -> Result<(), Box<dyn Error>> {
+    let setup = SetupPoolDirectory::init().await;
+    let mut institution = create_test_agent_trustee(setup.genesis_file_path.clone()).await;
+    let mut consumer = create_test_agent(setup.genesis_file_path).await;
+    let tails_server = LocalTailsServer::start().await;
+    let tails_client = HttpTailsServerClient::new(tails_server.url().parse()?, Default::default())?;
+
+    let schema = create_and_write_test_schema(
+        &institution.wallet,
+        &institution.anoncreds,
+        &institution.ledger_write,
+        &institution.institution_did,
+        &json!(attr_names_address_list()).to_string(),
+    )
+    .await;
+    let cred_def = create_and_write_test_cred_def(
+        &institution.wallet,
+        &institution.anoncreds,
+        &institution.ledger_read,
+        &institution.ledger_write,
+        &institution.institution_did,
+        &schema.schema_id,
+        true,
+    )
+    .await;
+    let mut rev_reg_manager = RevocationRegistryManager::new(
+        institution.institution_did.clone(),
+        cred_def.get_cred_def_id().to_owned(),
+        get_temp_dir_path().to_str().unwrap(),
+        10,
+        1,
+    )?;
+    let issuer = exchange_credential_with_rev_reg_manager(
+        &mut consumer,
+        &mut institution,
+        &mut rev_reg_manager,
+        &tails_client,
+        credential_data_address_1().to_string(),
+    )
+    .await;
+    let rev_reg_id = issuer.get_rev_reg_id()?;
+
+    let holder_tails_dir = get_temp_dir_path().join("holder_tails");
+    let _ = std::fs::remove_dir_all(&holder_tails_dir);
+    let tails_file_manager = TailsFileManager::new(
+        holder_tails_dir,
+        Arc::new(HttpTailsFileFetcher::new(Default::default())?),
+    );
+    let tails_file = tails_file_manager
+        .ensure_tails_file_for_rev_reg(&consumer.ledger_read, &rev_reg_id)
+        .await?;
+    assert_eq!(
+        Some(std::fs::read(&tails_file)?),
+        tails_server.tails_file(&rev_reg_id)
+    );
+
+    // the tails file is in use by the credential in the wallet
+    let removed = tails_file_manager
+        .remove_unused_for_wallet(&consumer.wallet, &consumer.anoncreds, &consumer.ledger_read)
+        .await?;
+    assert!(removed.is_empty());
+    assert!(tails_file.is_file());
+    Ok(())
+}
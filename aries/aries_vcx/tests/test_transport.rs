@@ -0,0 +1,149 @@
+use std::{
+    error::Error,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use aries_vcx::{
+    aries_vcx_wallet::wallet::base_wallet::{
+        record::Record, record_category::RecordCategory, BaseWallet,
+    },
+    errors::error::{AriesVcxError, AriesVcxErrorKind, VcxResult},
+    transport::{
+        queue::{FlushReport, OutboundQueue},
+        retry::RetryPolicy,
+        Transport,
+    },
+};
+use async_trait::async_trait;
+use test_utils::devsetup::dev_build_featured_wallet;
+use url::Url;
+
+/// Recipient which can be taken offline; records every message it receives while online.
+#[derive(Default)]
+struct Recipient {
+    offline: AtomicBool,
+    received: Mutex<Vec<Vec<u8>>>,
+}
+
+#[async_trait]
+impl Transport for Recipient {
+    async fn send_message(&self, msg: Vec<u8>, _service_endpoint: &Url) -> VcxResult<()> {
+        if self.offline.load(Ordering::SeqCst) {
+            return Err(AriesVcxError::from_msg(
+                AriesVcxErrorKind::PostMessageFailed,
+                "recipient is offline",
+            ));
+        }
+        self.received.lock().unwrap().push(msg);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_outbound_queue_delivers_once_recipient_is_back_online() -> Result<(), Box<dyn Error>>
+{
+    let (_, wallet) = dev_build_featured_wallet("000000000000000000000000Outbound").await;
+    let recipient = Recipient::default();
+    let policy = RetryPolicy {
+        max_retries: 2,
+        initial_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+        multiplier: 2,
+    };
+    let queue = OutboundQueue::new(wallet, &recipient, policy);
+    let url: Url = "http://example.org/didcomm".parse()?;
+
+    recipient.offline.store(true, Ordering::SeqCst);
+    queue.send_message(b"first".to_vec(), &url).await?;
+    queue.send_message(b"second".to_vec(), &url).await?;
+    assert_eq!(queue.pending_count().await?, 2);
+
+    let report = queue.flush().await?;
+    assert_eq!(
+        report,
+        FlushReport {
+            delivered: 0,
+            rescheduled: 2,
+            dropped: 0,
+            skipped: 0
+        }
+    );
+
+    recipient.offline.store(false, Ordering::SeqCst);
+    let report = queue.flush().await?;
+    assert_eq!(report.delivered, 2);
+    assert_eq!(queue.pending_count().await?, 0);
+
+    let mut received = recipient.received.lock().unwrap().clone();
+    received.sort();
+    assert_eq!(received, vec![b"first".to_vec(), b"second".to_vec()]);
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_outbound_queue_drops_messages_after_max_retries() -> Result<(), Box<dyn Error>> {
+    let (_, wallet) = dev_build_featured_wallet("00000000000000000000000Unreached").await;
+    let recipient = Recipient::default();
+    recipient.offline.store(true, Ordering::SeqCst);
+    let policy = RetryPolicy {
+        max_retries: 1,
+        initial_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+        multiplier: 2,
+    };
+    let queue = OutboundQueue::new(wallet, &recipient, policy);
+    let url: Url = "http://example.org/didcomm".parse()?;
+
+    queue.enqueue(b"lost".as_slice(), &url).await?;
+    assert_eq!(queue.flush().await?.rescheduled, 1);
+    assert_eq!(queue.flush().await?.dropped, 1);
+    assert_eq!(queue.pending_count().await?, 0);
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_outbound_queue_skips_malformed_records() -> Result<(), Box<dyn Error>> {
+    let (_, wallet) = dev_build_featured_wallet("00000000000000000000000Malformed").await;
+    wallet
+        .add_record(
+            Record::builder()
+                .category(RecordCategory::OutboundMessage)
+                .name("malformed".into())
+                .value("not a queued message".into())
+                .build(),
+        )
+        .await?;
+    let recipient = Recipient::default();
+    let queue = OutboundQueue::new(wallet, &recipient, RetryPolicy::no_retries());
+    let url: Url = "http://example.org/didcomm".parse()?;
+
+    queue.enqueue(b"valid".as_slice(), &url).await?;
+    let report = queue.flush().await?;
+    assert_eq!(report.delivered, 1);
+    assert_eq!(report.skipped, 1);
+    assert_eq!(queue.pending_count().await?, 1);
+    assert_eq!(*recipient.received.lock().unwrap(), vec![b"valid".to_vec()]);
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_outbound_queue_concurrent_flushes_deliver_once() -> Result<(), Box<dyn Error>> {
+    let (_, wallet) = dev_build_featured_wallet("0000000000000000000000Concurrent").await;
+    let recipient = Recipient::default();
+    let queue = OutboundQueue::new(wallet, &recipient, RetryPolicy::no_retries());
+    let url: Url = "http://example.org/didcomm".parse()?;
+
+    queue.enqueue(b"once".as_slice(), &url).await?;
+    let (first, second) = tokio::join!(queue.flush(), queue.flush());
+    assert_eq!(first?.delivered + second?.delivered, 1);
+    assert_eq!(*recipient.received.lock().unwrap(), vec![b"once".to_vec()]);
+    Ok(())
+}
@@ -7,6 +7,7 @@ use aries_vcx::{
     common::primitives::{
         credential_definition::CredentialDef, credential_schema::Schema,
         revocation_registry::RevocationRegistry,
+        revocation_registry_manager::RevocationRegistryManager, tails_server::TailsServerClient,
     },
     handlers::{
         issuance::{holder::Holder, issuer::Issuer},
@@ -332,6 +333,69 @@ pub async fn exchange_credential(
     issuer
 }
 
+pub async fn exchange_credential_with_rev_reg_manager(
+    consumer: &mut TestAgent<
+        impl IndyLedgerRead + AnoncredsLedgerRead,
+        impl IndyLedgerWrite + AnoncredsLedgerWrite,
+        impl BaseAnonCreds,
+        impl BaseWallet,
+    >,
+    institution: &mut TestAgent<
+        impl IndyLedgerRead + AnoncredsLedgerRead,
+        impl IndyLedgerWrite + AnoncredsLedgerWrite,
+        impl BaseAnonCreds,
+        impl BaseWallet,
+    >,
+    rev_reg_manager: &mut RevocationRegistryManager,
+    tails_server: &impl TailsServerClient,
+    credential_data: String,
+) -> Issuer {
+    let mut issuer = Issuer::create("1").unwrap();
+    issuer
+        .build_credential_offer_msg_with_rev_reg_manager(
+            &institution.wallet,
+            &institution.anoncreds,
+            &institution.ledger_write,
+            tails_server,
+            rev_reg_manager,
+            credential_data,
+            None,
+        )
+        .await
+        .unwrap();
+    // a registry might have just been published
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let mut holder =
+        create_credential_request(consumer, issuer.get_credential_offer().unwrap()).await;
+    issuer
+        .receive_request(holder.get_msg_credential_request().unwrap())
+        .await
+        .unwrap();
+    issuer
+        .build_credential_with_rev_reg_manager(
+            &institution.wallet,
+            &institution.anoncreds,
+            rev_reg_manager,
+        )
+        .await
+        .unwrap();
+    holder
+        .process_credential(
+            &consumer.wallet,
+            &consumer.ledger_read,
+            &consumer.anoncreds,
+            issuer.get_msg_issue_credential().unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(HolderState::Finished, holder.get_state());
+    assert_eq!(
+        holder.get_rev_reg_id().unwrap(),
+        issuer.get_rev_reg_id().unwrap()
+    );
+    issuer
+}
+
 pub async fn exchange_credential_with_proposal(
     consumer: &mut TestAgent<
         impl IndyLedgerRead + AnoncredsLedgerRead,
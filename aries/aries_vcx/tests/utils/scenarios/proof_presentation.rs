@@ -22,10 +22,13 @@ use aries_vcx::{
         proof_presentation::{prover::Prover, verifier::Verifier},
         util::PresentationProposalData,
     },
-    protocols::proof_presentation::{
-        prover::state_machine::ProverState,
-        verifier::{
-            state_machine::VerifierState, verification_status::PresentationVerificationStatus,
+    protocols::{
+        problem_report::descriptors,
+        proof_presentation::{
+            prover::state_machine::ProverState,
+            verifier::{
+                state_machine::VerifierState, verification_status::PresentationVerificationStatus,
+            },
         },
     },
 };
@@ -138,6 +141,11 @@ pub async fn receive_proof_proposal_rejection(prover: &mut Prover, rejection: Pr
     assert_eq!(prover.get_state(), ProverState::PresentationProposalSent);
     prover.process_aries_msg(rejection.into()).await.unwrap();
     assert_eq!(prover.get_state(), ProverState::Failed);
+    let problem = prover.get_problem().unwrap();
+    assert_eq!(
+        problem.problem_code().unwrap().descriptor,
+        descriptors::REJECTED
+    );
 }
 
 pub async fn create_proof_request_data(
@@ -260,6 +268,7 @@ pub async fn revoke_credential_and_publish_accumulator(
         .publish_local_revocations(
             &faber.wallet,
             &faber.anoncreds,
+            &faber.ledger_read,
             &faber.ledger_write,
             &faber.institution_did,
         )
@@ -325,6 +334,7 @@ pub async fn publish_revocation(
         .publish_local_revocations(
             &institution.wallet,
             &institution.anoncreds,
+            &institution.ledger_read,
             &institution.ledger_write,
             &institution.institution_did,
         )
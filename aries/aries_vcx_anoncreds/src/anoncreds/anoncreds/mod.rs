@@ -1,12 +1,12 @@
 mod type_conversion;
 
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     path::Path,
 };
 
 use anoncreds::{
-    cl::{Accumulator, RevocationRegistry as CryptoRevocationRegistry},
+    cl::RevocationRegistry as CryptoRevocationRegistry,
     data_types::{
         cred_def::{
             CredentialDefinition as AnoncredsCredentialDefinition,
@@ -41,9 +41,8 @@ use anoncreds_types::{
         },
         ledger::{
             cred_def::{CredentialDefinition, SignatureType},
-            rev_reg::RevocationRegistry,
             rev_reg_def::RevocationRegistryDefinition,
-            rev_reg_delta::{RevocationRegistryDelta, RevocationRegistryDeltaValue},
+            rev_reg_delta::RevocationRegistryDelta,
             rev_status_list::RevocationStatusList,
             schema::{AttributeNames, Schema},
         },
@@ -63,9 +62,12 @@ use anoncreds_types::{
     },
     utils::conversions::from_revocation_registry_delta_to_revocation_status_list,
 };
-use aries_vcx_wallet::wallet::{
-    base_wallet::{record::Record, record_category::RecordCategory, BaseWallet},
-    record_tags::{RecordTag, RecordTags},
+use aries_vcx_wallet::{
+    errors::error::VcxWalletError,
+    wallet::{
+        base_wallet::{record::Record, record_category::RecordCategory, BaseWallet},
+        record_tags::{RecordTag, RecordTags},
+    },
 };
 use async_trait::async_trait;
 use did_parser_nom::Did;
@@ -84,59 +86,6 @@ use crate::{
     errors::error::{VcxAnoncredsError, VcxAnoncredsResult},
 };
 
-fn from_revocation_status_list_to_revocation_registry_delta(
-    rev_status_list: &RevocationStatusList,
-    prev_accum: Option<Accumulator>,
-) -> VcxAnoncredsResult<RevocationRegistryDelta> {
-    let _issued = rev_status_list
-        .state()
-        .iter()
-        .enumerate()
-        .filter_map(
-            |(idx, is_revoked)| {
-                if !is_revoked {
-                    Some(idx as u32)
-                } else {
-                    None
-                }
-            },
-        )
-        .collect::<Vec<_>>();
-    let revoked = rev_status_list
-        .state()
-        .iter()
-        .enumerate()
-        .filter_map(
-            |(idx, is_revoked)| {
-                if *is_revoked {
-                    Some(idx as u32)
-                } else {
-                    None
-                }
-            },
-        )
-        .collect::<Vec<_>>();
-
-    let registry = CryptoRevocationRegistry {
-        accum: rev_status_list.accum().ok_or_else(|| {
-            VcxAnoncredsError::InvalidState(
-                "Revocation registry delta cannot be created from revocation status list without \
-                 accumulator"
-                    .into(),
-            )
-        })?,
-    };
-
-    Ok(RevocationRegistryDelta {
-        value: RevocationRegistryDeltaValue {
-            prev_accum,
-            accum: registry.accum,
-            issued: Default::default(), // TODO
-            revoked,
-        },
-    })
-}
-
 #[derive(Debug, Copy, Clone)]
 pub struct Anoncreds;
 
@@ -161,6 +110,48 @@ impl Anoncreds {
         serde_json::from_str(str_record.value()).map_err(From::from)
     }
 
+    /// Pending revocations used to be stored as a [RevocationRegistryDelta] holding every
+    /// revocation of the registry. Moves such a record, left by an earlier version, over to a
+    /// pending status list.
+    async fn migrate_legacy_pending_revocations(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+    ) -> VcxAnoncredsResult<Option<RevocationStatusList>> {
+        let rev_reg_delta: RevocationRegistryDelta = match self
+            .get_wallet_record_value(wallet, RecordCategory::RevRegDelta, &rev_reg_id.to_string())
+            .await
+        {
+            Ok(rev_reg_delta) => rev_reg_delta,
+            Err(VcxAnoncredsError::WalletError(VcxWalletError::RecordNotFound(_))) => {
+                return Ok(None)
+            }
+            Err(err) => return Err(err),
+        };
+        let rev_reg_def: RevocationRegistryDefinition = self
+            .get_wallet_record_value(wallet, RecordCategory::RevRegDef, &rev_reg_id.to_string())
+            .await?;
+
+        let rev_status_list = from_revocation_registry_delta_to_revocation_status_list(
+            &rev_reg_delta.value,
+            OffsetDateTime::now_utc().unix_timestamp() as u64,
+            &rev_reg_def.id,
+            rev_reg_def.value.max_cred_num as usize,
+            rev_reg_def.issuer_id,
+        )?;
+        let record = Record::builder()
+            .name(rev_reg_id.to_string())
+            .category(RecordCategory::RevStatusList)
+            .value(serde_json::to_string(&rev_status_list)?)
+            .build();
+        wallet.add_record(record).await?;
+        wallet
+            .delete_record(RecordCategory::RevRegDelta, &rev_reg_id.to_string())
+            .await?;
+
+        Ok(Some(rev_status_list))
+    }
+
     async fn get_link_secret(
         &self,
         wallet: &impl BaseWallet,
@@ -304,7 +295,7 @@ impl BaseAnonCreds for Anoncreds {
     ) -> VcxAnoncredsResult<(
         RevocationRegistryDefinitionId,
         RevocationRegistryDefinition,
-        RevocationRegistry,
+        RevocationStatusList,
     )> {
         let mut tails_writer = TailsFileWriter::new(Some(tails_dir.to_str().unwrap().to_string()));
 
@@ -394,7 +385,7 @@ impl BaseAnonCreds for Anoncreds {
         Ok((
             rev_reg_id.to_string().try_into()?,
             rev_reg_def.convert((rev_reg_id.to_string(),))?,
-            rev_reg.convert(())?,
+            rev_status_list.convert(())?,
         ))
     }
 
@@ -941,18 +932,10 @@ impl BaseAnonCreds for Anoncreds {
         cred_rev_id: u32,
     ) -> VcxAnoncredsResult<CredentialRevocationState> {
         let revoc_reg_def: AnoncredsRevocationRegistryDefinition = rev_reg_def_json.convert(())?;
-        let tails_file_hash = revoc_reg_def.value.tails_hash.as_str();
-
-        let mut tails_file_path = std::path::PathBuf::new();
-        tails_file_path.push(tails_dir);
-        tails_file_path.push(tails_file_hash);
-
-        let tails_path = tails_file_path.to_str().ok_or_else(|| {
-            VcxAnoncredsError::InvalidOption("tails file is not an unicode string".into())
-        })?;
+        let tails_path = tails_file_path(tails_dir, &revoc_reg_def)?;
 
         let rev_state = anoncreds::prover::create_or_update_revocation_state(
-            tails_path,
+            &tails_path,
             &revoc_reg_def,
             &rev_status_list.convert(())?,
             cred_rev_id,
@@ -963,6 +946,30 @@ impl BaseAnonCreds for Anoncreds {
         Ok(rev_state.convert(())?)
     }
 
+    async fn update_revocation_state(
+        &self,
+        tails_dir: &Path,
+        rev_reg_def_json: RevocationRegistryDefinition,
+        rev_status_list: RevocationStatusList,
+        cred_rev_id: u32,
+        rev_state: CredentialRevocationState,
+        old_rev_status_list: RevocationStatusList,
+    ) -> VcxAnoncredsResult<CredentialRevocationState> {
+        let revoc_reg_def: AnoncredsRevocationRegistryDefinition = rev_reg_def_json.convert(())?;
+        let tails_path = tails_file_path(tails_dir, &revoc_reg_def)?;
+
+        let rev_state = anoncreds::prover::create_or_update_revocation_state(
+            &tails_path,
+            &revoc_reg_def,
+            &rev_status_list.convert(())?,
+            cred_rev_id,
+            Some(&rev_state.convert(())?),
+            Some(&old_rev_status_list.convert(())?),
+        )?;
+
+        Ok(rev_state.convert(())?)
+    }
+
     async fn prover_store_credential(
         &self,
         wallet: &impl BaseWallet,
@@ -1106,27 +1113,31 @@ impl BaseAnonCreds for Anoncreds {
         wallet: &impl BaseWallet,
         rev_reg_id: &RevocationRegistryDefinitionId,
         cred_rev_id: u32,
-        ledger_rev_reg_delta_json: RevocationRegistryDelta,
+        ledger_rev_status_list: RevocationStatusList,
+    ) -> VcxAnoncredsResult<()> {
+        self.revoke_credentials_local(
+            wallet,
+            rev_reg_id,
+            BTreeSet::from([cred_rev_id]),
+            ledger_rev_status_list,
+        )
+        .await
+    }
+
+    async fn revoke_credentials_local(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+        cred_rev_ids: BTreeSet<u32>,
+        ledger_rev_status_list: RevocationStatusList,
     ) -> VcxAnoncredsResult<()> {
         let rev_reg_def: RevocationRegistryDefinition = self
             .get_wallet_record_value(wallet, RecordCategory::RevRegDef, &rev_reg_id.to_string())
             .await?;
 
-        let last_rev_reg_delta_stored = self.get_rev_reg_delta(wallet, rev_reg_id).await?;
-        let last_rev_reg_delta = last_rev_reg_delta_stored
-            .clone()
-            .unwrap_or(ledger_rev_reg_delta_json.clone());
-
-        let prev_accum = ledger_rev_reg_delta_json.value.accum;
-
-        let current_time = OffsetDateTime::now_utc().unix_timestamp() as u64;
-        let rev_status_list = from_revocation_registry_delta_to_revocation_status_list(
-            &last_rev_reg_delta.value,
-            current_time,
-            &rev_reg_def.id,
-            rev_reg_def.value.max_cred_num as usize,
-            rev_reg_def.issuer_id.clone(),
-        )?;
+        let pending_rev_status_list = self.get_pending_rev_status_list(wallet, rev_reg_id).await?;
+        let is_pending = pending_rev_status_list.is_some();
+        let rev_status_list = pending_rev_status_list.unwrap_or(ledger_rev_status_list);
 
         let cred_def = self
             .get_wallet_record_value(
@@ -1150,31 +1161,25 @@ impl BaseAnonCreds for Anoncreds {
             &rev_reg_def_priv,
             &rev_status_list.convert(())?,
             None,
-            Some(vec![cred_rev_id].into_iter().collect()),
+            Some(cred_rev_ids),
             None,
         )?;
+        let updated_rev_status_list: RevocationStatusList = updated_rev_status_list.convert(())?;
+        let updated_rev_status_list_str = serde_json::to_string(&updated_rev_status_list)?;
 
-        let updated_revocation_registry_delta =
-            from_revocation_status_list_to_revocation_registry_delta(
-                &updated_rev_status_list.convert(())?,
-                Some(prev_accum),
-            )?;
-        let updated_revocation_registry_delta_str =
-            serde_json::to_string(&updated_revocation_registry_delta)?;
-
-        if last_rev_reg_delta_stored.is_some() {
+        if is_pending {
             wallet
                 .update_record_value(
-                    RecordCategory::RevRegDelta,
+                    RecordCategory::RevStatusList,
                     &rev_reg_id.to_string(),
-                    &updated_revocation_registry_delta_str,
+                    &updated_rev_status_list_str,
                 )
                 .await?;
         } else {
             let record = Record::builder()
                 .name(rev_reg_id.to_string())
-                .category(RecordCategory::RevRegDelta)
-                .value(updated_revocation_registry_delta_str)
+                .category(RecordCategory::RevStatusList)
+                .value(updated_rev_status_list_str)
                 .build();
             wallet.add_record(record).await?;
         };
@@ -1182,38 +1187,69 @@ impl BaseAnonCreds for Anoncreds {
         Ok(())
     }
 
-    async fn get_rev_reg_delta(
+    async fn get_rev_reg_ids_with_pending_revocations(
+        &self,
+        wallet: &impl BaseWallet,
+    ) -> VcxAnoncredsResult<Vec<RevocationRegistryDefinitionId>> {
+        let mut rev_reg_ids = BTreeSet::new();
+        // Includes the registries with legacy pending revocations not migrated yet
+        for category in [RecordCategory::RevStatusList, RecordCategory::RevRegDelta] {
+            for record in wallet.search_record(category, None).await? {
+                rev_reg_ids.insert(record.name().to_owned());
+            }
+        }
+        rev_reg_ids
+            .into_iter()
+            .map(|rev_reg_id| Ok(RevocationRegistryDefinitionId::new(rev_reg_id)?))
+            .collect()
+    }
+
+    async fn get_pending_rev_status_list(
         &self,
         wallet: &impl BaseWallet,
         rev_reg_id: &RevocationRegistryDefinitionId,
-    ) -> VcxAnoncredsResult<Option<RevocationRegistryDelta>> {
-        let res_rev_reg_delta = self
-            .get_wallet_record_value::<RevocationRegistryDelta>(
+    ) -> VcxAnoncredsResult<Option<RevocationStatusList>> {
+        let res_rev_status_list = match self
+            .get_wallet_record_value::<RevocationStatusList>(
                 wallet,
-                RecordCategory::RevRegDelta,
+                RecordCategory::RevStatusList,
                 &rev_reg_id.to_string(),
             )
-            .await;
+            .await
+        {
+            Err(VcxAnoncredsError::WalletError(VcxWalletError::RecordNotFound(_))) => {
+                // Failing to migrate is an error, as the next revocation would otherwise
+                // start over from the ledger and drop the legacy pending revocations
+                return self
+                    .migrate_legacy_pending_revocations(wallet, rev_reg_id)
+                    .await;
+            }
+            res => res,
+        };
 
-        if let Err(err) = &res_rev_reg_delta {
+        if let Err(err) = &res_rev_status_list {
             warn!(
-                "get_rev_reg_delta >> Unable to get rev_reg_delta cache for rev_reg_id: {}, \
-                 error: {}",
+                "get_pending_rev_status_list >> Unable to get pending rev_status_list for \
+                 rev_reg_id: {}, error: {}",
                 rev_reg_id, err
             );
         }
 
-        Ok(res_rev_reg_delta.ok())
+        Ok(res_rev_status_list.ok())
     }
 
-    async fn clear_rev_reg_delta(
+    async fn clear_pending_rev_status_list(
         &self,
         wallet: &impl BaseWallet,
         rev_reg_id: &RevocationRegistryDefinitionId,
     ) -> VcxAnoncredsResult<()> {
-        if self.get_rev_reg_delta(wallet, rev_reg_id).await?.is_some() {
+        if self
+            .get_pending_rev_status_list(wallet, rev_reg_id)
+            .await?
+            .is_some()
+        {
             wallet
-                .delete_record(RecordCategory::RevRegDelta, &rev_reg_id.to_string())
+                .delete_record(RecordCategory::RevStatusList, &rev_reg_id.to_string())
                 .await?;
         }
 
@@ -1225,6 +1261,22 @@ impl BaseAnonCreds for Anoncreds {
     }
 }
 
+fn tails_file_path(
+    tails_dir: &Path,
+    rev_reg_def: &AnoncredsRevocationRegistryDefinition,
+) -> VcxAnoncredsResult<String> {
+    let mut tails_file_path = std::path::PathBuf::new();
+    tails_file_path.push(tails_dir);
+    tails_file_path.push(rev_reg_def.value.tails_hash.as_str());
+
+    tails_file_path
+        .to_str()
+        .map(ToOwned::to_owned)
+        .ok_or_else(|| {
+            VcxAnoncredsError::InvalidOption("tails file is not an unicode string".into())
+        })
+}
+
 fn get_rev_state(
     cred_id: &str,
     credential: &Credential,
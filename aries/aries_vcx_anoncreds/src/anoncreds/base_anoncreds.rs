@@ -1,4 +1,7 @@
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::Path,
+};
 
 use anoncreds_types::data_types::{
     identifiers::{
@@ -9,7 +12,6 @@ use anoncreds_types::data_types::{
         cred_def::CredentialDefinition,
         rev_reg::RevocationRegistry,
         rev_reg_def::RevocationRegistryDefinition,
-        rev_reg_delta::RevocationRegistryDelta,
         rev_status_list::RevocationStatusList,
         schema::{AttributeNames, Schema},
     },
@@ -67,7 +69,7 @@ pub trait BaseAnonCreds: std::fmt::Debug + Send + Sync {
     ) -> VcxAnoncredsResult<(
         RevocationRegistryDefinitionId,
         RevocationRegistryDefinition,
-        RevocationRegistry,
+        RevocationStatusList,
     )>;
 
     #[allow(clippy::too_many_arguments)]
@@ -143,6 +145,19 @@ pub trait BaseAnonCreds: std::fmt::Debug + Send + Sync {
         cred_rev_id: u32,
     ) -> VcxAnoncredsResult<CredentialRevocationState>;
 
+    /// Updates the witness of `rev_state`, created for `old_rev_status_list`, to
+    /// `rev_status_list`. Only the tails of the indices changed in between are read, which is
+    /// much cheaper than creating the state anew.
+    async fn update_revocation_state(
+        &self,
+        tails_dir: &Path,
+        rev_reg_def_json: RevocationRegistryDefinition,
+        rev_status_list: RevocationStatusList,
+        cred_rev_id: u32,
+        rev_state: CredentialRevocationState,
+        old_rev_status_list: RevocationStatusList,
+    ) -> VcxAnoncredsResult<CredentialRevocationState>;
+
     async fn prover_store_credential(
         &self,
         wallet: &impl BaseWallet,
@@ -176,22 +191,41 @@ pub trait BaseAnonCreds: std::fmt::Debug + Send + Sync {
     // TODO - FUTURE - think about moving this to somewhere else, as it aggregates other calls (not
     // PURE Anoncreds)
     // ^ YES
-    // TODO - review functionality below and convert to using statuslists (https://github.com/hyperledger/aries-vcx/issues/1309)
+    /// Revokes the credential in the pending status list of the registry, which starts from
+    /// `ledger_rev_status_list`, the status list currently published, if no revocation is
+    /// pending yet.
     async fn revoke_credential_local(
         &self,
         wallet: &impl BaseWallet,
         rev_reg_id: &RevocationRegistryDefinitionId,
         cred_rev_id: u32,
-        rev_reg_delta_json: RevocationRegistryDelta,
+        ledger_rev_status_list: RevocationStatusList,
     ) -> VcxAnoncredsResult<()>;
 
-    async fn get_rev_reg_delta(
+    /// Same as [BaseAnonCreds::revoke_credential_local], revoking all of `cred_rev_ids` in a
+    /// single update of the pending status list.
+    async fn revoke_credentials_local(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+        cred_rev_ids: BTreeSet<u32>,
+        ledger_rev_status_list: RevocationStatusList,
+    ) -> VcxAnoncredsResult<()>;
+
+    /// Registries with a pending status list, i.e. with revocations not published yet.
+    async fn get_rev_reg_ids_with_pending_revocations(
+        &self,
+        wallet: &impl BaseWallet,
+    ) -> VcxAnoncredsResult<Vec<RevocationRegistryDefinitionId>>;
+
+    /// Status list of the registry including the revocations not published yet, if any.
+    async fn get_pending_rev_status_list(
         &self,
         wallet: &impl BaseWallet,
         rev_reg_id: &RevocationRegistryDefinitionId,
-    ) -> VcxAnoncredsResult<Option<RevocationRegistryDelta>>;
+    ) -> VcxAnoncredsResult<Option<RevocationStatusList>>;
 
-    async fn clear_rev_reg_delta(
+    async fn clear_pending_rev_status_list(
         &self,
         wallet: &impl BaseWallet,
         rev_reg_id: &RevocationRegistryDefinitionId,
@@ -50,12 +50,54 @@ pub trait IndyLedgerWrite: Debug + Send + Sync {
         request: &str,
         endorser: &Did,
     ) -> VcxLedgerResult<String>;
+    /// Adds the signature of `endorser_did` to a request prepared for endorsement and submits
+    /// it, returning the endorsed request.
     async fn endorse_transaction(
         &self,
         wallet: &impl BaseWallet,
         endorser_did: &Did,
         request_json: &str,
-    ) -> VcxLedgerResult<()>;
+    ) -> VcxLedgerResult<String>;
+    /// Builds the NYM request of `author_did`, signed by the author, for `endorser_did` to
+    /// submit with [IndyLedgerWrite::endorse_transaction].
+    #[allow(clippy::too_many_arguments)]
+    async fn prepare_nym_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        author_did: &Did,
+        target_did: &Did,
+        target_vk: Option<&Key>,
+        role: Option<UpdateRole>,
+        alias: Option<String>,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String>;
+    /// Builds the SCHEMA request of `author_did`, signed by the author, for `endorser_did` to
+    /// submit with [IndyLedgerWrite::endorse_transaction].
+    async fn prepare_schema_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        schema: Schema,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String>;
+    /// Builds the CLAIM_DEF request of `author_did`, signed by the author, for `endorser_did` to
+    /// submit with [IndyLedgerWrite::endorse_transaction].
+    async fn prepare_cred_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_def: CredentialDefinition,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String>;
+    /// Builds the REVOC_REG_DEF request of `author_did`, signed by the author, for
+    /// `endorser_did` to submit with [IndyLedgerWrite::endorse_transaction].
+    async fn prepare_rev_reg_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_def: RevocationRegistryDefinition,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String>;
     async fn add_attr(
         &self,
         wallet: &impl BaseWallet,
@@ -159,6 +201,19 @@ pub trait AnoncredsLedgerWrite: Debug + Send + Sync {
         rev_reg_entry_json: RevocationRegistryDelta,
         submitter_did: &Did,
     ) -> VcxLedgerResult<()>;
+
+    /// Publish the complete [RevocationStatusList] of the revocation registry it refers to.
+    /// `prev_rev_status_list` is the status list it updates, as currently found on the ledger,
+    /// or `None` when publishing the initial status list of the registry.
+    ///
+    /// Ledgers storing revocation registry deltas publish the difference between both lists.
+    async fn publish_rev_status_list(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_status_list: RevocationStatusList,
+        prev_rev_status_list: Option<&RevocationStatusList>,
+        submitter_did: &Did,
+    ) -> VcxLedgerResult<()>;
 }
 
 /// Simple utility trait to determine whether the implementor can support reading/writing
@@ -26,10 +26,19 @@ use models::{
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use super::base_ledger::{AnoncredsLedgerRead, AnoncredsLedgerSupport};
+use super::{
+    base_ledger::{AnoncredsLedgerRead, AnoncredsLedgerSupport},
+    response_cacher::{
+        cache_object, get_cached_object, is_settled, noop::NoopResponseCacher, LedgerObjectKind,
+        ResponseCacher,
+    },
+};
 use crate::errors::error::{VcxLedgerError, VcxLedgerResult};
 
 mod models;
+mod write;
+
+pub use write::{CheqdAnoncredsLedgerWrite, CheqdResource, CheqdResourceWriter};
 
 const SCHEMA_RESOURCE_TYPE: &str = "anonCredsSchema";
 const CRED_DEF_RESOURCE_TYPE: &str = "anonCredsCredDef";
@@ -39,14 +48,32 @@ const STATUS_LIST_RESOURCE_TYPE: &str = "anonCredsStatusList";
 /// Struct for resolving anoncreds objects from cheqd ledgers using the cheqd
 /// anoncreds object method: https://docs.cheqd.io/product/advanced/anoncreds.
 ///
-/// Relies on a cheqd DID resolver ([DidCheqdResolver]) to fetch DID resources.
-pub struct CheqdAnoncredsLedgerRead {
+/// Relies on a cheqd DID resolver ([DidCheqdResolver]) to fetch DID resources. Resolved objects
+/// are cached with the [ResponseCacher] set by [CheqdAnoncredsLedgerRead::with_response_cacher],
+/// nothing is cached by default.
+pub struct CheqdAnoncredsLedgerRead<V: ResponseCacher = NoopResponseCacher> {
     resolver: Arc<DidCheqdResolver>,
+    response_cacher: V,
 }
 
 impl CheqdAnoncredsLedgerRead {
     pub fn new(resolver: Arc<DidCheqdResolver>) -> Self {
-        Self { resolver }
+        Self {
+            resolver,
+            response_cacher: NoopResponseCacher,
+        }
+    }
+}
+
+impl<V: ResponseCacher> CheqdAnoncredsLedgerRead<V> {
+    pub fn with_response_cacher<C: ResponseCacher>(
+        self,
+        response_cacher: C,
+    ) -> CheqdAnoncredsLedgerRead<C> {
+        CheqdAnoncredsLedgerRead {
+            resolver: self.resolver,
+            response_cacher,
+        }
     }
 
     fn check_resource_type(&self, resource: &DidResource, expected: &str) -> VcxLedgerResult<()> {
@@ -89,24 +116,39 @@ pub struct RevocationRegistryDefinitionAdditionalMetadata {
 }
 
 #[async_trait]
-impl AnoncredsLedgerRead for CheqdAnoncredsLedgerRead {
+impl<V: ResponseCacher> AnoncredsLedgerRead for CheqdAnoncredsLedgerRead<V> {
     type RevocationRegistryDefinitionAdditionalMetadata =
         RevocationRegistryDefinitionAdditionalMetadata;
 
     async fn get_schema(&self, schema_id: &SchemaId, _: Option<&Did>) -> VcxLedgerResult<Schema> {
+        let cache_id = schema_id.to_string();
+        if let Some(schema) =
+            get_cached_object(&self.response_cacher, LedgerObjectKind::Schema, &cache_id).await
+        {
+            return Ok(schema);
+        }
+
         let url = DidUrl::parse(schema_id.to_string())?;
         let resource = self.resolver.resolve_resource(&url).await?;
         self.check_resource_type(&resource, SCHEMA_RESOURCE_TYPE)?;
 
         let data: CheqdAnoncredsSchema = serde_json::from_slice(&resource.content)?;
-        Ok(Schema {
+        let schema = Schema {
             id: schema_id.to_owned(),
             seq_no: None,
             name: data.name,
             version: data.version,
             attr_names: AttributeNames(data.attr_names),
             issuer_id: extract_issuer_id(&url)?,
-        })
+        };
+        cache_object(
+            &self.response_cacher,
+            LedgerObjectKind::Schema,
+            &cache_id,
+            schema.clone(),
+        )
+        .await;
+        Ok(schema)
     }
 
     async fn get_cred_def(
@@ -114,19 +156,34 @@ impl AnoncredsLedgerRead for CheqdAnoncredsLedgerRead {
         cred_def_id: &CredentialDefinitionId,
         _: Option<&Did>,
     ) -> VcxLedgerResult<CredentialDefinition> {
+        let cache_id = cred_def_id.to_string();
+        if let Some(cred_def) =
+            get_cached_object(&self.response_cacher, LedgerObjectKind::CredDef, &cache_id).await
+        {
+            return Ok(cred_def);
+        }
+
         let url = DidUrl::parse(cred_def_id.to_string())?;
         let resource = self.resolver.resolve_resource(&url).await?;
         self.check_resource_type(&resource, CRED_DEF_RESOURCE_TYPE)?;
 
         let data: CheqdAnoncredsCredentialDefinition = serde_json::from_slice(&resource.content)?;
-        Ok(CredentialDefinition {
+        let cred_def = CredentialDefinition {
             id: cred_def_id.to_owned(),
             schema_id: data.schema_id,
             signature_type: data.signature_type,
             tag: data.tag,
             value: data.value,
             issuer_id: extract_issuer_id(&url)?,
-        })
+        };
+        cache_object(
+            &self.response_cacher,
+            LedgerObjectKind::CredDef,
+            &cache_id,
+            cred_def.clone(),
+        )
+        .await;
+        Ok(cred_def)
     }
 
     async fn get_rev_reg_def_json(
@@ -136,6 +193,17 @@ impl AnoncredsLedgerRead for CheqdAnoncredsLedgerRead {
         RevocationRegistryDefinition,
         RevocationRegistryDefinitionAdditionalMetadata,
     )> {
+        let cache_id = rev_reg_id.to_string();
+        if let Some(cached) = get_cached_object(
+            &self.response_cacher,
+            LedgerObjectKind::RevRegDef,
+            &cache_id,
+        )
+        .await
+        {
+            return Ok(cached);
+        }
+
         let (rev_reg_def, resource_meta) = self
             .get_rev_reg_def_with_resource_metadata(rev_reg_id)
             .await?;
@@ -143,6 +211,13 @@ impl AnoncredsLedgerRead for CheqdAnoncredsLedgerRead {
         let meta = RevocationRegistryDefinitionAdditionalMetadata {
             resource_name: resource_meta.resource_name,
         };
+        cache_object(
+            &self.response_cacher,
+            LedgerObjectKind::RevRegDef,
+            &cache_id,
+            (rev_reg_def.clone(), meta.clone()),
+        )
+        .await;
 
         Ok((rev_reg_def, meta))
     }
@@ -165,6 +240,20 @@ impl AnoncredsLedgerRead for CheqdAnoncredsLedgerRead {
         timestamp: u64,
         rev_reg_def_meta: Option<&RevocationRegistryDefinitionAdditionalMetadata>,
     ) -> VcxLedgerResult<(RevocationStatusList, u64)> {
+        // a status list may still be published for a recent or future timestamp
+        let cache_id = is_settled(timestamp).then(|| format!("{rev_reg_id}:{timestamp}"));
+        if let Some(cache_id) = &cache_id {
+            if let Some(cached) = get_cached_object(
+                &self.response_cacher,
+                LedgerObjectKind::RevStatusList,
+                cache_id,
+            )
+            .await
+            {
+                return Ok(cached);
+            }
+        }
+
         let rev_reg_def_url = DidUrl::parse(rev_reg_id.to_string())?;
 
         // refetch if needed
@@ -211,6 +300,15 @@ impl AnoncredsLedgerRead for CheqdAnoncredsLedgerRead {
             accum: data.accum,
             timestamp: Some(timestamp),
         };
+        if let Some(cache_id) = &cache_id {
+            cache_object(
+                &self.response_cacher,
+                LedgerObjectKind::RevStatusList,
+                cache_id,
+                (status_list.clone(), timestamp),
+            )
+            .await;
+        }
 
         Ok((status_list, timestamp))
     }
@@ -246,7 +344,7 @@ fn extract_issuer_id(url: &DidUrl) -> VcxLedgerResult<IssuerId> {
         .map_err(|e| VcxLedgerError::InvalidInput(format!("DID is not an IssuerId {e}")))
 }
 
-impl AnoncredsLedgerSupport for CheqdAnoncredsLedgerRead {
+impl<V: ResponseCacher> AnoncredsLedgerSupport for CheqdAnoncredsLedgerRead<V> {
     fn supports_schema(&self, id: &SchemaId) -> bool {
         let Ok(url) = DidUrl::parse(id.to_string()) else {
             return false;
@@ -269,7 +367,7 @@ impl AnoncredsLedgerSupport for CheqdAnoncredsLedgerRead {
     }
 }
 
-impl Debug for CheqdAnoncredsLedgerRead {
+impl<V: ResponseCacher> Debug for CheqdAnoncredsLedgerRead<V> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "CheqdAnoncredsLedgerRead instance")
     }
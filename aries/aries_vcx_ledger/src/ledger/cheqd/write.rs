@@ -0,0 +1,225 @@
+use std::{fmt::Debug, sync::Arc};
+
+use anoncreds_types::data_types::{
+    identifiers::rev_reg_def_id::RevocationRegistryDefinitionId,
+    ledger::{
+        cred_def::CredentialDefinition, rev_reg_def::RevocationRegistryDefinition,
+        rev_reg_delta::RevocationRegistryDelta, rev_status_list::RevocationStatusList,
+        schema::Schema,
+    },
+};
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use did_cheqd::resolution::resolver::DidCheqdResolver;
+use did_parser_nom::{Did, DidUrl};
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::{
+    models::{
+        CheqdAnoncredsCredentialDefinition, CheqdAnoncredsRevocationRegistryDefinition,
+        CheqdAnoncredsRevocationStatusList, CheqdAnoncredsSchema,
+    },
+    CheqdAnoncredsLedgerRead, CRED_DEF_RESOURCE_TYPE, REV_REG_DEF_RESOURCE_TYPE,
+    SCHEMA_RESOURCE_TYPE, STATUS_LIST_RESOURCE_TYPE,
+};
+use crate::{
+    errors::error::{VcxLedgerError, VcxLedgerResult},
+    ledger::base_ledger::AnoncredsLedgerWrite,
+};
+
+/// A DID-Linked Resource to create on a cheqd ledger: https://docs.cheqd.io/product/advanced/did-linked-resources.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheqdResource {
+    /// Unique identifier of the DID the resource is linked to.
+    pub collection_id: String,
+    /// UUID of the resource, the last segment of its DID URL.
+    pub id: String,
+    pub name: String,
+    pub resource_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Submits `MsgCreateResource` transactions to a cheqd ledger, signed with the keys of the DID
+/// controlling the resource collection.
+#[async_trait]
+pub trait CheqdResourceWriter: Send + Sync {
+    async fn create_resource(
+        &self,
+        wallet: &impl BaseWallet,
+        resource: CheqdResource,
+    ) -> VcxLedgerResult<()>;
+}
+
+/// Struct for publishing anoncreds objects to cheqd ledgers as DID-Linked Resources, following
+/// the cheqd anoncreds object method: https://docs.cheqd.io/product/advanced/anoncreds.
+///
+/// Resources are created by a [CheqdResourceWriter], while a [DidCheqdResolver] is used to look
+/// up the revocation registry definition a status list belongs to.
+pub struct CheqdAnoncredsLedgerWrite<W> {
+    reader: CheqdAnoncredsLedgerRead,
+    writer: W,
+}
+
+impl<W> CheqdAnoncredsLedgerWrite<W>
+where
+    W: CheqdResourceWriter,
+{
+    pub fn new(resolver: Arc<DidCheqdResolver>, writer: W) -> Self {
+        Self {
+            reader: CheqdAnoncredsLedgerRead::new(resolver),
+            writer,
+        }
+    }
+
+    async fn create_resource(
+        &self,
+        wallet: &impl BaseWallet,
+        url: &DidUrl,
+        name: String,
+        resource_type: &str,
+        data: &impl Serialize,
+    ) -> VcxLedgerResult<()> {
+        let collection_id = url.id().ok_or_else(|| {
+            VcxLedgerError::InvalidInput(format!("DID URL is missing a DID: {url}"))
+        })?;
+        let id = url
+            .path()
+            .and_then(|path| path.strip_prefix("/resources/"))
+            .ok_or_else(|| {
+                VcxLedgerError::InvalidInput(format!("DID URL is not a resource URL: {url}"))
+            })?;
+        let resource = CheqdResource {
+            collection_id: collection_id.to_owned(),
+            id: id.to_owned(),
+            name,
+            resource_type: resource_type.to_owned(),
+            data: serde_json::to_vec(data)?,
+        };
+        self.writer.create_resource(wallet, resource).await
+    }
+}
+
+#[async_trait]
+impl<W> AnoncredsLedgerWrite for CheqdAnoncredsLedgerWrite<W>
+where
+    W: CheqdResourceWriter,
+{
+    async fn publish_schema(
+        &self,
+        wallet: &impl BaseWallet,
+        schema_json: Schema,
+        _submitter_did: &Did,
+        _endorser_did: Option<&Did>,
+    ) -> VcxLedgerResult<()> {
+        let url = DidUrl::parse(schema_json.id.to_string())?;
+        let data = CheqdAnoncredsSchema {
+            name: schema_json.name.clone(),
+            version: schema_json.version,
+            attr_names: schema_json.attr_names.0,
+        };
+        self.create_resource(wallet, &url, schema_json.name, SCHEMA_RESOURCE_TYPE, &data)
+            .await
+    }
+
+    async fn publish_cred_def(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_def_json: CredentialDefinition,
+        _submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        let url = DidUrl::parse(cred_def_json.id.to_string())?;
+        let data = CheqdAnoncredsCredentialDefinition {
+            schema_id: cred_def_json.schema_id,
+            signature_type: cred_def_json.signature_type,
+            tag: cred_def_json.tag.clone(),
+            value: cred_def_json.value,
+        };
+        self.create_resource(
+            wallet,
+            &url,
+            cred_def_json.tag,
+            CRED_DEF_RESOURCE_TYPE,
+            &data,
+        )
+        .await
+    }
+
+    async fn publish_rev_reg_def(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_def: RevocationRegistryDefinition,
+        _submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        let url = DidUrl::parse(rev_reg_def.id.to_string())?;
+        let data = CheqdAnoncredsRevocationRegistryDefinition {
+            revoc_def_type: rev_reg_def.revoc_def_type,
+            cred_def_id: rev_reg_def.cred_def_id,
+            tag: rev_reg_def.tag.clone(),
+            value: rev_reg_def.value,
+        };
+        self.create_resource(
+            wallet,
+            &url,
+            rev_reg_def.tag,
+            REV_REG_DEF_RESOURCE_TYPE,
+            &data,
+        )
+        .await
+    }
+
+    async fn publish_rev_reg_delta(
+        &self,
+        _wallet: &impl BaseWallet,
+        _rev_reg_id: &RevocationRegistryDefinitionId,
+        _rev_reg_entry_json: RevocationRegistryDelta,
+        _submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        Err(VcxLedgerError::UnimplementedFeature(
+            "publish_rev_reg_delta not supported for cheqd, publish status lists instead".into(),
+        ))
+    }
+
+    async fn publish_rev_status_list(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_status_list: RevocationStatusList,
+        _prev_rev_status_list: Option<&RevocationStatusList>,
+        _submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        let rev_reg_id = rev_status_list.rev_reg_def_id.as_ref().ok_or_else(|| {
+            VcxLedgerError::InvalidInput(
+                "Revocation status list is missing its revocation registry id".into(),
+            )
+        })?;
+        // status lists are versions of a resource named after the registry definition resource
+        let (_, rev_reg_def_meta) = self
+            .reader
+            .get_rev_reg_def_with_resource_metadata(rev_reg_id)
+            .await?;
+        let rev_reg_def_url = DidUrl::parse(rev_reg_id.to_string())?;
+        let did = rev_reg_def_url.did().ok_or_else(|| {
+            VcxLedgerError::InvalidInput(format!("DID URL missing DID {rev_reg_def_url}"))
+        })?;
+        let url = DidUrl::parse(format!("{did}/resources/{}", Uuid::new_v4()))?;
+
+        let data = CheqdAnoncredsRevocationStatusList {
+            revocation_list: rev_status_list.revocation_list,
+            accum: rev_status_list.accum,
+        };
+        self.create_resource(
+            wallet,
+            &url,
+            rev_reg_def_meta.resource_name,
+            STATUS_LIST_RESOURCE_TYPE,
+            &data,
+        )
+        .await
+    }
+}
+
+impl<W> Debug for CheqdAnoncredsLedgerWrite<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CheqdAnoncredsLedgerWrite instance")
+    }
+}
@@ -0,0 +1,417 @@
+use std::{fs, path::Path, time::Duration};
+
+use futures::future::join_all;
+use indy_vdr::config::PoolConfig;
+use log::{info, warn};
+use serde_json::Value;
+use tokio::{net::TcpStream, time::timeout};
+
+use crate::{
+    errors::error::{VcxLedgerError, VcxLedgerResult},
+    ledger::{
+        base_ledger::TxnAuthrAgrmtOptions,
+        indy_vdr_ledger::{
+            indyvdr_build_ledger_read, indyvdr_build_ledger_write, DefaultIndyLedgerRead,
+            DefaultIndyLedgerWrite,
+        },
+        multi_ledger::MultiLedgerAnoncredsRead,
+        request_submitter::vdr_ledger::{IndyVdrLedgerPool, IndyVdrSubmitter},
+        response_cacher::in_memory::InMemoryResponseCacherConfig,
+    },
+};
+
+const NODE_TXN_TYPE: &str = "0";
+const VALIDATOR_SERVICE: &str = "VALIDATOR";
+
+/// Configuration of a named pool managed by [IndyPoolManager].
+#[derive(Clone, Debug)]
+pub struct IndyPoolConfig {
+    /// Name of the pool, also used as the did:sov namespace of its DIDs (e.g. `idunion` for
+    /// `did:sov:idunion:<id>`).
+    pub name: String,
+    /// Genesis transactions of the pool. The file is rewritten when the pool is refreshed.
+    pub genesis_file_path: String,
+    pub indy_vdr_config: PoolConfig,
+    pub exclude_nodes: Vec<String>,
+    /// Pools with a higher priority are asked first when resolving unqualified identifiers.
+    pub priority: u32,
+}
+
+impl IndyPoolConfig {
+    pub fn new(name: impl Into<String>, genesis_file_path: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            genesis_file_path: genesis_file_path.into(),
+            indy_vdr_config: PoolConfig::default(),
+            exclude_nodes: vec![],
+            priority: 0,
+        }
+    }
+}
+
+/// A node of the pool, as described by the NODE transactions of the pool ledger.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolNode {
+    pub alias: String,
+    /// Address (`ip:port`) clients connect to.
+    pub client_address: Option<String>,
+    pub validator: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeStatus {
+    pub alias: String,
+    pub client_address: Option<String>,
+    pub reachable: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolStatus {
+    pub name: String,
+    /// Number of pool ledger transactions known to the pool.
+    pub transactions: usize,
+    /// Reachability of the validator nodes.
+    pub nodes: Vec<NodeStatus>,
+}
+
+impl PoolStatus {
+    pub fn reachable_nodes(&self) -> usize {
+        self.nodes.iter().filter(|node| node.reachable).count()
+    }
+}
+
+#[derive(Debug)]
+struct ManagedPool {
+    config: IndyPoolConfig,
+    pool: IndyVdrLedgerPool,
+}
+
+/// Manages several named Indy pools (e.g. Sovrin MainNet and IDUnion), keeping their genesis
+/// files up to date and providing ledger readers and writers for each of them.
+#[derive(Debug, Default)]
+pub struct IndyPoolManager {
+    // kept in priority order
+    pools: Vec<ManagedPool>,
+}
+
+impl IndyPoolManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_configs(configs: Vec<IndyPoolConfig>) -> VcxLedgerResult<Self> {
+        let mut manager = Self::new();
+        for config in configs {
+            manager.add_pool(config)?;
+        }
+        Ok(manager)
+    }
+
+    /// Opens the pool described by the genesis file of `config`.
+    pub fn add_pool(&mut self, config: IndyPoolConfig) -> VcxLedgerResult<()> {
+        info!(
+            "IndyPoolManager::add_pool >> name: {}, genesis_file_path: {}",
+            config.name, config.genesis_file_path
+        );
+        if self.pools.iter().any(|p| p.config.name == config.name) {
+            return Err(VcxLedgerError::InvalidInput(format!(
+                "Pool {} is already registered",
+                config.name
+            )));
+        }
+        let pool = IndyVdrLedgerPool::new(
+            config.genesis_file_path.clone(),
+            config.indy_vdr_config.clone(),
+            config.exclude_nodes.clone(),
+        )?;
+        let position = self
+            .pools
+            .iter()
+            .position(|p| p.config.priority < config.priority)
+            .unwrap_or(self.pools.len());
+        self.pools.insert(position, ManagedPool { config, pool });
+        Ok(())
+    }
+
+    /// Names of the managed pools, in priority order.
+    pub fn pool_names(&self) -> Vec<&str> {
+        self.pools.iter().map(|p| p.config.name.as_str()).collect()
+    }
+
+    fn managed_pool(&self, name: &str) -> VcxLedgerResult<&ManagedPool> {
+        self.pools
+            .iter()
+            .find(|p| p.config.name == name)
+            .ok_or_else(|| VcxLedgerError::InvalidInput(format!("Unknown pool {name}")))
+    }
+
+    pub fn pool(&self, name: &str) -> VcxLedgerResult<&IndyVdrLedgerPool> {
+        Ok(&self.managed_pool(name)?.pool)
+    }
+
+    /// Catches up with the pool ledger of pool `name` and persists the transactions to its
+    /// genesis file if new ones were found. Returns the number of new transactions.
+    pub async fn refresh(&self, name: &str) -> VcxLedgerResult<usize> {
+        let managed = self.managed_pool(name)?;
+        let known = managed.pool.transactions().await?.len();
+        managed.pool.refresh().await?;
+        let transactions = managed.pool.transactions().await?;
+
+        let new_transactions = transactions.len().saturating_sub(known);
+        if new_transactions > 0 {
+            info!("Pool {name} caught up with {new_transactions} new transactions");
+            persist_genesis_file(&managed.config.genesis_file_path, &transactions)?;
+        }
+        Ok(new_transactions)
+    }
+
+    /// Refreshes every managed pool, reporting the outcome for each of them.
+    pub async fn refresh_all(&self) -> Vec<(String, VcxLedgerResult<usize>)> {
+        let mut results = Vec::with_capacity(self.pools.len());
+        for managed in &self.pools {
+            let name = managed.config.name.clone();
+            let result = self.refresh(&name).await;
+            if let Err(err) = &result {
+                warn!("Failed to refresh pool {name}; err: {err}");
+            }
+            results.push((name, result));
+        }
+        results
+    }
+
+    /// Checks whether the validator nodes of pool `name` accept connections within
+    /// `connect_timeout`.
+    pub async fn status(
+        &self,
+        name: &str,
+        connect_timeout: Duration,
+    ) -> VcxLedgerResult<PoolStatus> {
+        let managed = self.managed_pool(name)?;
+        let transactions = managed.pool.transactions().await?;
+        let validators = pool_nodes(&transactions)?
+            .into_iter()
+            .filter(|node| node.validator)
+            .collect();
+
+        Ok(PoolStatus {
+            name: name.to_owned(),
+            transactions: transactions.len(),
+            nodes: check_nodes(validators, connect_timeout).await,
+        })
+    }
+
+    pub fn ledger_read(
+        &self,
+        name: &str,
+        cache_config: InMemoryResponseCacherConfig,
+    ) -> VcxLedgerResult<DefaultIndyLedgerRead> {
+        let submitter = IndyVdrSubmitter::new(self.pool(name)?.clone());
+        indyvdr_build_ledger_read(submitter, cache_config)
+    }
+
+    pub fn ledger_write(
+        &self,
+        name: &str,
+        taa_options: Option<TxnAuthrAgrmtOptions>,
+    ) -> VcxLedgerResult<DefaultIndyLedgerWrite> {
+        let submitter = IndyVdrSubmitter::new(self.pool(name)?.clone());
+        Ok(indyvdr_build_ledger_write(submitter, taa_options))
+    }
+
+    /// Ledger readers of all managed pools keyed by pool name, in priority order. Used to
+    /// resolve did:sov DIDs by their namespace.
+    pub fn ledger_reads(
+        &self,
+        cache_config: InMemoryResponseCacherConfig,
+    ) -> VcxLedgerResult<Vec<(String, DefaultIndyLedgerRead)>> {
+        self.pools
+            .iter()
+            .map(|p| {
+                let read = self.ledger_read(&p.config.name, cache_config.clone())?;
+                Ok((p.config.name.clone(), read))
+            })
+            .collect()
+    }
+
+    /// Registers a reader for each managed pool, named after the pool and with its priority.
+    pub fn register_anoncreds_readers(
+        &self,
+        mut multi_ledger: MultiLedgerAnoncredsRead,
+        cache_config: InMemoryResponseCacherConfig,
+    ) -> VcxLedgerResult<MultiLedgerAnoncredsRead> {
+        for p in &self.pools {
+            let read = self.ledger_read(&p.config.name, cache_config.clone())?;
+            multi_ledger =
+                multi_ledger.register_named_reader(p.config.name.clone(), p.config.priority, read);
+        }
+        Ok(multi_ledger)
+    }
+}
+
+/// Current state of the pool nodes after applying the NODE transactions in order.
+pub fn pool_nodes(transactions: &[String]) -> VcxLedgerResult<Vec<PoolNode>> {
+    let mut nodes: Vec<(String, PoolNode)> = vec![];
+    for transaction in transactions {
+        let transaction: Value = serde_json::from_str(transaction)?;
+        let txn = &transaction["txn"];
+        if txn["type"].as_str() != Some(NODE_TXN_TYPE) {
+            continue;
+        }
+        let dest = txn["data"]["dest"].as_str().ok_or_else(|| {
+            VcxLedgerError::InvalidLedgerResponse(format!(
+                "NODE transaction without dest: {transaction}"
+            ))
+        })?;
+        let data = &txn["data"]["data"];
+
+        let index = match nodes.iter().position(|(d, _)| d == dest) {
+            Some(index) => index,
+            None => {
+                nodes.push((
+                    dest.to_owned(),
+                    PoolNode {
+                        alias: dest.to_owned(),
+                        client_address: None,
+                        validator: false,
+                    },
+                ));
+                nodes.len() - 1
+            }
+        };
+        let node = &mut nodes[index].1;
+        if let Some(alias) = data["alias"].as_str() {
+            node.alias = alias.to_owned();
+        }
+        if let (Some(ip), Some(port)) = (data["client_ip"].as_str(), data["client_port"].as_u64()) {
+            node.client_address = Some(format!("{ip}:{port}"));
+        }
+        if let Some(services) = data["services"].as_array() {
+            node.validator = services
+                .iter()
+                .any(|service| service.as_str() == Some(VALIDATOR_SERVICE));
+        }
+    }
+    Ok(nodes.into_iter().map(|(_, node)| node).collect())
+}
+
+/// Tries to connect to the client address of each node concurrently.
+pub async fn check_nodes(nodes: Vec<PoolNode>, connect_timeout: Duration) -> Vec<NodeStatus> {
+    join_all(nodes.into_iter().map(|node| async move {
+        let error = match &node.client_address {
+            None => Some("Node has no client address".to_owned()),
+            Some(address) => match timeout(connect_timeout, TcpStream::connect(address)).await {
+                Ok(Ok(_)) => None,
+                Ok(Err(err)) => Some(err.to_string()),
+                Err(_) => Some(format!("Connection timed out after {connect_timeout:?}")),
+            },
+        };
+        NodeStatus {
+            alias: node.alias,
+            client_address: node.client_address,
+            reachable: error.is_none(),
+            error,
+        }
+    }))
+    .await
+}
+
+fn persist_genesis_file(genesis_file_path: &str, transactions: &[String]) -> VcxLedgerResult<()> {
+    let map_err = |err: std::io::Error| {
+        VcxLedgerError::InvalidState(format!(
+            "Failed to persist genesis file {genesis_file_path}: {err}"
+        ))
+    };
+    // write to a temporary file first, so the genesis file is never left half-written
+    let tmp_path = Path::new(genesis_file_path).with_extension("tmp");
+    fs::write(&tmp_path, transactions.join("\n")).map_err(map_err)?;
+    fs::rename(&tmp_path, genesis_file_path).map_err(map_err)
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::ledger::indy::pool::test_utils::{get_temp_file_path, get_txns_localhost};
+
+    #[test]
+    fn test_pool_nodes_from_genesis() {
+        let nodes = pool_nodes(&get_txns_localhost("127.0.0.1".to_owned())).unwrap();
+        assert_eq!(nodes.len(), 4);
+        assert_eq!(nodes[0].alias, "Node1");
+        assert_eq!(nodes[0].client_address.as_deref(), Some("127.0.0.1:9702"));
+        assert!(nodes.iter().all(|node| node.validator));
+    }
+
+    #[test]
+    fn test_pool_nodes_apply_node_updates() {
+        let mut transactions = get_txns_localhost("127.0.0.1".to_owned());
+        let node1: Value = serde_json::from_str(&transactions[0]).unwrap();
+        let dest = node1["txn"]["data"]["dest"].as_str().unwrap();
+        transactions.push(
+            serde_json::json!({
+                "txn": {
+                    "data": {
+                        "data": {"alias": "Node1", "client_ip": "10.0.0.1", "client_port": 9702},
+                        "dest": dest
+                    },
+                    "type": "0"
+                }
+            })
+            .to_string(),
+        );
+        transactions.push(
+            serde_json::json!({
+                "txn": {
+                    "data": {"data": {"alias": "Node1", "services": []}, "dest": dest},
+                    "type": "0"
+                }
+            })
+            .to_string(),
+        );
+
+        let nodes = pool_nodes(&transactions).unwrap();
+        assert_eq!(nodes.len(), 4);
+        assert_eq!(nodes[0].client_address.as_deref(), Some("10.0.0.1:9702"));
+        assert!(!nodes[0].validator);
+        assert!(nodes[1].validator);
+    }
+
+    #[tokio::test]
+    async fn test_check_nodes_reports_reachability() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let reachable = listener.local_addr().unwrap().to_string();
+        let nodes = vec![
+            PoolNode {
+                alias: "Node1".to_owned(),
+                client_address: Some(reachable.clone()),
+                validator: true,
+            },
+            PoolNode {
+                alias: "Node2".to_owned(),
+                client_address: None,
+                validator: true,
+            },
+        ];
+
+        let statuses = check_nodes(nodes, Duration::from_secs(5)).await;
+        assert!(statuses[0].reachable);
+        assert_eq!(statuses[0].client_address, Some(reachable));
+        assert!(!statuses[1].reachable);
+        assert!(statuses[1].error.is_some());
+    }
+
+    #[test]
+    fn test_persist_genesis_file() {
+        let path = get_temp_file_path(&format!("{}.txn", uuid::Uuid::new_v4()));
+        let path = path.to_str().unwrap();
+        let transactions = get_txns_localhost("127.0.0.1".to_owned());
+
+        persist_genesis_file(path, &transactions).unwrap();
+
+        let persisted = fs::read_to_string(path).unwrap();
+        assert_eq!(persisted.lines().collect::<Vec<_>>(), transactions);
+        fs::remove_file(path).unwrap();
+    }
+}
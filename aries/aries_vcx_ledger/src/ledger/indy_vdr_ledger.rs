@@ -16,7 +16,10 @@ use anoncreds_types::{
             rev_status_list::RevocationStatusList, schema::Schema,
         },
     },
-    utils::conversions::from_revocation_registry_delta_to_revocation_status_list,
+    utils::conversions::{
+        from_revocation_registry_delta_to_revocation_status_list,
+        from_revocation_status_list_to_revocation_registry_delta,
+    },
 };
 use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
 use async_trait::async_trait;
@@ -66,8 +69,9 @@ use super::{
         RequestSubmitter,
     },
     response_cacher::{
+        cache_object, get_cached_object,
         in_memory::{InMemoryResponseCacher, InMemoryResponseCacherConfig},
-        ResponseCacher,
+        is_settled, LedgerObjectKind, ResponseCacher,
     },
 };
 use crate::{
@@ -142,38 +146,10 @@ where
         Ok(RequestBuilder::new(self.protocol_version))
     }
 
-    async fn submit_request(
-        &self,
-        cache_id: Option<&str>,
-        request: PreparedRequest,
-    ) -> VcxLedgerResult<String> {
-        trace!(
-            "submit_request >> Submitting ledger request, cache_id: {cache_id:?}, request: \
-             {request:?}"
-        );
-        let (response, is_from_cache) = match cache_id {
-            Some(cache_id) => match self.response_cacher.get(cache_id, None).await? {
-                Some(response) => {
-                    trace!("submit_request << Returning cached response");
-                    (response, true)
-                }
-                None => {
-                    trace!(
-                        "submit_request << cache miss, will make ledger request. Response will be \
-                         cached."
-                    );
-                    let response = self.request_submitter.submit(request).await?;
-                    self.response_cacher.put(cache_id, response.clone()).await?;
-                    (response, false)
-                }
-            },
-            None => {
-                trace!("submit_request << caching is disabled for this request");
-                let response = self.request_submitter.submit(request).await?;
-                (response, false)
-            }
-        };
-        trace!("submit_request << ledger response (is from cache: {is_from_cache}): {response}");
+    async fn submit_request(&self, request: PreparedRequest) -> VcxLedgerResult<String> {
+        trace!("submit_request >> Submitting ledger request: {request:?}");
+        let response = self.request_submitter.submit(request).await?;
+        trace!("submit_request << ledger response: {response}");
         Ok(response)
     }
 
@@ -311,7 +287,7 @@ where
             None,
             None,
         )?;
-        let response = self.submit_request(None, request).await?;
+        let response = self.submit_request(request).await?;
         debug!("get_attr << response: {response}");
         Ok(response)
     }
@@ -321,7 +297,7 @@ where
         let request =
             self.request_builder()?
                 .build_get_nym_request(None, &did.convert(())?, None, None)?;
-        let response = self.submit_request(None, request).await?;
+        let response = self.submit_request(request).await?;
         debug!("get_nym << response: {response}");
         Ok(response)
     }
@@ -331,7 +307,7 @@ where
         let request = self
             .request_builder()?
             .build_get_txn_author_agreement_request(None, None)?;
-        let response = self.submit_request(None, request).await?;
+        let response = self.submit_request(request).await?;
         debug!("get_txn_author_agreement << response: {response}");
         map_error_not_found_to_none(
             self.response_parser
@@ -353,7 +329,7 @@ where
             LedgerType::DOMAIN.to_id(),
             seq_no,
         )?;
-        let response = self.submit_request(None, request).await?;
+        let response = self.submit_request(request).await?;
         debug!("get_ledger_txn << response: {response}");
         Ok(response)
     }
@@ -385,6 +361,20 @@ where
             Ok(request)
         }
     }
+
+    async fn prepare_request_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        author_did: &Did,
+        request: PreparedRequest,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        let mut request = self.append_txn_author_agreement_to_request(request).await?;
+        request.set_endorser(&endorser_did.convert(())?)?;
+        let signature_author = Self::sign_request(wallet, author_did, &request).await?;
+        request.set_multi_signature(&author_did.convert(())?, &signature_author)?;
+        Ok(request.req_json.to_string())
+    }
 }
 
 #[async_trait]
@@ -436,12 +426,79 @@ where
         wallet: &impl BaseWallet,
         endorser_did: &Did,
         request_json: &str,
-    ) -> VcxLedgerResult<()> {
+    ) -> VcxLedgerResult<String> {
         let mut request = PreparedRequest::from_request_json(request_json)?;
         verify_transaction_can_be_endorsed(request_json, endorser_did)?;
         let signature_endorser = Self::sign_request(wallet, endorser_did, &request).await?;
         request.set_multi_signature(&endorser_did.convert(())?, &signature_endorser)?;
-        self.request_submitter.submit(request).await.map(|_| ())
+        let endorsed_request = request.req_json.to_string();
+        self.request_submitter.submit(request).await?;
+        Ok(endorsed_request)
+    }
+
+    async fn prepare_nym_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        author_did: &Did,
+        target_did: &Did,
+        target_vk: Option<&Key>,
+        role: Option<UpdateRole>,
+        alias: Option<String>,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        let request = self.request_builder()?.build_nym_request(
+            &author_did.convert(())?,
+            &target_did.convert(())?,
+            target_vk.map(Key::base58),
+            alias,
+            role,
+            None,
+            None,
+        )?;
+        self.prepare_request_for_endorsement(wallet, author_did, request, endorser_did)
+            .await
+    }
+
+    async fn prepare_schema_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        schema: Schema,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        let request = self
+            .request_builder()?
+            .build_schema_request(&author_did.convert(())?, schema.convert(())?)?;
+        self.prepare_request_for_endorsement(wallet, author_did, request, endorser_did)
+            .await
+    }
+
+    async fn prepare_cred_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_def: CredentialDefinition,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        let request = self
+            .request_builder()?
+            .build_cred_def_request(&author_did.convert(())?, cred_def.convert(())?)?;
+        self.prepare_request_for_endorsement(wallet, author_did, request, endorser_did)
+            .await
+    }
+
+    async fn prepare_rev_reg_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_def: RevocationRegistryDefinition,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        let request = self
+            .request_builder()?
+            .build_revoc_reg_def_request(&author_did.convert(())?, rev_reg_def.convert(())?)?;
+        self.prepare_request_for_endorsement(wallet, author_did, request, endorser_did)
+            .await
     }
 
     async fn add_attr(
@@ -516,15 +573,29 @@ where
         _submitter_did: Option<&Did>,
     ) -> VcxLedgerResult<Schema> {
         debug!("get_schema >> schema_id: {schema_id}");
+        let cache_id = schema_id.to_string();
+        if let Some(schema) =
+            get_cached_object(&self.response_cacher, LedgerObjectKind::Schema, &cache_id).await
+        {
+            return Ok(schema);
+        }
         let request = self
             .request_builder()?
             .build_get_schema_request(None, &schema_id.convert(())?)?;
-        let response = self.submit_request(None, request).await?;
+        let response = self.submit_request(request).await?;
         debug!("get_schema << response: {response}");
         let schema = self
             .response_parser
             .parse_get_schema_response(&response, None)?;
-        Ok(schema.convert(())?)
+        let schema: Schema = schema.convert(())?;
+        cache_object(
+            &self.response_cacher,
+            LedgerObjectKind::Schema,
+            &cache_id,
+            schema.clone(),
+        )
+        .await;
+        Ok(schema)
     }
 
     async fn get_cred_def(
@@ -533,6 +604,12 @@ where
         submitter_did: Option<&Did>,
     ) -> VcxLedgerResult<CredentialDefinition> {
         debug!("get_cred_def >> cred_def_id: {cred_def_id}");
+        let cache_id = cred_def_id.to_string();
+        if let Some(cred_def) =
+            get_cached_object(&self.response_cacher, LedgerObjectKind::CredDef, &cache_id).await
+        {
+            return Ok(cred_def);
+        }
         let identifier = submitter_did.map(|did| did.convert(())).transpose()?;
         let id = IndyVdrCredentialDefinitionId::from_str(&cred_def_id.to_string())?;
         let request = self
@@ -540,11 +617,9 @@ where
             .build_get_cred_def_request(identifier.as_ref(), &id)?;
         // note: Before we try to create credential definition, we are checking if it already
         //       doesn't exist on the ledger to prevent invalidating the old one.
-        //       When we make the first request, it typically doesn't exist, but we don't want to
-        //       cache such as result. So caching strategy should perhaps only store data in cache
-        //       if ledger response was found / the response is success.
-        //       Therefore parsing should happen prior to caching.
-        let response = self.submit_request(None, request).await?;
+        //       When we make the first request, it typically doesn't exist, so only the parsed
+        //       credential definition is cached, never the raw "not found" response.
+        let response = self.submit_request(request).await?;
         debug!("get_cred_def << response: {response}");
         let cred_def = self
             .response_parser
@@ -558,7 +633,15 @@ where
                 .await?;
         }
 
-        let cred_def = IndyVdrCredentialDefinition::CredentialDefinitionV1(cred_def).convert(())?;
+        let cred_def: CredentialDefinition =
+            IndyVdrCredentialDefinition::CredentialDefinitionV1(cred_def).convert(())?;
+        cache_object(
+            &self.response_cacher,
+            LedgerObjectKind::CredDef,
+            &cache_id,
+            cred_def.clone(),
+        )
+        .await;
 
         Ok(cred_def)
     }
@@ -571,18 +654,36 @@ where
         RevocationRegistryDefinitionAdditionalMetadata,
     )> {
         debug!("get_rev_reg_def_json >> rev_reg_id: {rev_reg_id}");
-        let id = RevocationRegistryId::from_str(&rev_reg_id.to_string())?;
-        let request = self
-            .request_builder()?
-            .build_get_revoc_reg_def_request(None, &id)?;
-        let response = self
-            .submit_request(Some(&rev_reg_id.to_string()), request)
-            .await?;
-        debug!("get_rev_reg_def_json << response: {response}");
-        let rev_reg_def = self
-            .response_parser
-            .parse_get_revoc_reg_def_response(&response)?;
-        let def = rev_reg_def.convert(())?;
+        let cache_id = rev_reg_id.to_string();
+        let def: RevocationRegistryDefinition = match get_cached_object(
+            &self.response_cacher,
+            LedgerObjectKind::RevRegDef,
+            &cache_id,
+        )
+        .await
+        {
+            Some(def) => def,
+            None => {
+                let id = RevocationRegistryId::from_str(&cache_id)?;
+                let request = self
+                    .request_builder()?
+                    .build_get_revoc_reg_def_request(None, &id)?;
+                let response = self.submit_request(request).await?;
+                debug!("get_rev_reg_def_json << response: {response}");
+                let rev_reg_def = self
+                    .response_parser
+                    .parse_get_revoc_reg_def_response(&response)?;
+                let def: RevocationRegistryDefinition = rev_reg_def.convert(())?;
+                cache_object(
+                    &self.response_cacher,
+                    LedgerObjectKind::RevRegDef,
+                    &cache_id,
+                    def.clone(),
+                )
+                .await;
+                def
+            }
+        };
 
         let meta = RevocationRegistryDefinitionAdditionalMetadata {
             max_cred_num: def.value.max_cred_num as usize,
@@ -601,6 +702,23 @@ where
         debug!("get_rev_reg_delta_json >> rev_reg_id: {rev_reg_id}, from: {from:?}, to: {to:?}");
         let revoc_reg_def_id = RevocationRegistryId::from_str(&rev_reg_id.to_string())?;
 
+        // deltas up to the current time change with every revocation, so only deltas up to a
+        // timestamp which is well in the past are cached
+        let cache_id = to
+            .filter(|to| is_settled(*to))
+            .map(|to| format!("{rev_reg_id}:{}:{to}", from.unwrap_or_default()));
+        if let Some(cache_id) = &cache_id {
+            if let Some(delta) = get_cached_object(
+                &self.response_cacher,
+                LedgerObjectKind::RevRegDelta,
+                cache_id,
+            )
+            .await
+            {
+                return Ok(delta);
+            }
+        }
+
         let from = from.map(|x| x as i64);
         let current_time = OffsetDateTime::now_utc().unix_timestamp();
         let to = to.map_or(current_time, |x| x as i64);
@@ -611,7 +729,7 @@ where
             from,
             to,
         )?;
-        let response = self.submit_request(None, request).await?;
+        let response = self.submit_request(request).await?;
         debug!("get_rev_reg_delta_json << response: {response}");
 
         let RevocationRegistryDeltaInfo {
@@ -621,7 +739,17 @@ where
         } = self
             .response_parser
             .parse_get_revoc_reg_delta_response(&response)?;
-        Ok((revoc_reg_delta.convert(())?, timestamp))
+        let delta: RevocationRegistryDelta = revoc_reg_delta.convert(())?;
+        if let Some(cache_id) = &cache_id {
+            cache_object(
+                &self.response_cacher,
+                LedgerObjectKind::RevRegDelta,
+                cache_id,
+                (delta.clone(), timestamp),
+            )
+            .await;
+        }
+        Ok((delta, timestamp))
     }
 
     async fn get_rev_status_list(
@@ -669,7 +797,7 @@ where
             &revoc_reg_def_id,
             timestamp.try_into().unwrap(),
         )?;
-        let response = self.submit_request(None, request).await?;
+        let response = self.submit_request(request).await?;
         debug!("get_rev_reg << response: {response}");
 
         let RevocationRegistryInfo {
@@ -803,6 +931,31 @@ where
             .await
             .map(|_| ())
     }
+
+    async fn publish_rev_status_list(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_status_list: RevocationStatusList,
+        prev_rev_status_list: Option<&RevocationStatusList>,
+        submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        let rev_reg_id = rev_status_list.rev_reg_def_id.clone().ok_or_else(|| {
+            VcxLedgerError::InvalidInput(
+                "Revocation status list is missing its revocation registry id".into(),
+            )
+        })?;
+        let delta = from_revocation_status_list_to_revocation_registry_delta(
+            &rev_status_list,
+            prev_rev_status_list,
+        )
+        .map_err(|e| {
+            VcxLedgerError::InvalidInput(format!(
+                "revocation status list could not be translated to a delta: {e}"
+            ))
+        })?;
+        self.publish_rev_reg_delta(wallet, &rev_reg_id, delta, submitter_did)
+            .await
+    }
 }
 
 pub fn indyvdr_build_ledger_read(
@@ -887,6 +1040,45 @@ mod unit_tests {
         })
     }
 
+    #[tokio::test]
+    async fn test_get_schema_is_cached() {
+        let mut request_submitter = MockRequestSubmitter::new();
+        request_submitter.expect_submit().times(1).returning(|_| {
+            Ok(json!({
+                "op": "REPLY",
+                "result": {
+                    "type": "107",
+                    "seqNo": 10,
+                    "dest": "7BPMqYgYLQni258J8JPS8K",
+                    "data": {
+                        "name": "degree schema",
+                        "version": "46.58.87",
+                        "attr_names": ["name"]
+                    }
+                }
+            })
+            .to_string())
+        });
+        let reader = IndyVdrLedgerRead::new(IndyVdrLedgerReadConfig {
+            request_submitter,
+            response_parser: indy_ledger_response_parser::ResponseParser,
+            response_cacher: InMemoryResponseCacher::new(
+                InMemoryResponseCacherConfig::builder()
+                    .ttl(std::time::Duration::from_secs(60))
+                    .capacity(10)
+                    .unwrap()
+                    .build(),
+            ),
+            protocol_version: ProtocolVersion::Node1_4,
+        });
+        let schema_id = SchemaId::new("7BPMqYgYLQni258J8JPS8K:2:degree schema:46.58.87").unwrap();
+
+        let schema = reader.get_schema(&schema_id, None).await.unwrap();
+        let cached_schema = reader.get_schema(&schema_id, None).await.unwrap();
+        assert_eq!(schema, cached_schema);
+        assert_eq!(cached_schema.id, schema_id);
+    }
+
     #[test]
     fn test_anoncreds_schema_support() {
         let reader = dummy_indy_vdr_reader();
@@ -13,29 +13,53 @@ use anoncreds_types::data_types::{
 };
 use async_trait::async_trait;
 use did_parser_nom::Did;
+use futures::future::{select_ok, BoxFuture};
+use log::{debug, warn};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::base_ledger::{AnoncredsLedgerRead, AnoncredsLedgerSupport};
 use crate::errors::error::{VcxLedgerError, VcxLedgerResult};
 
-// FUTURE - this multi-ledger anoncreds reader finds the first impl that supports the identifier
-// and attempts to use it. This behaviour may need some enhancements in the future when
-// considering coordination of unqualified object resolution, when multiple capable anoncreds
-// readers are available (e.g. sovrin testnet & sovrin mainnet).
-// Enhancements may include:
-// * priority system - try A resolver before B if A & B both support the identifier
-// * fallback/chain system - try A resolver, if it fails, try B resolver
-// Alternatively these enhancements can be skipped if qualified DIDs/objects are used instead,
-// e.g. did:indy:a:123, did:indy:b:123
+/// Determines how [MultiLedgerAnoncredsRead] resolves an object when several registered readers
+/// support its identifier, e.g. an unqualified sov identifier which may live on Sovrin MainNet
+/// as well as on IDUnion. Supporting readers are always considered in priority order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ResolutionStrategy {
+    /// Use the first supporting reader only.
+    #[default]
+    FirstSupporting,
+    /// Try the supporting readers one after another, moving on to the next reader whenever the
+    /// object cannot be resolved (not found or any other error).
+    Fallback,
+    /// Query all supporting readers concurrently and take the first successful answer.
+    Race,
+}
+
+/// An object resolved by [MultiLedgerAnoncredsRead], along with the name of the ledger reader
+/// which answered.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Resolved<T> {
+    pub value: T,
+    pub ledger: String,
+}
+
+#[derive(Debug)]
+struct RegisteredReader {
+    name: String,
+    priority: u32,
+    reader: Box<dyn AnoncredsLedgerReadAdaptorTrait>,
+}
 
 /// Struct to aggregate multiple [AnoncredsLedgerRead] implementations into a single
 /// [AnoncredsLedgerRead]. The child [AnoncredsLedgerRead] implementations are
 /// utilized depending on whether or not they support resolution of the given object ID
-/// (e.g. based on the DID Method).
+/// (e.g. based on the DID Method), in the order of their priority and following the configured
+/// [ResolutionStrategy].
 #[derive(Default, Debug)]
 pub struct MultiLedgerAnoncredsRead {
-    readers: Vec<Box<dyn AnoncredsLedgerReadAdaptorTrait>>,
+    readers: Vec<RegisteredReader>,
+    strategy: ResolutionStrategy,
 }
 
 #[async_trait]
@@ -47,15 +71,7 @@ impl AnoncredsLedgerRead for MultiLedgerAnoncredsRead {
         schema_id: &SchemaId,
         submitter_did: Option<&Did>,
     ) -> VcxLedgerResult<Schema> {
-        let reader = self
-            .readers
-            .iter()
-            .find(|r| r.supports_schema(schema_id))
-            .ok_or(VcxLedgerError::UnsupportedLedgerIdentifier(
-                schema_id.to_string(),
-            ))?;
-
-        reader.get_schema(schema_id, submitter_did).await
+        Ok(self.resolve_schema(schema_id, submitter_did).await?.value)
     }
 
     async fn get_cred_def(
@@ -63,15 +79,10 @@ impl AnoncredsLedgerRead for MultiLedgerAnoncredsRead {
         cred_def_id: &CredentialDefinitionId,
         submitter_did: Option<&Did>,
     ) -> VcxLedgerResult<CredentialDefinition> {
-        let reader = self
-            .readers
-            .iter()
-            .find(|r| r.supports_credential_definition(cred_def_id))
-            .ok_or(VcxLedgerError::UnsupportedLedgerIdentifier(
-                cred_def_id.to_string(),
-            ))?;
-
-        reader.get_cred_def(cred_def_id, submitter_did).await
+        Ok(self
+            .resolve_cred_def(cred_def_id, submitter_did)
+            .await?
+            .value)
     }
 
     async fn get_rev_reg_def_json(
@@ -81,15 +92,7 @@ impl AnoncredsLedgerRead for MultiLedgerAnoncredsRead {
         RevocationRegistryDefinition,
         Self::RevocationRegistryDefinitionAdditionalMetadata,
     )> {
-        let reader = self
-            .readers
-            .iter()
-            .find(|r| r.supports_revocation_registry(rev_reg_id))
-            .ok_or(VcxLedgerError::UnsupportedLedgerIdentifier(
-                rev_reg_id.to_string(),
-            ))?;
-
-        reader.get_rev_reg_def_json(rev_reg_id).await
+        Ok(self.resolve_rev_reg_def(rev_reg_id).await?.value)
     }
     async fn get_rev_reg_delta_json(
         &self,
@@ -97,16 +100,10 @@ impl AnoncredsLedgerRead for MultiLedgerAnoncredsRead {
         from: Option<u64>,
         to: Option<u64>,
     ) -> VcxLedgerResult<(RevocationRegistryDelta, u64)> {
-        let reader = self
-            .readers
-            .iter()
-            .find(|r| r.supports_revocation_registry(rev_reg_id))
-            .ok_or(VcxLedgerError::UnsupportedLedgerIdentifier(
-                rev_reg_id.to_string(),
-            ))?;
-
-        #[allow(deprecated)] // TODO - https://github.com/hyperledger/aries-vcx/issues/1309
-        reader.get_rev_reg_delta_json(rev_reg_id, from, to).await
+        Ok(self
+            .resolve_rev_reg_delta(rev_reg_id, from, to)
+            .await?
+            .value)
     }
 
     async fn get_rev_status_list(
@@ -115,17 +112,10 @@ impl AnoncredsLedgerRead for MultiLedgerAnoncredsRead {
         timestamp: u64,
         rev_reg_def_meta: Option<&Self::RevocationRegistryDefinitionAdditionalMetadata>,
     ) -> VcxLedgerResult<(RevocationStatusList, u64)> {
-        let reader = self
-            .readers
-            .iter()
-            .find(|r| r.supports_revocation_registry(rev_reg_id))
-            .ok_or(VcxLedgerError::UnsupportedLedgerIdentifier(
-                rev_reg_id.to_string(),
-            ))?;
-
-        reader
-            .get_rev_status_list(rev_reg_id, timestamp, rev_reg_def_meta)
-            .await
+        Ok(self
+            .resolve_rev_status_list(rev_reg_id, timestamp, rev_reg_def_meta)
+            .await?
+            .value)
     }
 
     async fn get_rev_reg(
@@ -133,15 +123,7 @@ impl AnoncredsLedgerRead for MultiLedgerAnoncredsRead {
         rev_reg_id: &RevocationRegistryDefinitionId,
         timestamp: u64,
     ) -> VcxLedgerResult<(RevocationRegistry, u64)> {
-        let reader = self
-            .readers
-            .iter()
-            .find(|r| r.supports_revocation_registry(rev_reg_id))
-            .ok_or(VcxLedgerError::UnsupportedLedgerIdentifier(
-                rev_reg_id.to_string(),
-            ))?;
-
-        reader.get_rev_reg(rev_reg_id, timestamp).await
+        Ok(self.resolve_rev_reg(rev_reg_id, timestamp).await?.value)
     }
 }
 
@@ -150,33 +132,218 @@ impl MultiLedgerAnoncredsRead {
         Self::default()
     }
 
-    pub fn register_reader<T>(mut self, reader: T) -> Self
+    pub fn with_strategy(mut self, strategy: ResolutionStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Registers `reader` with the lowest priority, under a generated name.
+    pub fn register_reader<T>(self, reader: T) -> Self
+    where
+        T: AnoncredsLedgerRead + AnoncredsLedgerSupport + 'static,
+        for<'de> <T as AnoncredsLedgerRead>::RevocationRegistryDefinitionAdditionalMetadata:
+            Serialize + Deserialize<'de> + Send + Sync,
+    {
+        let name = format!("ledger-{}", self.readers.len());
+        self.register_named_reader(name, 0, reader)
+    }
+
+    /// Registers `reader` under `name`, which is reported in [Resolved] results. Readers with a
+    /// higher `priority` are used first; readers of equal priority keep their registration order.
+    pub fn register_named_reader<T>(
+        mut self,
+        name: impl Into<String>,
+        priority: u32,
+        reader: T,
+    ) -> Self
     where
         T: AnoncredsLedgerRead + AnoncredsLedgerSupport + 'static,
         for<'de> <T as AnoncredsLedgerRead>::RevocationRegistryDefinitionAdditionalMetadata:
             Serialize + Deserialize<'de> + Send + Sync,
     {
         let adaptor = AnoncredsLedgerReadAdaptor { inner: reader };
-        self.readers.push(Box::new(adaptor));
+        let position = self
+            .readers
+            .iter()
+            .position(|r| r.priority < priority)
+            .unwrap_or(self.readers.len());
+        self.readers.insert(
+            position,
+            RegisteredReader {
+                name: name.into(),
+                priority,
+                reader: Box::new(adaptor),
+            },
+        );
         self
     }
+
+    /// Names of the registered readers, in priority order.
+    pub fn ledger_names(&self) -> Vec<&str> {
+        self.readers.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    pub async fn resolve_schema(
+        &self,
+        schema_id: &SchemaId,
+        submitter_did: Option<&Did>,
+    ) -> VcxLedgerResult<Resolved<Schema>> {
+        self.resolve(
+            schema_id.to_string(),
+            |r| r.supports_schema(schema_id),
+            |r| r.get_schema(schema_id, submitter_did),
+        )
+        .await
+    }
+
+    pub async fn resolve_cred_def(
+        &self,
+        cred_def_id: &CredentialDefinitionId,
+        submitter_did: Option<&Did>,
+    ) -> VcxLedgerResult<Resolved<CredentialDefinition>> {
+        self.resolve(
+            cred_def_id.to_string(),
+            |r| r.supports_credential_definition(cred_def_id),
+            |r| r.get_cred_def(cred_def_id, submitter_did),
+        )
+        .await
+    }
+
+    pub async fn resolve_rev_reg_def(
+        &self,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+    ) -> VcxLedgerResult<Resolved<(RevocationRegistryDefinition, Value)>> {
+        self.resolve(
+            rev_reg_id.to_string(),
+            |r| r.supports_revocation_registry(rev_reg_id),
+            |r| r.get_rev_reg_def_json(rev_reg_id),
+        )
+        .await
+    }
+
+    #[allow(deprecated)] // TODO - https://github.com/hyperledger/aries-vcx/issues/1309
+    pub async fn resolve_rev_reg_delta(
+        &self,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> VcxLedgerResult<Resolved<(RevocationRegistryDelta, u64)>> {
+        self.resolve(
+            rev_reg_id.to_string(),
+            |r| r.supports_revocation_registry(rev_reg_id),
+            |r| r.get_rev_reg_delta_json(rev_reg_id, from, to),
+        )
+        .await
+    }
+
+    pub async fn resolve_rev_status_list(
+        &self,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+        timestamp: u64,
+        rev_reg_def_meta: Option<&Value>,
+    ) -> VcxLedgerResult<Resolved<(RevocationStatusList, u64)>> {
+        self.resolve(
+            rev_reg_id.to_string(),
+            |r| r.supports_revocation_registry(rev_reg_id),
+            |r| r.get_rev_status_list(rev_reg_id, timestamp, rev_reg_def_meta),
+        )
+        .await
+    }
+
+    pub async fn resolve_rev_reg(
+        &self,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+        timestamp: u64,
+    ) -> VcxLedgerResult<Resolved<(RevocationRegistry, u64)>> {
+        self.resolve(
+            rev_reg_id.to_string(),
+            |r| r.supports_revocation_registry(rev_reg_id),
+            |r| r.get_rev_reg(rev_reg_id, timestamp),
+        )
+        .await
+    }
+
+    async fn resolve<'a, T, S, Q>(
+        &'a self,
+        id: String,
+        supports: S,
+        query: Q,
+    ) -> VcxLedgerResult<Resolved<T>>
+    where
+        S: Fn(&dyn AnoncredsLedgerReadAdaptorTrait) -> bool + Send,
+        Q: Fn(&'a dyn AnoncredsLedgerReadAdaptorTrait) -> BoxFuture<'a, VcxLedgerResult<T>>
+            + Send
+            + Sync,
+        T: Send,
+    {
+        let candidates: Vec<&RegisteredReader> = self
+            .readers
+            .iter()
+            .filter(|r| supports(r.reader.as_ref()))
+            .collect();
+        let Some(&first) = candidates.first() else {
+            return Err(VcxLedgerError::UnsupportedLedgerIdentifier(id));
+        };
+
+        let resolved = match self.strategy {
+            ResolutionStrategy::FirstSupporting => Resolved {
+                value: query(first.reader.as_ref()).await?,
+                ledger: first.name.clone(),
+            },
+            ResolutionStrategy::Fallback => {
+                let mut result = Err(VcxLedgerError::UnsupportedLedgerIdentifier(id.clone()));
+                for candidate in candidates {
+                    result = query(candidate.reader.as_ref())
+                        .await
+                        .map(|value| Resolved {
+                            value,
+                            ledger: candidate.name.clone(),
+                        });
+                    match &result {
+                        Ok(_) => break,
+                        Err(err) => warn!(
+                            "Failed to resolve {id} on ledger {}, trying next ledger; err: {err}",
+                            candidate.name
+                        ),
+                    }
+                }
+                result?
+            }
+            ResolutionStrategy::Race => {
+                let query = &query;
+                let queries = candidates.into_iter().map(|candidate| {
+                    Box::pin(async move {
+                        query(candidate.reader.as_ref())
+                            .await
+                            .map(|value| Resolved {
+                                value,
+                                ledger: candidate.name.clone(),
+                            })
+                    })
+                });
+                select_ok(queries).await?.0
+            }
+        };
+        debug!("Resolved {id} on ledger {}", resolved.ledger);
+        Ok(resolved)
+    }
 }
 
 impl AnoncredsLedgerSupport for MultiLedgerAnoncredsRead {
     fn supports_schema(&self, id: &SchemaId) -> bool {
-        self.readers.iter().any(|r| r.supports_schema(id))
+        self.readers.iter().any(|r| r.reader.supports_schema(id))
     }
 
     fn supports_credential_definition(&self, id: &CredentialDefinitionId) -> bool {
         self.readers
             .iter()
-            .any(|r| r.supports_credential_definition(id))
+            .any(|r| r.reader.supports_credential_definition(id))
     }
 
     fn supports_revocation_registry(&self, id: &RevocationRegistryDefinitionId) -> bool {
         self.readers
             .iter()
-            .any(|r| r.supports_revocation_registry(id))
+            .any(|r| r.reader.supports_revocation_registry(id))
     }
 }
 
@@ -682,4 +849,132 @@ mod unit_tests {
         assert_eq!(actual_reg, rev_reg);
         assert_eq!(actual_timestamp, timestamp);
     }
+
+    fn _schema() -> Schema {
+        serde_json::from_value(json!({
+            "id": "2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4",
+            "issuerId": "2hoqvcwupRTUNkXn6ArYzs",
+            "name": "test-licence",
+            "version": "4.4.4",
+            "attrNames": ["name", "age"]
+        }))
+        .unwrap()
+    }
+
+    fn _schema_reader(result: Option<VcxLedgerResult<Schema>>) -> MockReader {
+        let mut reader = MockReader::new();
+        reader.expect_supports_schema().return_const(true);
+        match result {
+            Some(result) => {
+                reader
+                    .expect_get_schema()
+                    .times(1)
+                    .return_once(move |_, _| Box::pin(async { result }));
+            }
+            None => {
+                reader.expect_get_schema().never();
+            }
+        }
+        reader
+    }
+
+    #[tokio::test]
+    async fn test_readers_are_used_in_priority_order() {
+        let id = SchemaId::new_unchecked("2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4");
+        let reader = MultiLedgerAnoncredsRead::new()
+            .register_named_reader("idunion", 0, _schema_reader(None))
+            .register_named_reader("sovrin-mainnet", 1, _schema_reader(Some(Ok(_schema()))));
+        assert_eq!(reader.ledger_names(), vec!["sovrin-mainnet", "idunion"]);
+
+        let resolved = reader.resolve_schema(&id, None).await.unwrap();
+        assert_eq!(resolved.value, _schema());
+        assert_eq!(resolved.ledger, "sovrin-mainnet");
+    }
+
+    #[tokio::test]
+    async fn test_first_supporting_strategy_does_not_fall_back() {
+        let id = SchemaId::new_unchecked("2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4");
+        let reader = MultiLedgerAnoncredsRead::new()
+            .register_named_reader(
+                "sovrin-mainnet",
+                0,
+                _schema_reader(Some(Err(VcxLedgerError::LedgerItemNotFound))),
+            )
+            .register_named_reader("idunion", 0, _schema_reader(None));
+
+        let err = reader.get_schema(&id, None).await.unwrap_err();
+        assert!(matches!(err, VcxLedgerError::LedgerItemNotFound));
+    }
+
+    #[tokio::test]
+    async fn test_fallback_strategy_tries_next_reader() {
+        let id = SchemaId::new_unchecked("2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4");
+        let reader = MultiLedgerAnoncredsRead::new()
+            .with_strategy(ResolutionStrategy::Fallback)
+            .register_named_reader(
+                "sovrin-mainnet",
+                0,
+                _schema_reader(Some(Err(VcxLedgerError::LedgerItemNotFound))),
+            )
+            .register_named_reader("idunion", 0, _schema_reader(Some(Ok(_schema()))));
+
+        let resolved = reader.resolve_schema(&id, None).await.unwrap();
+        assert_eq!(resolved.value, _schema());
+        assert_eq!(resolved.ledger, "idunion");
+    }
+
+    #[tokio::test]
+    async fn test_fallback_strategy_returns_last_error() {
+        let id = SchemaId::new_unchecked("2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4");
+        let reader = MultiLedgerAnoncredsRead::new()
+            .with_strategy(ResolutionStrategy::Fallback)
+            .register_reader(_schema_reader(Some(Err(
+                VcxLedgerError::LedgerItemNotFound,
+            ))))
+            .register_reader(_schema_reader(Some(Err(
+                VcxLedgerError::InvalidLedgerResponse("timeout".to_string()),
+            ))));
+
+        let err = reader.get_schema(&id, None).await.unwrap_err();
+        assert!(matches!(err, VcxLedgerError::InvalidLedgerResponse(_)));
+    }
+
+    #[tokio::test]
+    async fn test_race_strategy_returns_first_success() {
+        let id = SchemaId::new_unchecked("2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4");
+        let mut unresponsive = MockReader::new();
+        unresponsive.expect_supports_schema().return_const(true);
+        unresponsive
+            .expect_get_schema()
+            .times(1)
+            .return_once(|_, _| Box::pin(futures::future::pending()));
+        let reader = MultiLedgerAnoncredsRead::new()
+            .with_strategy(ResolutionStrategy::Race)
+            .register_named_reader("sovrin-mainnet", 1, unresponsive)
+            .register_named_reader(
+                "sovrin-staging",
+                0,
+                _schema_reader(Some(Err(VcxLedgerError::LedgerItemNotFound))),
+            )
+            .register_named_reader("idunion", 0, _schema_reader(Some(Ok(_schema()))));
+
+        let resolved = reader.resolve_schema(&id, None).await.unwrap();
+        assert_eq!(resolved.ledger, "idunion");
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_identifier_with_any_strategy() {
+        let id = SchemaId::new_unchecked("2hoqvcwupRTUNkXn6ArYzs:2:test-licence:4.4.4");
+        let mut unsupporting = MockReader::new();
+        unsupporting.expect_supports_schema().return_const(false);
+        let reader = MultiLedgerAnoncredsRead::new()
+            .with_strategy(ResolutionStrategy::Race)
+            .register_reader(unsupporting);
+
+        let err = reader.get_schema(&id, None).await.unwrap_err();
+        assert!(matches!(
+            err,
+            VcxLedgerError::UnsupportedLedgerIdentifier(_)
+        ));
+    }
 }
@@ -6,7 +6,7 @@ use std::{
 
 use async_trait::async_trait;
 use indy_vdr::{
-    common::error::VdrError,
+    common::error::{VdrError, VdrResult},
     config::PoolConfig,
     pool::{
         PoolBuilder, PoolRunner, PoolTransactions, PreparedRequest, RequestResult,
@@ -56,6 +56,33 @@ impl IndyVdrLedgerPool {
             runner: Arc::new(runner),
         })
     }
+
+    /// Catches up with the pool ledger, switching over to the current set of validator nodes if
+    /// it changed since the genesis transactions were written.
+    pub async fn refresh(&self) -> VcxLedgerResult<()> {
+        let (sender, recv) = oneshot::channel::<VdrResult<()>>();
+        self.runner.refresh(Box::new(move |result| {
+            // unable to handle a failure from `send` here
+            sender.send(result.map(|_| ())).ok();
+        }))?;
+
+        recv.await
+            .map_err(|e| VcxLedgerError::InvalidState(e.to_string()))??;
+        Ok(())
+    }
+
+    /// Pool ledger transactions currently known to the pool, in the genesis file format.
+    pub async fn transactions(&self) -> VcxLedgerResult<Vec<String>> {
+        let (sender, recv) = oneshot::channel::<VdrResult<Vec<String>>>();
+        self.runner.get_transactions(Box::new(move |result| {
+            // unable to handle a failure from `send` here
+            sender.send(result).ok();
+        }))?;
+
+        Ok(recv
+            .await
+            .map_err(|e| VcxLedgerError::InvalidState(e.to_string()))??)
+    }
 }
 
 impl Debug for IndyVdrLedgerPool {
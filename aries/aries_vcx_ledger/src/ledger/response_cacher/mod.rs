@@ -1,11 +1,51 @@
 pub mod in_memory;
 pub mod noop;
+pub mod wallet;
+
+use std::{
+    fmt::{self, Display, Formatter},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use async_trait::async_trait;
+use log::warn;
 use serde::{Deserialize, Serialize};
 
 use crate::errors::error::VcxLedgerResult;
 
+/// The kind of ledger object held by a cache entry. Schemas, credential definitions and
+/// revocation registry definitions never change once written, while revocation data does, so
+/// cachers may expire them differently.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LedgerObjectKind {
+    Schema,
+    CredDef,
+    RevRegDef,
+    RevRegDelta,
+    RevStatusList,
+    Other,
+}
+
+impl LedgerObjectKind {
+    pub fn cache_id(&self, id: &str) -> String {
+        format!("{self}:{id}")
+    }
+}
+
+impl Display for LedgerObjectKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let value = match self {
+            LedgerObjectKind::Schema => "schema",
+            LedgerObjectKind::CredDef => "cred_def",
+            LedgerObjectKind::RevRegDef => "rev_reg_def",
+            LedgerObjectKind::RevRegDelta => "rev_reg_delta",
+            LedgerObjectKind::RevStatusList => "rev_status_list",
+            LedgerObjectKind::Other => "other",
+        };
+        write!(f, "{}", value)
+    }
+}
+
 #[async_trait]
 pub trait ResponseCacher: Send + Sync {
     type Options: Send + Sync;
@@ -19,4 +59,88 @@ pub trait ResponseCacher: Send + Sync {
     where
         S: ToString + Send,
         T: Serialize + for<'de> Deserialize<'de> + Send;
+
+    /// Caches a ledger object of the given kind. Cachers which don't differentiate between
+    /// kinds store it under an id prefixed by the kind.
+    async fn put_object<T>(&self, kind: LedgerObjectKind, id: &str, obj: T) -> VcxLedgerResult<()>
+    where
+        T: Serialize + for<'de> Deserialize<'de> + Send,
+    {
+        self.put(kind.cache_id(id), obj).await
+    }
+
+    async fn get_object<T>(&self, kind: LedgerObjectKind, id: &str) -> VcxLedgerResult<Option<T>>
+    where
+        T: Serialize + for<'de> Deserialize<'de> + Send,
+    {
+        self.get(kind.cache_id(id), None).await
+    }
+}
+
+/// Revocation data as of a time this close to now may still change, e.g. because a revocation is
+/// being written or the clocks of the agent and the ledger disagree.
+const SETTLED_MARGIN: Duration = Duration::from_secs(10 * 60);
+
+/// Whether the ledger state as of `timestamp`, in seconds since the unix epoch, can no longer
+/// change, so that revocation data up to it may be cached. Open-ended and future ranges are never
+/// settled.
+pub(crate) fn is_settled(timestamp: u64) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    timestamp.saturating_add(SETTLED_MARGIN.as_secs()) < now
+}
+
+/// Looks up a cached ledger object. The cache is best-effort: failing to read it is logged and
+/// treated as a miss, so the object is fetched from the ledger instead.
+pub(crate) async fn get_cached_object<V, T>(
+    response_cacher: &V,
+    kind: LedgerObjectKind,
+    id: &str,
+) -> Option<T>
+where
+    V: ResponseCacher,
+    T: Serialize + for<'de> Deserialize<'de> + Send,
+{
+    response_cacher
+        .get_object(kind, id)
+        .await
+        .unwrap_or_else(|err| {
+            warn!("Reading {kind} {id} from the ledger response cache failed; err: {err}");
+            None
+        })
+}
+
+/// Caches a ledger object fetched from the ledger. Failing to do so is logged and otherwise
+/// ignored, the object is still returned to the caller.
+pub(crate) async fn cache_object<V, T>(
+    response_cacher: &V,
+    kind: LedgerObjectKind,
+    id: &str,
+    obj: T,
+) where
+    V: ResponseCacher,
+    T: Serialize + for<'de> Deserialize<'de> + Send,
+{
+    if let Err(err) = response_cacher.put_object(kind, id, obj).await {
+        warn!("Writing {kind} {id} to the ledger response cache failed; err: {err}");
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    #[test]
+    fn test_only_past_timestamps_are_settled() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        assert!(is_settled(now - 3600));
+        assert!(!is_settled(now));
+        assert!(!is_settled(now + 3600));
+        assert!(!is_settled(u64::MAX));
+    }
 }
@@ -0,0 +1,63 @@
+use std::{collections::HashMap, time::Duration};
+
+use crate::ledger::response_cacher::LedgerObjectKind;
+
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+const DEFAULT_REVOCATION_TTL: Duration = Duration::from_secs(30);
+
+/// Expiration policy of a [super::WalletResponseCacher], per kind of ledger object. A TTL of
+/// [None] keeps the object cached forever.
+#[derive(Clone, Debug)]
+pub struct WalletResponseCacherConfig {
+    ttls: HashMap<LedgerObjectKind, Option<Duration>>,
+}
+
+impl Default for WalletResponseCacherConfig {
+    /// Caches immutable objects forever and revocation data for 30 seconds.
+    fn default() -> Self {
+        Self {
+            ttls: HashMap::from([
+                (LedgerObjectKind::Schema, None),
+                (LedgerObjectKind::CredDef, None),
+                (LedgerObjectKind::RevRegDef, None),
+                (LedgerObjectKind::RevRegDelta, Some(DEFAULT_REVOCATION_TTL)),
+                (
+                    LedgerObjectKind::RevStatusList,
+                    Some(DEFAULT_REVOCATION_TTL),
+                ),
+                (LedgerObjectKind::Other, Some(DEFAULT_TTL)),
+            ]),
+        }
+    }
+}
+
+impl WalletResponseCacherConfig {
+    pub fn builder() -> WalletResponseCacherConfigBuilder {
+        WalletResponseCacherConfigBuilder::default()
+    }
+
+    pub fn ttl(&self, kind: LedgerObjectKind) -> Option<Duration> {
+        self.ttls.get(&kind).copied().unwrap_or(Some(DEFAULT_TTL))
+    }
+}
+
+#[derive(Default)]
+pub struct WalletResponseCacherConfigBuilder {
+    config: WalletResponseCacherConfig,
+}
+
+impl WalletResponseCacherConfigBuilder {
+    pub fn ttl(mut self, kind: LedgerObjectKind, ttl: Duration) -> Self {
+        self.config.ttls.insert(kind, Some(ttl));
+        self
+    }
+
+    pub fn cache_forever(mut self, kind: LedgerObjectKind) -> Self {
+        self.config.ttls.insert(kind, None);
+        self
+    }
+
+    pub fn build(self) -> WalletResponseCacherConfig {
+        self.config
+    }
+}
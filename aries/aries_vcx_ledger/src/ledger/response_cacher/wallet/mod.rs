@@ -0,0 +1,422 @@
+mod config;
+mod options;
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use aries_vcx_wallet::{
+    errors::error::VcxWalletError,
+    wallet::base_wallet::{
+        record::Record, record_category::RecordCategory, record_wallet::RecordWallet,
+    },
+};
+use async_trait::async_trait;
+pub use config::*;
+use log::{info, warn};
+pub use options::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{LedgerObjectKind, ResponseCacher};
+use crate::errors::error::VcxLedgerResult;
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    kind: LedgerObjectKind,
+    /// Milliseconds since the unix epoch.
+    stored_at: u64,
+    object: Value,
+}
+
+impl CacheEntry {
+    fn is_expired(&self, ttl: Option<Duration>) -> bool {
+        let age = Duration::from_millis(now_millis().saturating_sub(self.stored_at));
+        ttl.is_some_and(|ttl| age > ttl)
+    }
+}
+
+/// Response cacher persisting ledger objects as wallet records, so they survive restarts of
+/// the agent. Entries expire according to the TTL of their [LedgerObjectKind].
+///
+/// Expired entries are only removed when they are read or by
+/// [WalletResponseCacher::prune_expired], which owners should call periodically. Entries are
+/// stored in the `VCX_LEDGER_CACHE` record category and, like any other record, are part of
+/// wallet exports; call [WalletResponseCacher::clear] beforehand to leave them out.
+pub struct WalletResponseCacher<W: RecordWallet + Send + Sync> {
+    wallet: W,
+    config: WalletResponseCacherConfig,
+}
+
+impl<W: RecordWallet + Send + Sync> WalletResponseCacher<W> {
+    pub fn new(wallet: W, config: WalletResponseCacherConfig) -> Self {
+        info!("WalletResponseCacher::new >> config: {config:?}");
+        Self { wallet, config }
+    }
+
+    /// Removes all cached ledger objects from the wallet.
+    pub async fn clear(&self) -> VcxLedgerResult<()> {
+        let records = self
+            .wallet
+            .search_record(RecordCategory::LedgerCache, None)
+            .await?;
+        for record in records {
+            self.delete(record.name()).await?;
+        }
+        Ok(())
+    }
+
+    /// Removes expired and unreadable entries from the wallet, returning how many were removed.
+    pub async fn prune_expired(&self) -> VcxLedgerResult<usize> {
+        let records = self
+            .wallet
+            .search_record(RecordCategory::LedgerCache, None)
+            .await?;
+        let mut pruned = 0;
+        for record in records {
+            let expired = match serde_json::from_str::<CacheEntry>(record.value()) {
+                Ok(entry) => entry.is_expired(self.config.ttl(entry.kind)),
+                Err(_) => true,
+            };
+            if expired {
+                self.delete(record.name()).await?;
+                pruned += 1;
+            }
+        }
+        info!("WalletResponseCacher::prune_expired << removed {pruned} entries");
+        Ok(pruned)
+    }
+
+    async fn store(
+        &self,
+        name: String,
+        kind: LedgerObjectKind,
+        object: Value,
+    ) -> VcxLedgerResult<()> {
+        let entry = CacheEntry {
+            kind,
+            stored_at: now_millis(),
+            object,
+        };
+        let value = serde_json::to_string(&entry)?;
+        let record = Record::builder()
+            .category(RecordCategory::LedgerCache)
+            .name(name.clone())
+            .value(value.clone())
+            .build();
+        match self.wallet.add_record(record).await {
+            Err(VcxWalletError::DuplicateRecord(_)) => self
+                .wallet
+                .update_record_value(RecordCategory::LedgerCache, &name, &value)
+                .await
+                .map_err(From::from),
+            res => res.map_err(From::from),
+        }
+    }
+
+    async fn delete(&self, name: &str) -> VcxLedgerResult<()> {
+        match self
+            .wallet
+            .delete_record(RecordCategory::LedgerCache, name)
+            .await
+        {
+            Ok(()) | Err(VcxWalletError::RecordNotFound(_)) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+#[async_trait]
+impl<W: RecordWallet + Send + Sync> ResponseCacher for WalletResponseCacher<W> {
+    type Options = WalletResponseCacherOptions;
+
+    async fn put<S, T>(&self, id: S, obj: T) -> VcxLedgerResult<()>
+    where
+        S: ToString + Send,
+        T: Serialize + for<'de> Deserialize<'de> + Send,
+    {
+        self.store(
+            id.to_string(),
+            LedgerObjectKind::Other,
+            serde_json::to_value(&obj)?,
+        )
+        .await
+    }
+
+    async fn get<S, T>(&self, id: S, opt: Option<Self::Options>) -> VcxLedgerResult<Option<T>>
+    where
+        S: ToString + Send,
+        T: Serialize + for<'de> Deserialize<'de> + Send,
+    {
+        let name = id.to_string();
+        let record = match self
+            .wallet
+            .get_record(RecordCategory::LedgerCache, &name)
+            .await
+        {
+            Ok(record) => record,
+            Err(VcxWalletError::RecordNotFound(_)) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let entry: CacheEntry = match serde_json::from_str(record.value()) {
+            Ok(entry) => entry,
+            Err(err) => {
+                warn!("Discarding unreadable cached ledger object {name}; err: {err}");
+                self.delete(&name).await?;
+                return Ok(None);
+            }
+        };
+
+        let ttl = match opt.and_then(|opt| opt.ttl()) {
+            Some(ttl) => Some(ttl),
+            None => self.config.ttl(entry.kind),
+        };
+        if entry.is_expired(ttl) {
+            self.delete(&name).await?;
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_value(entry.object)?))
+    }
+
+    async fn put_object<T>(&self, kind: LedgerObjectKind, id: &str, obj: T) -> VcxLedgerResult<()>
+    where
+        T: Serialize + for<'de> Deserialize<'de> + Send,
+    {
+        self.store(kind.cache_id(id), kind, serde_json::to_value(&obj)?)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Mutex};
+
+    use aries_vcx_wallet::{
+        errors::error::{NotFoundInfo, VcxWalletResult},
+        wallet::{base_wallet::record::AllRecords, record_tags::RecordTags},
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct TestRecordWallet {
+        records: Mutex<HashMap<String, Record>>,
+    }
+
+    #[async_trait]
+    impl RecordWallet for TestRecordWallet {
+        async fn all_records(&self) -> VcxWalletResult<Box<dyn AllRecords + Send>> {
+            unimplemented!()
+        }
+
+        async fn add_record(&self, record: Record) -> VcxWalletResult<()> {
+            let mut records = self.records.lock().unwrap();
+            if records.contains_key(record.name()) {
+                return Err(VcxWalletError::DuplicateRecord(record.name().to_owned()));
+            }
+            records.insert(record.name().to_owned(), record);
+            Ok(())
+        }
+
+        async fn get_record(
+            &self,
+            category: RecordCategory,
+            name: &str,
+        ) -> VcxWalletResult<Record> {
+            self.records
+                .lock()
+                .unwrap()
+                .get(name)
+                .cloned()
+                .ok_or_else(|| {
+                    VcxWalletError::RecordNotFound(NotFoundInfo::new_with_details(category, name))
+                })
+        }
+
+        async fn update_record_tags(
+            &self,
+            _category: RecordCategory,
+            _name: &str,
+            _new_tags: RecordTags,
+        ) -> VcxWalletResult<()> {
+            unimplemented!()
+        }
+
+        async fn update_record_value(
+            &self,
+            category: RecordCategory,
+            name: &str,
+            new_value: &str,
+        ) -> VcxWalletResult<()> {
+            let record = Record::builder()
+                .category(category)
+                .name(name.to_owned())
+                .value(new_value.to_owned())
+                .build();
+            self.records.lock().unwrap().insert(name.to_owned(), record);
+            Ok(())
+        }
+
+        async fn delete_record(&self, category: RecordCategory, name: &str) -> VcxWalletResult<()> {
+            self.records
+                .lock()
+                .unwrap()
+                .remove(name)
+                .map(|_| ())
+                .ok_or_else(|| {
+                    VcxWalletError::RecordNotFound(NotFoundInfo::new_with_details(category, name))
+                })
+        }
+
+        async fn search_record(
+            &self,
+            _category: RecordCategory,
+            _search_filter: Option<String>,
+        ) -> VcxWalletResult<Vec<Record>> {
+            Ok(self.records.lock().unwrap().values().cloned().collect())
+        }
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+    struct TestStruct {
+        field: String,
+    }
+
+    fn _test_object() -> TestStruct {
+        TestStruct {
+            field: "test".to_string(),
+        }
+    }
+
+    fn _cacher(config: WalletResponseCacherConfig) -> WalletResponseCacher<TestRecordWallet> {
+        WalletResponseCacher::new(TestRecordWallet::default(), config)
+    }
+
+    #[tokio::test]
+    async fn test_put_and_get_object() -> VcxLedgerResult<()> {
+        let cacher = _cacher(WalletResponseCacherConfig::default());
+        let test_object = _test_object();
+
+        cacher
+            .put_object(LedgerObjectKind::Schema, "id1", test_object.clone())
+            .await?;
+
+        let cached_object: Option<TestStruct> =
+            cacher.get_object(LedgerObjectKind::Schema, "id1").await?;
+        assert_eq!(Some(test_object), cached_object);
+        let other_kind: Option<TestStruct> =
+            cacher.get_object(LedgerObjectKind::CredDef, "id1").await?;
+        assert_eq!(None, other_kind);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_expiration_per_object_kind() -> VcxLedgerResult<()> {
+        let config = WalletResponseCacherConfig::builder()
+            .ttl(LedgerObjectKind::RevRegDelta, Duration::from_millis(1))
+            .build();
+        let cacher = _cacher(config);
+        let test_object = _test_object();
+
+        cacher
+            .put_object(LedgerObjectKind::Schema, "id1", test_object.clone())
+            .await?;
+        cacher
+            .put_object(LedgerObjectKind::RevRegDelta, "id1", test_object.clone())
+            .await?;
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let schema: Option<TestStruct> = cacher.get_object(LedgerObjectKind::Schema, "id1").await?;
+        assert_eq!(Some(test_object), schema);
+        let delta: Option<TestStruct> = cacher
+            .get_object(LedgerObjectKind::RevRegDelta, "id1")
+            .await?;
+        assert_eq!(None, delta);
+        assert_eq!(cacher.wallet.records.lock().unwrap().len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prune_expired_removes_only_expired_entries() -> VcxLedgerResult<()> {
+        let config = WalletResponseCacherConfig::builder()
+            .ttl(LedgerObjectKind::RevRegDelta, Duration::from_millis(1))
+            .build();
+        let cacher = _cacher(config);
+
+        cacher
+            .put_object(LedgerObjectKind::Schema, "id1", _test_object())
+            .await?;
+        cacher
+            .put_object(LedgerObjectKind::RevRegDelta, "id1", _test_object())
+            .await?;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        assert_eq!(cacher.prune_expired().await?, 1);
+        let records = cacher.wallet.records.lock().unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(records.contains_key(&LedgerObjectKind::Schema.cache_id("id1")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_put_overwrites_existing_entry() -> VcxLedgerResult<()> {
+        let cacher = _cacher(WalletResponseCacherConfig::default());
+
+        cacher.put("id1", _test_object()).await?;
+        let updated = TestStruct {
+            field: "updated".to_string(),
+        };
+        cacher.put("id1", updated.clone()).await?;
+
+        let cached_object: Option<TestStruct> = cacher.get("id1", None).await?;
+        assert_eq!(Some(updated), cached_object);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_options_ttl_override_config_ttl() -> VcxLedgerResult<()> {
+        let cacher = _cacher(WalletResponseCacherConfig::default());
+
+        cacher.put("id1", _test_object()).await?;
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let options = WalletResponseCacherOptions::builder()
+            .ttl(Duration::from_millis(1))
+            .build();
+        let cached_object: Option<TestStruct> = cacher.get("id1", Some(options)).await?;
+        assert_eq!(None, cached_object);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_entry_is_discarded() -> VcxLedgerResult<()> {
+        let cacher = _cacher(WalletResponseCacherConfig::default());
+        cacher
+            .wallet
+            .add_record(
+                Record::builder()
+                    .category(RecordCategory::LedgerCache)
+                    .name("id1".to_owned())
+                    .value("not json".to_owned())
+                    .build(),
+            )
+            .await?;
+
+        let cached_object: Option<TestStruct> = cacher.get("id1", None).await?;
+        assert_eq!(None, cached_object);
+        assert!(cacher.wallet.records.lock().unwrap().is_empty());
+
+        Ok(())
+    }
+}
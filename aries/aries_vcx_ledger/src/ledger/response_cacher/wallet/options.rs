@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+#[derive(Default)]
+pub struct WalletResponseCacherOptions {
+    ttl: Option<Duration>,
+}
+
+impl WalletResponseCacherOptions {
+    pub fn builder() -> WalletResponseCacherOptionsBuilder {
+        WalletResponseCacherOptionsBuilder::default()
+    }
+
+    pub fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+}
+
+#[derive(Default)]
+pub struct WalletResponseCacherOptionsBuilder {
+    ttl: Option<Duration>,
+}
+
+impl WalletResponseCacherOptionsBuilder {
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn build(self) -> WalletResponseCacherOptions {
+        WalletResponseCacherOptions { ttl: self.ttl }
+    }
+}
@@ -6,17 +6,22 @@ use async_trait::async_trait;
 use public_key::Key;
 
 use super::{
-    askar_utils::{local_key_to_public_key, public_key_to_local_key, seed_from_opt},
+    askar_utils::{into_x25519, local_key_to_public_key, public_key_to_local_key, seed_from_opt},
     pack::Pack,
+    pack_v2::pack_v2,
+    packing_types_v2::JweV2,
     sig_type::SigType,
     unpack::unpack,
+    unpack_v2::unpack_v2,
     AskarWallet,
 };
 use crate::{
     errors::error::{VcxWalletError, VcxWalletResult},
     wallet::{
         base_wallet::{did_data::DidData, did_wallet::DidWallet, record_category::RecordCategory},
-        structs_io::UnpackMessageOutput,
+        structs_io::{
+            JweContentEncryption, KeyAgreementKey, UnpackMessageOutput, UnpackMessageOutputV2,
+        },
     },
 };
 
@@ -188,4 +193,47 @@ impl DidWallet for AskarWallet {
     async fn unpack_message(&self, msg: &[u8]) -> VcxWalletResult<UnpackMessageOutput> {
         Ok(unpack(serde_json::from_slice(msg)?, &mut self.session().await?).await?)
     }
+
+    async fn pack_message_v2(
+        &self,
+        sender: Option<KeyAgreementKey>,
+        recipients: Vec<KeyAgreementKey>,
+        enc: JweContentEncryption,
+        msg: &[u8],
+    ) -> VcxWalletResult<Vec<u8>> {
+        let sender = match &sender {
+            Some(sender) => {
+                let my_key = self
+                    .fetch_local_key(&mut self.session().await?, &sender.key.base58())
+                    .await?;
+                Some((sender.kid.as_str(), into_x25519(my_key)?))
+            }
+            None => None,
+        };
+        pack_v2(sender, &recipients, enc, msg)
+    }
+
+    async fn unpack_message_v2(
+        &self,
+        msg: &[u8],
+        recipients: Vec<KeyAgreementKey>,
+        sender: Option<KeyAgreementKey>,
+    ) -> VcxWalletResult<UnpackMessageOutputV2> {
+        let jwe: JweV2 = serde_json::from_slice(msg)?;
+        let mut session = self.session().await?;
+        for recipient in jwe.recipients.iter() {
+            let Some(candidate) = recipients
+                .iter()
+                .find(|candidate| candidate.kid == recipient.header.kid)
+            else {
+                continue;
+            };
+            if let Some(key_entry) = session.fetch_key(&candidate.key.base58(), false).await? {
+                let local_key = into_x25519(key_entry.load_local_key()?)?;
+                return unpack_v2(&jwe, &candidate.kid, &local_key, sender.as_ref());
+            }
+        }
+
+        Err(VcxWalletError::NoRecipientKeyFound)
+    }
 }
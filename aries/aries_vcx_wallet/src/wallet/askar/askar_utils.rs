@@ -53,6 +53,15 @@ pub fn ed25519_to_x25519(local_key: &LocalKey) -> VcxWalletResult<LocalKey> {
     Ok(local_key.convert_key(KeyAlg::X25519)?)
 }
 
+/// X25519 key to perform ECDH with, for a key held by the wallet which is either an X25519 key
+/// agreement key or an Ed25519 key to convert.
+pub fn into_x25519(local_key: LocalKey) -> VcxWalletResult<LocalKey> {
+    match local_key.algorithm() {
+        KeyAlg::X25519 => Ok(local_key),
+        _ => ed25519_to_x25519(&local_key),
+    }
+}
+
 pub fn seed_from_opt(maybe_seed: Option<&str>) -> String {
     match maybe_seed {
         Some(val) => val.into(),
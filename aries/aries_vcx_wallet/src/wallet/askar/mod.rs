@@ -25,11 +25,14 @@ mod entry;
 mod entry_tags;
 pub mod key_method;
 mod pack;
+mod pack_v2;
 mod packing_types;
+mod packing_types_v2;
 mod partial_record;
 mod rng_method;
 mod sig_type;
 mod unpack;
+mod unpack_v2;
 
 #[derive(Debug)]
 pub struct AskarWallet {
@@ -234,10 +237,14 @@ impl AskarWallet {
 
 #[cfg(test)]
 pub mod tests {
+    use aries_askar::kms::{KeyAlg, LocalKey};
+    use public_key::{Key, KeyType};
+
     use super::AskarWallet;
     use crate::wallet::{
         askar::{askar_wallet_config::AskarWalletConfig, key_method::KeyMethod},
-        base_wallet::ManageWallet,
+        base_wallet::{did_wallet::DidWallet, ManageWallet},
+        structs_io::{JweContentEncryption, KeyAgreementKey},
     };
 
     pub async fn dev_setup_askar_wallet() -> AskarWallet {
@@ -252,4 +259,44 @@ pub mod tests {
 
         config.create_wallet().await.unwrap()
     }
+
+    #[tokio::test]
+    async fn test_pack_unpack_v2_with_x25519_key_agreement_key() {
+        let wallet = dev_setup_askar_wallet().await;
+        let local_key = LocalKey::generate_with_rng(KeyAlg::X25519, false).unwrap();
+        let key = Key::new(
+            local_key.to_public_bytes().unwrap().to_vec(),
+            KeyType::X25519,
+        )
+        .unwrap();
+        wallet
+            .session()
+            .await
+            .unwrap()
+            .insert_key(&key.base58(), &local_key, None, None, None, None)
+            .await
+            .unwrap();
+        let key_agreement_key = KeyAgreementKey::new("did:example:bob#key-x25519", key);
+
+        let packed = wallet
+            .pack_message_v2(
+                Some(key_agreement_key.clone()),
+                vec![key_agreement_key.clone()],
+                JweContentEncryption::A256CbcHs512,
+                b"foobar",
+            )
+            .await
+            .unwrap();
+        let unpacked = wallet
+            .unpack_message_v2(
+                &packed,
+                vec![key_agreement_key.clone()],
+                Some(key_agreement_key),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(unpacked.message, "foobar");
+        assert_eq!(unpacked.recipient_kid, "did:example:bob#key-x25519");
+    }
 }
@@ -0,0 +1,124 @@
+use aries_askar::{
+    crypto::alg::{AesTypes, KeyAlg},
+    kms::{derive_key_ecdh_1pu, derive_key_ecdh_es, LocalKey},
+};
+use public_key::{Key, KeyType};
+
+use super::{
+    askar_utils::ed25519_to_x25519,
+    packing_types_v2::{
+        content_key_alg, recipients_apv, JweV2, KeyWrapAlg, ProtectedDataV2, RecipientHeaderV2,
+        RecipientV2, DIDCOMM_ENCRYPTED_TYP,
+    },
+};
+use crate::{
+    errors::error::{VcxWalletError, VcxWalletResult},
+    wallet::{
+        base_wallet::base64_string::Base64String,
+        structs_io::{JweContentEncryption, KeyAgreementKey},
+    },
+};
+
+const KEY_WRAP_ALG: KeyAlg = KeyAlg::Aes(AesTypes::A256Kw);
+
+/// Converts a public key agreement key to the X25519 key used for ECDH.
+pub fn to_x25519_public_key(key: &Key) -> VcxWalletResult<LocalKey> {
+    match key.key_type() {
+        KeyType::X25519 => Ok(LocalKey::from_public_bytes(KeyAlg::X25519, key.key())?),
+        KeyType::Ed25519 => {
+            ed25519_to_x25519(&LocalKey::from_public_bytes(KeyAlg::Ed25519, key.key())?)
+        }
+        other => Err(VcxWalletError::Unimplemented(format!(
+            "Unsupported key agreement key type: {other:?}"
+        ))),
+    }
+}
+
+/// Packs a DIDComm v2 envelope. The sender, if any, is given with its X25519 secret key.
+pub fn pack_v2(
+    sender: Option<(&str, LocalKey)>,
+    recipients: &[KeyAgreementKey],
+    enc: JweContentEncryption,
+    msg: &[u8],
+) -> VcxWalletResult<Vec<u8>> {
+    if recipients.is_empty() {
+        return Err(VcxWalletError::InvalidInput(
+            "recipient keys should not be empty for 'pack_message_v2'".into(),
+        ));
+    }
+    if sender.is_some() && enc != JweContentEncryption::A256CbcHs512 {
+        return Err(VcxWalletError::InvalidInput(format!(
+            "authcrypt requires A256CBC-HS512 content encryption, got {enc:?}"
+        )));
+    }
+
+    let recipient_keys = recipients
+        .iter()
+        .map(|recipient| to_x25519_public_key(&recipient.key))
+        .collect::<VcxWalletResult<Vec<_>>>()?;
+    let apv = recipients_apv(recipients.iter().map(|recipient| recipient.kid.as_str()));
+    let ephemeral_key = LocalKey::generate_with_rng(KeyAlg::X25519, true)?;
+
+    let alg = match sender {
+        Some(_) => KeyWrapAlg::Ecdh1PuA256Kw,
+        None => KeyWrapAlg::EcdhEsA256Kw,
+    };
+    let protected = ProtectedDataV2 {
+        typ: DIDCOMM_ENCRYPTED_TYP.to_owned(),
+        alg,
+        enc,
+        skid: sender.as_ref().map(|(kid, _)| kid.to_string()),
+        apu: sender
+            .as_ref()
+            .map(|(kid, _)| Base64String::from_bytes(kid.as_bytes())),
+        apv: Base64String::from_bytes(&apv),
+        epk: serde_json::from_str(&ephemeral_key.to_jwk_public(None)?)?,
+    };
+    let protected = Base64String::from_bytes(serde_json::to_string(&protected)?.as_bytes());
+
+    let cek = LocalKey::generate_with_rng(content_key_alg(enc), true)?;
+    let encrypted = cek.aead_encrypt(msg, &cek.aead_random_nonce()?, &protected.as_bytes())?;
+
+    let mut encrypted_recipients = Vec::with_capacity(recipients.len());
+    for (recipient, recipient_key) in recipients.iter().zip(recipient_keys) {
+        let kek = match &sender {
+            Some((kid, sender_key)) => derive_key_ecdh_1pu(
+                KEY_WRAP_ALG,
+                &ephemeral_key,
+                sender_key,
+                &recipient_key,
+                alg.id().as_bytes(),
+                kid.as_bytes(),
+                &apv,
+                encrypted.tag(),
+                false,
+            )?,
+            None => derive_key_ecdh_es(
+                KEY_WRAP_ALG,
+                &ephemeral_key,
+                &recipient_key,
+                alg.id().as_bytes(),
+                &[],
+                &apv,
+                false,
+            )?,
+        };
+        let wrapped = kek.wrap_key(&cek, &[])?;
+        encrypted_recipients.push(RecipientV2 {
+            header: RecipientHeaderV2 {
+                kid: recipient.kid.clone(),
+            },
+            encrypted_key: Base64String::from_bytes(
+                &[wrapped.ciphertext(), wrapped.tag()].concat(),
+            ),
+        });
+    }
+
+    Ok(serde_json::to_vec(&JweV2 {
+        protected,
+        recipients: encrypted_recipients,
+        iv: Base64String::from_bytes(encrypted.nonce()),
+        ciphertext: Base64String::from_bytes(encrypted.ciphertext()),
+        tag: Base64String::from_bytes(encrypted.tag()),
+    })?)
+}
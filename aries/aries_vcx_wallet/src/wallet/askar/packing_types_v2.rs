@@ -0,0 +1,96 @@
+use aries_askar::{
+    crypto::alg::{AesTypes, Chacha20Types, KeyAlg},
+    kms::LocalKey,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    errors::error::VcxWalletResult,
+    wallet::{
+        base_wallet::base64_string::Base64String, structs_io::JweContentEncryption,
+        utils::bytes_to_string,
+    },
+};
+
+pub const DIDCOMM_ENCRYPTED_TYP: &str = "application/didcomm-encrypted+json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum KeyWrapAlg {
+    #[serde(rename = "ECDH-ES+A256KW")]
+    EcdhEsA256Kw,
+    #[serde(rename = "ECDH-1PU+A256KW")]
+    Ecdh1PuA256Kw,
+}
+
+impl KeyWrapAlg {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::EcdhEsA256Kw => "ECDH-ES+A256KW",
+            Self::Ecdh1PuA256Kw => "ECDH-1PU+A256KW",
+        }
+    }
+}
+
+pub fn content_key_alg(enc: JweContentEncryption) -> KeyAlg {
+    match enc {
+        JweContentEncryption::Xc20P => KeyAlg::Chacha20(Chacha20Types::XC20P),
+        JweContentEncryption::A256Gcm => KeyAlg::Aes(AesTypes::A256Gcm),
+        JweContentEncryption::A256CbcHs512 => KeyAlg::Aes(AesTypes::A256CbcHs512),
+    }
+}
+
+/// `apv` header value: the hash of the sorted recipient key ids, joined with `.`.
+pub fn recipients_apv<'a>(kids: impl IntoIterator<Item = &'a str>) -> Vec<u8> {
+    let mut kids: Vec<_> = kids.into_iter().collect();
+    kids.sort_unstable();
+    Sha256::digest(kids.join(".").as_bytes()).to_vec()
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct JweV2 {
+    pub protected: Base64String,
+    pub recipients: Vec<RecipientV2>,
+    pub iv: Base64String,
+    pub ciphertext: Base64String,
+    pub tag: Base64String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecipientV2 {
+    pub header: RecipientHeaderV2,
+    pub encrypted_key: Base64String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecipientHeaderV2 {
+    pub kid: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProtectedDataV2 {
+    pub typ: String,
+    pub alg: KeyWrapAlg,
+    pub enc: JweContentEncryption,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub apu: Option<Base64String>,
+    pub apv: Base64String,
+    /// Ephemeral public key, as a JWK.
+    pub epk: serde_json::Value,
+}
+
+impl ProtectedDataV2 {
+    pub fn ephemeral_key(&self) -> VcxWalletResult<LocalKey> {
+        Ok(LocalKey::from_jwk(&self.epk.to_string())?)
+    }
+
+    pub fn sender_kid(&self) -> VcxWalletResult<Option<String>> {
+        match (&self.skid, &self.apu) {
+            (Some(skid), _) => Ok(Some(skid.clone())),
+            (None, Some(apu)) => Ok(Some(bytes_to_string(apu.decode()?)?)),
+            (None, None) => Ok(None),
+        }
+    }
+}
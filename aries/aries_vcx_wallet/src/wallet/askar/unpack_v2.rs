@@ -0,0 +1,97 @@
+use aries_askar::{
+    crypto::alg::{AesTypes, KeyAlg},
+    kms::{derive_key_ecdh_1pu, derive_key_ecdh_es, LocalKey, ToDecrypt},
+};
+
+use super::{
+    askar_utils::from_json_str,
+    pack_v2::to_x25519_public_key,
+    packing_types_v2::{content_key_alg, JweV2, KeyWrapAlg, ProtectedDataV2},
+};
+use crate::{
+    errors::error::{VcxWalletError, VcxWalletResult},
+    wallet::{
+        structs_io::{KeyAgreementKey, UnpackMessageOutputV2},
+        utils::bytes_to_string,
+    },
+};
+
+const KEY_WRAP_ALG: KeyAlg = KeyAlg::Aes(AesTypes::A256Kw);
+
+pub fn unpack_protected_data_v2(jwe: &JweV2) -> VcxWalletResult<ProtectedDataV2> {
+    from_json_str(&jwe.protected.decode_to_string()?)
+}
+
+/// Unpacks a DIDComm v2 envelope for the recipient `recipient_kid`, given with its X25519 secret
+/// key.
+pub fn unpack_v2(
+    jwe: &JweV2,
+    recipient_kid: &str,
+    recipient_key: &LocalKey,
+    sender: Option<&KeyAgreementKey>,
+) -> VcxWalletResult<UnpackMessageOutputV2> {
+    let protected = unpack_protected_data_v2(jwe)?;
+    let recipient = jwe
+        .recipients
+        .iter()
+        .find(|recipient| recipient.header.kid == recipient_kid)
+        .ok_or(VcxWalletError::NoRecipientKeyFound)?;
+
+    let ephemeral_key = protected.ephemeral_key()?;
+    let apv = protected.apv.decode()?;
+    let tag = jwe.tag.decode()?;
+    let sender_kid = protected.sender_kid()?;
+
+    let kek = match protected.alg {
+        KeyWrapAlg::EcdhEsA256Kw => derive_key_ecdh_es(
+            KEY_WRAP_ALG,
+            &ephemeral_key,
+            recipient_key,
+            protected.alg.id().as_bytes(),
+            &[],
+            &apv,
+            true,
+        )?,
+        KeyWrapAlg::Ecdh1PuA256Kw => {
+            let sender_kid = sender_kid.as_deref().ok_or_else(|| {
+                VcxWalletError::InvalidInput("authcrypt envelope is missing skid".into())
+            })?;
+            let sender = sender
+                .filter(|sender| sender.kid == sender_kid)
+                .ok_or_else(|| {
+                    VcxWalletError::InvalidInput(format!(
+                        "sender key {sender_kid} is needed to unpack authcrypt envelope"
+                    ))
+                })?;
+            derive_key_ecdh_1pu(
+                KEY_WRAP_ALG,
+                &ephemeral_key,
+                &to_x25519_public_key(&sender.key)?,
+                recipient_key,
+                protected.alg.id().as_bytes(),
+                sender_kid.as_bytes(),
+                &apv,
+                &tag,
+                true,
+            )?
+        }
+    };
+
+    let cek = kek.unwrap_key(
+        content_key_alg(protected.enc),
+        ToDecrypt::from(recipient.encrypted_key.decode()?.as_slice()),
+        &[],
+    )?;
+    let ciphertext = jwe.ciphertext.decode()?;
+    let message = cek.aead_decrypt(
+        ToDecrypt::from((ciphertext.as_ref(), tag.as_ref())),
+        &jwe.iv.decode()?,
+        &jwe.protected.as_bytes(),
+    )?;
+
+    Ok(UnpackMessageOutputV2 {
+        message: bytes_to_string(message.to_vec())?,
+        recipient_kid: recipient_kid.to_owned(),
+        sender_kid,
+    })
+}
@@ -2,7 +2,12 @@ use async_trait::async_trait;
 use public_key::Key;
 
 use super::did_data::DidData;
-use crate::{errors::error::VcxWalletResult, wallet::structs_io::UnpackMessageOutput};
+use crate::{
+    errors::error::VcxWalletResult,
+    wallet::structs_io::{
+        JweContentEncryption, KeyAgreementKey, UnpackMessageOutput, UnpackMessageOutputV2,
+    },
+};
 
 #[async_trait]
 pub trait DidWallet {
@@ -32,4 +37,27 @@ pub trait DidWallet {
     ) -> VcxWalletResult<Vec<u8>>;
 
     async fn unpack_message(&self, msg: &[u8]) -> VcxWalletResult<UnpackMessageOutput>;
+
+    /// Packs `msg` into a DIDComm v2 JWE envelope.
+    ///
+    /// Without a sender the message is anoncrypted (`ECDH-ES+A256KW`), otherwise it is
+    /// authcrypted (`ECDH-1PU+A256KW`), which requires `A256CBC-HS512` content encryption. The
+    /// sender key must be held by the wallet. All keys may be X25519 or Ed25519 keys.
+    async fn pack_message_v2(
+        &self,
+        sender: Option<KeyAgreementKey>,
+        recipients: Vec<KeyAgreementKey>,
+        enc: JweContentEncryption,
+        msg: &[u8],
+    ) -> VcxWalletResult<Vec<u8>>;
+
+    /// Unpacks a DIDComm v2 JWE envelope addressed to one of `recipients`, whose X25519 or Ed25519
+    /// keys must be held by the wallet. Authcrypted envelopes additionally need the key of the
+    /// sender referenced by their `skid` header.
+    async fn unpack_message_v2(
+        &self,
+        msg: &[u8],
+        recipients: Vec<KeyAgreementKey>,
+        sender: Option<KeyAgreementKey>,
+    ) -> VcxWalletResult<UnpackMessageOutputV2>;
 }
@@ -72,6 +72,7 @@ mod tests {
                 record_wallet::RecordWallet,
             },
             record_tags::{RecordTag, RecordTags},
+            structs_io::{JweContentEncryption, KeyAgreementKey},
             utils::random_seed,
         },
     };
@@ -256,6 +257,96 @@ mod tests {
         assert_eq!(msg, unpacked.message);
     }
 
+    #[tokio::test]
+    async fn did_wallet_should_pack_and_unpack_anoncrypt_v2() {
+        let wallet = build_test_wallet().await;
+
+        let receiver_data = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let receiver =
+            KeyAgreementKey::new("did:example:bob#key-1", receiver_data.verkey().clone());
+
+        let msg = "pack me";
+
+        for enc in [JweContentEncryption::Xc20P, JweContentEncryption::A256Gcm] {
+            let packed = wallet
+                .pack_message_v2(None, vec![receiver.clone()], enc, msg.as_bytes())
+                .await
+                .unwrap();
+
+            let unpacked = wallet
+                .unpack_message_v2(&packed, vec![receiver.clone()], None)
+                .await
+                .unwrap();
+
+            assert_eq!(msg, unpacked.message);
+            assert_eq!(receiver.kid, unpacked.recipient_kid);
+            assert_eq!(None, unpacked.sender_kid);
+        }
+    }
+
+    #[tokio::test]
+    async fn did_wallet_should_pack_and_unpack_authcrypt_v2() {
+        let wallet = build_test_wallet().await;
+
+        let sender_data = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let sender = KeyAgreementKey::new("did:example:alice#key-1", sender_data.verkey().clone());
+        let receiver_data = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let receiver =
+            KeyAgreementKey::new("did:example:bob#key-1", receiver_data.verkey().clone());
+
+        let msg = "pack me";
+
+        let packed = wallet
+            .pack_message_v2(
+                Some(sender.clone()),
+                vec![receiver.clone()],
+                JweContentEncryption::A256CbcHs512,
+                msg.as_bytes(),
+            )
+            .await
+            .unwrap();
+
+        let err = wallet
+            .unpack_message_v2(&packed, vec![receiver.clone()], None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, VcxWalletError::InvalidInput(_)));
+
+        let unpacked = wallet
+            .unpack_message_v2(&packed, vec![receiver], Some(sender.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(msg, unpacked.message);
+        assert_eq!(Some(sender.kid), unpacked.sender_kid);
+    }
+
+    #[tokio::test]
+    async fn did_wallet_should_not_authcrypt_v2_without_cbc_hmac() {
+        let wallet = build_test_wallet().await;
+
+        let sender_data = wallet.create_and_store_my_did(None, None).await.unwrap();
+        let receiver_data = wallet.create_and_store_my_did(None, None).await.unwrap();
+
+        let err = wallet
+            .pack_message_v2(
+                Some(KeyAgreementKey::new(
+                    "did:example:alice#key-1",
+                    sender_data.verkey().clone(),
+                )),
+                vec![KeyAgreementKey::new(
+                    "did:example:bob#key-1",
+                    receiver_data.verkey().clone(),
+                )],
+                JweContentEncryption::Xc20P,
+                b"pack me",
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, VcxWalletError::InvalidInput(_)));
+    }
+
     #[tokio::test]
     async fn record_wallet_should_create_record() {
         let wallet = build_test_wallet().await;
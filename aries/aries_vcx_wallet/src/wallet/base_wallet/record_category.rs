@@ -14,12 +14,23 @@ const CRED_SCHEMA: &str = "VCX_CRED_SCHEMA";
 const CRED_MAP_SCHEMA_ID: &str = "VCX_CRED_MAP_SCHEMA_ID";
 const REV_REG: &str = "VCX_REV_REG";
 const REV_REG_DELTA: &str = "VCX_REV_REG_DELTA";
+const REV_STATUS_LIST: &str = "VCX_REV_STATUS_LIST";
 const REV_REG_INFO: &str = "VCX_REV_REG_INFO";
 const REV_REG_DEF: &str = "VCX_REV_REG_DEF";
 const REV_REG_DEF_PRIV: &str = "VCX_REV_REG_DEF_PRIV";
+const REV_STATE: &str = "VCX_REV_STATE";
+const LEDGER_CACHE: &str = "VCX_LEDGER_CACHE";
 const DID: &str = "Indy::Did";
 const TMP_DID: &str = "Indy::TemporaryDid";
 const KEY: &str = "Indy::Key";
+const OUTBOUND_MESSAGE: &str = "VCX_OUTBOUND_MESSAGE";
+const PROTOCOL_CONNECTION: &str = "VCX_PROTOCOL_CONNECTION";
+const PROTOCOL_ISSUER: &str = "VCX_PROTOCOL_ISSUER";
+const PROTOCOL_HOLDER: &str = "VCX_PROTOCOL_HOLDER";
+const PROTOCOL_PROVER: &str = "VCX_PROTOCOL_PROVER";
+const PROTOCOL_VERIFIER: &str = "VCX_PROTOCOL_VERIFIER";
+const BASIC_MESSAGE: &str = "VCX_BASIC_MESSAGE";
+const DISPATCHED_MESSAGE: &str = "VCX_DISPATCHED_MESSAGE";
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum RecordCategory {
@@ -33,12 +44,23 @@ pub enum RecordCategory {
     CredMapSchemaId,
     RevReg,
     RevRegDelta,
+    RevStatusList,
     RevRegInfo,
     RevRegDef,
     RevRegDefPriv,
+    RevState,
+    LedgerCache,
     Did,
     TmpDid,
     Key,
+    OutboundMessage,
+    ProtocolConnection,
+    ProtocolIssuer,
+    ProtocolHolder,
+    ProtocolProver,
+    ProtocolVerifier,
+    BasicMessage,
+    DispatchedMessage,
 }
 
 impl FromStr for RecordCategory {
@@ -55,12 +77,23 @@ impl FromStr for RecordCategory {
             CRED_MAP_SCHEMA_ID => Ok(RecordCategory::CredMapSchemaId),
             REV_REG => Ok(RecordCategory::RevReg),
             REV_REG_DELTA => Ok(RecordCategory::RevRegDelta),
+            REV_STATUS_LIST => Ok(RecordCategory::RevStatusList),
             REV_REG_INFO => Ok(RecordCategory::RevRegInfo),
             REV_REG_DEF => Ok(RecordCategory::RevRegDef),
             REV_REG_DEF_PRIV => Ok(RecordCategory::RevRegDefPriv),
+            REV_STATE => Ok(RecordCategory::RevState),
+            LEDGER_CACHE => Ok(RecordCategory::LedgerCache),
             DID => Ok(RecordCategory::Did),
             TMP_DID => Ok(RecordCategory::TmpDid),
             KEY => Ok(RecordCategory::Key),
+            OUTBOUND_MESSAGE => Ok(RecordCategory::OutboundMessage),
+            PROTOCOL_CONNECTION => Ok(RecordCategory::ProtocolConnection),
+            PROTOCOL_ISSUER => Ok(RecordCategory::ProtocolIssuer),
+            PROTOCOL_HOLDER => Ok(RecordCategory::ProtocolHolder),
+            PROTOCOL_PROVER => Ok(RecordCategory::ProtocolProver),
+            PROTOCOL_VERIFIER => Ok(RecordCategory::ProtocolVerifier),
+            BASIC_MESSAGE => Ok(RecordCategory::BasicMessage),
+            DISPATCHED_MESSAGE => Ok(RecordCategory::DispatchedMessage),
             _ => Err(Self::Err::UnknownRecordCategory(s.into())),
         }
     }
@@ -78,12 +111,23 @@ impl Display for RecordCategory {
             RecordCategory::CredMapSchemaId => CRED_MAP_SCHEMA_ID,
             RecordCategory::RevReg => REV_REG,
             RecordCategory::RevRegDelta => REV_REG_DELTA,
+            RecordCategory::RevStatusList => REV_STATUS_LIST,
             RecordCategory::RevRegInfo => REV_REG_INFO,
             RecordCategory::RevRegDef => REV_REG_DEF,
             RecordCategory::RevRegDefPriv => REV_REG_DEF_PRIV,
+            RecordCategory::RevState => REV_STATE,
+            RecordCategory::LedgerCache => LEDGER_CACHE,
             RecordCategory::Did => DID,
             RecordCategory::TmpDid => TMP_DID,
             RecordCategory::Key => KEY,
+            RecordCategory::OutboundMessage => OUTBOUND_MESSAGE,
+            RecordCategory::ProtocolConnection => PROTOCOL_CONNECTION,
+            RecordCategory::ProtocolIssuer => PROTOCOL_ISSUER,
+            RecordCategory::ProtocolHolder => PROTOCOL_HOLDER,
+            RecordCategory::ProtocolProver => PROTOCOL_PROVER,
+            RecordCategory::ProtocolVerifier => PROTOCOL_VERIFIER,
+            RecordCategory::BasicMessage => BASIC_MESSAGE,
+            RecordCategory::DispatchedMessage => DISPATCHED_MESSAGE,
         };
 
         write!(f, "{}", value)
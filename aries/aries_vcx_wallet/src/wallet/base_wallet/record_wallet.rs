@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 
 use super::{
@@ -36,3 +38,53 @@ pub trait RecordWallet {
         search_filter: Option<String>,
     ) -> VcxWalletResult<Vec<Record>>;
 }
+
+/// Lets a wallet be shared, e.g. between an agent and a ledger response cache.
+#[async_trait]
+impl<T: RecordWallet + Send + Sync + ?Sized> RecordWallet for Arc<T> {
+    async fn all_records(&self) -> VcxWalletResult<Box<dyn AllRecords + Send>> {
+        self.as_ref().all_records().await
+    }
+
+    async fn add_record(&self, record: Record) -> VcxWalletResult<()> {
+        self.as_ref().add_record(record).await
+    }
+
+    async fn get_record(&self, category: RecordCategory, name: &str) -> VcxWalletResult<Record> {
+        self.as_ref().get_record(category, name).await
+    }
+
+    async fn update_record_tags(
+        &self,
+        category: RecordCategory,
+        name: &str,
+        new_tags: RecordTags,
+    ) -> VcxWalletResult<()> {
+        self.as_ref()
+            .update_record_tags(category, name, new_tags)
+            .await
+    }
+
+    async fn update_record_value(
+        &self,
+        category: RecordCategory,
+        name: &str,
+        new_value: &str,
+    ) -> VcxWalletResult<()> {
+        self.as_ref()
+            .update_record_value(category, name, new_value)
+            .await
+    }
+
+    async fn delete_record(&self, category: RecordCategory, name: &str) -> VcxWalletResult<()> {
+        self.as_ref().delete_record(category, name).await
+    }
+
+    async fn search_record(
+        &self,
+        category: RecordCategory,
+        search_filter: Option<String>,
+    ) -> VcxWalletResult<Vec<Record>> {
+        self.as_ref().search_record(category, search_filter).await
+    }
+}
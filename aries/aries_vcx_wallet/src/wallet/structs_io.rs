@@ -1,3 +1,4 @@
+use public_key::Key;
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
@@ -7,3 +8,39 @@ pub struct UnpackMessageOutput {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sender_verkey: Option<String>,
 }
+
+/// A key referenced from a DIDComm v2 envelope by its key id, usually a DID URL pointing to a
+/// `keyAgreement` verification method.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyAgreementKey {
+    pub kid: String,
+    pub key: Key,
+}
+
+impl KeyAgreementKey {
+    pub fn new(kid: impl Into<String>, key: Key) -> Self {
+        Self {
+            kid: kid.into(),
+            key,
+        }
+    }
+}
+
+/// Content encryption algorithm of a DIDComm v2 JWE envelope.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum JweContentEncryption {
+    #[serde(rename = "XC20P")]
+    Xc20P,
+    #[serde(rename = "A256GCM")]
+    A256Gcm,
+    #[serde(rename = "A256CBC-HS512")]
+    A256CbcHs512,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq)]
+pub struct UnpackMessageOutputV2 {
+    pub message: String,
+    pub recipient_kid: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sender_kid: Option<String>,
+}
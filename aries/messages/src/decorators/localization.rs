@@ -12,6 +12,10 @@ use url::Url;
 /// Struct representing the `~l10n` decorator, when it decorates the entire message, from its [RFC](<https://github.com/hyperledger/aries-rfcs/blob/main/features/0043-l10n/README.md>).
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, TypedBuilder)]
 pub struct MsgLocalization {
+    /// Locale the message was written in.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<Locale>,
     #[builder(default, setter(strip_option))]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub catalogs: Option<Vec<Url>>,
@@ -154,6 +158,7 @@ pub mod tests {
         )]);
 
         MsgLocalization::builder()
+            .locale(Locale(Language::Eng))
             .catalogs(catalogs)
             .locales(locales)
             .build()
@@ -200,6 +205,7 @@ pub mod tests {
         let localization = make_extended_msg_localization();
 
         let expected = json!({
+            "locale": localization.locale,
             "catalogs": localization.catalogs,
             "locales": localization.locales
         });
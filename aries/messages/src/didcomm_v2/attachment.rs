@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use typed_builder::TypedBuilder;
+use url::Url;
+
+use super::{from_epoch_secs, to_epoch_secs};
+use crate::{
+    decorators::attachment::{Attachment, AttachmentData, AttachmentType},
+    error::{DidCommV2Error, DidCommV2Result},
+};
+
+/// DIDComm v2 attachment, as defined in the [spec](<https://identity.foundation/didcomm-messaging/spec/v2.1/#attachments>).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TypedBuilder)]
+pub struct AttachmentV2 {
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media_type: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Seconds since the UNIX epoch.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lastmod_time: Option<u64>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub byte_count: Option<u64>,
+    pub data: AttachmentDataV2,
+}
+
+/// Unlike with DIDComm v1, the data of a DIDComm v2 attachment may be given in several
+/// representations at once.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, TypedBuilder)]
+pub struct AttachmentDataV2 {
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jws: Option<Value>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub links: Option<Vec<Url>>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base64: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub json: Option<Value>,
+}
+
+impl TryFrom<Attachment> for AttachmentV2 {
+    type Error = DidCommV2Error;
+
+    fn try_from(value: Attachment) -> DidCommV2Result<Self> {
+        let media_type = value
+            .mime_type
+            .map(serde_json::to_value)
+            .transpose()?
+            .and_then(|media_type| media_type.as_str().map(ToOwned::to_owned));
+        let jws = value
+            .data
+            .jws
+            .map(|jws| Value::Object(jws.into_iter().collect()));
+        let mut data = AttachmentDataV2 {
+            jws,
+            hash: value.data.sha256,
+            ..Default::default()
+        };
+        match value.data.content {
+            AttachmentType::Base64(base64) => data.base64 = Some(base64),
+            AttachmentType::Json(json) => data.json = Some(json),
+            AttachmentType::Links(links) => data.links = Some(links),
+        }
+
+        Ok(Self {
+            id: value.id,
+            description: value.description,
+            filename: value.filename,
+            media_type,
+            format: None,
+            lastmod_time: value.lastmod_time.as_ref().map(to_epoch_secs),
+            byte_count: value.byte_count,
+            data,
+        })
+    }
+}
+
+impl TryFrom<AttachmentV2> for Attachment {
+    type Error = DidCommV2Error;
+
+    /// The DIDComm v1 attachment keeps a single representation of the data, preferring JSON over
+    /// base64 over links.
+    fn try_from(value: AttachmentV2) -> DidCommV2Result<Self> {
+        let content = match (value.data.json, value.data.base64, value.data.links) {
+            (Some(json), _, _) => AttachmentType::Json(json),
+            (None, Some(base64), _) => AttachmentType::Base64(base64),
+            (None, None, Some(links)) => AttachmentType::Links(links),
+            (None, None, None) => return Err(DidCommV2Error::MissingField("attachment data")),
+        };
+        let jws = value.data.jws.map(serde_json::from_value).transpose()?;
+        let mime_type = value
+            .media_type
+            .map(|media_type| serde_json::from_value(Value::String(media_type)))
+            .transpose()?;
+
+        Ok(Self {
+            id: value.id,
+            description: value.description,
+            filename: value.filename,
+            mime_type,
+            lastmod_time: value.lastmod_time.and_then(from_epoch_secs),
+            byte_count: value.byte_count,
+            data: AttachmentData {
+                jws,
+                sha256: value.data.hash,
+                content,
+            },
+        })
+    }
+}
@@ -0,0 +1,791 @@
+//! Conversions between [`DidCommV2Message`] and the typed contents of the protocols which have a
+//! DIDComm v2 equivalent:
+//!
+//! | DIDComm v1 protocol | DIDComm v2 protocol |
+//! |---------------------|---------------------|
+//! | `trust_ping/1.0` | `trust-ping/2.0` |
+//! | `discover-features/1.0` | `discover-features/2.0` |
+//! | `basicmessage/1.0` | `basicmessage/2.0` |
+//! | `messagepickup/2.0` | `messagepickup/3.0` |
+//! | `coordinate-mediation/2.0` | `coordinate-mediation/2.0` |
+//! | `report-problem/1.0` | `report-problem/2.0` |
+//!
+//! The `~thread` and `~timing` decorators map to the `thid`, `pthid`, `created_time` and
+//! `expires_time` headers, except for problem reports, whose `~thread` is the thread the problem
+//! occurred in and maps to the `pthid` header. Decorators without a DIDComm v2 counterpart, such
+//! as `~transport` or `~l10n`, are dropped.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+use super::{from_epoch_secs, to_epoch_secs, AttachmentV2, DidCommV2Message};
+use crate::{
+    decorators::{attachment::Attachment, localization::Locale, thread::Thread, timing::Timing},
+    error::{DidCommV2Error, DidCommV2Result},
+    msg_fields::protocols::{
+        basic_message::{BasicMessage, BasicMessageContent, BasicMessageDecorators},
+        coordinate_mediation::v2::{
+            CoordinateMediationV2, MediateDenyV2, MediateDenyV2Content, MediateDenyV2Decorators,
+            MediateGrantV2, MediateGrantV2Content, MediateGrantV2Decorators, MediateRequestV2,
+            MediateRequestV2Content, Recipient, RecipientContent, RecipientDecorators,
+            RecipientQuery, RecipientQueryContent, RecipientUpdate, RecipientUpdateContent,
+            RecipientUpdateResponse, RecipientUpdateResponseContent,
+            RecipientUpdateResponseDecorators,
+        },
+        discover_features::{
+            disclose::{Disclose, DiscloseContent, DiscloseDecorators},
+            query::{Query, QueryContent, QueryDecorators},
+            DiscoverFeatures, ProtocolDescriptor,
+        },
+        pickup::{
+            Delivery, DeliveryContent, DeliveryDecorators, DeliveryRequest, DeliveryRequestContent,
+            DeliveryRequestDecorators, LiveDeliveryChange, LiveDeliveryChangeContent,
+            LiveDeliveryChangeDecorators, MessagesReceived, MessagesReceivedContent,
+            MessagesReceivedDecorators, Pickup, Status, StatusContent, StatusDecorators,
+            StatusRequest, StatusRequestContent, StatusRequestDecorators,
+        },
+        report_problem::{
+            Description, ProblemReport, ProblemReportContent, ProblemReportDecorators,
+        },
+        trust_ping::{
+            ping::{Ping, PingContent, PingDecorators},
+            ping_response::{PingResponse, PingResponseContent, PingResponseDecorators},
+            TrustPing,
+        },
+    },
+    AriesMessage,
+};
+
+pub const TRUST_PING_PING: &str = "https://didcomm.org/trust-ping/2.0/ping";
+pub const TRUST_PING_PING_RESPONSE: &str = "https://didcomm.org/trust-ping/2.0/ping-response";
+pub const DISCOVER_FEATURES_QUERIES: &str = "https://didcomm.org/discover-features/2.0/queries";
+pub const DISCOVER_FEATURES_DISCLOSE: &str = "https://didcomm.org/discover-features/2.0/disclose";
+pub const BASIC_MESSAGE_MESSAGE: &str = "https://didcomm.org/basicmessage/2.0/message";
+pub const PICKUP_STATUS_REQUEST: &str = "https://didcomm.org/messagepickup/3.0/status-request";
+pub const PICKUP_STATUS: &str = "https://didcomm.org/messagepickup/3.0/status";
+pub const PICKUP_DELIVERY_REQUEST: &str = "https://didcomm.org/messagepickup/3.0/delivery-request";
+pub const PICKUP_DELIVERY: &str = "https://didcomm.org/messagepickup/3.0/delivery";
+pub const PICKUP_MESSAGES_RECEIVED: &str =
+    "https://didcomm.org/messagepickup/3.0/messages-received";
+pub const PICKUP_LIVE_DELIVERY_CHANGE: &str =
+    "https://didcomm.org/messagepickup/3.0/live-delivery-change";
+pub const COORDINATE_MEDIATION_MEDIATE_REQUEST: &str =
+    "https://didcomm.org/coordinate-mediation/2.0/mediate-request";
+pub const COORDINATE_MEDIATION_MEDIATE_DENY: &str =
+    "https://didcomm.org/coordinate-mediation/2.0/mediate-deny";
+pub const COORDINATE_MEDIATION_MEDIATE_GRANT: &str =
+    "https://didcomm.org/coordinate-mediation/2.0/mediate-grant";
+pub const COORDINATE_MEDIATION_RECIPIENT_UPDATE: &str =
+    "https://didcomm.org/coordinate-mediation/2.0/recipient-update";
+pub const COORDINATE_MEDIATION_RECIPIENT_UPDATE_RESPONSE: &str =
+    "https://didcomm.org/coordinate-mediation/2.0/recipient-update-response";
+pub const COORDINATE_MEDIATION_RECIPIENT_QUERY: &str =
+    "https://didcomm.org/coordinate-mediation/2.0/recipient-query";
+pub const COORDINATE_MEDIATION_RECIPIENT: &str =
+    "https://didcomm.org/coordinate-mediation/2.0/recipient";
+pub const REPORT_PROBLEM_PROBLEM_REPORT: &str =
+    "https://didcomm.org/report-problem/2.0/problem-report";
+
+/// Feature type of the discover-features 2.0 queries and disclosures about protocols.
+const FEATURE_TYPE_PROTOCOL: &str = "protocol";
+
+/// Pickup 3.0 identifies the recipient by DID rather than by key.
+const PICKUP_RECIPIENT_KEY: &str = "recipient_key";
+const PICKUP_RECIPIENT_DID: &str = "recipient_did";
+
+impl TryFrom<AriesMessage> for DidCommV2Message {
+    type Error = DidCommV2Error;
+
+    fn try_from(value: AriesMessage) -> DidCommV2Result<Self> {
+        let msg = match value {
+            AriesMessage::TrustPing(TrustPing::Ping(msg)) => {
+                let mut v2 = with_body(msg.id, TRUST_PING_PING, &msg.content)?;
+                v2.set_thread(msg.decorators.thread.as_ref());
+                v2.set_timing(msg.decorators.timing.as_ref());
+                v2
+            }
+            AriesMessage::TrustPing(TrustPing::PingResponse(msg)) => {
+                let mut v2 = with_body(msg.id, TRUST_PING_PING_RESPONSE, &msg.content)?;
+                v2.set_thread(Some(&msg.decorators.thread));
+                v2.set_timing(msg.decorators.timing.as_ref());
+                v2
+            }
+            AriesMessage::DiscoverFeatures(DiscoverFeatures::Query(msg)) => {
+                let body = json!({
+                    "queries": [{
+                        "feature-type": FEATURE_TYPE_PROTOCOL,
+                        "match": msg.content.query,
+                    }],
+                });
+                let mut v2 = with_body(msg.id, DISCOVER_FEATURES_QUERIES, &body)?;
+                v2.set_timing(msg.decorators.timing.as_ref());
+                v2
+            }
+            AriesMessage::DiscoverFeatures(DiscoverFeatures::Disclose(msg)) => {
+                let disclosures = msg
+                    .content
+                    .protocols
+                    .iter()
+                    .map(|protocol| {
+                        let mut disclosure = json!({
+                            "feature-type": FEATURE_TYPE_PROTOCOL,
+                            "id": protocol.pid,
+                        });
+                        if let Some(roles) = &protocol.roles {
+                            disclosure["roles"] = serde_json::to_value(roles)?;
+                        }
+                        Ok(disclosure)
+                    })
+                    .collect::<DidCommV2Result<Vec<_>>>()?;
+                let mut v2 = with_body(
+                    msg.id,
+                    DISCOVER_FEATURES_DISCLOSE,
+                    &json!({ "disclosures": disclosures }),
+                )?;
+                v2.set_thread(Some(&msg.decorators.thread));
+                v2.set_timing(msg.decorators.timing.as_ref());
+                v2
+            }
+            AriesMessage::BasicMessage(msg) => {
+                let mut v2 = with_body(
+                    msg.id,
+                    BASIC_MESSAGE_MESSAGE,
+                    &json!({ "content": msg.content.content }),
+                )?;
+                v2.set_thread(msg.decorators.thread.as_ref());
+                v2.set_timing(msg.decorators.timing.as_ref());
+                v2.created_time = Some(to_epoch_secs(&msg.content.sent_time));
+                v2
+            }
+            AriesMessage::Pickup(msg) => pickup_to_v2(msg)?,
+            AriesMessage::CoordinateMediationV2(msg) => coordinate_mediation_to_v2(msg)?,
+            AriesMessage::ReportProblem(msg) => {
+                let mut body = json!({ "code": msg.content.description.code });
+                if let Some(comment) = msg.content.description.translations.get(&Locale::default())
+                {
+                    body["comment"] = Value::String(comment.clone());
+                }
+                if let Some(escalation_uri) = &msg.content.escalation_uri {
+                    body["escalate_to"] = Value::String(escalation_uri.to_string());
+                }
+                let mut v2 = with_body(msg.id, REPORT_PROBLEM_PROBLEM_REPORT, &body)?;
+                v2.pthid = msg.decorators.thread.map(|thread| thread.thid);
+                v2.set_timing(msg.decorators.timing.as_ref());
+                v2
+            }
+            other => {
+                let msg_type = serde_json::to_value(&other)?
+                    .get("@type")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_owned();
+                return Err(DidCommV2Error::NoV2Equivalent(msg_type));
+            }
+        };
+        Ok(msg)
+    }
+}
+
+fn pickup_to_v2(msg: Pickup) -> DidCommV2Result<DidCommV2Message> {
+    let (mut v2, thread) = match msg {
+        Pickup::StatusRequest(msg) => (
+            with_pickup_body(msg.id, PICKUP_STATUS_REQUEST, &msg.content)?,
+            msg.decorators.thread,
+        ),
+        Pickup::Status(msg) => (
+            with_pickup_body(msg.id, PICKUP_STATUS, &msg.content)?,
+            msg.decorators.thread,
+        ),
+        Pickup::DeliveryRequest(msg) => (
+            with_pickup_body(msg.id, PICKUP_DELIVERY_REQUEST, &msg.content)?,
+            msg.decorators.thread,
+        ),
+        Pickup::Delivery(msg) => {
+            let mut v2 = with_body(msg.id, PICKUP_DELIVERY, &json!({}))?;
+            if let Some(recipient_key) = msg.content.recipient_key {
+                v2.body[PICKUP_RECIPIENT_DID] = Value::String(recipient_key);
+            }
+            v2.attachments = msg
+                .content
+                .attach
+                .into_iter()
+                .map(AttachmentV2::try_from)
+                .collect::<DidCommV2Result<_>>()?;
+            (v2, msg.decorators.thread)
+        }
+        Pickup::MessagesReceived(msg) => (
+            with_body(msg.id, PICKUP_MESSAGES_RECEIVED, &msg.content)?,
+            msg.decorators.thread,
+        ),
+        Pickup::LiveDeliveryChange(msg) => (
+            with_body(msg.id, PICKUP_LIVE_DELIVERY_CHANGE, &msg.content)?,
+            msg.decorators.thread,
+        ),
+    };
+    v2.set_thread(thread.as_ref());
+    Ok(v2)
+}
+
+fn coordinate_mediation_to_v2(msg: CoordinateMediationV2) -> DidCommV2Result<DidCommV2Message> {
+    let (mut v2, thread) = match msg {
+        CoordinateMediationV2::MediateRequest(msg) => (
+            with_body(msg.id, COORDINATE_MEDIATION_MEDIATE_REQUEST, &msg.content)?,
+            None,
+        ),
+        CoordinateMediationV2::MediateDeny(msg) => (
+            with_body(msg.id, COORDINATE_MEDIATION_MEDIATE_DENY, &msg.content)?,
+            msg.decorators.thread,
+        ),
+        CoordinateMediationV2::MediateGrant(msg) => (
+            with_body(msg.id, COORDINATE_MEDIATION_MEDIATE_GRANT, &msg.content)?,
+            msg.decorators.thread,
+        ),
+        CoordinateMediationV2::RecipientUpdate(msg) => (
+            with_body(msg.id, COORDINATE_MEDIATION_RECIPIENT_UPDATE, &msg.content)?,
+            None,
+        ),
+        CoordinateMediationV2::RecipientUpdateResponse(msg) => (
+            with_body(
+                msg.id,
+                COORDINATE_MEDIATION_RECIPIENT_UPDATE_RESPONSE,
+                &msg.content,
+            )?,
+            msg.decorators.thread,
+        ),
+        CoordinateMediationV2::RecipientQuery(msg) => (
+            with_body(msg.id, COORDINATE_MEDIATION_RECIPIENT_QUERY, &msg.content)?,
+            None,
+        ),
+        CoordinateMediationV2::Recipient(msg) => (
+            with_body(msg.id, COORDINATE_MEDIATION_RECIPIENT, &msg.content)?,
+            msg.decorators.thread,
+        ),
+    };
+    v2.set_thread(thread.as_ref());
+    Ok(v2)
+}
+
+impl TryFrom<DidCommV2Message> for AriesMessage {
+    type Error = DidCommV2Error;
+
+    fn try_from(value: DidCommV2Message) -> DidCommV2Result<Self> {
+        let thread = value.thread();
+        let timing = value.timing();
+        let msg = match value.msg_type.as_str() {
+            TRUST_PING_PING => Ping::builder()
+                .id(value.id)
+                .content(body::<PingContent>(value.body)?)
+                .decorators(PingDecorators { thread, timing })
+                .build(),
+            TRUST_PING_PING_RESPONSE => PingResponse::builder()
+                .id(value.id)
+                .content(body::<PingResponseContent>(value.body)?)
+                .decorators(PingResponseDecorators {
+                    thread: thread.ok_or(DidCommV2Error::MissingField("thid"))?,
+                    timing,
+                })
+                .build(),
+            DISCOVER_FEATURES_QUERIES => {
+                let query = protocol_features(&value.body, "queries")
+                    .find_map(|query| query.get("match").and_then(Value::as_str))
+                    .ok_or(DidCommV2Error::MissingField("protocol query"))?;
+                Query::builder()
+                    .id(value.id)
+                    .content(QueryContent::builder().query(query.to_owned()).build())
+                    .decorators(QueryDecorators { timing })
+                    .build()
+            }
+            DISCOVER_FEATURES_DISCLOSE => {
+                let protocols = protocol_features(&value.body, "disclosures")
+                    .map(|disclosure| {
+                        Ok(ProtocolDescriptor {
+                            pid: serde_json::from_value(
+                                disclosure.get("id").cloned().unwrap_or_default(),
+                            )?,
+                            roles: disclosure
+                                .get("roles")
+                                .cloned()
+                                .map(serde_json::from_value)
+                                .transpose()?,
+                        })
+                    })
+                    .collect::<DidCommV2Result<Vec<_>>>()?;
+                Disclose::builder()
+                    .id(value.id)
+                    .content(DiscloseContent::builder().protocols(protocols).build())
+                    .decorators(DiscloseDecorators {
+                        thread: thread.ok_or(DidCommV2Error::MissingField("thid"))?,
+                        timing,
+                    })
+                    .build()
+            }
+            BASIC_MESSAGE_MESSAGE => {
+                let sent_time = value
+                    .created_time
+                    .and_then(from_epoch_secs)
+                    .ok_or(DidCommV2Error::MissingField("created_time"))?;
+                let content = value
+                    .body
+                    .get("content")
+                    .and_then(Value::as_str)
+                    .ok_or(DidCommV2Error::MissingField("content"))?;
+                BasicMessage::builder()
+                    .id(value.id)
+                    .content(
+                        BasicMessageContent::builder()
+                            .content(content.to_owned())
+                            .sent_time(sent_time)
+                            .build(),
+                    )
+                    .decorators(BasicMessageDecorators {
+                        l10n: None,
+                        thread,
+                        // `created_time` is the time the message was sent rather than the
+                        // `~timing.out_time`
+                        timing: timing
+                            .map(|timing| Timing {
+                                out_time: None,
+                                ..timing
+                            })
+                            .filter(|timing| timing != &Timing::default()),
+                    })
+                    .build()
+            }
+            PICKUP_STATUS_REQUEST => StatusRequest::builder()
+                .id(value.id)
+                .content(pickup_body::<StatusRequestContent>(value.body)?)
+                .decorators(StatusRequestDecorators {
+                    transport: None,
+                    thread,
+                })
+                .build(),
+            PICKUP_STATUS => Status::builder()
+                .id(value.id)
+                .content(pickup_body::<StatusContent>(value.body)?)
+                .decorators(StatusDecorators {
+                    transport: None,
+                    thread,
+                })
+                .build(),
+            PICKUP_DELIVERY_REQUEST => DeliveryRequest::builder()
+                .id(value.id)
+                .content(pickup_body::<DeliveryRequestContent>(value.body)?)
+                .decorators(DeliveryRequestDecorators {
+                    thread,
+                    transport: None,
+                })
+                .build(),
+            PICKUP_DELIVERY => {
+                let recipient_key = value
+                    .body
+                    .get(PICKUP_RECIPIENT_DID)
+                    .and_then(Value::as_str)
+                    .map(ToOwned::to_owned);
+                let attach = value
+                    .attachments
+                    .into_iter()
+                    .map(Attachment::try_from)
+                    .collect::<DidCommV2Result<_>>()?;
+                Delivery::builder()
+                    .id(value.id)
+                    .content(DeliveryContent {
+                        recipient_key,
+                        attach,
+                    })
+                    .decorators(DeliveryDecorators {
+                        transport: None,
+                        thread,
+                    })
+                    .build()
+            }
+            PICKUP_MESSAGES_RECEIVED => MessagesReceived::builder()
+                .id(value.id)
+                .content(body::<MessagesReceivedContent>(value.body)?)
+                .decorators(MessagesReceivedDecorators {
+                    transport: None,
+                    thread,
+                })
+                .build(),
+            PICKUP_LIVE_DELIVERY_CHANGE => LiveDeliveryChange::builder()
+                .id(value.id)
+                .content(body::<LiveDeliveryChangeContent>(value.body)?)
+                .decorators(LiveDeliveryChangeDecorators {
+                    transport: None,
+                    thread,
+                })
+                .build(),
+            COORDINATE_MEDIATION_MEDIATE_REQUEST => MediateRequestV2::builder()
+                .id(value.id)
+                .content(body::<MediateRequestV2Content>(value.body)?)
+                .build(),
+            COORDINATE_MEDIATION_MEDIATE_DENY => MediateDenyV2::builder()
+                .id(value.id)
+                .content(body::<MediateDenyV2Content>(value.body)?)
+                .decorators(MediateDenyV2Decorators { thread })
+                .build(),
+            COORDINATE_MEDIATION_MEDIATE_GRANT => MediateGrantV2::builder()
+                .id(value.id)
+                .content(body::<MediateGrantV2Content>(value.body)?)
+                .decorators(MediateGrantV2Decorators { thread })
+                .build(),
+            COORDINATE_MEDIATION_RECIPIENT_UPDATE => RecipientUpdate::builder()
+                .id(value.id)
+                .content(body::<RecipientUpdateContent>(value.body)?)
+                .build(),
+            COORDINATE_MEDIATION_RECIPIENT_UPDATE_RESPONSE => RecipientUpdateResponse::builder()
+                .id(value.id)
+                .content(body::<RecipientUpdateResponseContent>(value.body)?)
+                .decorators(RecipientUpdateResponseDecorators { thread })
+                .build(),
+            COORDINATE_MEDIATION_RECIPIENT_QUERY => RecipientQuery::builder()
+                .id(value.id)
+                .content(body::<RecipientQueryContent>(value.body)?)
+                .build(),
+            COORDINATE_MEDIATION_RECIPIENT => Recipient::builder()
+                .id(value.id)
+                .content(body::<RecipientContent>(value.body)?)
+                .decorators(RecipientDecorators { thread })
+                .build(),
+            REPORT_PROBLEM_PROBLEM_REPORT => {
+                let code = value
+                    .body
+                    .get("code")
+                    .and_then(Value::as_str)
+                    .ok_or(DidCommV2Error::MissingField("code"))?;
+                let mut description = Description::builder().code(code.to_owned()).build();
+                if let Some(comment) = value.body.get("comment").and_then(Value::as_str) {
+                    description
+                        .translations
+                        .insert(Locale::default(), comment.to_owned());
+                }
+                let mut content: ProblemReportContent = ProblemReportContent::builder()
+                    .description(description)
+                    .build();
+                content.escalation_uri = value
+                    .body
+                    .get("escalate_to")
+                    .cloned()
+                    .map(serde_json::from_value)
+                    .transpose()?;
+                ProblemReport::builder()
+                    .id(value.id)
+                    .content(content)
+                    .decorators(ProblemReportDecorators {
+                        thread: value.pthid.map(Thread::new),
+                        timing,
+                        description_locale: None,
+                        fix_hint_locale: None,
+                    })
+                    .build()
+            }
+            _ => return Err(DidCommV2Error::UnsupportedType(value.msg_type)),
+        };
+        Ok(msg)
+    }
+}
+
+fn with_body<T: Serialize>(
+    id: String,
+    msg_type: &str,
+    body: &T,
+) -> DidCommV2Result<DidCommV2Message> {
+    Ok(DidCommV2Message::builder()
+        .id(id)
+        .msg_type(msg_type.to_owned())
+        .body(serde_json::to_value(body)?)
+        .build())
+}
+
+fn with_pickup_body<T: Serialize>(
+    id: String,
+    msg_type: &str,
+    content: &T,
+) -> DidCommV2Result<DidCommV2Message> {
+    let mut msg = with_body(id, msg_type, content)?;
+    rename_field(&mut msg.body, PICKUP_RECIPIENT_KEY, PICKUP_RECIPIENT_DID);
+    Ok(msg)
+}
+
+fn body<T: DeserializeOwned>(body: Value) -> DidCommV2Result<T> {
+    Ok(serde_json::from_value(body)?)
+}
+
+fn pickup_body<T: DeserializeOwned>(mut body: Value) -> DidCommV2Result<T> {
+    rename_field(&mut body, PICKUP_RECIPIENT_DID, PICKUP_RECIPIENT_KEY);
+    self::body(body)
+}
+
+fn rename_field(value: &mut Value, from: &str, to: &str) {
+    if let Some(object) = value.as_object_mut() {
+        if let Some(field) = object.remove(from) {
+            object.insert(to.to_owned(), field);
+        }
+    }
+}
+
+/// The discover-features 2.0 `queries` or `disclosures` of `protocol` feature type.
+fn protocol_features<'a>(body: &'a Value, field: &str) -> impl Iterator<Item = &'a Value> {
+    body.get(field)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|feature| {
+            feature.get("feature-type").and_then(Value::as_str) == Some(FEATURE_TYPE_PROTOCOL)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+    use serde_json::json;
+    use shared::maybe_known::MaybeKnown;
+
+    use super::*;
+    use crate::decorators::attachment::tests::make_extended_attachment;
+
+    fn roundtrip(msg: AriesMessage) -> DidCommV2Message {
+        let v2 = DidCommV2Message::try_from(msg.clone()).unwrap();
+        let back = AriesMessage::try_from(v2.clone()).unwrap();
+        assert_eq!(msg, back);
+        v2
+    }
+
+    #[test]
+    fn test_ping_to_v2() {
+        let msg: AriesMessage = Ping::builder()
+            .id("test_id".to_owned())
+            .content(PingContent::builder().response_requested(true).build())
+            .decorators(
+                PingDecorators::builder()
+                    .thread(Thread::new("thid".to_owned()))
+                    .build(),
+            )
+            .build();
+
+        let v2 = roundtrip(msg);
+
+        let expected = json!({
+            "id": "test_id",
+            "type": TRUST_PING_PING,
+            "thid": "thid",
+            "body": { "response_requested": true }
+        });
+        assert_eq!(serde_json::to_value(&v2).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_ping_response_from_v2() {
+        let v2: DidCommV2Message = serde_json::from_value(json!({
+            "id": "test_id",
+            "type": TRUST_PING_PING_RESPONSE,
+            "thid": "thid",
+            "created_time": 1_700_000_000u64,
+            "body": {}
+        }))
+        .unwrap();
+
+        let msg = AriesMessage::try_from(v2).unwrap();
+
+        let AriesMessage::TrustPing(TrustPing::PingResponse(response)) = msg else {
+            panic!("expected a ping response");
+        };
+        assert_eq!(response.decorators.thread.thid, "thid");
+        assert_eq!(
+            response
+                .decorators
+                .timing
+                .and_then(|timing| timing.out_time),
+            DateTime::from_timestamp(1_700_000_000, 0)
+        );
+    }
+
+    #[test]
+    fn test_ping_response_requires_thread() {
+        let v2 = DidCommV2Message::builder()
+            .id("test_id".to_owned())
+            .msg_type(TRUST_PING_PING_RESPONSE.to_owned())
+            .build();
+
+        assert!(matches!(
+            AriesMessage::try_from(v2),
+            Err(DidCommV2Error::MissingField("thid"))
+        ));
+    }
+
+    #[test]
+    fn test_basic_message_roundtrip() {
+        let sent_time = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+        let msg: AriesMessage = BasicMessage::builder()
+            .id("test_id".to_owned())
+            .content(
+                BasicMessageContent::builder()
+                    .content("hello".to_owned())
+                    .sent_time(sent_time)
+                    .build(),
+            )
+            .build();
+
+        let v2 = roundtrip(msg);
+
+        assert_eq!(v2.created_time, Some(1_700_000_000));
+        assert_eq!(v2.body, json!({ "content": "hello" }));
+    }
+
+    #[test]
+    fn test_discover_features_query_to_v2() {
+        let msg: AriesMessage = Query::builder()
+            .id("test_id".to_owned())
+            .content(
+                QueryContent::builder()
+                    .query("https://didcomm.org/*".to_owned())
+                    .build(),
+            )
+            .build();
+
+        let v2 = roundtrip(msg);
+
+        assert_eq!(
+            v2.body,
+            json!({ "queries": [{ "feature-type": "protocol", "match": "https://didcomm.org/*" }] })
+        );
+    }
+
+    #[test]
+    fn test_discover_features_disclose_roundtrip() {
+        let protocol = ProtocolDescriptor::builder()
+            .pid(MaybeKnown::Unknown("test_dummy_pid".to_owned()))
+            .build();
+        let msg: AriesMessage = Disclose::builder()
+            .id("test_id".to_owned())
+            .content(DiscloseContent::builder().protocols(vec![protocol]).build())
+            .decorators(
+                DiscloseDecorators::builder()
+                    .thread(Thread::new("thid".to_owned()))
+                    .build(),
+            )
+            .build();
+
+        let v2 = roundtrip(msg);
+
+        assert_eq!(v2.thid.as_deref(), Some("thid"));
+        assert_eq!(v2.body["disclosures"][0]["id"], json!("test_dummy_pid"));
+    }
+
+    #[test]
+    fn test_pickup_delivery_roundtrip() {
+        let msg: AriesMessage = Delivery::builder()
+            .id("test_id".to_owned())
+            .content(DeliveryContent {
+                recipient_key: Some("did:example:alice".to_owned()),
+                attach: vec![make_extended_attachment()],
+            })
+            .build();
+
+        let v2 = roundtrip(msg);
+
+        assert_eq!(v2.body, json!({ "recipient_did": "did:example:alice" }));
+        assert_eq!(v2.attachments.len(), 1);
+    }
+
+    #[test]
+    fn test_pickup_status_request_from_v2() {
+        let v2 = DidCommV2Message::builder()
+            .id("test_id".to_owned())
+            .msg_type(PICKUP_STATUS_REQUEST.to_owned())
+            .body(json!({ "recipient_did": "did:example:alice" }))
+            .build();
+
+        let msg = AriesMessage::try_from(v2).unwrap();
+
+        let AriesMessage::Pickup(Pickup::StatusRequest(request)) = msg else {
+            panic!("expected a status request");
+        };
+        assert_eq!(
+            request.content.recipient_key.as_deref(),
+            Some("did:example:alice")
+        );
+    }
+
+    #[test]
+    fn test_mediate_grant_roundtrip() {
+        let msg: AriesMessage = MediateGrantV2::builder()
+            .id("test_id".to_owned())
+            .content(
+                MediateGrantV2Content::builder()
+                    .routing_did(vec!["did:peer:4routing".to_owned()])
+                    .build(),
+            )
+            .decorators(
+                MediateGrantV2Decorators::builder()
+                    .thread(Thread::new("thid".to_owned()))
+                    .build(),
+            )
+            .build();
+
+        let v2 = roundtrip(msg);
+
+        assert_eq!(v2.msg_type, COORDINATE_MEDIATION_MEDIATE_GRANT);
+        assert_eq!(v2.thid.as_deref(), Some("thid"));
+        assert_eq!(v2.body, json!({ "routing_did": ["did:peer:4routing"] }));
+    }
+
+    #[test]
+    fn test_mediate_request_from_v2() {
+        let v2 = DidCommV2Message::builder()
+            .id("test_id".to_owned())
+            .msg_type(COORDINATE_MEDIATION_MEDIATE_REQUEST.to_owned())
+            .build();
+
+        let msg = AriesMessage::try_from(v2).unwrap();
+
+        assert!(matches!(
+            msg,
+            AriesMessage::CoordinateMediationV2(CoordinateMediationV2::MediateRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_problem_report_roundtrip() {
+        let mut description = Description::builder()
+            .code("e.p.internal-error".to_owned())
+            .build();
+        description
+            .translations
+            .insert(Locale::default(), "retry later".to_owned());
+        let msg: AriesMessage = ProblemReport::builder()
+            .id("test_id".to_owned())
+            .content(
+                ProblemReportContent::builder()
+                    .description(description)
+                    .build(),
+            )
+            .decorators(
+                ProblemReportDecorators::builder()
+                    .thread(Thread::new("thid".to_owned()))
+                    .build(),
+            )
+            .build();
+
+        let v2 = roundtrip(msg);
+
+        assert_eq!(v2.thid, None);
+        assert_eq!(v2.pthid.as_deref(), Some("thid"));
+        assert_eq!(
+            v2.body,
+            json!({ "code": "e.p.internal-error", "comment": "retry later" })
+        );
+    }
+
+    #[test]
+    fn test_unsupported_v2_type() {
+        let v2 = DidCommV2Message::builder()
+            .id("test_id".to_owned())
+            .msg_type("https://didcomm.org/routing/2.0/forward".to_owned())
+            .build();
+
+        assert!(matches!(
+            AriesMessage::try_from(v2),
+            Err(DidCommV2Error::UnsupportedType(_))
+        ));
+    }
+}
@@ -0,0 +1,107 @@
+//! Module containing the DIDComm v2 plaintext message representation, as defined in the
+//! [spec](<https://identity.foundation/didcomm-messaging/spec/v2.1/#plaintext-message-structure>).
+//!
+//! Protocols which have a DIDComm v2 equivalent can be converted to and from
+//! [`crate::AriesMessage`], so that the same protocol logic can serve both envelope generations.
+//! See [`conversions`].
+
+pub mod attachment;
+pub mod conversions;
+
+use chrono::{DateTime, Utc};
+use display_as_json::Display;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use typed_builder::TypedBuilder;
+
+pub use self::attachment::{AttachmentDataV2, AttachmentV2};
+use crate::decorators::{thread::Thread, timing::Timing};
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder, Display)]
+pub struct DidCommV2Message {
+    pub id: String,
+    /// The message type URI, such as `https://didcomm.org/trust-ping/2.0/ping`.
+    #[serde(rename = "type")]
+    pub msg_type: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub to: Vec<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thid: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pthid: Option<String>,
+    /// Seconds since the UNIX epoch.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_time: Option<u64>,
+    /// Seconds since the UNIX epoch.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_time: Option<u64>,
+    #[builder(default = Value::Object(Default::default()))]
+    #[serde(default)]
+    pub body: Value,
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attachments: Vec<AttachmentV2>,
+    /// JWT conveying a DID rotation, see the
+    /// [spec](<https://identity.foundation/didcomm-messaging/spec/v2.1/#did-rotation>).
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_prior: Option<String>,
+}
+
+impl DidCommV2Message {
+    /// The thread this message belongs to, which is its own id if it starts a new thread.
+    pub fn thread_id(&self) -> &str {
+        self.thid.as_deref().unwrap_or(&self.id)
+    }
+
+    /// The `thid` and `pthid` of this message as a `~thread` decorator, if it has any.
+    pub fn thread(&self) -> Option<Thread> {
+        self.thid.as_ref().map(|thid| Thread {
+            pthid: self.pthid.clone(),
+            ..Thread::new(thid.clone())
+        })
+    }
+
+    /// The `created_time` and `expires_time` of this message as a `~timing` decorator, if it
+    /// has any.
+    pub fn timing(&self) -> Option<Timing> {
+        if self.created_time.is_none() && self.expires_time.is_none() {
+            return None;
+        }
+        Some(Timing {
+            out_time: self.created_time.and_then(from_epoch_secs),
+            expires_time: self.expires_time.and_then(from_epoch_secs),
+            ..Default::default()
+        })
+    }
+
+    pub(crate) fn set_thread(&mut self, thread: Option<&Thread>) {
+        if let Some(thread) = thread {
+            self.thid = Some(thread.thid.clone());
+            self.pthid.clone_from(&thread.pthid);
+        }
+    }
+
+    pub(crate) fn set_timing(&mut self, timing: Option<&Timing>) {
+        if let Some(timing) = timing {
+            self.created_time = timing.out_time.as_ref().map(to_epoch_secs);
+            self.expires_time = timing.expires_time.as_ref().map(to_epoch_secs);
+        }
+    }
+}
+
+pub(crate) fn from_epoch_secs(secs: u64) -> Option<DateTime<Utc>> {
+    DateTime::from_timestamp(i64::try_from(secs).ok()?, 0)
+}
+
+pub(crate) fn to_epoch_secs(time: &DateTime<Utc>) -> u64 {
+    u64::try_from(time.timestamp()).unwrap_or_default()
+}
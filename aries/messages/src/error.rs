@@ -47,3 +47,18 @@ impl MsgTypeError {
         Self::PartNotFound(part)
     }
 }
+
+pub type DidCommV2Result<T> = Result<T, DidCommV2Error>;
+
+/// Errors of converting between DIDComm v2 messages and [`crate::AriesMessage`].
+#[derive(Debug, ThisError)]
+pub enum DidCommV2Error {
+    #[error("Unsupported DIDComm v2 message type: {0}")]
+    UnsupportedType(String),
+    #[error("Message of type {0} has no DIDComm v2 equivalent")]
+    NoV2Equivalent(String),
+    #[error("No {0} found in the message")]
+    MissingField(&'static str),
+    #[error("Invalid message body: {0}")]
+    InvalidBody(#[from] serde_json::Error),
+}
@@ -6,6 +6,7 @@
 #![allow(clippy::large_enum_variant)]
 
 pub mod decorators;
+pub mod didcomm_v2;
 pub mod error;
 pub mod misc;
 pub mod msg_fields;
@@ -16,6 +17,7 @@ use derive_more::From;
 use display_as_json::Display;
 use misc::utils;
 use msg_fields::protocols::{
+    coordinate_mediation::v2::CoordinateMediationV2,
     cred_issuance::{v1::CredentialIssuanceV1, v2::CredentialIssuanceV2, CredentialIssuance},
     did_exchange::{v1_0::DidExchangeV1_0, v1_1::DidExchangeV1_1, DidExchange},
     pickup::Pickup,
@@ -24,7 +26,10 @@ use msg_fields::protocols::{
 use msg_types::{
     cred_issuance::CredentialIssuanceType,
     present_proof::PresentProofType,
-    protocols::did_exchange::{DidExchangeType, DidExchangeTypeV1},
+    protocols::{
+        coordinate_mediation::CoordinateMediationType,
+        did_exchange::{DidExchangeType, DidExchangeTypeV1},
+    },
     report_problem::ReportProblemTypeV1_0,
     routing::RoutingTypeV1_0,
     MsgWithType,
@@ -34,11 +39,11 @@ use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 use crate::{
     msg_fields::{
         protocols::{
-            basic_message::BasicMessage, connection::Connection,
+            action_menu::ActionMenu, basic_message::BasicMessage, connection::Connection,
             coordinate_mediation::CoordinateMediation, discover_features::DiscoverFeatures,
             notification::Notification, out_of_band::OutOfBand, present_proof::v1::PresentProofV1,
-            report_problem::ProblemReport, revocation::Revocation, routing::Forward,
-            trust_ping::TrustPing,
+            question_answer::QuestionAnswer, report_problem::ProblemReport, revocation::Revocation,
+            routing::Forward, transactions::Transactions, trust_ping::TrustPing,
         },
         traits::DelayedSerde,
     },
@@ -75,7 +80,11 @@ pub enum AriesMessage {
     Notification(Notification),
     Pickup(Pickup),
     CoordinateMediation(CoordinateMediation),
+    CoordinateMediationV2(CoordinateMediationV2),
     DidExchange(DidExchange),
+    ActionMenu(ActionMenu),
+    QuestionAnswer(QuestionAnswer),
+    Transactions(Transactions),
 }
 
 impl DelayedSerde for AriesMessage {
@@ -188,9 +197,19 @@ impl DelayedSerde for AriesMessage {
             Protocol::PickupType(msg_type) => {
                 Pickup::delayed_deserialize((msg_type, kind_str), deserializer).map(From::from)
             }
-            Protocol::CoordinateMediationType(msg_type) => {
-                CoordinateMediation::delayed_deserialize((msg_type, kind_str), deserializer)
-                    .map(From::from)
+            Protocol::CoordinateMediationType(CoordinateMediationType::V1(msg_type)) => {
+                CoordinateMediation::delayed_deserialize(
+                    (CoordinateMediationType::V1(msg_type), kind_str),
+                    deserializer,
+                )
+                .map(From::from)
+            }
+            Protocol::CoordinateMediationType(CoordinateMediationType::V2(msg_type)) => {
+                CoordinateMediationV2::delayed_deserialize(
+                    (CoordinateMediationType::V2(msg_type), kind_str),
+                    deserializer,
+                )
+                .map(From::from)
             }
             Protocol::DidExchangeType(DidExchangeType::V1(DidExchangeTypeV1::V1_0(msg_type))) => {
                 DidExchangeV1_0::delayed_deserialize((msg_type, kind_str), deserializer)
@@ -200,6 +219,17 @@ impl DelayedSerde for AriesMessage {
                 DidExchangeV1_1::delayed_deserialize((msg_type, kind_str), deserializer)
                     .map(|x| AriesMessage::from(DidExchange::V1_1(x)))
             }
+            Protocol::ActionMenuType(msg_type) => {
+                ActionMenu::delayed_deserialize((msg_type, kind_str), deserializer).map(From::from)
+            }
+            Protocol::QuestionAnswerType(msg_type) => {
+                QuestionAnswer::delayed_deserialize((msg_type, kind_str), deserializer)
+                    .map(From::from)
+            }
+            Protocol::TransactionsType(msg_type) => {
+                Transactions::delayed_deserialize((msg_type, kind_str), deserializer)
+                    .map(From::from)
+            }
         }
     }
 
@@ -223,8 +253,12 @@ impl DelayedSerde for AriesMessage {
             Self::Notification(v) => v.delayed_serialize(serializer),
             Self::Pickup(v) => v.delayed_serialize(serializer),
             Self::CoordinateMediation(v) => v.delayed_serialize(serializer),
+            Self::CoordinateMediationV2(v) => v.delayed_serialize(serializer),
             Self::DidExchange(DidExchange::V1_0(v)) => v.delayed_serialize(serializer),
             Self::DidExchange(DidExchange::V1_1(v)) => v.delayed_serialize(serializer),
+            Self::ActionMenu(v) => v.delayed_serialize(serializer),
+            Self::QuestionAnswer(v) => v.delayed_serialize(serializer),
+            Self::Transactions(v) => v.delayed_serialize(serializer),
         }
     }
 }
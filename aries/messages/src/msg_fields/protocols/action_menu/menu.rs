@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type Menu = MsgParts<MenuContent, MenuDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct MenuContent {
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Set when the menu is sent in response to a failed `perform`.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub errormsg: Option<String>,
+    pub options: Vec<MenuOption>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct MenuOption {
+    /// The name the requester sends back in a `perform` message to select this option.
+    pub name: String,
+    pub title: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[builder(default)]
+    #[serde(default)]
+    pub disabled: bool,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub form: Option<MenuForm>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct MenuForm {
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[builder(default)]
+    #[serde(default)]
+    pub params: Vec<MenuFormParam>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "submit-label")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub submit_label: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct MenuFormParam {
+    pub name: String,
+    pub title: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[builder(default)]
+    #[serde(default)]
+    pub required: bool,
+    /// Input type hint for the requester's UI, `text` if not set.
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub param_type: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct MenuDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::thread::tests::make_extended_thread, misc::test_utils,
+        msg_types::action_menu::ActionMenuTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_menu() {
+        let option = MenuOption::builder()
+            .name("status".to_owned())
+            .title("Status".to_owned())
+            .build();
+        let content = MenuContent::builder().options(vec![option]).build();
+
+        let decorators = MenuDecorators::default();
+
+        let expected = json!({
+            "options": [{ "name": "status", "title": "Status", "disabled": false }]
+        });
+
+        test_utils::test_msg(content, decorators, ActionMenuTypeV1_0::Menu, expected);
+    }
+
+    #[test]
+    fn test_extended_menu() {
+        let param = MenuFormParam::builder()
+            .name("query".to_owned())
+            .title("Attendee name".to_owned())
+            .default("".to_owned())
+            .required(true)
+            .build();
+        let form = MenuForm::builder()
+            .description("Enter a name to search for".to_owned())
+            .params(vec![param])
+            .submit_label("Search".to_owned())
+            .build();
+        let option = MenuOption::builder()
+            .name("search-introductions".to_owned())
+            .title("Search introductions".to_owned())
+            .description("Filter attendee records".to_owned())
+            .form(form)
+            .build();
+        let content = MenuContent::builder()
+            .title("Welcome to IIWBook".to_owned())
+            .description("IIWBook facilitates connections between attendees".to_owned())
+            .errormsg("No IIWBook names were found.".to_owned())
+            .options(vec![option])
+            .build();
+
+        let decorators = MenuDecorators::builder()
+            .thread(make_extended_thread())
+            .build();
+
+        let expected = json!({
+            "title": "Welcome to IIWBook",
+            "description": "IIWBook facilitates connections between attendees",
+            "errormsg": "No IIWBook names were found.",
+            "options": [{
+                "name": "search-introductions",
+                "title": "Search introductions",
+                "description": "Filter attendee records",
+                "disabled": false,
+                "form": {
+                    "description": "Enter a name to search for",
+                    "params": [{
+                        "name": "query",
+                        "title": "Attendee name",
+                        "default": "",
+                        "required": true
+                    }],
+                    "submit-label": "Search"
+                }
+            }],
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(content, decorators, ActionMenuTypeV1_0::Menu, expected);
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type Perform = MsgParts<PerformContent, PerformDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct PerformContent {
+    /// Name of the selected menu option.
+    pub name: String,
+    /// Values of the option's form parameters, keyed by parameter name.
+    #[builder(default)]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub params: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct PerformDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::thread::tests::make_extended_thread, misc::test_utils,
+        msg_types::action_menu::ActionMenuTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_perform() {
+        let content = PerformContent::builder().name("status".to_owned()).build();
+
+        let decorators = PerformDecorators::default();
+
+        let expected = json!({
+            "name": "status"
+        });
+
+        test_utils::test_msg(content, decorators, ActionMenuTypeV1_0::Perform, expected);
+    }
+
+    #[test]
+    fn test_extended_perform() {
+        let content = PerformContent::builder()
+            .name("search-introductions".to_owned())
+            .params(HashMap::from([("query".to_owned(), "Alice".to_owned())]))
+            .build();
+
+        let decorators = PerformDecorators::builder()
+            .thread(make_extended_thread())
+            .build();
+
+        let expected = json!({
+            "name": "search-introductions",
+            "params": { "query": "Alice" },
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(content, decorators, ActionMenuTypeV1_0::Perform, expected);
+    }
+}
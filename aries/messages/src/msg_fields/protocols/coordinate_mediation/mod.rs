@@ -5,6 +5,7 @@ pub mod keylist_update_response;
 mod mediate_deny;
 mod mediate_grant;
 mod mediate_request;
+pub mod v2;
 use derive_more::From;
 use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
 
@@ -57,6 +58,9 @@ impl DelayedSerde for CoordinateMediation {
             CoordinateMediationType::V1(CoordinateMediationTypeV1::V1_0(kind)) => {
                 kind.kind_from_str(kind_str)
             }
+            CoordinateMediationType::V2(_) => return Err(D::Error::custom(
+                "Cannot deserialize coordinate-mediation-v2 message type into coordinate-mediation-v1",
+            )),
         };
 
         match kind.map_err(D::Error::custom)? {
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{decorators::thread::Thread, msg_parts::MsgParts};
+
+/// https://didcomm.org/coordinate-mediation/2.0/#mediate-deny
+pub type MediateDenyV2 = MsgParts<MediateDenyV2Content, MediateDenyV2Decorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct MediateDenyV2Content {}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct MediateDenyV2Decorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        misc::test_utils, msg_types::protocols::coordinate_mediation::CoordinateMediationTypeV2_0,
+    };
+
+    #[test]
+    fn test_mediate_deny_v2() {
+        let expected = json!(
+            {
+                "@id": "123456781",
+                "@type": "https://didcomm.org/coordinate-mediation/2.0/mediate-deny",
+                "~thread": {
+                    "thid": "123456780"
+                }
+            }
+        );
+        let content = MediateDenyV2Content::builder().build();
+        let decorators = MediateDenyV2Decorators::builder()
+            .thread(Thread::builder().thid("123456780".to_owned()).build())
+            .build();
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            CoordinateMediationTypeV2_0::MediateDeny,
+            expected,
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{decorators::thread::Thread, msg_parts::MsgParts};
+
+/// https://didcomm.org/coordinate-mediation/2.0/#mediate-grant
+pub type MediateGrantV2 = MsgParts<MediateGrantV2Content, MediateGrantV2Decorators>;
+
+/// Unlike the 1.0 grant, the mediator hands out routing DIDs (`did:peer:2` / `did:peer:4`)
+/// carrying its own endpoint, instead of an endpoint and bare routing keys.
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct MediateGrantV2Content {
+    pub routing_did: Vec<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct MediateGrantV2Decorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        misc::test_utils, msg_types::protocols::coordinate_mediation::CoordinateMediationTypeV2_0,
+    };
+
+    #[test]
+    fn test_mediate_grant_v2() {
+        let expected = json!(
+            {
+                "@id": "123456781",
+                "@type": "https://didcomm.org/coordinate-mediation/2.0/mediate-grant",
+                "routing_did": ["did:peer:2.Ez6LSpkcni2KTTxf4nAp6cPxjRbu26Tj4b957BgHcknVeNFEj.Vz6MksXhfmxm2i3RnoHH2mKQcx7EY4tToJR9JziUs6bp8a6FM.SeyJ0IjoiZG0iLCJzIjoiaHR0cDovL2xvY2FsaG9zdDo4MDAwIn0"]
+            }
+        );
+        let content = MediateGrantV2Content::builder()
+            .routing_did(vec![
+                "did:peer:2.Ez6LSpkcni2KTTxf4nAp6cPxjRbu26Tj4b957BgHcknVeNFEj.Vz6MksXhfmxm2i3RnoHH2mKQcx7EY4tToJR9JziUs6bp8a6FM.SeyJ0IjoiZG0iLCJzIjoiaHR0cDovL2xvY2FsaG9zdDo4MDAwIn0".to_owned(),
+            ])
+            .build();
+        let decorators = MediateGrantV2Decorators::builder().build();
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            CoordinateMediationTypeV2_0::MediateGrant,
+            expected,
+        );
+    }
+}
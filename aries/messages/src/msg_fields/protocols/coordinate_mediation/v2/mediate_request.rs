@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::msg_parts::MsgParts;
+
+/// https://didcomm.org/coordinate-mediation/2.0/#mediate-request
+pub type MediateRequestV2 = MsgParts<MediateRequestV2Content>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct MediateRequestV2Content {}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+    use shared::misc::serde_ignored::SerdeIgnored as NoDecorators;
+
+    use super::*;
+    use crate::{
+        misc::test_utils, msg_types::protocols::coordinate_mediation::CoordinateMediationTypeV2_0,
+    };
+
+    #[test]
+    fn test_mediate_request_v2() {
+        let expected = json!(
+            {
+                "@id": "123456781",
+                "@type": "https://didcomm.org/coordinate-mediation/2.0/mediate-request",
+            }
+        );
+        let content = MediateRequestV2Content::builder().build();
+
+        test_utils::test_msg(
+            content,
+            NoDecorators,
+            CoordinateMediationTypeV2_0::MediateRequest,
+            expected,
+        );
+    }
+}
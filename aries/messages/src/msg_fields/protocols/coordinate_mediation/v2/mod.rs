@@ -0,0 +1,141 @@
+//! Module containing the `coordinate-mediation` 2.0 protocol messages, as defined in the [spec](<https://didcomm.org/coordinate-mediation/2.0/>).
+//!
+//! The 2.0 flavour registers recipient DIDs (`did:peer:2` / `did:peer:4`) with the mediator
+//! instead of raw verkeys.
+
+pub mod mediate_deny;
+pub mod mediate_grant;
+pub mod mediate_request;
+pub mod recipient;
+pub mod recipient_query;
+pub mod recipient_update;
+pub mod recipient_update_response;
+
+use derive_more::From;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+pub use self::{
+    mediate_deny::{MediateDenyV2, MediateDenyV2Content, MediateDenyV2Decorators},
+    mediate_grant::{MediateGrantV2, MediateGrantV2Content, MediateGrantV2Decorators},
+    mediate_request::{MediateRequestV2, MediateRequestV2Content},
+    recipient::{
+        Recipient, RecipientContent, RecipientDecorators, RecipientItem, RecipientPagination,
+    },
+    recipient_query::{RecipientQuery, RecipientQueryContent, RecipientQueryPaginateParams},
+    recipient_update::{RecipientUpdate, RecipientUpdateContent, RecipientUpdateItem},
+    recipient_update_response::{
+        RecipientUpdateResponse, RecipientUpdateResponseContent, RecipientUpdateResponseDecorators,
+        RecipientUpdateResponseItem,
+    },
+};
+use crate::{
+    misc::utils::{into_msg_with_type, transit_to_aries_msg},
+    msg_fields::traits::DelayedSerde,
+    msg_types::{
+        protocols::coordinate_mediation::{
+            CoordinateMediationType, CoordinateMediationTypeV2, CoordinateMediationTypeV2_0,
+        },
+        MsgWithType,
+    },
+};
+
+#[derive(Clone, Debug, From, PartialEq)]
+pub enum CoordinateMediationV2 {
+    MediateRequest(MediateRequestV2),
+    MediateDeny(MediateDenyV2),
+    MediateGrant(MediateGrantV2),
+    RecipientUpdate(RecipientUpdate),
+    RecipientUpdateResponse(RecipientUpdateResponse),
+    RecipientQuery(RecipientQuery),
+    Recipient(Recipient),
+}
+
+impl DelayedSerde for CoordinateMediationV2 {
+    type MsgType<'a> = (CoordinateMediationType, &'a str);
+
+    fn delayed_deserialize<'de, D>(
+        msg_type: Self::MsgType<'de>,
+        deserializer: D,
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (protocol, kind_str) = msg_type;
+
+        let kind = match protocol {
+            CoordinateMediationType::V2(CoordinateMediationTypeV2::V2_0(kind)) => {
+                kind.kind_from_str(kind_str)
+            }
+            CoordinateMediationType::V1(_) => return Err(D::Error::custom(
+                "Cannot deserialize coordinate-mediation-v1 message type into coordinate-mediation-v2",
+            )),
+        };
+
+        match kind.map_err(D::Error::custom)? {
+            CoordinateMediationTypeV2_0::MediateRequest => {
+                MediateRequestV2::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::MediateDeny => {
+                MediateDenyV2::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::MediateGrant => {
+                MediateGrantV2::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::RecipientUpdate => {
+                RecipientUpdate::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::RecipientUpdateResponse => {
+                RecipientUpdateResponse::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::RecipientQuery => {
+                RecipientQuery::deserialize(deserializer).map(From::from)
+            }
+            CoordinateMediationTypeV2_0::Recipient => {
+                Recipient::deserialize(deserializer).map(From::from)
+            }
+        }
+    }
+
+    fn delayed_serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::MediateRequest(v) => MsgWithType::from(v).serialize(serializer),
+            Self::MediateDeny(v) => MsgWithType::from(v).serialize(serializer),
+            Self::MediateGrant(v) => MsgWithType::from(v).serialize(serializer),
+            Self::RecipientUpdate(v) => MsgWithType::from(v).serialize(serializer),
+            Self::RecipientUpdateResponse(v) => MsgWithType::from(v).serialize(serializer),
+            Self::RecipientQuery(v) => MsgWithType::from(v).serialize(serializer),
+            Self::Recipient(v) => MsgWithType::from(v).serialize(serializer),
+        }
+    }
+}
+
+transit_to_aries_msg!(MediateRequestV2Content, CoordinateMediationV2);
+transit_to_aries_msg!(MediateDenyV2Content: MediateDenyV2Decorators, CoordinateMediationV2);
+transit_to_aries_msg!(MediateGrantV2Content: MediateGrantV2Decorators, CoordinateMediationV2);
+transit_to_aries_msg!(RecipientUpdateContent, CoordinateMediationV2);
+transit_to_aries_msg!(RecipientUpdateResponseContent: RecipientUpdateResponseDecorators, CoordinateMediationV2);
+transit_to_aries_msg!(RecipientQueryContent, CoordinateMediationV2);
+transit_to_aries_msg!(RecipientContent: RecipientDecorators, CoordinateMediationV2);
+
+into_msg_with_type!(
+    MediateRequestV2,
+    CoordinateMediationTypeV2_0,
+    MediateRequest
+);
+into_msg_with_type!(MediateDenyV2, CoordinateMediationTypeV2_0, MediateDeny);
+into_msg_with_type!(MediateGrantV2, CoordinateMediationTypeV2_0, MediateGrant);
+into_msg_with_type!(
+    RecipientUpdate,
+    CoordinateMediationTypeV2_0,
+    RecipientUpdate
+);
+into_msg_with_type!(
+    RecipientUpdateResponse,
+    CoordinateMediationTypeV2_0,
+    RecipientUpdateResponse
+);
+into_msg_with_type!(RecipientQuery, CoordinateMediationTypeV2_0, RecipientQuery);
+into_msg_with_type!(Recipient, CoordinateMediationTypeV2_0, Recipient);
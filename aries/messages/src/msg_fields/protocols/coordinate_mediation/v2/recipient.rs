@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{decorators::thread::Thread, msg_parts::MsgParts};
+
+/// https://didcomm.org/coordinate-mediation/2.0/#recipient
+pub type Recipient = MsgParts<RecipientContent, RecipientDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RecipientContent {
+    pub dids: Vec<RecipientItem>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pagination: Option<RecipientPagination>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RecipientItem {
+    pub recipient_did: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RecipientPagination {
+    pub count: u64,
+    pub offset: u64,
+    pub remaining: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RecipientDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        misc::test_utils, msg_types::protocols::coordinate_mediation::CoordinateMediationTypeV2_0,
+    };
+
+    #[test]
+    fn test_recipient() {
+        let expected = json!(
+            {
+                "@id": "123456781",
+                "@type": "https://didcomm.org/coordinate-mediation/2.0/recipient",
+                "dids": [
+                    {
+                        "recipient_did": "did:peer:2.Ez6LSpkcni2KTTxf4nAp6cPxjRbu26Tj4b957BgHcknVeNFEj.Vz6MksXhfmxm2i3RnoHH2mKQcx7EY4tToJR9JziUs6bp8a6FM"
+                    }
+                ],
+                "pagination": {
+                    "count": 1,
+                    "offset": 0,
+                    "remaining": 0
+                }
+            }
+        );
+        let item = RecipientItem::builder()
+            .recipient_did("did:peer:2.Ez6LSpkcni2KTTxf4nAp6cPxjRbu26Tj4b957BgHcknVeNFEj.Vz6MksXhfmxm2i3RnoHH2mKQcx7EY4tToJR9JziUs6bp8a6FM".to_owned())
+            .build();
+        let pagination = RecipientPagination::builder()
+            .count(1)
+            .offset(0)
+            .remaining(0)
+            .build();
+        let content = RecipientContent::builder()
+            .dids(vec![item])
+            .pagination(pagination)
+            .build();
+        let decorators = RecipientDecorators::builder().build();
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            CoordinateMediationTypeV2_0::Recipient,
+            expected,
+        );
+    }
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::msg_parts::MsgParts;
+
+/// https://didcomm.org/coordinate-mediation/2.0/#recipient-query
+pub type RecipientQuery = MsgParts<RecipientQueryContent>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RecipientQueryContent {
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub paginate: Option<RecipientQueryPaginateParams>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RecipientQueryPaginateParams {
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+    use shared::misc::serde_ignored::SerdeIgnored as NoDecorators;
+
+    use super::*;
+    use crate::{
+        misc::test_utils, msg_types::protocols::coordinate_mediation::CoordinateMediationTypeV2_0,
+    };
+
+    #[test]
+    fn test_recipient_query() {
+        let expected = json!(
+            {
+                "@id": "123456781",
+                "@type": "https://didcomm.org/coordinate-mediation/2.0/recipient-query",
+                "paginate": {
+                    "limit": 30,
+                    "offset": 0
+                }
+            }
+        );
+        let paginate_params = RecipientQueryPaginateParams::builder()
+            .limit(30)
+            .offset(0)
+            .build();
+        let content = RecipientQueryContent::builder()
+            .paginate(paginate_params)
+            .build();
+
+        test_utils::test_msg(
+            content,
+            NoDecorators,
+            CoordinateMediationTypeV2_0::RecipientQuery,
+            expected,
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    msg_fields::protocols::coordinate_mediation::keylist_update::KeylistUpdateItemAction,
+    msg_parts::MsgParts,
+};
+
+/// https://didcomm.org/coordinate-mediation/2.0/#recipient-update
+pub type RecipientUpdate = MsgParts<RecipientUpdateContent>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RecipientUpdateContent {
+    pub updates: Vec<RecipientUpdateItem>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct RecipientUpdateItem {
+    pub recipient_did: String,
+    pub action: KeylistUpdateItemAction,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+    use shared::misc::serde_ignored::SerdeIgnored as NoDecorators;
+
+    use super::*;
+    use crate::{
+        misc::test_utils, msg_types::protocols::coordinate_mediation::CoordinateMediationTypeV2_0,
+    };
+
+    #[test]
+    fn test_recipient_update() {
+        let expected = json!(
+            {
+                "@id": "123456781",
+                "@type": "https://didcomm.org/coordinate-mediation/2.0/recipient-update",
+                "updates":[
+                    {
+                        "recipient_did": "did:peer:2.Ez6LSpkcni2KTTxf4nAp6cPxjRbu26Tj4b957BgHcknVeNFEj.Vz6MksXhfmxm2i3RnoHH2mKQcx7EY4tToJR9JziUs6bp8a6FM",
+                        "action": "add"
+                    }
+                ]
+            }
+        );
+        let update_item = RecipientUpdateItem::builder()
+            .recipient_did("did:peer:2.Ez6LSpkcni2KTTxf4nAp6cPxjRbu26Tj4b957BgHcknVeNFEj.Vz6MksXhfmxm2i3RnoHH2mKQcx7EY4tToJR9JziUs6bp8a6FM".to_owned())
+            .action(KeylistUpdateItemAction::Add)
+            .build();
+        let content = RecipientUpdateContent::builder()
+            .updates(vec![update_item])
+            .build();
+
+        test_utils::test_msg(
+            content,
+            NoDecorators,
+            CoordinateMediationTypeV2_0::RecipientUpdate,
+            expected,
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::thread::Thread,
+    msg_fields::protocols::coordinate_mediation::{
+        keylist_update::KeylistUpdateItemAction, keylist_update_response::KeylistUpdateItemResult,
+    },
+    msg_parts::MsgParts,
+};
+
+/// https://didcomm.org/coordinate-mediation/2.0/#recipient-update-response
+pub type RecipientUpdateResponse =
+    MsgParts<RecipientUpdateResponseContent, RecipientUpdateResponseDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RecipientUpdateResponseContent {
+    pub updated: Vec<RecipientUpdateResponseItem>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct RecipientUpdateResponseItem {
+    pub recipient_did: String,
+    pub action: KeylistUpdateItemAction,
+    pub result: KeylistUpdateItemResult,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct RecipientUpdateResponseDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        misc::test_utils, msg_types::protocols::coordinate_mediation::CoordinateMediationTypeV2_0,
+    };
+
+    #[test]
+    fn test_recipient_update_response() {
+        let expected = json!(
+            {
+                "@id": "123456781",
+                "@type": "https://didcomm.org/coordinate-mediation/2.0/recipient-update-response",
+                "updated": [
+                    {
+                        "recipient_did": "did:peer:2.Ez6LSpkcni2KTTxf4nAp6cPxjRbu26Tj4b957BgHcknVeNFEj.Vz6MksXhfmxm2i3RnoHH2mKQcx7EY4tToJR9JziUs6bp8a6FM",
+                        "action": "remove",
+                        "result": "no_change"
+                    }
+                ]
+            }
+        );
+        let update_item = RecipientUpdateResponseItem::builder()
+            .recipient_did("did:peer:2.Ez6LSpkcni2KTTxf4nAp6cPxjRbu26Tj4b957BgHcknVeNFEj.Vz6MksXhfmxm2i3RnoHH2mKQcx7EY4tToJR9JziUs6bp8a6FM".to_owned())
+            .action(KeylistUpdateItemAction::Remove)
+            .result(KeylistUpdateItemResult::NoChange)
+            .build();
+        let content = RecipientUpdateResponseContent::builder()
+            .updated(vec![update_item])
+            .build();
+        let decorators = RecipientUpdateResponseDecorators::builder().build();
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            CoordinateMediationTypeV2_0::RecipientUpdateResponse,
+            expected,
+        );
+    }
+}
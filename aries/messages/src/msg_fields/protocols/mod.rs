@@ -1,3 +1,4 @@
+pub mod action_menu;
 pub mod basic_message;
 pub mod common;
 pub mod connection;
@@ -9,7 +10,9 @@ pub mod notification;
 pub mod out_of_band;
 pub mod pickup;
 pub mod present_proof;
+pub mod question_answer;
 pub mod report_problem;
 pub mod revocation;
 pub mod routing;
+pub mod transactions;
 pub mod trust_ping;
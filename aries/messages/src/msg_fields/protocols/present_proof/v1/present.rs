@@ -2,7 +2,10 @@ use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
 use crate::{
-    decorators::{attachment::Attachment, please_ack::PleaseAck, thread::Thread, timing::Timing},
+    decorators::{
+        attachment::Attachment, please_ack::PleaseAck, service::Service, thread::Thread,
+        timing::Timing,
+    },
     msg_parts::MsgParts,
 };
 
@@ -29,6 +32,10 @@ pub struct PresentationV1Decorators {
     #[serde(rename = "~timing")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timing: Option<Timing>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~service")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<Service>,
 }
 
 #[cfg(test)]
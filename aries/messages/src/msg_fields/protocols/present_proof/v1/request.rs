@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
 use crate::{
-    decorators::{attachment::Attachment, thread::Thread, timing::Timing},
+    decorators::{attachment::Attachment, service::Service, thread::Thread, timing::Timing},
     msg_parts::MsgParts,
 };
 
@@ -28,6 +28,10 @@ pub struct RequestPresentationV1Decorators {
     #[serde(rename = "~timing")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timing: Option<Timing>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~service")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<Service>,
 }
 
 #[cfg(test)]
@@ -38,8 +42,8 @@ mod tests {
     use super::*;
     use crate::{
         decorators::{
-            attachment::tests::make_extended_attachment, thread::tests::make_extended_thread,
-            timing::tests::make_extended_timing,
+            attachment::tests::make_extended_attachment, service::tests::make_extended_service,
+            thread::tests::make_extended_thread, timing::tests::make_extended_timing,
         },
         misc::test_utils,
         msg_types::present_proof::PresentProofTypeV1_0,
@@ -75,13 +79,15 @@ mod tests {
         let decorators = RequestPresentationV1Decorators::builder()
             .thread(make_extended_thread())
             .timing(make_extended_timing())
+            .service(make_extended_service())
             .build();
 
         let expected = json!({
             "request_presentations~attach": content.request_presentations_attach,
             "comment": content.comment,
             "~thread": decorators.thread,
-            "~timing": decorators.timing
+            "~timing": decorators.timing,
+            "~service": decorators.service
         });
 
         test_utils::test_msg(
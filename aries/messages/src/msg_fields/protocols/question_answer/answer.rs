@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_fields::protocols::connection::response::ConnectionSignature,
+    msg_parts::MsgParts,
+};
+
+pub type Answer = MsgParts<AnswerContent, AnswerDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct AnswerContent {
+    /// One of the `valid_responses` of the question.
+    pub response: String,
+    /// An `ed25519Sha512_single` signature over the question text, the response and the
+    /// question nonce. Present if the question required a signature.
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "response~sig")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_sig: Option<ConnectionSignature>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct AnswerDecorators {
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::question_answer::QuestionAnswerTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_answer() {
+        let content = AnswerContent::builder()
+            .response("Yes, it's me".to_owned())
+            .build();
+
+        let decorators = AnswerDecorators::builder()
+            .thread(make_extended_thread())
+            .build();
+
+        let expected = json!({
+            "response": content.response,
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            QuestionAnswerTypeV1_0::Answer,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_extended_answer() {
+        let signature = ConnectionSignature::new(
+            "test_signature".to_owned(),
+            "test_sig_data".to_owned(),
+            "test_signer".to_owned(),
+        );
+        let content = AnswerContent::builder()
+            .response("Yes, it's me".to_owned())
+            .response_sig(signature)
+            .build();
+
+        let decorators = AnswerDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "response": content.response,
+            "response~sig": {
+                "@type": "https://didcomm.org/signature/1.0/ed25519Sha512_single",
+                "signature": "test_signature",
+                "sig_data": "test_sig_data",
+                "signer": "test_signer"
+            },
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            QuestionAnswerTypeV1_0::Answer,
+            expected,
+        );
+    }
+}
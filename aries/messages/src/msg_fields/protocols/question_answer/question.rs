@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type Question = MsgParts<QuestionContent, QuestionDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct QuestionContent {
+    pub question_text: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub question_detail: Option<String>,
+    /// Included in the signed data of the answer, so that a signature cannot be replayed to
+    /// answer another question.
+    pub nonce: String,
+    #[builder(default)]
+    #[serde(default)]
+    pub signature_required: bool,
+    pub valid_responses: Vec<ValidResponse>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub struct ValidResponse {
+    pub text: String,
+}
+
+impl ValidResponse {
+    pub fn new(text: String) -> Self {
+        Self { text }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct QuestionDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    /// The `expires_time` of the timing decorator is the deadline for answering the question.
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::timing::tests::make_extended_timing, misc::test_utils,
+        msg_types::question_answer::QuestionAnswerTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_question() {
+        let content = QuestionContent::builder()
+            .question_text("Are you on the phone with Bob from Faber Bank?".to_owned())
+            .nonce("1234567890".to_owned())
+            .valid_responses(vec![
+                ValidResponse::new("Yes, it's me".to_owned()),
+                ValidResponse::new("No, that's not me!".to_owned()),
+            ])
+            .build();
+
+        let decorators = QuestionDecorators::default();
+
+        let expected = json!({
+            "question_text": content.question_text,
+            "nonce": content.nonce,
+            "signature_required": false,
+            "valid_responses": [{ "text": "Yes, it's me" }, { "text": "No, that's not me!" }]
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            QuestionAnswerTypeV1_0::Question,
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_extended_question() {
+        let content = QuestionContent::builder()
+            .question_text("Are you on the phone with Bob from Faber Bank?".to_owned())
+            .question_detail("Faber Bank will never ask for your PIN".to_owned())
+            .nonce("1234567890".to_owned())
+            .signature_required(true)
+            .valid_responses(vec![ValidResponse::new("Yes, it's me".to_owned())])
+            .build();
+
+        let decorators = QuestionDecorators::builder()
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "question_text": content.question_text,
+            "question_detail": content.question_detail,
+            "nonce": content.nonce,
+            "signature_required": true,
+            "valid_responses": [{ "text": "Yes, it's me" }],
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(
+            content,
+            decorators,
+            QuestionAnswerTypeV1_0::Question,
+            expected,
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type EndorseTransaction = MsgParts<EndorseTransactionContent, EndorseTransactionDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct EndorseTransactionContent {
+    pub endorser_did: String,
+    pub signature_response: Vec<SignatureResponse>,
+    /// The ledger reply, present if the endorser wrote the transaction itself.
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ledger_response: Option<Value>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct SignatureResponse {
+    /// The `@id` of the request message the signature answers.
+    pub message_id: String,
+    pub context: String,
+    pub method: String,
+    pub signer_goal_code: String,
+    pub signature_type: String,
+    /// Signatures keyed by the signing DID.
+    pub signature: HashMap<String, String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct EndorseTransactionDecorators {
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::transactions::TransactionsTypeV1_0,
+    };
+
+    fn make_signature_response() -> SignatureResponse {
+        SignatureResponse::builder()
+            .message_id("test_request_id".to_owned())
+            .context("did:sov".to_owned())
+            .method("add-signature".to_owned())
+            .signer_goal_code("aries.transaction.endorse".to_owned())
+            .signature_type("default".to_owned())
+            .signature(HashMap::from([(
+                "V4SGRU86Z58d6TV7PBUe6f".to_owned(),
+                "test_signed_request".to_owned(),
+            )]))
+            .build()
+    }
+
+    #[test]
+    fn test_minimal_endorse_transaction() {
+        let content = EndorseTransactionContent::builder()
+            .endorser_did("V4SGRU86Z58d6TV7PBUe6f".to_owned())
+            .signature_response(vec![make_signature_response()])
+            .build();
+
+        let decorators = EndorseTransactionDecorators::builder()
+            .thread(make_extended_thread())
+            .build();
+
+        let expected = json!({
+            "endorser_did": content.endorser_did,
+            "signature_response": content.signature_response,
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(content, decorators, TransactionsTypeV1_0::Endorse, expected);
+    }
+
+    #[test]
+    fn test_extended_endorse_transaction() {
+        let content = EndorseTransactionContent::builder()
+            .endorser_did("V4SGRU86Z58d6TV7PBUe6f".to_owned())
+            .signature_response(vec![make_signature_response()])
+            .ledger_response(json!({ "op": "REPLY" }))
+            .build();
+
+        let decorators = EndorseTransactionDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "endorser_did": content.endorser_did,
+            "signature_response": [{
+                "message_id": "test_request_id",
+                "context": "did:sov",
+                "method": "add-signature",
+                "signer_goal_code": "aries.transaction.endorse",
+                "signature_type": "default",
+                "signature": { "V4SGRU86Z58d6TV7PBUe6f": "test_signed_request" }
+            }],
+            "ledger_response": { "op": "REPLY" },
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, TransactionsTypeV1_0::Endorse, expected);
+    }
+}
@@ -0,0 +1,81 @@
+//! Module containing the `transactions` protocol messages, through which the author of a ledger
+//! transaction asks an endorser to endorse and write it, as used by ACA-Py for Indy ledgers.
+
+pub mod endorse;
+pub mod refuse;
+pub mod request;
+
+use derive_more::From;
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use self::{
+    endorse::{EndorseTransaction, EndorseTransactionContent, EndorseTransactionDecorators},
+    refuse::{RefuseTransaction, RefuseTransactionContent, RefuseTransactionDecorators},
+    request::{TransactionRequest, TransactionRequestContent, TransactionRequestDecorators},
+};
+use crate::{
+    misc::utils::{into_msg_with_type, transit_to_aries_msg},
+    msg_fields::traits::DelayedSerde,
+    msg_types::{
+        protocols::transactions::{
+            TransactionsType as TransactionsKind, TransactionsTypeV1, TransactionsTypeV1_0,
+        },
+        MsgWithType,
+    },
+};
+
+#[derive(Clone, Debug, From, PartialEq)]
+pub enum Transactions {
+    Request(TransactionRequest),
+    Endorse(EndorseTransaction),
+    Refuse(RefuseTransaction),
+}
+
+impl DelayedSerde for Transactions {
+    type MsgType<'a> = (TransactionsKind, &'a str);
+
+    fn delayed_deserialize<'de, D>(
+        msg_type: Self::MsgType<'de>,
+        deserializer: D,
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (protocol, kind_str) = msg_type;
+
+        let kind = match protocol {
+            TransactionsKind::V1(TransactionsTypeV1::V1_0(kind)) => kind.kind_from_str(kind_str),
+        };
+
+        match kind.map_err(D::Error::custom)? {
+            TransactionsTypeV1_0::Request => {
+                TransactionRequest::deserialize(deserializer).map(From::from)
+            }
+            TransactionsTypeV1_0::Endorse => {
+                EndorseTransaction::deserialize(deserializer).map(From::from)
+            }
+            TransactionsTypeV1_0::Refuse => {
+                RefuseTransaction::deserialize(deserializer).map(From::from)
+            }
+        }
+    }
+
+    fn delayed_serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Request(v) => MsgWithType::from(v).serialize(serializer),
+            Self::Endorse(v) => MsgWithType::from(v).serialize(serializer),
+            Self::Refuse(v) => MsgWithType::from(v).serialize(serializer),
+        }
+    }
+}
+
+transit_to_aries_msg!(TransactionRequestContent: TransactionRequestDecorators, Transactions);
+transit_to_aries_msg!(EndorseTransactionContent: EndorseTransactionDecorators, Transactions);
+transit_to_aries_msg!(RefuseTransactionContent: RefuseTransactionDecorators, Transactions);
+
+into_msg_with_type!(TransactionRequest, TransactionsTypeV1_0, Request);
+into_msg_with_type!(EndorseTransaction, TransactionsTypeV1_0, Endorse);
+into_msg_with_type!(RefuseTransaction, TransactionsTypeV1_0, Refuse);
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type RefuseTransaction = MsgParts<RefuseTransactionContent, RefuseTransactionDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct RefuseTransactionContent {
+    pub endorser_did: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct RefuseTransactionDecorators {
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{thread::tests::make_extended_thread, timing::tests::make_extended_timing},
+        misc::test_utils,
+        msg_types::transactions::TransactionsTypeV1_0,
+    };
+
+    #[test]
+    fn test_minimal_refuse_transaction() {
+        let content = RefuseTransactionContent::builder()
+            .endorser_did("V4SGRU86Z58d6TV7PBUe6f".to_owned())
+            .build();
+
+        let decorators = RefuseTransactionDecorators::builder()
+            .thread(make_extended_thread())
+            .build();
+
+        let expected = json!({
+            "endorser_did": content.endorser_did,
+            "~thread": decorators.thread
+        });
+
+        test_utils::test_msg(content, decorators, TransactionsTypeV1_0::Refuse, expected);
+    }
+
+    #[test]
+    fn test_extended_refuse_transaction() {
+        let content = RefuseTransactionContent::builder()
+            .endorser_did("V4SGRU86Z58d6TV7PBUe6f".to_owned())
+            .comment("Schema attributes are not allowed".to_owned())
+            .build();
+
+        let decorators = RefuseTransactionDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "endorser_did": content.endorser_did,
+            "comment": content.comment,
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, TransactionsTypeV1_0::Refuse, expected);
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    decorators::{attachment::Attachment, thread::Thread, timing::Timing},
+    msg_parts::MsgParts,
+};
+
+pub type TransactionRequest = MsgParts<TransactionRequestContent, TransactionRequestDecorators>;
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct TransactionRequestContent {
+    pub signature_request: Vec<SignatureRequest>,
+    /// The author-signed ledger request, carried as a JSON attachment.
+    #[serde(rename = "messages~attach")]
+    pub messages_attach: Vec<Attachment>,
+    /// Whether the endorser is expected to write the transaction to the ledger after endorsing
+    /// it, instead of handing it back to the author.
+    #[builder(default)]
+    #[serde(default)]
+    pub endorser_write_txn: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, TypedBuilder)]
+pub struct SignatureRequest {
+    pub context: String,
+    pub method: String,
+    pub signature_type: String,
+    pub signer_goal_code: String,
+    #[builder(default, setter(strip_option))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author_goal_code: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, Default, PartialEq, TypedBuilder)]
+pub struct TransactionRequestDecorators {
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[builder(default, setter(strip_option))]
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+#[cfg(test)]
+#[allow(clippy::field_reassign_with_default)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        decorators::{
+            attachment::tests::make_extended_attachment, thread::tests::make_extended_thread,
+            timing::tests::make_extended_timing,
+        },
+        misc::test_utils,
+        msg_types::transactions::TransactionsTypeV1_0,
+    };
+
+    fn make_signature_request() -> SignatureRequest {
+        SignatureRequest::builder()
+            .context("did:sov".to_owned())
+            .method("add-signature".to_owned())
+            .signature_type("default".to_owned())
+            .signer_goal_code("aries.transaction.endorse".to_owned())
+            .build()
+    }
+
+    #[test]
+    fn test_minimal_transaction_request() {
+        let content = TransactionRequestContent::builder()
+            .signature_request(vec![make_signature_request()])
+            .messages_attach(vec![make_extended_attachment()])
+            .build();
+
+        let decorators = TransactionRequestDecorators::default();
+
+        let expected = json!({
+            "signature_request": content.signature_request,
+            "messages~attach": content.messages_attach,
+            "endorser_write_txn": false
+        });
+
+        test_utils::test_msg(content, decorators, TransactionsTypeV1_0::Request, expected);
+    }
+
+    #[test]
+    fn test_extended_transaction_request() {
+        let mut signature_request = make_signature_request();
+        signature_request.author_goal_code = Some("aries.transaction.ledger.write".to_owned());
+
+        let content = TransactionRequestContent::builder()
+            .signature_request(vec![signature_request])
+            .messages_attach(vec![make_extended_attachment()])
+            .endorser_write_txn(true)
+            .build();
+
+        let decorators = TransactionRequestDecorators::builder()
+            .thread(make_extended_thread())
+            .timing(make_extended_timing())
+            .build();
+
+        let expected = json!({
+            "signature_request": [{
+                "context": "did:sov",
+                "method": "add-signature",
+                "signature_type": "default",
+                "signer_goal_code": "aries.transaction.endorse",
+                "author_goal_code": "aries.transaction.ledger.write"
+            }],
+            "messages~attach": content.messages_attach,
+            "endorser_write_txn": true,
+            "~thread": decorators.thread,
+            "~timing": decorators.timing
+        });
+
+        test_utils::test_msg(content, decorators, TransactionsTypeV1_0::Request, expected);
+    }
+}
@@ -10,6 +10,7 @@ use crate::msg_types::{MsgKindType, Role};
 #[msg_type(protocol = "coordinate-mediation")]
 pub enum CoordinateMediationType {
     V1(CoordinateMediationTypeV1),
+    V2(CoordinateMediationTypeV2),
 }
 
 #[derive(Copy, Clone, Debug, From, TryInto, PartialEq, Transitive, MessageType)]
@@ -20,6 +21,14 @@ pub enum CoordinateMediationTypeV1 {
     V1_0(MsgKindType<CoordinateMediationTypeV1_0>),
 }
 
+#[derive(Copy, Clone, Debug, From, TryInto, PartialEq, Transitive, MessageType)]
+#[transitive(into(CoordinateMediationType, Protocol))]
+#[msg_type(major = 2)]
+pub enum CoordinateMediationTypeV2 {
+    #[msg_type(minor = 0, roles = "Role::Mediator, Role::Recipient")]
+    V2_0(MsgKindType<CoordinateMediationTypeV2_0>),
+}
+
 #[derive(Copy, Clone, Debug, AsRefStr, EnumString, PartialEq)]
 #[strum(serialize_all = "kebab-case")]
 pub enum CoordinateMediationTypeV1_0 {
@@ -31,3 +40,83 @@ pub enum CoordinateMediationTypeV1_0 {
     KeylistQuery,
     Keylist,
 }
+
+#[derive(Copy, Clone, Debug, AsRefStr, EnumString, PartialEq)]
+#[strum(serialize_all = "kebab-case")]
+pub enum CoordinateMediationTypeV2_0 {
+    MediateRequest,
+    MediateDeny,
+    MediateGrant,
+    RecipientUpdate,
+    RecipientUpdateResponse,
+    RecipientQuery,
+    Recipient,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::misc::test_utils;
+
+    #[test]
+    fn test_protocol_coordinate_mediation_v1() {
+        test_utils::test_serde(
+            Protocol::from(CoordinateMediationTypeV1::new_v1_0()),
+            json!("https://didcomm.org/coordinate-mediation/1.0"),
+        )
+    }
+
+    #[test]
+    fn test_protocol_coordinate_mediation_v2() {
+        test_utils::test_serde(
+            Protocol::from(CoordinateMediationTypeV2::new_v2_0()),
+            json!("https://didcomm.org/coordinate-mediation/2.0"),
+        )
+    }
+
+    #[test]
+    fn test_version_resolution_coordinate_mediation_v2() {
+        test_utils::test_msg_type_resolution(
+            "https://didcomm.org/coordinate-mediation/2.255",
+            CoordinateMediationTypeV2::new_v2_0(),
+        )
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unsupported_version_coordinate_mediation_v2() {
+        test_utils::test_serde(
+            Protocol::from(CoordinateMediationTypeV2::new_v2_0()),
+            json!("https://didcomm.org/coordinate-mediation/1.0"),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_recipient_update_v2() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/coordinate-mediation/2.0",
+            "recipient-update",
+            CoordinateMediationTypeV2::new_v2_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_recipient_query_v2() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/coordinate-mediation/2.0",
+            "recipient-query",
+            CoordinateMediationTypeV2::new_v2_0(),
+        )
+    }
+
+    #[test]
+    fn test_msg_type_recipient_v2() {
+        test_utils::test_msg_type(
+            "https://didcomm.org/coordinate-mediation/2.0",
+            "recipient",
+            CoordinateMediationTypeV2::new_v2_0(),
+        )
+    }
+}
@@ -5,18 +5,20 @@ use serde::{Deserialize, Serialize};
 use shared::misc::utils::CowStr;
 
 use self::{
-    basic_message::BasicMessageType, connection::ConnectionType,
+    action_menu::ActionMenuType, basic_message::BasicMessageType, connection::ConnectionType,
     coordinate_mediation::CoordinateMediationType, cred_issuance::CredentialIssuanceType,
     did_exchange::DidExchangeType, discover_features::DiscoverFeaturesType,
     notification::NotificationType, out_of_band::OutOfBandType, pickup::PickupType,
-    present_proof::PresentProofType, report_problem::ReportProblemType, revocation::RevocationType,
-    routing::RoutingType, signature::SignatureType, trust_ping::TrustPingType,
+    present_proof::PresentProofType, question_answer::QuestionAnswerType,
+    report_problem::ReportProblemType, revocation::RevocationType, routing::RoutingType,
+    signature::SignatureType, transactions::TransactionsType, trust_ping::TrustPingType,
 };
 use crate::{
     error::{MsgTypeError, MsgTypeResult},
     msg_types::traits::ProtocolName,
 };
 
+pub mod action_menu;
 pub mod basic_message;
 pub mod connection;
 pub mod coordinate_mediation;
@@ -27,10 +29,12 @@ pub mod notification;
 pub mod out_of_band;
 pub mod pickup;
 pub mod present_proof;
+pub mod question_answer;
 pub mod report_problem;
 pub mod revocation;
 pub mod routing;
 pub mod signature;
+pub mod transactions;
 pub mod trust_ping;
 
 /// Type representing all protocols that are currently supported.
@@ -65,6 +69,9 @@ pub enum Protocol {
     PickupType(PickupType),
     CoordinateMediationType(CoordinateMediationType),
     DidExchangeType(DidExchangeType),
+    ActionMenuType(ActionMenuType),
+    QuestionAnswerType(QuestionAnswerType),
+    TransactionsType(TransactionsType),
 }
 
 /// Utility macro to avoid harder to read and error prone calling
@@ -103,6 +110,9 @@ impl Protocol {
         match_protocol!(PickupType, protocol, major, minor);
         match_protocol!(CoordinateMediationType, protocol, major, minor);
         match_protocol!(DidExchangeType, protocol, major, minor);
+        match_protocol!(ActionMenuType, protocol, major, minor);
+        match_protocol!(QuestionAnswerType, protocol, major, minor);
+        match_protocol!(TransactionsType, protocol, major, minor);
 
         Err(MsgTypeError::unknown_protocol(protocol.to_owned()))
     }
@@ -125,6 +135,9 @@ impl Protocol {
             Self::PickupType(v) => v.as_protocol_parts(),
             Self::CoordinateMediationType(v) => v.as_protocol_parts(),
             Self::DidExchangeType(v) => v.as_protocol_parts(),
+            Self::ActionMenuType(v) => v.as_protocol_parts(),
+            Self::QuestionAnswerType(v) => v.as_protocol_parts(),
+            Self::TransactionsType(v) => v.as_protocol_parts(),
         }
     }
 
@@ -7,9 +7,10 @@ use super::{role::Role, Protocol};
 use crate::msg_types::{
     present_proof::PresentProofTypeV2,
     protocols::{
+        action_menu::ActionMenuTypeV1,
         basic_message::BasicMessageTypeV1,
         connection::ConnectionTypeV1,
-        coordinate_mediation::CoordinateMediationTypeV1,
+        coordinate_mediation::{CoordinateMediationTypeV1, CoordinateMediationTypeV2},
         cred_issuance::{CredentialIssuanceTypeV1, CredentialIssuanceTypeV2},
         did_exchange::DidExchangeTypeV1,
         discover_features::DiscoverFeaturesTypeV1,
@@ -17,10 +18,12 @@ use crate::msg_types::{
         out_of_band::OutOfBandTypeV1,
         pickup::PickupTypeV2,
         present_proof::PresentProofTypeV1,
+        question_answer::QuestionAnswerTypeV1,
         report_problem::ReportProblemTypeV1,
         revocation::RevocationTypeV2,
         routing::RoutingTypeV1,
         signature::SignatureTypeV1,
+        transactions::TransactionsTypeV1,
         trust_ping::TrustPingTypeV1,
     },
 };
@@ -96,8 +99,12 @@ lazy_static! {
         map_insert(&mut m, extract_parts!(TrustPingTypeV1::new_v1_0()));
         map_insert(&mut m, extract_parts!(PickupTypeV2::new_v2_0()));
         map_insert(&mut m, extract_parts!(CoordinateMediationTypeV1::new_v1_0()));
+        map_insert(&mut m, extract_parts!(CoordinateMediationTypeV2::new_v2_0()));
         map_insert(&mut m, extract_parts!(DidExchangeTypeV1::new_v1_0()));
         map_insert(&mut m, extract_parts!(DidExchangeTypeV1::new_v1_1()));
+        map_insert(&mut m, extract_parts!(ActionMenuTypeV1::new_v1_0()));
+        map_insert(&mut m, extract_parts!(QuestionAnswerTypeV1::new_v1_0()));
+        map_insert(&mut m, extract_parts!(TransactionsTypeV1::new_v1_0()));
         m
     };
 }
@@ -15,6 +15,9 @@ pub enum Role {
     Receiver,
     Requester,
     Responder,
+    Questioner,
+    Author,
+    Endorser,
     Notified,
     Notifier,
     Mediator,
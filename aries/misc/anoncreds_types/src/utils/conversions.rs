@@ -2,7 +2,10 @@ use bitvec::bitvec;
 
 use crate::data_types::{
     identifiers::{issuer_id::IssuerId, rev_reg_def_id::RevocationRegistryDefinitionId},
-    ledger::{rev_reg_delta::RevocationRegistryDeltaValue, rev_status_list::RevocationStatusList},
+    ledger::{
+        rev_reg_delta::{RevocationRegistryDelta, RevocationRegistryDeltaValue},
+        rev_status_list::RevocationStatusList,
+    },
 };
 
 /// Converts from a [RevocationRegistryDeltaValue] into a completed [RevocationStatusList]
@@ -35,7 +38,7 @@ pub fn from_revocation_registry_delta_to_revocation_status_list(
                 ),
             ));
         }
-        revocation_list.insert(*issued as usize, false);
+        revocation_list.set(*issued as usize, false);
     }
 
     for revoked in &delta.revoked {
@@ -49,7 +52,7 @@ pub fn from_revocation_registry_delta_to_revocation_status_list(
                 ),
             ));
         }
-        revocation_list.insert(*revoked as usize, true);
+        revocation_list.set(*revoked as usize, true);
     }
 
     let accum = delta.accum.into();
@@ -63,3 +66,98 @@ pub fn from_revocation_registry_delta_to_revocation_status_list(
     )
     .map_err(Into::into)
 }
+
+/// Converts from a [RevocationStatusList] into the [RevocationRegistryDelta] (older format)
+/// updating the registry from `prev_status_list`, the status list currently published, to it.
+///
+/// Without a previous status list, the delta is the initial entry of a registry: every index
+/// revoked in the status list is reported as revoked.
+pub fn from_revocation_status_list_to_revocation_registry_delta(
+    rev_status_list: &RevocationStatusList,
+    prev_status_list: Option<&RevocationStatusList>,
+) -> Result<RevocationRegistryDelta, crate::Error> {
+    let accum = rev_status_list.accum().ok_or_else(|| {
+        crate::Error::from_msg(
+            crate::ErrorKind::ConversionError,
+            "Revocation registry delta cannot be created from revocation status list without \
+             accumulator",
+        )
+    })?;
+    let was_revoked = |idx: usize| {
+        prev_status_list.is_some_and(|prev| prev.state().get(idx).is_some_and(|bit| *bit))
+    };
+
+    let mut issued = Vec::new();
+    let mut revoked = Vec::new();
+    for (idx, is_revoked) in rev_status_list.state().iter().enumerate() {
+        match (*is_revoked, was_revoked(idx)) {
+            (true, false) => revoked.push(idx as u32),
+            (false, true) => issued.push(idx as u32),
+            _ => {}
+        }
+    }
+
+    Ok(RevocationRegistryDelta {
+        value: RevocationRegistryDeltaValue {
+            prev_accum: prev_status_list.and_then(RevocationStatusList::accum),
+            accum,
+            issued,
+            revoked,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bitvec::prelude::*;
+
+    use super::*;
+
+    const ACCUM: &str = "1 1379509F4D411630D308A5ABB4F422FCE6737B330B1C5FD286AA5C26F2061E60 1 \
+                         235535CC45D4816C7686C5A402A230B35A62DDE82B4A652E384FD31912C4E4BB 1 \
+                         0C94B61595FCAEFC892BB98A27D524C97ED0B7ED1CC49AD6F178A59D4199C9A4 1 \
+                         172482285606DEE8500FC8A13E6A35EC071F8B84F0EB4CD3DD091C0B4CD30E5E 2 \
+                         095E45DDF417D05FB10933FFC63D474548B7FFFF7888802F07FFFFFF7D07A8A8 1 \
+                         0000000000000000000000000000000000000000000000000000000000000000";
+
+    fn status_list(revocation_list: BitVec) -> RevocationStatusList {
+        RevocationStatusList::new(
+            Some(
+                "4xE68b6S5VRFrKMMG1U95M:4:4xE68b6S5VRFrKMMG1U95M:3:CL:59232:default:CL_ACCUM:\
+                 4ae1cc6c-f6bd-486c-8057-88f2ce74e960",
+            ),
+            IssuerId::new("4xE68b6S5VRFrKMMG1U95M").unwrap(),
+            revocation_list,
+            Some(serde_json::from_value(serde_json::json!({ "accum": ACCUM })).unwrap()),
+            Some(1234),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_status_list_to_delta_without_previous_list() {
+        let delta = from_revocation_status_list_to_revocation_registry_delta(
+            &status_list(bitvec![0, 1, 1, 0]),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(delta.value.prev_accum, None);
+        assert!(delta.value.issued.is_empty());
+        assert_eq!(delta.value.revoked, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_status_list_to_delta_from_previous_list() {
+        let prev = status_list(bitvec![0, 1, 1, 0]);
+        let delta = from_revocation_status_list_to_revocation_registry_delta(
+            &status_list(bitvec![1, 0, 1, 0]),
+            Some(&prev),
+        )
+        .unwrap();
+
+        assert_eq!(delta.value.prev_accum, prev.accum);
+        assert_eq!(delta.value.issued, vec![1]);
+        assert_eq!(delta.value.revoked, vec![0]);
+    }
+}
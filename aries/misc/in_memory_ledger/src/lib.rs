@@ -0,0 +1,738 @@
+//! A ledger kept in process memory, optionally persisted to a file, implementing the ledger
+//! traits of [`aries_vcx_ledger`]. It lets issuance, proof and revocation scenarios run without
+//! an Indy pool.
+//!
+//! Responses to Indy specific reads (NYM, ATTRIB, transactions) mimic the replies of an Indy
+//! ledger. Writes are not authorized: signatures and roles of submitters are not verified.
+
+mod state;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, RwLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anoncreds_types::{
+    data_types::{
+        identifiers::{
+            cred_def_id::CredentialDefinitionId, issuer_id::IssuerId,
+            rev_reg_def_id::RevocationRegistryDefinitionId, schema_id::SchemaId,
+        },
+        ledger::{
+            cred_def::CredentialDefinition,
+            rev_reg::RevocationRegistry,
+            rev_reg_def::RevocationRegistryDefinition,
+            rev_reg_delta::RevocationRegistryDelta,
+            rev_status_list::RevocationStatusList,
+            schema::{AttributeNames, Schema},
+        },
+    },
+    utils::conversions::from_revocation_status_list_to_revocation_registry_delta,
+};
+use aries_vcx_ledger::{
+    errors::error::{VcxLedgerError, VcxLedgerResult},
+    ledger::{
+        base_ledger::{
+            AnoncredsLedgerRead, AnoncredsLedgerSupport, AnoncredsLedgerWrite, IndyLedgerRead,
+            IndyLedgerWrite,
+        },
+        common::verify_transaction_can_be_endorsed,
+        indy_vdr_ledger::UpdateRole,
+    },
+};
+use aries_vcx_wallet::wallet::base_wallet::BaseWallet;
+use async_trait::async_trait;
+use did_parser_nom::Did;
+use log::debug;
+use public_key::Key;
+use serde_json::{json, Map, Value};
+
+use crate::state::{
+    LedgerState, Txn, TxnAuthorAgreement, ATTRIB, CRED_DEF, NYM, REVOC_REG_DEF, SCHEMA,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryLedger {
+    state: Arc<RwLock<LedgerState>>,
+    file_path: Option<Arc<PathBuf>>,
+}
+
+impl InMemoryLedger {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Opens the ledger persisted at `path`, which is written after each transaction. The
+    /// file is created by the first transaction if it does not exist yet.
+    pub fn open(path: impl AsRef<Path>) -> VcxLedgerResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let state = match fs::read(&path) {
+            Ok(content) => serde_json::from_slice(&content)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => LedgerState::default(),
+            Err(err) => {
+                return Err(VcxLedgerError::UnknownError(format!(
+                    "Cannot read ledger file {}: {err}",
+                    path.display()
+                )))
+            }
+        };
+        Ok(Self {
+            state: Arc::new(RwLock::new(state)),
+            file_path: Some(Arc::new(path)),
+        })
+    }
+
+    /// Writes the NYM of a trustee, as found in the genesis transactions of an Indy pool.
+    pub fn add_trustee(&self, did: &str, verkey: &str) -> VcxLedgerResult<()> {
+        self.write(|state| {
+            state.write_nym(
+                did,
+                did,
+                Some(verkey.to_owned()),
+                serde_json::to_value(UpdateRole::from_str("TRUSTEE")?)?,
+                None,
+            );
+            Ok(())
+        })
+    }
+
+    /// Sets the transaction author agreement returned by the ledger. Acceptance of it is not
+    /// enforced.
+    pub fn set_txn_author_agreement(&self, text: &str, version: &str) -> VcxLedgerResult<()> {
+        self.write(|state| {
+            state.taa = Some(TxnAuthorAgreement {
+                text: text.to_owned(),
+                version: version.to_owned(),
+            });
+            Ok(())
+        })
+    }
+
+    // Applies the transaction to a copy of the state, which replaces the state only once it is
+    // persisted, so a failed transaction or write leaves the ledger untouched.
+    fn write<T>(
+        &self,
+        transaction: impl FnOnce(&mut LedgerState) -> VcxLedgerResult<T>,
+    ) -> VcxLedgerResult<T> {
+        let mut state = self.state.write()?;
+        let mut updated = state.clone();
+        let result = transaction(&mut updated)?;
+        if let Some(path) = &self.file_path {
+            persist_state(path, &updated)?;
+        }
+        *state = updated;
+        Ok(result)
+    }
+
+    // Applies a request which was signed by its author and the endorser.
+    fn apply_request(&self, request: &Value) -> VcxLedgerResult<Txn> {
+        let identifier = request["identifier"].as_str().unwrap_or_default();
+        let operation = &request["operation"];
+        match operation["type"].as_str() {
+            Some(NYM) => {
+                let dest = operation["dest"].as_str().ok_or_else(|| {
+                    VcxLedgerError::InvalidInput(format!("NYM request without dest: {request}"))
+                })?;
+                self.write(|state| {
+                    Ok(state.write_nym(
+                        identifier,
+                        dest,
+                        operation["verkey"].as_str().map(ToOwned::to_owned),
+                        operation["role"].clone(),
+                        operation["alias"].as_str().map(ToOwned::to_owned),
+                    ))
+                })
+            }
+            Some(ATTRIB) => {
+                let dest = operation["dest"].as_str().unwrap_or(identifier);
+                let raw = operation["raw"].as_str().ok_or_else(|| {
+                    VcxLedgerError::InvalidInput(format!("ATTRIB request without raw: {request}"))
+                })?;
+                let raw = serde_json::from_str(raw)?;
+                self.write(|state| state.write_attrib(dest, raw))
+            }
+            Some(SCHEMA) => {
+                let data = &operation["data"];
+                let name = data["name"].as_str().unwrap_or_default();
+                let version = data["version"].as_str().unwrap_or_default();
+                let schema = Schema {
+                    id: SchemaId::new_unchecked(format!("{identifier}:2:{name}:{version}")),
+                    seq_no: None,
+                    name: name.to_owned(),
+                    version: version.to_owned(),
+                    attr_names: AttributeNames(serde_json::from_value(data["attr_names"].clone())?),
+                    issuer_id: IssuerId::new_unchecked(identifier),
+                };
+                self.write(|state| state.write_schema(schema))
+            }
+            Some(CRED_DEF) => {
+                let cred_def = serde_json::from_value(operation["data"].clone())?;
+                self.write(|state| {
+                    state.write_cred_def(cred_def)?;
+                    last_txn(state)
+                })
+            }
+            Some(REVOC_REG_DEF) => {
+                let rev_reg_def = serde_json::from_value(operation["data"].clone())?;
+                self.write(|state| {
+                    state.write_rev_reg_def(rev_reg_def)?;
+                    last_txn(state)
+                })
+            }
+            txn_type => Err(VcxLedgerError::UnimplementedFeature(format!(
+                "Endorsing transactions of type {txn_type:?} is not supported by the in-memory \
+                 ledger"
+            ))),
+        }
+    }
+
+    // Requests prepared for endorsement carry the anoncreds objects as they are instead of
+    // their Indy representation, as only this ledger reads them back.
+    async fn prepare_request_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        author_did: &Did,
+        operation: Value,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        let req_id = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| since_epoch.as_nanos() as u64)
+            .unwrap_or_default();
+        let request = json!({
+            "reqId": req_id,
+            "identifier": author_did.to_string(),
+            "operation": operation,
+            "protocolVersion": 2,
+        });
+        self.set_endorser(wallet, author_did, &request.to_string(), endorser_did)
+            .await
+    }
+}
+
+fn persist_state(path: &Path, state: &LedgerState) -> VcxLedgerResult<()> {
+    let map_err = |err: std::io::Error| {
+        VcxLedgerError::UnknownError(format!(
+            "Cannot write ledger file {}: {err}",
+            path.display()
+        ))
+    };
+    // write to a temporary file first, so the ledger file is never left half-written
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serde_json::to_vec(state)?).map_err(map_err)?;
+    fs::rename(&tmp_path, path).map_err(map_err)
+}
+
+fn last_txn(state: &LedgerState) -> VcxLedgerResult<Txn> {
+    state
+        .last_txn()
+        .cloned()
+        .ok_or_else(|| VcxLedgerError::InvalidState("No transaction was written".into()))
+}
+
+fn reply(result: Value) -> String {
+    json!({ "op": "REPLY", "result": result }).to_string()
+}
+
+fn txn_reply(txn: &Txn) -> Value {
+    json!({
+        "txn": {
+            "type": txn.txn_type,
+            "data": txn.data,
+            "metadata": { "from": txn.from },
+        },
+        "txnMetadata": { "seqNo": txn.seq_no, "txnTime": txn.txn_time },
+        "ver": "1",
+    })
+}
+
+/// Signs the request as `did`, standing in for the multi-signature of indy requests.
+async fn add_signature(
+    wallet: &impl BaseWallet,
+    request: &mut Value,
+    did: &Did,
+) -> VcxLedgerResult<()> {
+    let verkey = wallet.key_for_did(&did.to_string()).await?;
+    let signature = wallet.sign(&verkey, request.to_string().as_bytes()).await?;
+    request["signatures"][did.to_string()] = json!(bs58::encode(signature).into_string());
+    Ok(())
+}
+
+fn not_found(id: &impl ToString) -> VcxLedgerError {
+    debug!("in-memory ledger << {} not found", id.to_string());
+    VcxLedgerError::LedgerItemNotFound
+}
+
+#[async_trait]
+impl IndyLedgerRead for InMemoryLedger {
+    async fn get_attr(&self, target_did: &Did, attr_name: &str) -> VcxLedgerResult<String> {
+        let state = self.state.read()?;
+        let data = state
+            .attrib(&target_did.to_string(), attr_name)
+            .map(|value| json!({ attr_name: value }).to_string());
+        Ok(reply(json!({
+            "type": "104",
+            "dest": target_did.to_string(),
+            "raw": attr_name,
+            "data": data,
+        })))
+    }
+
+    async fn get_nym(&self, did: &Did) -> VcxLedgerResult<String> {
+        let did = did.to_string();
+        let state = self.state.read()?;
+        let nym = state.nym(&did);
+        let data = nym.map(|nym| {
+            json!({
+                "dest": did,
+                "identifier": nym.identifier,
+                "verkey": nym.verkey,
+                "role": nym.role,
+                "alias": nym.alias,
+            })
+            .to_string()
+        });
+        Ok(reply(json!({
+            "type": "105",
+            "dest": did,
+            "seqNo": nym.map(|nym| nym.seq_no),
+            "txnTime": nym.map(|nym| nym.txn_time),
+            "data": data,
+        })))
+    }
+
+    async fn get_txn_author_agreement(&self) -> VcxLedgerResult<Option<String>> {
+        let state = self.state.read()?;
+        Ok(state
+            .taa
+            .as_ref()
+            .map(|taa| json!({ "text": taa.text, "version": taa.version }).to_string()))
+    }
+
+    async fn get_ledger_txn(
+        &self,
+        seq_no: i32,
+        _submitter_did: Option<&Did>,
+    ) -> VcxLedgerResult<String> {
+        let state = self.state.read()?;
+        let data = u64::try_from(seq_no)
+            .ok()
+            .and_then(|seq_no| state.txn(seq_no))
+            .map(txn_reply);
+        Ok(reply(json!({ "type": "3", "seqNo": seq_no, "data": data })))
+    }
+}
+
+#[async_trait]
+impl IndyLedgerWrite for InMemoryLedger {
+    async fn publish_nym(
+        &self,
+        _wallet: &impl BaseWallet,
+        submitter_did: &Did,
+        target_did: &Did,
+        verkey: Option<&Key>,
+        data: Option<&str>,
+        role: Option<&str>,
+    ) -> VcxLedgerResult<String> {
+        let role = role.map(UpdateRole::from_str).transpose()?;
+        let txn = self.write(|state| {
+            Ok(state.write_nym(
+                &submitter_did.to_string(),
+                &target_did.to_string(),
+                verkey.map(Key::base58),
+                serde_json::to_value(role)?,
+                data.map(ToOwned::to_owned),
+            ))
+        })?;
+        Ok(reply(txn_reply(&txn)))
+    }
+
+    async fn set_endorser(
+        &self,
+        wallet: &impl BaseWallet,
+        submitter_did: &Did,
+        request: &str,
+        endorser: &Did,
+    ) -> VcxLedgerResult<String> {
+        let mut request: Value = serde_json::from_str(request)?;
+        request["endorser"] = json!(endorser.to_string());
+        add_signature(wallet, &mut request, submitter_did).await?;
+        Ok(request.to_string())
+    }
+
+    async fn endorse_transaction(
+        &self,
+        wallet: &impl BaseWallet,
+        endorser_did: &Did,
+        request_json: &str,
+    ) -> VcxLedgerResult<String> {
+        verify_transaction_can_be_endorsed(request_json, endorser_did)?;
+        let mut request: Value = serde_json::from_str(request_json)?;
+        add_signature(wallet, &mut request, endorser_did).await?;
+        self.apply_request(&request)?;
+        Ok(request.to_string())
+    }
+
+    async fn prepare_nym_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        author_did: &Did,
+        target_did: &Did,
+        target_vk: Option<&Key>,
+        role: Option<UpdateRole>,
+        alias: Option<String>,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        let operation = json!({
+            "type": NYM,
+            "dest": target_did.to_string(),
+            "verkey": target_vk.map(Key::base58),
+            "role": serde_json::to_value(role)?,
+            "alias": alias,
+        });
+        self.prepare_request_for_endorsement(wallet, author_did, operation, endorser_did)
+            .await
+    }
+
+    async fn prepare_schema_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        schema: Schema,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        let operation = json!({
+            "type": SCHEMA,
+            "data": {
+                "name": schema.name,
+                "version": schema.version,
+                "attr_names": schema.attr_names,
+            },
+        });
+        self.prepare_request_for_endorsement(wallet, author_did, operation, endorser_did)
+            .await
+    }
+
+    async fn prepare_cred_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_def: CredentialDefinition,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        let operation = json!({ "type": CRED_DEF, "data": cred_def });
+        self.prepare_request_for_endorsement(wallet, author_did, operation, endorser_did)
+            .await
+    }
+
+    async fn prepare_rev_reg_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_def: RevocationRegistryDefinition,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        let operation = json!({ "type": REVOC_REG_DEF, "data": rev_reg_def });
+        self.prepare_request_for_endorsement(wallet, author_did, operation, endorser_did)
+            .await
+    }
+
+    async fn add_attr(
+        &self,
+        _wallet: &impl BaseWallet,
+        target_did: &Did,
+        attrib_json: &str,
+    ) -> VcxLedgerResult<String> {
+        let raw: Map<String, Value> = serde_json::from_str(attrib_json)?;
+        let txn = self.write(|state| state.write_attrib(&target_did.to_string(), raw))?;
+        Ok(reply(txn_reply(&txn)))
+    }
+
+    async fn write_did(
+        &self,
+        _wallet: &impl BaseWallet,
+        submitter_did: &Did,
+        target_did: &Did,
+        target_vk: &Key,
+        role: Option<UpdateRole>,
+        alias: Option<String>,
+    ) -> VcxLedgerResult<String> {
+        let txn = self.write(|state| {
+            Ok(state.write_nym(
+                &submitter_did.to_string(),
+                &target_did.to_string(),
+                Some(target_vk.base58()),
+                serde_json::to_value(role)?,
+                alias,
+            ))
+        })?;
+        Ok(reply(txn_reply(&txn)))
+    }
+}
+
+#[async_trait]
+impl AnoncredsLedgerRead for InMemoryLedger {
+    type RevocationRegistryDefinitionAdditionalMetadata = ();
+
+    async fn get_schema(
+        &self,
+        schema_id: &SchemaId,
+        _submitter_did: Option<&Did>,
+    ) -> VcxLedgerResult<Schema> {
+        let state = self.state.read()?;
+        state
+            .schema(&schema_id.to_string())
+            .cloned()
+            .ok_or_else(|| not_found(schema_id))
+    }
+
+    async fn get_cred_def(
+        &self,
+        cred_def_id: &CredentialDefinitionId,
+        _submitter_did: Option<&Did>,
+    ) -> VcxLedgerResult<CredentialDefinition> {
+        let state = self.state.read()?;
+        state
+            .cred_def(&cred_def_id.to_string())
+            .cloned()
+            .ok_or_else(|| not_found(cred_def_id))
+    }
+
+    async fn get_rev_reg_def_json(
+        &self,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+    ) -> VcxLedgerResult<(RevocationRegistryDefinition, ())> {
+        let state = self.state.read()?;
+        state
+            .rev_reg_def(&rev_reg_id.to_string())
+            .cloned()
+            .map(|rev_reg_def| (rev_reg_def, ()))
+            .ok_or_else(|| not_found(rev_reg_id))
+    }
+
+    async fn get_rev_reg_delta_json(
+        &self,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+        from: Option<u64>,
+        to: Option<u64>,
+    ) -> VcxLedgerResult<(RevocationRegistryDelta, u64)> {
+        let state = self.state.read()?;
+        state.rev_reg_delta(&rev_reg_id.to_string(), from, to.unwrap_or(u64::MAX))
+    }
+
+    async fn get_rev_status_list(
+        &self,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+        timestamp: u64,
+        _rev_reg_def_meta: Option<&()>,
+    ) -> VcxLedgerResult<(RevocationStatusList, u64)> {
+        let state = self.state.read()?;
+        state.rev_status_list(&rev_reg_id.to_string(), timestamp)
+    }
+
+    async fn get_rev_reg(
+        &self,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+        timestamp: u64,
+    ) -> VcxLedgerResult<(RevocationRegistry, u64)> {
+        let state = self.state.read()?;
+        let (delta, timestamp) = state.rev_reg_delta(&rev_reg_id.to_string(), None, timestamp)?;
+        Ok((
+            RevocationRegistry {
+                value: delta.value.accum.into(),
+            },
+            timestamp,
+        ))
+    }
+}
+
+#[async_trait]
+impl AnoncredsLedgerWrite for InMemoryLedger {
+    async fn publish_schema(
+        &self,
+        _wallet: &impl BaseWallet,
+        schema_json: Schema,
+        _submitter_did: &Did,
+        _endorser_did: Option<&Did>,
+    ) -> VcxLedgerResult<()> {
+        self.write(|state| state.write_schema(schema_json))
+            .map(|_| ())
+    }
+
+    async fn publish_cred_def(
+        &self,
+        _wallet: &impl BaseWallet,
+        cred_def_json: CredentialDefinition,
+        _submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        self.write(|state| state.write_cred_def(cred_def_json))
+    }
+
+    async fn publish_rev_reg_def(
+        &self,
+        _wallet: &impl BaseWallet,
+        rev_reg_def: RevocationRegistryDefinition,
+        _submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        self.write(|state| state.write_rev_reg_def(rev_reg_def))
+    }
+
+    async fn publish_rev_reg_delta(
+        &self,
+        _wallet: &impl BaseWallet,
+        rev_reg_id: &RevocationRegistryDefinitionId,
+        rev_reg_entry_json: RevocationRegistryDelta,
+        submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        self.write(|state| {
+            state.write_rev_reg_entry(
+                &rev_reg_id.to_string(),
+                &submitter_did.to_string(),
+                rev_reg_entry_json,
+            )
+        })
+    }
+
+    async fn publish_rev_status_list(
+        &self,
+        _wallet: &impl BaseWallet,
+        rev_status_list: RevocationStatusList,
+        prev_rev_status_list: Option<&RevocationStatusList>,
+        submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        let rev_reg_id = rev_status_list.rev_reg_def_id.clone().ok_or_else(|| {
+            VcxLedgerError::InvalidInput(
+                "Revocation status list is missing its revocation registry id".into(),
+            )
+        })?;
+        let delta = from_revocation_status_list_to_revocation_registry_delta(
+            &rev_status_list,
+            prev_rev_status_list,
+        )
+        .map_err(|err| VcxLedgerError::InvalidInput(err.to_string()))?;
+        self.write(|state| {
+            state.write_rev_reg_entry(&rev_reg_id.to_string(), &submitter_did.to_string(), delta)
+        })
+    }
+}
+
+impl AnoncredsLedgerSupport for InMemoryLedger {
+    fn supports_schema(&self, _id: &SchemaId) -> bool {
+        true
+    }
+
+    fn supports_credential_definition(&self, _id: &CredentialDefinitionId) -> bool {
+        true
+    }
+
+    fn supports_revocation_registry(&self, _id: &RevocationRegistryDefinitionId) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use std::env;
+
+    use super::*;
+
+    fn _did(did: &str) -> Did {
+        Did::parse(did.to_owned()).unwrap()
+    }
+
+    fn _schema() -> Schema {
+        Schema {
+            id: SchemaId::new_unchecked("V4SGRU86Z58d6TV7PBUe6f:2:degree:1.0"),
+            seq_no: None,
+            name: "degree".to_owned(),
+            version: "1.0".to_owned(),
+            attr_names: AttributeNames(vec!["name".to_owned()]),
+            issuer_id: IssuerId::new_unchecked("V4SGRU86Z58d6TV7PBUe6f"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nym_and_attrib_replies() {
+        let ledger = InMemoryLedger::new();
+        let did = _did("V4SGRU86Z58d6TV7PBUe6f");
+
+        let nym: Value = serde_json::from_str(&ledger.get_nym(&did).await.unwrap()).unwrap();
+        assert_eq!(nym["result"]["data"], Value::Null);
+
+        ledger
+            .write(|state| {
+                Ok(state.write_nym(
+                    "V4SGRU86Z58d6TV7PBUe6f",
+                    "V4SGRU86Z58d6TV7PBUe6f",
+                    Some("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL".to_owned()),
+                    Value::Null,
+                    None,
+                ))
+            })
+            .unwrap();
+        ledger
+            .write(|state| {
+                state.write_attrib(
+                    "V4SGRU86Z58d6TV7PBUe6f",
+                    serde_json::from_value(json!({ "endpoint": { "endpoint": "http://a" } }))
+                        .unwrap(),
+                )
+            })
+            .unwrap();
+
+        let nym: Value = serde_json::from_str(&ledger.get_nym(&did).await.unwrap()).unwrap();
+        let nym_data: Value =
+            serde_json::from_str(nym["result"]["data"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            nym_data["verkey"],
+            json!("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL")
+        );
+
+        let attr: Value =
+            serde_json::from_str(&ledger.get_attr(&did, "endpoint").await.unwrap()).unwrap();
+        let attr_data: Value =
+            serde_json::from_str(attr["result"]["data"].as_str().unwrap()).unwrap();
+        assert_eq!(attr_data["endpoint"]["endpoint"], json!("http://a"));
+
+        let txn: Value =
+            serde_json::from_str(&ledger.get_ledger_txn(2, None).await.unwrap()).unwrap();
+        assert_eq!(txn["result"]["data"]["txn"]["type"], json!(ATTRIB));
+    }
+
+    #[tokio::test]
+    async fn test_schema_is_persisted() {
+        let path = env::temp_dir().join(format!("in_memory_ledger_{}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let ledger = InMemoryLedger::open(&path).unwrap();
+        ledger.write(|state| state.write_schema(_schema())).unwrap();
+        assert!(matches!(
+            ledger.write(|state| state.write_schema(_schema())),
+            Err(VcxLedgerError::DuplicationSchema)
+        ));
+
+        let reopened = InMemoryLedger::open(&path).unwrap();
+        let schema = reopened.get_schema(&_schema().id, None).await.unwrap();
+        assert_eq!(schema.seq_no, Some(1));
+        assert_eq!(schema.name, "degree");
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failed_write_leaves_state_untouched() {
+        let path = env::temp_dir()
+            .join(format!("in_memory_ledger_missing_{}", std::process::id()))
+            .join("ledger.json");
+
+        let ledger = InMemoryLedger::open(&path).unwrap();
+        ledger
+            .write(|state| state.write_schema(_schema()))
+            .unwrap_err();
+        ledger.get_schema(&_schema().id, None).await.unwrap_err();
+        assert!(!path.exists());
+    }
+}
@@ -0,0 +1,384 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anoncreds_types::{
+    cl::Accumulator,
+    data_types::ledger::{
+        cred_def::CredentialDefinition,
+        rev_reg_def::RevocationRegistryDefinition,
+        rev_reg_delta::{RevocationRegistryDelta, RevocationRegistryDeltaValue},
+        rev_status_list::RevocationStatusList,
+        schema::Schema,
+    },
+};
+use aries_vcx_ledger::errors::error::{VcxLedgerError, VcxLedgerResult};
+use bitvec::bitvec;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+// Indy transaction types, as found in the `type` of ledger transactions.
+pub(crate) const NYM: &str = "1";
+pub(crate) const ATTRIB: &str = "100";
+pub(crate) const SCHEMA: &str = "101";
+pub(crate) const CRED_DEF: &str = "102";
+pub(crate) const REVOC_REG_DEF: &str = "113";
+pub(crate) const REVOC_REG_ENTRY: &str = "114";
+
+/// A transaction written to the ledger, in the order it was written.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Txn {
+    pub seq_no: u64,
+    pub txn_time: u64,
+    pub txn_type: String,
+    pub from: String,
+    pub data: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct Nym {
+    pub identifier: String,
+    pub verkey: Option<String>,
+    pub role: Value,
+    pub alias: Option<String>,
+    pub seq_no: u64,
+    pub txn_time: u64,
+}
+
+/// A revocation registry entry, holding the indices revoked and re-issued by it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct RevRegEntry {
+    pub timestamp: u64,
+    pub accum: Accumulator,
+    pub issued: Vec<u32>,
+    pub revoked: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct TxnAuthorAgreement {
+    pub text: String,
+    pub version: String,
+}
+
+/// Everything written to an [`crate::InMemoryLedger`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct LedgerState {
+    txns: Vec<Txn>,
+    nyms: HashMap<String, Nym>,
+    attribs: HashMap<String, Map<String, Value>>,
+    schemas: HashMap<String, Schema>,
+    cred_defs: HashMap<String, CredentialDefinition>,
+    rev_reg_defs: HashMap<String, RevocationRegistryDefinition>,
+    rev_reg_entries: HashMap<String, Vec<RevRegEntry>>,
+    pub taa: Option<TxnAuthorAgreement>,
+}
+
+impl LedgerState {
+    /// Appends a transaction, returning its sequence number and time. Transaction times never
+    /// go backwards, so that entries can be looked up by timestamp.
+    fn append_txn(&mut self, txn_type: &str, from: &str, data: Value) -> (u64, u64) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        let txn_time = self.txns.last().map_or(now, |last| last.txn_time.max(now));
+        let seq_no = self.txns.len() as u64 + 1;
+        self.txns.push(Txn {
+            seq_no,
+            txn_time,
+            txn_type: txn_type.to_owned(),
+            from: from.to_owned(),
+            data,
+        });
+        (seq_no, txn_time)
+    }
+
+    pub fn txn(&self, seq_no: u64) -> Option<&Txn> {
+        seq_no
+            .checked_sub(1)
+            .and_then(|index| self.txns.get(index as usize))
+    }
+
+    pub fn last_txn(&self) -> Option<&Txn> {
+        self.txns.last()
+    }
+
+    pub fn write_nym(
+        &mut self,
+        identifier: &str,
+        dest: &str,
+        verkey: Option<String>,
+        role: Value,
+        alias: Option<String>,
+    ) -> Txn {
+        let data = json!({
+            "dest": dest,
+            "verkey": verkey,
+            "role": role,
+            "alias": alias,
+        });
+        let (seq_no, txn_time) = self.append_txn(NYM, identifier, data);
+        let previous = self.nyms.remove(dest);
+        self.nyms.insert(
+            dest.to_owned(),
+            Nym {
+                identifier: identifier.to_owned(),
+                // Like on Indy, omitted fields keep their previous value
+                verkey: verkey.or_else(|| previous.as_ref().and_then(|nym| nym.verkey.clone())),
+                role: match (role, previous.as_ref()) {
+                    (Value::Null, Some(previous)) => previous.role.clone(),
+                    (role, _) => role,
+                },
+                alias: alias.or_else(|| previous.and_then(|nym| nym.alias)),
+                seq_no,
+                txn_time,
+            },
+        );
+        self.txns[seq_no as usize - 1].clone()
+    }
+
+    pub fn nym(&self, did: &str) -> Option<&Nym> {
+        self.nyms.get(did)
+    }
+
+    /// Writes each attribute of the JSON object `raw` for the DID. Attributes set to `null`
+    /// are cleared.
+    pub fn write_attrib(&mut self, did: &str, raw: Map<String, Value>) -> VcxLedgerResult<Txn> {
+        if !self.nyms.contains_key(did) {
+            return Err(VcxLedgerError::InvalidLedgerResponse(format!(
+                "Cannot write ATTRIB, NYM {did} is not on the ledger"
+            )));
+        }
+        let (seq_no, _) = self.append_txn(ATTRIB, did, json!({ "dest": did, "raw": raw }));
+        let attribs = self.attribs.entry(did.to_owned()).or_default();
+        for (name, value) in raw {
+            match value {
+                Value::Null => attribs.remove(&name),
+                value => attribs.insert(name, value),
+            };
+        }
+        Ok(self.txns[seq_no as usize - 1].clone())
+    }
+
+    pub fn attrib(&self, did: &str, name: &str) -> Option<&Value> {
+        self.attribs.get(did).and_then(|attribs| attribs.get(name))
+    }
+
+    pub fn write_schema(&mut self, mut schema: Schema) -> VcxLedgerResult<Txn> {
+        let id = schema.id.to_string();
+        if self.schemas.contains_key(&id) {
+            return Err(VcxLedgerError::DuplicationSchema);
+        }
+        let data = json!({
+            "name": schema.name,
+            "version": schema.version,
+            "attr_names": schema.attr_names,
+        });
+        let (seq_no, _) = self.append_txn(SCHEMA, &schema.issuer_id.to_string(), data);
+        schema.seq_no = Some(seq_no as u32);
+        self.schemas.insert(id, schema);
+        Ok(self.txns[seq_no as usize - 1].clone())
+    }
+
+    pub fn schema(&self, id: &str) -> Option<&Schema> {
+        self.schemas.get(id)
+    }
+
+    pub fn write_cred_def(&mut self, cred_def: CredentialDefinition) -> VcxLedgerResult<()> {
+        let data = serde_json::to_value(&cred_def)?;
+        self.append_txn(CRED_DEF, &cred_def.issuer_id.to_string(), data);
+        self.cred_defs.insert(cred_def.id.to_string(), cred_def);
+        Ok(())
+    }
+
+    pub fn cred_def(&self, id: &str) -> Option<&CredentialDefinition> {
+        self.cred_defs.get(id)
+    }
+
+    pub fn write_rev_reg_def(
+        &mut self,
+        rev_reg_def: RevocationRegistryDefinition,
+    ) -> VcxLedgerResult<()> {
+        let data = serde_json::to_value(&rev_reg_def)?;
+        self.append_txn(REVOC_REG_DEF, &rev_reg_def.issuer_id.to_string(), data);
+        self.rev_reg_defs
+            .insert(rev_reg_def.id.to_string(), rev_reg_def);
+        Ok(())
+    }
+
+    pub fn rev_reg_def(&self, id: &str) -> Option<&RevocationRegistryDefinition> {
+        self.rev_reg_defs.get(id)
+    }
+
+    pub fn write_rev_reg_entry(
+        &mut self,
+        id: &str,
+        from: &str,
+        delta: RevocationRegistryDelta,
+    ) -> VcxLedgerResult<()> {
+        if !self.rev_reg_defs.contains_key(id) {
+            return Err(VcxLedgerError::InvalidLedgerResponse(format!(
+                "Cannot write revocation registry entry, definition {id} is not on the ledger"
+            )));
+        }
+        let data = serde_json::to_value(&delta)?;
+        let (_, timestamp) = self.append_txn(REVOC_REG_ENTRY, from, data);
+        let RevocationRegistryDeltaValue {
+            accum,
+            issued,
+            revoked,
+            ..
+        } = delta.value;
+        self.rev_reg_entries
+            .entry(id.to_owned())
+            .or_default()
+            .push(RevRegEntry {
+                timestamp,
+                accum,
+                issued,
+                revoked,
+            });
+        Ok(())
+    }
+
+    fn rev_reg_entries_until(&self, id: &str, timestamp: u64) -> VcxLedgerResult<&[RevRegEntry]> {
+        let entries = self
+            .rev_reg_entries
+            .get(id)
+            .ok_or(VcxLedgerError::LedgerItemNotFound)?;
+        let count = entries
+            .iter()
+            .take_while(|entry| entry.timestamp <= timestamp)
+            .count();
+        if count == 0 {
+            return Err(VcxLedgerError::LedgerItemNotFound);
+        }
+        Ok(&entries[..count])
+    }
+
+    /// Accumulates the delta of the registry between the entries at `from` and `to`, returning
+    /// it along with the time of the latest entry at or before `to`.
+    pub fn rev_reg_delta(
+        &self,
+        id: &str,
+        from: Option<u64>,
+        to: u64,
+    ) -> VcxLedgerResult<(RevocationRegistryDelta, u64)> {
+        let entries_to = self.rev_reg_entries_until(id, to)?;
+        let entries_from = match from {
+            Some(from) => self.rev_reg_entries_until(id, from).unwrap_or_default(),
+            None => &[],
+        };
+
+        let revoked_to = revoked_indices(entries_to);
+        let revoked_from = revoked_indices(entries_from);
+        let last = &entries_to[entries_to.len() - 1];
+        let delta = RevocationRegistryDelta {
+            value: RevocationRegistryDeltaValue {
+                prev_accum: entries_from.last().map(|entry| entry.accum),
+                accum: last.accum,
+                issued: revoked_from.difference(&revoked_to).copied().collect(),
+                revoked: revoked_to.difference(&revoked_from).copied().collect(),
+            },
+        };
+        Ok((delta, last.timestamp))
+    }
+
+    /// Status of the whole registry as of the latest entry at or before `timestamp`.
+    pub fn rev_status_list(
+        &self,
+        id: &str,
+        timestamp: u64,
+    ) -> VcxLedgerResult<(RevocationStatusList, u64)> {
+        let rev_reg_def = self
+            .rev_reg_def(id)
+            .ok_or(VcxLedgerError::LedgerItemNotFound)?;
+        let entries = self.rev_reg_entries_until(id, timestamp)?;
+        let last = &entries[entries.len() - 1];
+
+        let mut revocation_list = bitvec![0; rev_reg_def.value.max_cred_num as usize];
+        for index in revoked_indices(entries) {
+            if index as usize >= revocation_list.len() {
+                return Err(VcxLedgerError::InvalidLedgerResponse(format!(
+                    "Revoked index {index} is out of bounds of registry {id}"
+                )));
+            }
+            revocation_list.set(index as usize, true);
+        }
+        let status_list = RevocationStatusList::new(
+            Some(id),
+            rev_reg_def.issuer_id.clone(),
+            revocation_list,
+            Some(last.accum.into()),
+            Some(last.timestamp),
+        )
+        .map_err(|err| VcxLedgerError::InvalidLedgerResponse(err.to_string()))?;
+        Ok((status_list, last.timestamp))
+    }
+}
+
+// Registries are issued by default, so each index is revoked from the entry revoking it until
+// an entry issues it again.
+fn revoked_indices(entries: &[RevRegEntry]) -> BTreeSet<u32> {
+    entries.iter().fold(BTreeSet::new(), |mut revoked, entry| {
+        entry.issued.iter().for_each(|index| {
+            revoked.remove(index);
+        });
+        revoked.extend(&entry.revoked);
+        revoked
+    })
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use super::*;
+
+    const ACCUM: &str = "2 0204F2D2B1F2B705A11AAFEEE73C9BA084C12AF1179294529AC4D14CA54E87F3 2 \
+                         222BAE38FAF2673F7BCBB86D8DE1A327F5065BDC892E9A122164260C97BC0C63 2 \
+                         1565105F8BA53037978B66E0CC9F53205F189DEEB6B7168744456DD98D2F4E88 2 \
+                         1AC9E76B2868141A42329778831C14AEAAF7A9981209C1D96AECA4E69CAFB243 2 \
+                         095E45DDF417D05FB10933FFC63D474548B7FFFF7888802F07FFFFFF7D07A8A8 1 \
+                         0000000000000000000000000000000000000000000000000000000000000000";
+
+    fn entry(timestamp: u64, issued: Vec<u32>, revoked: Vec<u32>) -> RevRegEntry {
+        RevRegEntry {
+            timestamp,
+            accum: serde_json::from_value(json!(ACCUM)).unwrap(),
+            issued,
+            revoked,
+        }
+    }
+
+    #[test]
+    fn test_revoked_indices_follow_entries() {
+        let entries = vec![
+            entry(10, vec![], vec![]),
+            entry(20, vec![], vec![1, 3]),
+            entry(30, vec![1], vec![4]),
+        ];
+
+        assert!(revoked_indices(&entries[..1]).is_empty());
+        assert_eq!(revoked_indices(&entries[..2]), BTreeSet::from_iter([1, 3]));
+        assert_eq!(revoked_indices(&entries), BTreeSet::from_iter([3, 4]));
+    }
+
+    #[test]
+    fn test_txn_time_never_goes_backwards() {
+        let mut state = LedgerState::default();
+        let nym = state.write_nym("did", "did", Some("verkey".into()), Value::Null, None);
+        state.txns[0].txn_time = nym.txn_time + 100;
+
+        let attrib = state
+            .write_attrib(
+                "did",
+                Map::from_iter([("endpoint".to_owned(), json!("url"))]),
+            )
+            .unwrap();
+
+        assert_eq!(attrib.seq_no, 2);
+        assert_eq!(attrib.txn_time, nym.txn_time + 100);
+        assert_eq!(state.attrib("did", "endpoint"), Some(&json!("url")));
+        assert_eq!(state.txn(2).unwrap().txn_type, ATTRIB);
+    }
+}
@@ -3,6 +3,7 @@ use std::{error::Error, fmt};
 #[derive(Debug, thiserror::Error)]
 pub struct HttpError {
     msg: String,
+    transient: bool,
 }
 
 impl fmt::Display for HttpError {
@@ -24,8 +25,25 @@ impl HttpError {
     {
         HttpError {
             msg: msg.to_string(),
+            transient: false,
         }
     }
+
+    /// Error which may go away if the request is repeated, such as a failure to connect, a
+    /// timeout or a 5xx response.
+    pub fn transient<D>(msg: D) -> HttpError
+    where
+        D: fmt::Display + fmt::Debug + Send + Sync + 'static,
+    {
+        HttpError {
+            msg: msg.to_string(),
+            transient: true,
+        }
+    }
+
+    pub fn is_transient(&self) -> bool {
+        self.transient
+    }
 }
 
 pub fn err_msg<D>(msg: D) -> HttpError
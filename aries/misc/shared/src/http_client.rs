@@ -2,41 +2,230 @@ use std::time::Duration;
 
 use reqwest::{
     self,
-    header::{CONTENT_TYPE, USER_AGENT},
+    header::{ACCEPT, CONTENT_TYPE, USER_AGENT},
     Client, Response, Url,
 };
 
 use crate::errors::http_error::{HttpError, HttpResult};
 
 lazy_static! {
-    static ref HTTP_CLIENT: Client = {
-        match reqwest::ClientBuilder::new()
-            .timeout(Duration::from_secs(50))
-            .pool_idle_timeout(Some(Duration::from_secs(4)))
-            .build()
+    static ref HTTP_CLIENT: HttpClient = match HttpClient::new(HttpClientConfig::default()) {
+        Ok(client) => client,
+        Err(e) => panic!("Building reqwest client failed: {:?}", e),
+    };
+}
+
+/// Timeouts applied by an [`HttpClient`]. The default matches the settings of the global
+/// client used by [`post_message`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpClientConfig {
+    /// Total time allowed for a request, from connecting until the response body is read.
+    pub timeout: Duration,
+    pub connect_timeout: Option<Duration>,
+    pub pool_idle_timeout: Option<Duration>,
+}
+
+impl Default for HttpClientConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(50),
+            connect_timeout: None,
+            pool_idle_timeout: Some(Duration::from_secs(4)),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct HttpClient {
+    client: Client,
+}
+
+impl HttpClient {
+    pub fn new(config: HttpClientConfig) -> HttpResult<Self> {
+        let mut builder = reqwest::ClientBuilder::new()
+            .timeout(config.timeout)
+            .pool_idle_timeout(config.pool_idle_timeout);
+        if let Some(connect_timeout) = config.connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        let client = builder.build().map_err(|err| {
+            HttpError::from_msg(format!("Building reqwest client failed, err: {}", err))
+        })?;
+        Ok(Self { client })
+    }
+
+    /// Posts a DIDComm envelope and returns the body of the response, which is empty unless
+    /// the recipient answered over the same exchange.
+    pub async fn post_message(&self, body_content: Vec<u8>, url: &Url) -> HttpResult<Vec<u8>> {
+        debug!("post_message >> http client sending request POST {}", &url);
+
+        let response = send_post_request(&self.client, url, body_content).await?;
+        process_response(response).await
+    }
+
+    /// Fetches the resource at `url`, such as the content of an attachment given by links.
+    pub async fn get(&self, url: &Url) -> HttpResult<Vec<u8>> {
+        debug!("get >> http client sending request GET {}", &url);
+
+        let response = self.send_get_request(url).await?;
+        let body = response.bytes().await.map_err(|err| {
+            HttpError::from_msg(format!(
+                "GET failed because the response body could not be read, err: {}",
+                err
+            ))
+        })?;
+        Ok(body.to_vec())
+    }
+
+    /// Like [`HttpClient::get`], but fails without reading further once the resource turns out
+    /// to be larger than `max_size` bytes, be it by its `Content-Length` or its actual content.
+    pub async fn get_with_max_size(&self, url: &Url, max_size: u64) -> HttpResult<Vec<u8>> {
+        debug!(
+            "get_with_max_size >> http client sending request GET {}, max size: {max_size}",
+            &url
+        );
+
+        let mut response = self.send_get_request(url).await?;
+        let too_large = || {
+            HttpError::from_msg(format!(
+                "GET failed because the response body exceeds the limit of {} bytes",
+                max_size
+            ))
+        };
+        if response
+            .content_length()
+            .is_some_and(|content_length| content_length > max_size)
         {
-            Ok(client) => client,
-            Err(e) => panic!("Building reqwest client failed: {:?}", e),
+            return Err(too_large());
         }
-    };
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|err| {
+            HttpError::from_msg(format!(
+                "GET failed because the response body could not be read, err: {}",
+                err
+            ))
+        })? {
+            if (body.len() + chunk.len()) as u64 > max_size {
+                return Err(too_large());
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    async fn send_get_request(&self, url: &Url) -> HttpResult<Response> {
+        let response = self
+            .client
+            .get(url.clone())
+            .header(USER_AGENT, "reqwest")
+            .send()
+            .await
+            .map_err(|err| {
+                HttpError::from_msg(format!("HTTP Client could not connect, err: {}", err))
+            })?;
+        let response_status = response.status();
+        if !response_status.is_success() {
+            return Err(HttpError::from_msg(format!(
+                "GET failed due to non-success HTTP status: {}",
+                response_status
+            )));
+        }
+        Ok(response)
+    }
+
+    /// Fetches the resource at `url` asking for JSON, following redirects. Returns the URL the
+    /// response was finally served from along with its body.
+    pub async fn get_json(&self, url: &Url) -> HttpResult<(Url, Vec<u8>)> {
+        debug!("get_json >> http client sending request GET {}", &url);
+
+        let response = self
+            .client
+            .get(url.clone())
+            .header(USER_AGENT, "reqwest")
+            .header(ACCEPT, "application/json")
+            .send()
+            .await
+            .map_err(|err| {
+                HttpError::from_msg(format!("HTTP Client could not connect, err: {}", err))
+            })?;
+        let response_status = response.status();
+        if !response_status.is_success() {
+            return Err(HttpError::from_msg(format!(
+                "GET failed due to non-success HTTP status: {}",
+                response_status
+            )));
+        }
+        let final_url = response.url().clone();
+        let body = response.bytes().await.map_err(|err| {
+            HttpError::from_msg(format!(
+                "GET failed because the response body could not be read, err: {}",
+                err
+            ))
+        })?;
+        Ok((final_url, body.to_vec()))
+    }
+
+    /// Uploads `body_content` to `url` with a PUT request, such as a tails file to a tails
+    /// server, and returns the body of the response.
+    pub async fn put(&self, body_content: Vec<u8>, url: &Url) -> HttpResult<Vec<u8>> {
+        debug!("put >> http client sending request PUT {}", &url);
+
+        let response = self
+            .client
+            .put(url.clone())
+            .body(body_content)
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .header(USER_AGENT, "reqwest")
+            .send()
+            .await
+            .map_err(|err| {
+                HttpError::from_msg(format!("HTTP Client could not connect, err: {}", err))
+            })?;
+        let response_status = response.status();
+        if !response_status.is_success() {
+            return Err(HttpError::from_msg(format!(
+                "PUT failed due to non-success HTTP status: {}",
+                response_status
+            )));
+        }
+        let body = response.bytes().await.map_err(|err| {
+            HttpError::from_msg(format!(
+                "PUT failed because the response body could not be read, err: {}",
+                err
+            ))
+        })?;
+        Ok(body.to_vec())
+    }
 }
 
 pub async fn post_message(body_content: Vec<u8>, url: &Url) -> HttpResult<Vec<u8>> {
-    debug!("post_message >> http client sending request POST {}", &url);
+    HTTP_CLIENT.post_message(body_content, url).await
+}
 
-    let response = send_post_request(url, body_content).await?;
-    process_response(response).await
+pub async fn get(url: &Url) -> HttpResult<Vec<u8>> {
+    HTTP_CLIENT.get(url).await
 }
 
-async fn send_post_request(url: &Url, body_content: Vec<u8>) -> HttpResult<Response> {
-    HTTP_CLIENT
+async fn send_post_request(
+    client: &Client,
+    url: &Url,
+    body_content: Vec<u8>,
+) -> HttpResult<Response> {
+    client
         .post(url.clone())
         .body(body_content)
         .header(CONTENT_TYPE, "application/ssi-agent-wire")
         .header(USER_AGENT, "reqwest")
         .send()
         .await
-        .map_err(|err| HttpError::from_msg(format!("HTTP Client could not connect, err: {}", err)))
+        .map_err(|err| {
+            let msg = format!("HTTP Client could not connect, err: {}", err);
+            if err.is_connect() || err.is_timeout() {
+                HttpError::transient(msg)
+            } else {
+                HttpError::from_msg(msg)
+            }
+        })
 }
 
 async fn process_response(response: Response) -> HttpResult<Vec<u8>> {
@@ -47,10 +236,15 @@ async fn process_response(response: Response) -> HttpResult<Vec<u8>> {
             if response_status.is_success() {
                 Ok(payload.into_bytes())
             } else {
-                Err(HttpError::from_msg(format!(
+                let msg = format!(
                     "POST failed due to non-success HTTP status: {}, response body: {}",
                     response_status, payload
-                )))
+                );
+                if response_status.is_server_error() {
+                    Err(HttpError::transient(msg))
+                } else {
+                    Err(HttpError::from_msg(msg))
+                }
             }
         }
         Err(error) => Err(HttpError::from_msg(format!(
@@ -35,6 +35,12 @@ pub mod vdr_proxy_ledger;
 
 #[cfg(feature = "vdr_proxy_ledger")]
 use crate::devsetup::vdr_proxy_ledger::dev_build_profile_vdr_proxy_ledger;
+
+#[cfg(feature = "in_memory_ledger")]
+pub mod in_memory_ledger;
+
+#[cfg(feature = "in_memory_ledger")]
+use crate::devsetup::in_memory_ledger::dev_build_profile_in_memory_ledger;
 use crate::logger::init_logger;
 
 #[cfg(feature = "askar_wallet")]
@@ -160,7 +166,13 @@ pub async fn dev_build_featured_indy_ledger(
         dev_build_profile_vdr_proxy_ledger().await
     };
 
-    #[cfg(not(feature = "vdr_proxy_ledger"))]
+    #[cfg(all(feature = "in_memory_ledger", not(feature = "vdr_proxy_ledger")))]
+    return {
+        info!("SetupProfile >> using in-memory ledger");
+        dev_build_profile_in_memory_ledger(&genesis_file_path)
+    };
+
+    #[cfg(not(any(feature = "vdr_proxy_ledger", feature = "in_memory_ledger")))]
     return {
         info!("SetupProfile >> using vdr ledger");
         dev_build_profile_vdr_ledger(genesis_file_path)
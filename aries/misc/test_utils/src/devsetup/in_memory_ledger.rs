@@ -0,0 +1,30 @@
+use std::{collections::BTreeMap, sync::Mutex};
+
+use in_memory_ledger::InMemoryLedger;
+use log::info;
+
+// DID and verkey derived from `TRUSTEE_SEED`
+const TRUSTEE_DID: &str = "V4SGRU86Z58d6TV7PBUe6f";
+const TRUSTEE_VERKEY: &str = "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL";
+
+// Agents built for the same genesis file share a ledger, like agents connected to the same pool
+static LEDGERS: Mutex<BTreeMap<String, InMemoryLedger>> = Mutex::new(BTreeMap::new());
+
+pub fn dev_build_profile_in_memory_ledger(
+    genesis_file_path: &str,
+) -> (InMemoryLedger, InMemoryLedger) {
+    info!("dev_build_profile_in_memory_ledger >>");
+
+    let ledger = LEDGERS
+        .lock()
+        .unwrap()
+        .entry(genesis_file_path.to_owned())
+        .or_insert_with(|| {
+            let ledger = InMemoryLedger::new();
+            ledger.add_trustee(TRUSTEE_DID, TRUSTEE_VERKEY).unwrap();
+            ledger
+        })
+        .clone();
+
+    (ledger.clone(), ledger)
+}
@@ -7,3 +7,4 @@ pub mod errors;
 pub mod logger;
 pub mod mock_wallet;
 pub mod settings;
+pub mod tails_server;
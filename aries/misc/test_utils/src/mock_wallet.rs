@@ -12,7 +12,9 @@ use aries_vcx_wallet::{
             BaseWallet,
         },
         record_tags::RecordTags,
-        structs_io::UnpackMessageOutput,
+        structs_io::{
+            JweContentEncryption, KeyAgreementKey, UnpackMessageOutput, UnpackMessageOutputV2,
+        },
     },
 };
 use async_trait::async_trait;
@@ -168,4 +170,27 @@ impl DidWallet for MockWallet {
             sender_verkey: None,
         })
     }
+
+    async fn pack_message_v2(
+        &self,
+        sender: Option<KeyAgreementKey>,
+        recipients: Vec<KeyAgreementKey>,
+        enc: JweContentEncryption,
+        msg: &[u8],
+    ) -> VcxWalletResult<Vec<u8>> {
+        Ok(Vec::from(msg))
+    }
+
+    async fn unpack_message_v2(
+        &self,
+        msg: &[u8],
+        recipients: Vec<KeyAgreementKey>,
+        sender: Option<KeyAgreementKey>,
+    ) -> VcxWalletResult<UnpackMessageOutputV2> {
+        Ok(UnpackMessageOutputV2 {
+            message: format!("{:?}", msg),
+            recipient_kid: "".to_owned(),
+            sender_kid: None,
+        })
+    }
 }
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::{collections::BTreeSet, path::Path};
 
 use anoncreds_types::data_types::{
     identifiers::{
@@ -7,9 +7,7 @@ use anoncreds_types::data_types::{
     },
     ledger::{
         cred_def::CredentialDefinition,
-        rev_reg::RevocationRegistry,
         rev_reg_def::RevocationRegistryDefinition,
-        rev_reg_delta::RevocationRegistryDelta,
         rev_status_list::RevocationStatusList,
         schema::{AttributeNames, Schema},
     },
@@ -38,7 +36,7 @@ use did_parser_nom::Did;
 
 use crate::constants::{
     CREDENTIAL_JSON, CREDENTIAL_REQ_METADATA, CREDENTIAL_REQ_STRING, LARGE_NONCE,
-    LIBINDY_CRED_OFFER, PROOF_JSON, REV_REG_DELTA_JSON, REV_STATE_JSON,
+    LIBINDY_CRED_OFFER, PROOF_JSON, REV_STATE_JSON, REV_STATUS_LIST_JSON,
 };
 
 #[derive(Debug)]
@@ -73,7 +71,7 @@ impl BaseAnonCreds for MockAnoncreds {
     ) -> VcxAnoncredsResult<(
         RevocationRegistryDefinitionId,
         RevocationRegistryDefinition,
-        RevocationRegistry,
+        RevocationStatusList,
     )> {
         // not needed yet
         Err(VcxAnoncredsError::UnimplementedFeature(
@@ -185,6 +183,18 @@ impl BaseAnonCreds for MockAnoncreds {
         Ok(serde_json::from_str(REV_STATE_JSON)?)
     }
 
+    async fn update_revocation_state(
+        &self,
+        _tails_dir: &Path,
+        _rev_reg_def_json: RevocationRegistryDefinition,
+        _rev_status_list: RevocationStatusList,
+        _cred_rev_id: u32,
+        _rev_state: CredentialRevocationState,
+        _old_rev_status_list: RevocationStatusList,
+    ) -> VcxAnoncredsResult<CredentialRevocationState> {
+        Ok(serde_json::from_str(REV_STATE_JSON)?)
+    }
+
     async fn prover_store_credential(
         &self,
         _wallet: &impl BaseWallet,
@@ -234,20 +244,37 @@ impl BaseAnonCreds for MockAnoncreds {
         _wallet: &impl BaseWallet,
         _rev_reg_id: &RevocationRegistryDefinitionId,
         _cred_rev_id: u32,
-        _rev_reg_delta_json: RevocationRegistryDelta,
+        _ledger_rev_status_list: RevocationStatusList,
+    ) -> VcxAnoncredsResult<()> {
+        Ok(())
+    }
+
+    async fn revoke_credentials_local(
+        &self,
+        _wallet: &impl BaseWallet,
+        _rev_reg_id: &RevocationRegistryDefinitionId,
+        _cred_rev_ids: BTreeSet<u32>,
+        _ledger_rev_status_list: RevocationStatusList,
     ) -> VcxAnoncredsResult<()> {
         Ok(())
     }
 
-    async fn get_rev_reg_delta(
+    async fn get_rev_reg_ids_with_pending_revocations(
+        &self,
+        _wallet: &impl BaseWallet,
+    ) -> VcxAnoncredsResult<Vec<RevocationRegistryDefinitionId>> {
+        Ok(vec![])
+    }
+
+    async fn get_pending_rev_status_list(
         &self,
         _wallet: &impl BaseWallet,
         _rev_reg_id: &RevocationRegistryDefinitionId,
-    ) -> VcxAnoncredsResult<Option<RevocationRegistryDelta>> {
-        Ok(Some(serde_json::from_str(REV_REG_DELTA_JSON)?))
+    ) -> VcxAnoncredsResult<Option<RevocationStatusList>> {
+        Ok(Some(serde_json::from_str(REV_STATUS_LIST_JSON)?))
     }
 
-    async fn clear_rev_reg_delta(
+    async fn clear_pending_rev_status_list(
         &self,
         _wallet: &impl BaseWallet,
         _rev_reg_id: &RevocationRegistryDefinitionId,
@@ -74,8 +74,51 @@ impl IndyLedgerWrite for MockLedger {
         wallet: &impl BaseWallet,
         endorser_did: &Did,
         request_json: &str,
-    ) -> VcxLedgerResult<()> {
-        Ok(())
+    ) -> VcxLedgerResult<String> {
+        Ok(REQUEST_WITH_ENDORSER.to_string())
+    }
+
+    async fn prepare_nym_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        author_did: &Did,
+        target_did: &Did,
+        target_vk: Option<&Key>,
+        role: Option<UpdateRole>,
+        alias: Option<String>,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        Ok(REQUEST_WITH_ENDORSER.to_string())
+    }
+
+    async fn prepare_schema_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        schema: Schema,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        Ok(REQUEST_WITH_ENDORSER.to_string())
+    }
+
+    async fn prepare_cred_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        cred_def: CredentialDefinition,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        Ok(REQUEST_WITH_ENDORSER.to_string())
+    }
+
+    async fn prepare_rev_reg_def_for_endorsement(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_reg_def: RevocationRegistryDefinition,
+        author_did: &Did,
+        endorser_did: &Did,
+    ) -> VcxLedgerResult<String> {
+        Ok(REQUEST_WITH_ENDORSER.to_string())
     }
 
     async fn publish_nym(
@@ -207,4 +250,14 @@ impl AnoncredsLedgerWrite for MockLedger {
     ) -> VcxLedgerResult<()> {
         Ok(())
     }
+
+    async fn publish_rev_status_list(
+        &self,
+        wallet: &impl BaseWallet,
+        rev_status_list: RevocationStatusList,
+        prev_rev_status_list: Option<&RevocationStatusList>,
+        submitter_did: &Did,
+    ) -> VcxLedgerResult<()> {
+        Ok(())
+    }
 }
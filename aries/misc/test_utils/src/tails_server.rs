@@ -0,0 +1,86 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::StatusCode,
+    routing::put,
+    Router,
+};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+type TailsFiles = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+/// Tails server stand-in for tests, listening on a local port. Tails files are uploaded with
+/// `PUT /{rev_reg_id}` and served back with `GET /{rev_reg_id}`, and kept in memory.
+pub struct LocalTailsServer {
+    addr: SocketAddr,
+    tails_files: TailsFiles,
+    handle: JoinHandle<()>,
+}
+
+impl LocalTailsServer {
+    pub async fn start() -> Self {
+        let tails_files = TailsFiles::default();
+        let app = Router::new()
+            .route("/{rev_reg_id}", put(upload_tails).get(download_tails))
+            .with_state(tails_files.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        Self {
+            addr,
+            tails_files,
+            handle,
+        }
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}/", self.addr)
+    }
+
+    pub fn tails_file(&self, rev_reg_id: &str) -> Option<Vec<u8>> {
+        self.tails_files.lock().unwrap().get(rev_reg_id).cloned()
+    }
+
+    pub fn uploaded_count(&self) -> usize {
+        self.tails_files.lock().unwrap().len()
+    }
+}
+
+impl Drop for LocalTailsServer {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+async fn upload_tails(
+    State(tails_files): State<TailsFiles>,
+    Path(rev_reg_id): Path<String>,
+    tails: Bytes,
+) -> StatusCode {
+    let mut tails_files = tails_files.lock().unwrap();
+    if tails_files.contains_key(&rev_reg_id) {
+        return StatusCode::CONFLICT;
+    }
+    tails_files.insert(rev_reg_id, tails.to_vec());
+    StatusCode::OK
+}
+
+async fn download_tails(
+    State(tails_files): State<TailsFiles>,
+    Path(rev_reg_id): Path<String>,
+) -> Result<Vec<u8>, StatusCode> {
+    tails_files
+        .lock()
+        .unwrap()
+        .get(&rev_reg_id)
+        .cloned()
+        .ok_or(StatusCode::NOT_FOUND)
+}
@@ -4,6 +4,10 @@ use did_resolver::did_parser_nom::Did;
 
 use crate::error::DidSovError;
 
+mod namespaced;
+
+pub use namespaced::NamespacedAttrReader;
+
 #[async_trait]
 #[cfg_attr(test, mockall::automock)]
 pub trait AttrReader: Send + Sync {
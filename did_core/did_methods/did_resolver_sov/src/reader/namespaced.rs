@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use aries_vcx_ledger::ledger::{
+    indy::pool_manager::IndyPoolManager, response_cacher::in_memory::InMemoryResponseCacherConfig,
+};
+use async_trait::async_trait;
+use did_resolver::did_parser_nom::Did;
+
+use super::AttrReader;
+use crate::error::DidSovError;
+
+/// [AttrReader] reading did:sov DIDs from the ledger of their namespace, e.g.
+/// `did:sov:idunion:<id>` from the IDUnion ledger. DIDs without a namespace are read from the
+/// ledger of the default namespace, which is the first registered one unless set explicitly.
+#[derive(Default)]
+pub struct NamespacedAttrReader {
+    readers: HashMap<String, Box<dyn AttrReader>>,
+    default_namespace: Option<String>,
+}
+
+impl NamespacedAttrReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a reader for each pool of `pool_manager`, using the pool names as namespaces.
+    /// The pool with the highest priority becomes the default namespace.
+    pub fn from_pool_manager(
+        pool_manager: &IndyPoolManager,
+        cache_config: InMemoryResponseCacherConfig,
+    ) -> Result<Self, DidSovError> {
+        Ok(pool_manager
+            .ledger_reads(cache_config)?
+            .into_iter()
+            .fold(Self::new(), |reader, (namespace, ledger_read)| {
+                reader.register_reader(namespace, ledger_read)
+            }))
+    }
+
+    pub fn register_reader<A>(mut self, namespace: impl Into<String>, reader: A) -> Self
+    where
+        A: AttrReader + 'static,
+    {
+        let namespace = namespace.into();
+        if self.default_namespace.is_none() {
+            self.default_namespace = Some(namespace.clone());
+        }
+        self.readers.insert(namespace, Box::new(reader));
+        self
+    }
+
+    pub fn with_default_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.default_namespace = Some(namespace.into());
+        self
+    }
+
+    /// Picks the reader for the namespace of `did`, along with the DID stripped of its namespace
+    /// as known to that ledger.
+    fn route(&self, did: &Did) -> Result<(&dyn AttrReader, Did), DidSovError> {
+        let (namespace, did) = match did.namespace() {
+            Some(namespace) => {
+                let did = Did::parse(format!("did:sov:{}", did.id()))
+                    .map_err(|err| DidSovError::InvalidDid(err.to_string()))?;
+                (namespace, did)
+            }
+            None => (
+                self.default_namespace.as_deref().ok_or_else(|| {
+                    DidSovError::InvalidConfiguration("No ledger registered".to_string())
+                })?,
+                did.to_owned(),
+            ),
+        };
+        let reader = self.readers.get(namespace).ok_or_else(|| {
+            DidSovError::NotFound(format!("No ledger registered for namespace {namespace}"))
+        })?;
+        Ok((reader.as_ref(), did))
+    }
+}
+
+#[async_trait]
+impl AttrReader for NamespacedAttrReader {
+    async fn get_attr(&self, target_did: &Did, attr_name: &str) -> Result<String, DidSovError> {
+        let (reader, did) = self.route(target_did)?;
+        reader.get_attr(&did, attr_name).await
+    }
+
+    async fn get_nym(&self, did: &Did) -> Result<String, DidSovError> {
+        let (reader, did) = self.route(did)?;
+        reader.get_nym(&did).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DID_ID: &str = "2wJPyULfLLnYTEFYzByfUR";
+
+    struct LedgerReader(&'static str);
+
+    #[async_trait]
+    impl AttrReader for LedgerReader {
+        async fn get_attr(&self, target_did: &Did, attr_name: &str) -> Result<String, DidSovError> {
+            Ok(format!("{} {} {}", self.0, target_did, attr_name))
+        }
+
+        async fn get_nym(&self, did: &Did) -> Result<String, DidSovError> {
+            Ok(format!("{} {}", self.0, did))
+        }
+    }
+
+    fn _reader() -> NamespacedAttrReader {
+        NamespacedAttrReader::new()
+            .register_reader("sovrin", LedgerReader("sovrin"))
+            .register_reader("idunion", LedgerReader("idunion"))
+    }
+
+    #[tokio::test]
+    async fn test_routes_by_namespace() {
+        let did = Did::parse(format!("did:sov:idunion:{DID_ID}")).unwrap();
+        assert_eq!(
+            _reader().get_nym(&did).await.unwrap(),
+            format!("idunion did:sov:{DID_ID}")
+        );
+        assert_eq!(
+            _reader().get_attr(&did, "endpoint").await.unwrap(),
+            format!("idunion did:sov:{DID_ID} endpoint")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_routes_did_without_namespace_to_default() {
+        let did = Did::parse(format!("did:sov:{DID_ID}")).unwrap();
+        assert_eq!(
+            _reader().get_nym(&did).await.unwrap(),
+            format!("sovrin did:sov:{DID_ID}")
+        );
+        assert_eq!(
+            _reader()
+                .with_default_namespace("idunion")
+                .get_nym(&did)
+                .await
+                .unwrap(),
+            format!("idunion did:sov:{DID_ID}")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unknown_namespace() {
+        let did = Did::parse(format!("did:sov:bcovrin:{DID_ID}")).unwrap();
+        assert!(matches!(
+            _reader().get_nym(&did).await.unwrap_err(),
+            DidSovError::NotFound(_)
+        ));
+    }
+}